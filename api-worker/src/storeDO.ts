@@ -141,6 +141,8 @@ export interface Env {
   POP_SIGNER_HD_ROTATION_START_UNIX?: string;
   POP_SIGNER_HD_ROTATION_INDEX_OFFSET?: string;
   POP_SIGNER_LEGACY_ENABLED?: string;
+  /** grant_program の initialize_hash_domain で設定した domain（white-label のデプロイのみ。既定は "we-ne"） */
+  POP_HASH_DOMAIN?: string;
   ENFORCE_ONCHAIN_POP?: string;
   AUDIT_LOGS?: R2Bucket;
   AUDIT_INDEX?: KVNamespace;
@@ -190,6 +192,7 @@ const POP_CHAIN_GLOBAL_PREFIX = 'pop_chain:lastHash:global:';
 const POP_CHAIN_STREAM_PREFIX = 'pop_chain:lastHash:stream:';
 const POP_PROOF_CACHE_PREFIX = 'pop_proof:claim:';
 const POP_HASH_LEN = 32;
+const POP_HASH_DEFAULT_DOMAIN = 'we-ne';
 const POP_MESSAGE_VERSION = 2;
 const POP_MESSAGE_LEN = 1 + 32 + 32 + 8 + 32 + 32 + 32 + 32 + 8;
 const POP_HASH_GENESIS_HEX = '0'.repeat(64);
@@ -2752,7 +2755,7 @@ export class SchoolStore implements DurableObject {
     const encoder = new TextEncoder();
     const periodBytes = u64ToLeBytes(params.periodIndex);
     const issuedAtBytes = i64ToLeBytes(params.issuedAt);
-    const domain = encoder.encode('we-ne:pop:v2');
+    const domain = encoder.encode(`${this.env.POP_HASH_DOMAIN?.trim() || POP_HASH_DEFAULT_DOMAIN}:pop:v2`);
     const input = new Uint8Array(
       domain.length +
       params.prevHash.length +
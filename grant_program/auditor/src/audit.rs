@@ -0,0 +1,461 @@
+// ===== receipt と PoP チェーンの突き合わせ =====
+// PoP チェーンの検証は client::verify_pop_chain をそのまま使う（同じ規則で2回実装しない）。
+// receipt 1件ごとに確認すること:
+// - アドレスが (grant, claimer, period_index, seed_salt) の receipt PDA と一致する
+// - PoP 付きの受給なら、同じ (claimer, period_index) の PopEntryRecorded がある
+//   （pop_bypassed の receipt は PopBypassClaimed がある）
+// - allowlist の root が与えられていれば、claimer の proof がいずれかの root に届く
+// 逆向きに、receipt のない PopEntryRecorded と、同じ (claimer, period_index) の重複エントリも報告する。
+// Reserved の receipt は受給記録ではないため対象外。
+// OffChainSettled の receipt（移行前の受給）は PoP と allowlist を経ていないため、アドレスだけを確認する。
+
+use std::collections::{HashMap, HashSet};
+
+use anchor_lang::prelude::*;
+use grant_program::{merkle, receipt, ClaimReceipt, Grant, HashDomain, PopEntryRecorded, ReceiptStatus};
+use grant_program_client::{verify_pop_chain_in, EventRecord, GrantEvent, PopChainIssue};
+
+use crate::report::{AuditReport, AUDIT_REPORT_VERSION};
+
+/// allowlist の検証材料（allowlist を使わない Grant は Default のまま）
+#[derive(Clone, Debug, Default)]
+pub struct AllowlistEvidence {
+    /// 受給時点で有効だった可能性のある root（公開済みの過去の root を含める）
+    /// AllowlistRootCommitted / AllowlistDeltaAppended の root は監査時に自動で加わる
+    pub roots: Vec<[u8; 32]>,
+    /// claimer ごとの SortedPair proof（claim で使ったもの）
+    pub proofs: HashMap<Pubkey, Vec<[u8; 32]>>,
+}
+
+impl AllowlistEvidence {
+    /// Grant の現在の merkle_root と差分 root を roots に加える（[0;32] = allowlist なしは加えない）
+    pub fn with_grant_roots(mut self, grant: &Grant) -> Self {
+        let deltas = &grant.allowlist_delta_roots[..grant.allowlist_delta_count as usize];
+        for root in std::iter::once(&grant.merkle_root).chain(deltas) {
+            if *root != [0u8; 32] && !self.roots.contains(root) {
+                self.roots.push(*root);
+            }
+        }
+        self
+    }
+}
+
+/// 監査の入力（1つの Grant 分）
+pub struct AuditInput {
+    pub grant: Pubkey,
+    /// grant の receipt（close 済みのものはアーカイブ済みのデータから補う）
+    pub receipts: Vec<(Pubkey, ClaimReceipt)>,
+    /// grant に触れたトランザクションのイベント（古い順。他の Grant のイベントは無視する）
+    pub events: Vec<EventRecord>,
+    pub allowlist: AllowlistEvidence,
+    /// PoP 署名者として認める鍵（PopConfig.signer_pubkey の履歴。空 = 署名者を確認しない）
+    pub pop_signers: Vec<Pubkey>,
+    /// Grant.hash_domain（PoP の entry_hash と allowlist の葉の domain。Default = DEFAULT_HASH_DOMAIN）
+    pub hash_domain: HashDomain,
+}
+
+/// 監査で見つかった不整合（index は grant の PopEntryRecorded を古い順に数えた位置）
+#[derive(Clone, Debug, PartialEq, Eq, AnchorSerialize, AnchorDeserialize)]
+pub enum AuditIssue {
+    /// entry_hash が入力から再計算した値と一致しない（または未知の version）
+    PopEntryHashMismatch { index: u64 },
+    /// prev_hash が、それより前のどの entry_hash とも一致しない
+    PopUnknownPrevHash { index: u64 },
+    /// pop_signers に含まれない鍵が署名した
+    UnexpectedPopSigner { index: u64, signer: Pubkey },
+    /// 同じ (claimer, period_index) の PopEntryRecorded が2件目以降に現れた
+    DuplicatePopEntry { index: u64, claimer: Pubkey, period_index: u64 },
+    /// PopEntryRecorded に対応する receipt がない
+    OrphanPopEntry { index: u64, claimer: Pubkey, period_index: u64 },
+    /// receipt の grant が監査対象の Grant と異なる
+    ReceiptGrantMismatch { receipt: Pubkey },
+    /// receipt のアドレスが receipt PDA と一致しない
+    ReceiptAddressMismatch { receipt: Pubkey },
+    /// PoP 付きの receipt に対応する PopEntryRecorded がない
+    MissingPopEntry { receipt: Pubkey, claimer: Pubkey, period_index: u64 },
+    /// pop_bypassed の receipt に対応する PopBypassClaimed がない
+    MissingBypassRecord { receipt: Pubkey, claimer: Pubkey, period_index: u64 },
+    /// claimer の proof がどの allowlist の root にも届かない（proof がない場合を含む）
+    NotInAllowlist { receipt: Pubkey, claimer: Pubkey },
+}
+
+/// input を検証して AuditReport を作る（署名は sign_report で行う）
+pub fn audit_grant(input: &AuditInput) -> AuditReport {
+    let grant = input.grant;
+    let mut issues = Vec::new();
+    let mut pop_entries: Vec<&PopEntryRecorded> = Vec::new();
+    let mut bypassed = HashSet::new();
+    let mut allowlist_roots = input.allowlist.roots.clone();
+    for record in input.events.iter() {
+        let root = match &record.event {
+            GrantEvent::PopEntryRecorded(entry) if entry.grant == grant => {
+                pop_entries.push(entry);
+                None
+            }
+            GrantEvent::PopBypassClaimed(claim) if claim.grant == grant => {
+                bypassed.insert((claim.claimer, claim.period_index));
+                None
+            }
+            GrantEvent::AllowlistRootCommitted(commit) if commit.grant == grant => Some(commit.merkle_root),
+            GrantEvent::AllowlistDeltaAppended(delta) if delta.grant == grant => Some(delta.delta_root),
+            _ => None,
+        };
+        if let Some(root) = root.filter(|root| *root != [0u8; 32] && !allowlist_roots.contains(root)) {
+            allowlist_roots.push(root);
+        }
+    }
+
+    let domain = input.hash_domain.as_bytes();
+    issues.extend(verify_pop_chain_in(domain, pop_entries.iter().copied()).into_iter().map(|issue| match issue {
+        PopChainIssue::EntryHashMismatch { index } => AuditIssue::PopEntryHashMismatch { index: index as u64 },
+        PopChainIssue::UnknownPrevHash { index } => AuditIssue::PopUnknownPrevHash { index: index as u64 },
+    }));
+
+    let claimed: HashSet<(Pubkey, u64)> = input
+        .receipts
+        .iter()
+        .filter(|(_, receipt)| receipt.grant == grant && receipt.status != ReceiptStatus::Reserved)
+        .map(|(_, receipt)| (receipt.claimer, receipt.period_index))
+        .collect();
+    let mut entries_by_claim = HashSet::new();
+    for (index, entry) in pop_entries.iter().enumerate() {
+        let index = index as u64;
+        if !input.pop_signers.is_empty() && !input.pop_signers.contains(&entry.signer) {
+            issues.push(AuditIssue::UnexpectedPopSigner {
+                index,
+                signer: entry.signer,
+            });
+        }
+        let key = (entry.claimer, entry.period_index);
+        if !entries_by_claim.insert(key) {
+            issues.push(AuditIssue::DuplicatePopEntry {
+                index,
+                claimer: entry.claimer,
+                period_index: entry.period_index,
+            });
+        }
+        if !claimed.contains(&key) {
+            issues.push(AuditIssue::OrphanPopEntry {
+                index,
+                claimer: entry.claimer,
+                period_index: entry.period_index,
+            });
+        }
+    }
+
+    let mut receipts_checked = 0u64;
+    for (address, receipt) in input.receipts.iter() {
+        if receipt.grant != grant {
+            issues.push(AuditIssue::ReceiptGrantMismatch { receipt: *address });
+            continue;
+        }
+        if receipt.status == ReceiptStatus::Reserved {
+            continue;
+        }
+        receipts_checked += 1;
+        let expected = receipt::receipt_address_with_bump(
+            &grant,
+            &receipt.claimer,
+            receipt.period_index,
+            &receipt.seed_salt,
+            receipt.bump,
+        );
+        if expected.ok() != Some(*address) {
+            issues.push(AuditIssue::ReceiptAddressMismatch { receipt: *address });
+        }
+
+        if receipt.status == ReceiptStatus::OffChainSettled {
+            continue;
+        }
+
+        let key = (receipt.claimer, receipt.period_index);
+        if receipt.pop_bypassed && !bypassed.contains(&key) {
+            issues.push(AuditIssue::MissingBypassRecord {
+                receipt: *address,
+                claimer: receipt.claimer,
+                period_index: receipt.period_index,
+            });
+        } else if !receipt.pop_bypassed && !entries_by_claim.contains(&key) {
+            issues.push(AuditIssue::MissingPopEntry {
+                receipt: *address,
+                claimer: receipt.claimer,
+                period_index: receipt.period_index,
+            });
+        }
+
+        if !allowlist_roots.is_empty() {
+            let leaf = merkle::allowlist_leaf_in(domain, &receipt.claimer);
+            let included = input
+                .allowlist
+                .proofs
+                .get(&receipt.claimer)
+                .is_some_and(|proof| allowlist_roots.contains(&merkle::sorted_pair_root(leaf, proof)));
+            if !included {
+                issues.push(AuditIssue::NotInAllowlist {
+                    receipt: *address,
+                    claimer: receipt.claimer,
+                });
+            }
+        }
+    }
+
+    AuditReport {
+        version: AUDIT_REPORT_VERSION,
+        grant,
+        receipts_checked,
+        pop_entries_checked: pop_entries.len() as u64,
+        pop_chain_head: pop_entries.last().map_or([0u8; 32], |entry| entry.entry_hash),
+        allowlist_roots,
+        last_slot: input.events.iter().map(|record| record.slot).max().unwrap_or(0),
+        issues,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use grant_program::{AllowlistRootCommitted, PopBypassClaimed, SEED_SALT_LEN};
+
+    use super::*;
+
+    const SALT: [u8; SEED_SALT_LEN] = [3; SEED_SALT_LEN];
+
+    fn key(n: u8) -> Pubkey {
+        Pubkey::new_from_array([n; 32])
+    }
+
+    fn grant() -> Pubkey {
+        key(1)
+    }
+
+    fn signer() -> Pubkey {
+        key(2)
+    }
+
+    fn record(slot: u64, event: GrantEvent) -> EventRecord {
+        EventRecord {
+            signature: format!("tx{slot}"),
+            slot,
+            block_time: None,
+            event,
+        }
+    }
+
+    /// prev_hash に繋がる PopEntryRecorded（entry_hash は再計算した正しい値）
+    fn pop_entry(claimer: Pubkey, period_index: u64, prev_hash: [u8; 32]) -> PopEntryRecorded {
+        let mut entry = PopEntryRecorded {
+            schema_version: grant_program::EVENT_SCHEMA_VERSION,
+            grant: grant(),
+            claimer,
+            signer: signer(),
+            version: grant_program::POP_MESSAGE_VERSION_V2,
+            prev_hash,
+            stream_prev_hash: [0; 32],
+            audit_hash: [claimer.to_bytes()[0]; 32],
+            entry_hash: [0; 32],
+            period_index,
+            issued_at: 1_700_000_000 + period_index as i64,
+            head_advanced: true,
+        };
+        entry.entry_hash = entry.computed_entry_hash().unwrap();
+        entry
+    }
+
+    fn claim_receipt(claimer: Pubkey, period_index: u64, pop_bypassed: bool, status: ReceiptStatus) -> (Pubkey, ClaimReceipt) {
+        let (address, bump) = receipt::receipt_address(&grant(), &claimer, period_index, &SALT);
+        (
+            address,
+            ClaimReceipt {
+                grant: grant(),
+                claimer,
+                period_index,
+                claimed_at: 1_700_000_000,
+                bump,
+                status,
+                amount: 100,
+                pop_bypassed,
+                seed_salt: SALT,
+            },
+        )
+    }
+
+    fn pop_entries(input: &mut AuditInput) -> impl Iterator<Item = &mut PopEntryRecorded> {
+        input.events.iter_mut().filter_map(|record| match &mut record.event {
+            GrantEvent::PopEntryRecorded(entry) => Some(entry),
+            _ => None,
+        })
+    }
+
+    fn last_entry_hash(input: &AuditInput) -> [u8; 32] {
+        input
+            .events
+            .iter()
+            .rev()
+            .find_map(|record| match &record.event {
+                GrantEvent::PopEntryRecorded(entry) => Some(entry.entry_hash),
+                _ => None,
+            })
+            .unwrap()
+    }
+
+    /// 不整合のない Grant:
+    /// A・B は PoP 付きで受給、C は PoP bypass 中に受給、D は移行前の受給（OffChainSettled）、E は予約のみ。
+    /// A・B・C は allowlist（AllowlistRootCommitted で公開）に含まれる
+    fn consistent_input() -> AuditInput {
+        let [a, b, c] = [key(10), key(11), key(12)];
+        let leaves = [a, b, c].map(|claimer| merkle::allowlist_leaf(&claimer));
+        let ab = merkle::sorted_pair_root(leaves[0], &[leaves[1]]);
+        let root = merkle::sorted_pair_root(ab, &[leaves[2]]);
+
+        let entry_a = pop_entry(a, 1, [0; 32]);
+        let entry_b = pop_entry(b, 1, entry_a.entry_hash);
+        let events = vec![
+            record(
+                10,
+                GrantEvent::AllowlistRootCommitted(AllowlistRootCommitted {
+                    schema_version: grant_program::EVENT_SCHEMA_VERSION,
+                    grant: grant(),
+                    merkle_root: root,
+                    leaf_count: 3,
+                    snapshot_hash: [0; 32],
+                    committed_by: key(9),
+                    committed_at: 1_700_000_000,
+                }),
+            ),
+            record(11, GrantEvent::PopEntryRecorded(entry_a)),
+            record(12, GrantEvent::PopEntryRecorded(entry_b)),
+            record(
+                13,
+                GrantEvent::PopBypassClaimed(PopBypassClaimed {
+                    schema_version: grant_program::EVENT_SCHEMA_VERSION,
+                    grant: grant(),
+                    claimer: c,
+                    period_index: 1,
+                    amount: 100,
+                    bypass_until: 1_700_100_000,
+                    claimed_at: 1_700_000_000,
+                }),
+            ),
+        ];
+
+        AuditInput {
+            grant: grant(),
+            receipts: vec![
+                claim_receipt(a, 1, false, ReceiptStatus::Valid),
+                claim_receipt(b, 1, false, ReceiptStatus::Valid),
+                claim_receipt(c, 1, true, ReceiptStatus::Valid),
+                claim_receipt(key(13), 0, false, ReceiptStatus::OffChainSettled),
+                claim_receipt(key(14), 1, false, ReceiptStatus::Reserved),
+            ],
+            events,
+            allowlist: AllowlistEvidence {
+                roots: Vec::new(),
+                proofs: HashMap::from([
+                    (a, vec![leaves[1], leaves[2]]),
+                    (b, vec![leaves[0], leaves[2]]),
+                    (c, vec![ab]),
+                ]),
+            },
+            pop_signers: vec![signer()],
+            hash_domain: HashDomain::default(),
+        }
+    }
+
+    fn assert_single_issue(input: &AuditInput, expected: AuditIssue) {
+        assert_eq!(audit_grant(input).issues, [expected]);
+    }
+
+    #[test]
+    fn consistent_grant_is_clean() {
+        let input = consistent_input();
+        let report = audit_grant(&input);
+        assert!(report.is_clean(), "{:?}", report.issues);
+        assert_eq!(report.receipts_checked, 4);
+        assert_eq!(report.pop_entries_checked, 2);
+        assert_eq!(report.allowlist_roots.len(), 1);
+        assert_eq!(report.last_slot, 13);
+        assert_eq!(report.pop_chain_head, last_entry_hash(&input));
+    }
+
+    #[test]
+    fn tampered_entry_hash_is_reported() {
+        let mut input = consistent_input();
+        pop_entries(&mut input).nth(1).unwrap().audit_hash = [0xee; 32];
+        assert_single_issue(&input, AuditIssue::PopEntryHashMismatch { index: 1 });
+    }
+
+    #[test]
+    fn broken_prev_hash_is_reported() {
+        let mut input = consistent_input();
+        let entry = pop_entries(&mut input).nth(1).unwrap();
+        *entry = pop_entry(entry.claimer, entry.period_index, [0xee; 32]);
+        assert_single_issue(&input, AuditIssue::PopUnknownPrevHash { index: 1 });
+    }
+
+    #[test]
+    fn unexpected_signer_is_reported() {
+        let mut input = consistent_input();
+        pop_entries(&mut input).next().unwrap().signer = key(99);
+        assert_single_issue(&input, AuditIssue::UnexpectedPopSigner { index: 0, signer: key(99) });
+    }
+
+    #[test]
+    fn duplicate_entry_is_reported() {
+        let mut input = consistent_input();
+        let prev_hash = last_entry_hash(&input);
+        input.events.push(record(14, GrantEvent::PopEntryRecorded(pop_entry(key(10), 1, prev_hash))));
+        assert_single_issue(&input, AuditIssue::DuplicatePopEntry { index: 2, claimer: key(10), period_index: 1 });
+    }
+
+    #[test]
+    fn orphan_entry_is_reported() {
+        let mut input = consistent_input();
+        let prev_hash = last_entry_hash(&input);
+        input.events.push(record(14, GrantEvent::PopEntryRecorded(pop_entry(key(20), 2, prev_hash))));
+        assert_single_issue(&input, AuditIssue::OrphanPopEntry { index: 2, claimer: key(20), period_index: 2 });
+    }
+
+    #[test]
+    fn receipt_of_another_grant_is_reported() {
+        let mut input = consistent_input();
+        let (address, mut other) = claim_receipt(key(10), 2, false, ReceiptStatus::Valid);
+        other.grant = key(50);
+        input.receipts.push((address, other));
+        assert_single_issue(&input, AuditIssue::ReceiptGrantMismatch { receipt: address });
+    }
+
+    #[test]
+    fn receipt_at_wrong_address_is_reported() {
+        let mut input = consistent_input();
+        // salt を無視して導出したアドレス
+        let (unsalted, _) = receipt::receipt_address(&grant(), &key(10), 1, &[0; SEED_SALT_LEN]);
+        input.receipts[0].0 = unsalted;
+        assert_single_issue(&input, AuditIssue::ReceiptAddressMismatch { receipt: unsalted });
+    }
+
+    #[test]
+    fn receipt_without_pop_entry_is_reported() {
+        let mut input = consistent_input();
+        input.events.retain(|record| !matches!(&record.event, GrantEvent::PopEntryRecorded(entry) if entry.claimer == key(11)));
+        let receipt = input.receipts[1].0;
+        assert_single_issue(&input, AuditIssue::MissingPopEntry { receipt, claimer: key(11), period_index: 1 });
+    }
+
+    #[test]
+    fn bypassed_receipt_without_bypass_record_is_reported() {
+        let mut input = consistent_input();
+        input.events.retain(|record| !matches!(record.event, GrantEvent::PopBypassClaimed(_)));
+        let receipt = input.receipts[2].0;
+        assert_single_issue(&input, AuditIssue::MissingBypassRecord { receipt, claimer: key(12), period_index: 1 });
+    }
+
+    #[test]
+    fn claimer_outside_allowlist_is_reported() {
+        let mut input = consistent_input();
+        input.allowlist.proofs.insert(key(12), vec![[0xee; 32]]);
+        let receipt = input.receipts[2].0;
+        assert_single_issue(&input, AuditIssue::NotInAllowlist { receipt, claimer: key(12) });
+
+        input.allowlist.proofs.remove(&key(12));
+        assert_single_issue(&input, AuditIssue::NotInAllowlist { receipt, claimer: key(12) });
+    }
+}
@@ -0,0 +1,61 @@
+// ===== RPC からの入力の収集 =====
+// receipt は getProgramAccounts（grant で絞り込み）、イベントは grant に触れた全トランザクションのログから集める。
+// 長期間運用した Grant ではトランザクション数が多いため、前回の監査の結果を保存しておき、
+// アーカイブ済みのデータから AuditInput を組み立てる方が安い場合がある。
+
+use std::pin::pin;
+
+use anchor_lang::prelude::Pubkey;
+use futures::StreamExt;
+use grant_program::HashDomain;
+use grant_program_client::{
+    backfill_events, fetch_receipts_by_grant, EventRecord, FetchError, ProgramAccountSource, TransactionLogSource,
+};
+
+use crate::audit::{AllowlistEvidence, AuditInput};
+
+#[derive(Debug)]
+pub enum CollectError<A, L> {
+    Accounts(FetchError<A>),
+    Logs(L),
+}
+
+/// grant の receipt と、grant に触れた全トランザクションのイベントを古い順に集める
+/// allowlist / pop_signers は RPC からは決まらないため、呼び出し側が渡す
+/// hash_domain は Grant.hash_domain（close 済みの Grant でも検証できるよう、アカウントからは読まない）
+pub async fn collect_audit_input<A, L>(
+    accounts: &A,
+    logs: &L,
+    grant: Pubkey,
+    allowlist: AllowlistEvidence,
+    pop_signers: Vec<Pubkey>,
+    hash_domain: HashDomain,
+) -> Result<AuditInput, CollectError<A::Error, L::Error>>
+where
+    A: ProgramAccountSource,
+    L: TransactionLogSource,
+{
+    let receipts = fetch_receipts_by_grant(accounts, &grant).map_err(CollectError::Accounts)?;
+
+    // backfill_events はトランザクションを新しい順に流す（1トランザクション内のイベントは発行順）。
+    // トランザクション単位で順序を反転し、同じトランザクション内の順序は保つ
+    let mut transactions: Vec<Vec<EventRecord>> = Vec::new();
+    let mut stream = pin!(backfill_events(logs, grant, None));
+    while let Some(record) = stream.next().await {
+        let record = record.map_err(CollectError::Logs)?;
+        match transactions.last_mut() {
+            Some(transaction) if transaction[0].signature == record.signature => transaction.push(record),
+            _ => transactions.push(vec![record]),
+        }
+    }
+    let events = transactions.into_iter().rev().flatten().collect();
+
+    Ok(AuditInput {
+        grant,
+        receipts,
+        events,
+        allowlist,
+        pop_signers,
+        hash_domain,
+    })
+}
@@ -0,0 +1,16 @@
+// ===== we-ne grant program auditor =====
+// 外部の監査人が、運営側のデータベースに頼らずに受給の経路を検証するためのクレート。
+// - PopEntryRecorded から PoP ハッシュチェーンを再構成して検証する
+// - すべての receipt を PoP エントリ（bypass 中の受給は PopBypassClaimed）と allowlist の root に突き合わせる
+// - 結果を AuditReport にまとめ、監査人の鍵で署名する（SignedAuditReport）
+// 入力は RPC から collect_audit_input で集めるか、アーカイブ済みのアカウント / トランザクションのデータから
+// AuditInput を直接組み立てる（close 済みの receipt はアーカイブ側から補う）。
+// RPC クライアントと署名鍵には依存せず、呼び出し側が client のソース trait と ReportSigner を実装する。
+
+pub mod audit;
+pub mod collect;
+pub mod report;
+
+pub use audit::{audit_grant, AllowlistEvidence, AuditInput, AuditIssue};
+pub use collect::{collect_audit_input, CollectError};
+pub use report::{sign_report, AuditReport, ReportSigner, SignedAuditReport, AUDIT_REPORT_VERSION};
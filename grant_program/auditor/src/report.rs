@@ -0,0 +1,70 @@
+// ===== 監査レポートと署名 =====
+// AuditReport は Borsh で直列化して配布する（issues を含めて決定的なバイト列になる）。
+// 署名対象は signing_message() = sha256( "we-ne:audit-report" || borsh(report) ) の 32 bytes。
+// 受け取った側は任意の ed25519 実装で signer / signature を検証できる
+// （オンチェーンで確認する場合は Ed25519 プログラムの命令にそのまま渡せる）。
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+
+use crate::audit::AuditIssue;
+
+/// AuditReport のレイアウトの版（フィールドを変える場合に上げる）
+pub const AUDIT_REPORT_VERSION: u8 = 1;
+
+#[derive(Clone, Debug, PartialEq, Eq, AnchorSerialize, AnchorDeserialize)]
+pub struct AuditReport {
+    pub version: u8,
+    pub grant: Pubkey,
+    /// 検証した receipt の数（Reserved と他の Grant の receipt は含めない）
+    pub receipts_checked: u64,
+    pub pop_entries_checked: u64,
+    /// 最後の PopEntryRecorded の entry_hash（エントリなしは [0;32]。次回の監査との連結に使う）
+    pub pop_chain_head: [u8; 32],
+    /// 照合に使った allowlist の root（入力とイベントから集めたもの）
+    pub allowlist_roots: Vec<[u8; 32]>,
+    /// 入力に含まれる最新のイベントの slot（0 = イベントなし）
+    pub last_slot: u64,
+    pub issues: Vec<AuditIssue>,
+}
+
+impl AuditReport {
+    pub fn is_clean(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// 署名対象のダイジェスト
+    pub fn signing_message(&self) -> [u8; 32] {
+        let encoded = self.try_to_vec().expect("AuditReport serializes into a Vec");
+        hashv(&[b"we-ne:audit-report", &encoded]).to_bytes()
+    }
+}
+
+/// 監査人の署名鍵（ed25519。鍵の保管方法は呼び出し側に任せる）
+pub trait ReportSigner {
+    type Error;
+
+    fn pubkey(&self) -> Pubkey;
+
+    fn sign(&self, message: &[u8]) -> std::result::Result<[u8; 64], Self::Error>;
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, AnchorSerialize, AnchorDeserialize)]
+pub struct SignedAuditReport {
+    pub report: AuditReport,
+    pub signer: Pubkey,
+    /// signing_message() への ed25519 署名
+    pub signature: [u8; 64],
+}
+
+pub fn sign_report<S: ReportSigner>(
+    report: AuditReport,
+    signer: &S,
+) -> std::result::Result<SignedAuditReport, S::Error> {
+    let signature = signer.sign(&report.signing_message())?;
+    Ok(SignedAuditReport {
+        report,
+        signer: signer.pubkey(),
+        signature,
+    })
+}
@@ -0,0 +1,171 @@
+// ===== compute budget / priority fee の見積もり =====
+// 期間切り替え直後は claim が集中し、priority fee なし・既定の CU 上限のままだと
+// 混雑時に落ちやすい。ここでは simulate で実際の消費 CU を測って上限を詰め、
+// 直近の prioritization fee から単価を決めて ComputeBudget を埋める。
+// RPC 呼び出しは呼び出し側が BudgetSource として実装する（events.rs と同じ方針）。
+
+use std::future::Future;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::message::Message;
+
+use crate::claim::{claim_instructions_with_budget, ClaimParams, ComputeBudget, SignedPopMessage};
+use crate::pda;
+
+/// 1トランザクションあたりの CU 上限
+pub const MAX_COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+
+const BPS_DENOMINATOR: u64 = 10_000;
+
+/// simulate / getRecentPrioritizationFees を実行する RPC 層
+pub trait BudgetSource {
+    type Error;
+
+    /// 署名検証なし・blockhash 差し替えありで simulate した消費 CU
+    /// （RPC が unitsConsumed を返さなければ None。トランザクション自体の失敗は Err にする）
+    fn simulate_units_consumed(&self, message: &Message) -> impl Future<Output = Result<Option<u64>, Self::Error>>;
+
+    /// 指定アカウントを書き込むトランザクションの直近の prioritization fee（micro-lamports / CU）
+    fn recent_prioritization_fees(
+        &self,
+        writable_accounts: &[Pubkey],
+    ) -> impl Future<Output = Result<Vec<u64>, Self::Error>>;
+}
+
+/// CU 単価の決め方
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PriorityFeeStrategy {
+    /// 単価命令を付けない
+    None,
+    /// 固定の単価
+    Fixed(u64),
+    /// 直近の fee の percentile（0〜100）を min〜max に収める（fee が取れなければ min）
+    RecentPercentile { percentile: u8, min: u64, max: u64 },
+}
+
+/// 見積もりの設定
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BudgetOptions {
+    pub priority_fee: PriorityFeeStrategy,
+    /// simulate した消費 CU に上乗せする余裕（bps）
+    pub unit_margin_bps: u16,
+    /// simulate で消費 CU が取れなかった場合の上限
+    pub fallback_unit_limit: u32,
+}
+
+impl BudgetOptions {
+    /// 平常時: 直近 fee の中央値（上限 100_000）
+    pub fn standard() -> Self {
+        Self {
+            priority_fee: PriorityFeeStrategy::RecentPercentile {
+                percentile: 50,
+                min: 0,
+                max: 100_000,
+            },
+            unit_margin_bps: 1_000,
+            fallback_unit_limit: 200_000,
+        }
+    }
+
+    /// 期間切り替え直後の集中時: 直近 fee の p90、最低 10_000（上限 2_000_000）
+    pub fn period_rollover() -> Self {
+        Self {
+            priority_fee: PriorityFeeStrategy::RecentPercentile {
+                percentile: 90,
+                min: 10_000,
+                max: 2_000_000,
+            },
+            unit_margin_bps: 2_000,
+            fallback_unit_limit: 300_000,
+        }
+    }
+}
+
+impl Default for BudgetOptions {
+    fn default() -> Self {
+        Self::standard()
+    }
+}
+
+/// 消費 CU に margin を上乗せした上限（MAX_COMPUTE_UNIT_LIMIT で頭打ち）
+pub fn unit_limit_with_margin(units_consumed: u64, margin_bps: u16) -> u32 {
+    let limit = units_consumed
+        .saturating_mul(BPS_DENOMINATOR + margin_bps as u64)
+        .div_ceil(BPS_DENOMINATOR);
+    limit.min(MAX_COMPUTE_UNIT_LIMIT as u64) as u32
+}
+
+/// 戦略に従った CU 単価（None = 単価命令なし）
+pub fn priority_fee(strategy: PriorityFeeStrategy, recent_fees: &[u64]) -> Option<u64> {
+    match strategy {
+        PriorityFeeStrategy::None => None,
+        PriorityFeeStrategy::Fixed(price) => Some(price),
+        PriorityFeeStrategy::RecentPercentile { percentile, min, max } => {
+            if recent_fees.is_empty() {
+                return Some(min);
+            }
+            let mut sorted = recent_fees.to_vec();
+            sorted.sort_unstable();
+            let percentile = percentile.min(100) as usize;
+            let index = (sorted.len() - 1) * percentile / 100;
+            Some(sorted[index].clamp(min, max.max(min)))
+        }
+    }
+}
+
+/// 混雑の指標にする書き込みアカウント（期間切り替え時に全員が書く grant / vault / period_stats）
+fn contended_accounts(params: &ClaimParams) -> Vec<Pubkey> {
+    vec![
+        params.grant,
+        params.vault,
+        pda::period_stats(&params.grant, params.period_index).0,
+    ]
+}
+
+/// simulate と直近 fee から ComputeBudget を見積もる
+/// simulate は CU 上限を最大にし、単価命令なしで行う（単価は消費 CU に影響しない）
+pub async fn estimate_claim_compute_budget<S: BudgetSource>(
+    source: &S,
+    params: &ClaimParams,
+    proof: &SignedPopMessage,
+    payer: &Pubkey,
+    options: &BudgetOptions,
+) -> Result<ComputeBudget, S::Error> {
+    let probe = ComputeBudget {
+        unit_limit: Some(MAX_COMPUTE_UNIT_LIMIT),
+        unit_price_micro_lamports: None,
+    };
+    let message = Message::new(&claim_instructions_with_budget(params, Some(proof), probe), Some(payer));
+    let unit_limit = match source.simulate_units_consumed(&message).await? {
+        Some(units) => unit_limit_with_margin(units, options.unit_margin_bps),
+        None => options.fallback_unit_limit,
+    };
+
+    let unit_price_micro_lamports = match options.priority_fee {
+        PriorityFeeStrategy::RecentPercentile { .. } => {
+            let recent_fees = source
+                .recent_prioritization_fees(&contended_accounts(params))
+                .await?;
+            priority_fee(options.priority_fee, &recent_fees)
+        }
+        strategy => priority_fee(strategy, &[]),
+    };
+
+    Ok(ComputeBudget {
+        unit_limit: Some(unit_limit),
+        unit_price_micro_lamports,
+    })
+}
+
+/// 見積もった ComputeBudget で組んだ未署名の legacy message（params.compute_budget は無視する）
+pub async fn build_budgeted_claim_transaction<S: BudgetSource>(
+    source: &S,
+    params: &ClaimParams,
+    proof: &SignedPopMessage,
+    payer: &Pubkey,
+    options: &BudgetOptions,
+) -> Result<(Message, ComputeBudget), S::Error> {
+    let budget = estimate_claim_compute_budget(source, params, proof, payer, options).await?;
+    let message = Message::new(&claim_instructions_with_budget(params, Some(proof), budget), Some(payer));
+    Ok((message, budget))
+}
@@ -0,0 +1,495 @@
+// ===== claim トランザクション構築 =====
+// claim 系命令は直前（current_index - 1）の ed25519 precompile 命令から PoP 署名を読む。
+// プログラム側のパーサは以下のみを受け付けるため、手組みせずにこのモジュールを使うこと:
+//   - 署名数 1
+//   - signature / public key / message がすべて同一命令内（instruction_index = u16::MAX）
+//   - ed25519 命令が claim 命令の直前に置かれている
+// claim intent（受給者が署名しない relayer 経由の claim）では、受給者の intent 署名の ed25519 命令を
+// PoP の ed25519 命令の直前に置く（PoP bypass 中は claim 命令の直前）。
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::{
+    ed25519_program,
+    instruction::{AccountMeta, Instruction},
+    message::Message,
+    pubkey,
+    sysvar,
+};
+use anchor_lang::{InstructionData, ToAccountMetas};
+
+use crate::pda;
+
+const COMPUTE_BUDGET_PROGRAM_ID: Pubkey = pubkey!("ComputeBudget111111111111111111111111111111");
+const COMPUTE_BUDGET_SET_UNIT_LIMIT: u8 = 2;
+const COMPUTE_BUDGET_SET_UNIT_PRICE: u8 = 3;
+
+/// ed25519 命令ヘッダ（署名数 + padding + offsets 1件）
+const ED25519_HEADER_LEN: usize = 2 + 14;
+const ED25519_PUBKEY_LEN: usize = 32;
+const ED25519_SIGNATURE_LEN: usize = 64;
+
+/// 受給者が署名した claim intent（claim_intent_message を ed25519 で署名したもの）
+/// relayer が claimer アカウントとして署名・rent を負担し、受給者はトランザクションに署名しない
+pub struct SignedClaimIntent {
+    /// claim を送る署名者（intent の relayer を default にした場合も必要）
+    pub relayer: Pubkey,
+    pub message: Vec<u8>,
+    pub signature: [u8; 64],
+}
+
+/// 受給者が署名する claim intent のメッセージ
+/// relayer: Pubkey::default() なら任意の relayer が送れる。expires_at: unix 秒（この時刻以降は無効）
+pub fn claim_intent_message(
+    grant: &Pubkey,
+    claimer: &Pubkey,
+    period_index: u64,
+    relayer: &Pubkey,
+    expires_at: i64,
+) -> Vec<u8> {
+    let mut message = Vec::with_capacity(grant_program::CLAIM_INTENT_LEN);
+    message.extend_from_slice(grant_program::CLAIM_INTENT_DOMAIN);
+    message.extend_from_slice(grant.as_ref());
+    message.extend_from_slice(claimer.as_ref());
+    message.extend_from_slice(&period_index.to_le_bytes());
+    message.extend_from_slice(relayer.as_ref());
+    message.extend_from_slice(&expires_at.to_le_bytes());
+    message
+}
+
+/// PoP signer が署名した claim 用メッセージ（v2 レイアウト、支給額を指定する場合は v3 レイアウト）
+pub struct SignedPopMessage {
+    pub signer: Pubkey,
+    pub message: Vec<u8>,
+    pub signature: [u8; 64],
+}
+
+impl SignedPopMessage {
+    /// プログラムが受け付ける v2 / v3 レイアウト（バージョン + 長さ）か
+    pub fn has_supported_layout(&self) -> bool {
+        match self.message.first() {
+            Some(&grant_program::POP_MESSAGE_VERSION_V2) => self.message.len() == grant_program::POP_MESSAGE_LEN_V2,
+            Some(&grant_program::POP_MESSAGE_VERSION_V3) => self.message.len() == grant_program::POP_MESSAGE_LEN_V3,
+            _ => false,
+        }
+    }
+}
+
+/// 末尾の optional アカウント（Grant の設定に応じて渡す）
+#[derive(Default)]
+pub struct ClaimOptionalAccounts {
+    pub rate_config: Option<Pubkey>,
+    pub gate_attestation: Option<Pubkey>,
+    pub platform_fee_account: Option<Pubkey>,
+    pub claim_streak: Option<Pubkey>,
+    pub escrow_schedule: Option<Pubkey>,
+    /// TreasuryConfig.treasury（claim 手数料が設定されている場合）
+    pub treasury: Option<Pubkey>,
+    /// claim 手数料を立て替える relayer（署名者になる。省略時は claimer が支払う）
+    pub fee_payer: Option<Pubkey>,
+    /// 受給者の StatementLog（pda::statement_log。作成済みの場合のみ）
+    pub statement_log: Option<Pubkey>,
+    /// grant.attestation_program と、そのプログラムが決める発行先（受給アテステーションが有効な場合）
+    pub attestation: Option<(Pubkey, Pubkey)>,
+    /// 受給者が保有する grant.holding_mint のトークンアカウント（受給資格の述語に token-holding がある場合）
+    pub holding_token_account: Option<Pubkey>,
+    /// grant.enrollment_grant（受給資格の述語に enrollment がある場合。受給者の ClaimerProfile を渡す）
+    pub enrollment_grant: Option<Pubkey>,
+    /// grant.mint（凍結された受給先を claim 時に解凍する場合。mint の freeze authority が Grant PDA であること）
+    pub mint: Option<Pubkey>,
+    /// 受給者が opt_out_canonical_destination 済みで、claimer_ata に ATA 以外を使う場合 true
+    pub destination_opt_out: bool,
+    /// open_budget_forecast 済みの Grant の場合 true（claim ごとに BudgetForecast が再計算される）
+    pub budget_forecast: bool,
+    /// 受給者が open_claim_index 済みの場合 true（今回の期間が ClaimIndex に記録される）
+    pub claim_index: bool,
+    /// grant.stake_program が所有する受給者のステーク記録（ステーク期間の倍率を受ける場合）
+    pub stake_record: Option<Pubkey>,
+    /// 受給者宛ての ClaimerMemo がある場合 true（未配信なら claim で ClaimerMemoDelivered として配信される）
+    pub claimer_memo: bool,
+    /// TreasuryConfig.fee_splits の受取先（fee_splits と同じ順。remaining_accounts として末尾に付く）
+    pub fee_recipients: Vec<Pubkey>,
+}
+
+/// None の場合は compute budget 命令を付けない
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ComputeBudget {
+    pub unit_limit: Option<u32>,
+    pub unit_price_micro_lamports: Option<u64>,
+}
+
+pub struct ClaimParams {
+    pub grant: Pubkey,
+    /// grant.authority（PoP 設定は authority 単位）
+    pub authority: Pubkey,
+    /// grant.seed_salt（receipt / pop_state の seed。set_seed_salt 未実行なら [0; SEED_SALT_LEN]）
+    pub seed_salt: [u8; grant_program::SEED_SALT_LEN],
+    /// grant.vault（mint 切り替え・rotate_vault 後は pda::vault(grant) と異なる）
+    pub vault: Pubkey,
+    /// 受給者（session_key を使う場合も受給者本人のアドレス）
+    pub claimer: Pubkey,
+    pub claimer_ata: Pubkey,
+    pub period_index: u64,
+    /// authorize_session で登録した session key（指定時は claimer の代わりに署名し、rent を支払う）
+    pub session_key: Option<Pubkey>,
+    /// 受給者が署名した claim intent（指定時は relayer が claimer の代わりに署名し、rent を支払う）
+    pub claim_intent: Option<SignedClaimIntent>,
+    /// schedule_claim で予約された claim を実行する keeper（指定時は keeper が claimer の代わりに署名し、rent を支払う）
+    pub scheduled_keeper: Option<Pubkey>,
+    /// allowlist が有効な Grant の場合の Merkle proof（claim_grant_with_proof を使う）
+    pub allowlist_proof: Option<Vec<[u8; 32]>>,
+    pub optional: ClaimOptionalAccounts,
+    pub compute_budget: ComputeBudget,
+}
+
+/// プログラムのパーサが期待するレイアウトの ed25519 precompile 命令
+/// data = [num_signatures=1, padding, offsets(14 bytes), pubkey, signature, message]
+pub fn ed25519_pop_instruction(proof: &SignedPopMessage) -> Instruction {
+    ed25519_instruction(&proof.signer, &proof.message, &proof.signature)
+}
+
+/// claim intent の ed25519 命令（claimer = 受給者、intent の署名者）
+pub fn ed25519_claim_intent_instruction(claimer: &Pubkey, intent: &SignedClaimIntent) -> Instruction {
+    ed25519_instruction(claimer, &intent.message, &intent.signature)
+}
+
+fn ed25519_instruction(signer: &Pubkey, message: &[u8], signature: &[u8; 64]) -> Instruction {
+    let public_key_offset = ED25519_HEADER_LEN;
+    let signature_offset = public_key_offset + ED25519_PUBKEY_LEN;
+    let message_offset = signature_offset + ED25519_SIGNATURE_LEN;
+
+    let mut data = Vec::with_capacity(message_offset + message.len());
+    data.push(1u8);
+    data.push(0u8);
+    for value in [
+        signature_offset as u16,
+        u16::MAX,
+        public_key_offset as u16,
+        u16::MAX,
+        message_offset as u16,
+        message.len() as u16,
+        u16::MAX,
+    ] {
+        data.extend_from_slice(&value.to_le_bytes());
+    }
+    data.extend_from_slice(signer.as_ref());
+    data.extend_from_slice(signature);
+    data.extend_from_slice(message);
+
+    Instruction {
+        program_id: ed25519_program::ID,
+        accounts: vec![],
+        data,
+    }
+}
+
+fn compute_budget_instructions(budget: ComputeBudget) -> Vec<Instruction> {
+    let mut ixs = Vec::new();
+    if let Some(units) = budget.unit_limit {
+        let mut data = vec![COMPUTE_BUDGET_SET_UNIT_LIMIT];
+        data.extend_from_slice(&units.to_le_bytes());
+        ixs.push(Instruction {
+            program_id: COMPUTE_BUDGET_PROGRAM_ID,
+            accounts: vec![],
+            data,
+        });
+    }
+    if let Some(price) = budget.unit_price_micro_lamports {
+        let mut data = vec![COMPUTE_BUDGET_SET_UNIT_PRICE];
+        data.extend_from_slice(&price.to_le_bytes());
+        ixs.push(Instruction {
+            program_id: COMPUTE_BUDGET_PROGRAM_ID,
+            accounts: vec![],
+            data,
+        });
+    }
+    ixs
+}
+
+fn claim_account_metas(params: &ClaimParams) -> Vec<AccountMeta> {
+    let (receipt, _) = pda::receipt(&params.grant, &params.claimer, params.period_index, &params.seed_salt);
+    let (pop_state, _) = pda::pop_state(&params.grant, &params.seed_salt);
+    let (pop_config, _) = pda::pop_config(&params.authority);
+    let (claimer_profile, _) = pda::claimer_profile(&params.grant, &params.claimer);
+    let (period_stats, _) = pda::period_stats(&params.grant, params.period_index);
+    let (treasury_config, _) = pda::treasury_config();
+
+    grant_program::accounts::ClaimGrant {
+        grant: params.grant,
+        vault: params.vault,
+        claimer: params
+            .session_key
+            .or(params.claim_intent.as_ref().map(|intent| intent.relayer))
+            .or(params.scheduled_keeper)
+            .unwrap_or(params.claimer),
+        claimer_ata: params.claimer_ata,
+        receipt,
+        pop_state,
+        pop_config,
+        instructions_sysvar: sysvar::instructions::ID,
+        token_program: anchor_spl::token::ID,
+        system_program: anchor_lang::system_program::ID,
+        rent: sysvar::rent::ID,
+        claimer_profile,
+        period_stats,
+        treasury_config,
+        rate_config: params.optional.rate_config,
+        gate_attestation: params.optional.gate_attestation,
+        platform_fee_account: params.optional.platform_fee_account,
+        claim_streak: params.optional.claim_streak,
+        escrow_schedule: params.optional.escrow_schedule,
+        treasury: params.optional.treasury,
+        fee_payer: params.optional.fee_payer,
+        session_authorization: params
+            .session_key
+            .map(|_| pda::session_authorization(&params.grant, &params.claimer).0),
+        statement_log: params.optional.statement_log,
+        intent_claimer: params.claim_intent.as_ref().map(|_| params.claimer),
+        pending_claim: params
+            .scheduled_keeper
+            .map(|_| pda::pending_claim(&params.grant, &params.claimer, params.period_index).0),
+        attestation_program: params.optional.attestation.map(|(program, _)| program),
+        attestation_record: params.optional.attestation.map(|(_, record)| record),
+        holding_token_account: params.optional.holding_token_account,
+        enrollment_profile: params
+            .optional
+            .enrollment_grant
+            .map(|enrollment_grant| pda::claimer_profile(&enrollment_grant, &params.claimer).0),
+        mint: params.optional.mint,
+        destination_opt_out: params
+            .optional
+            .destination_opt_out
+            .then(|| pda::destination_opt_out(&params.grant, &params.claimer).0),
+        budget_forecast: params
+            .optional
+            .budget_forecast
+            .then(|| pda::budget_forecast(&params.grant).0),
+        claim_index: params
+            .optional
+            .claim_index
+            .then(|| pda::claim_index(&params.grant, &params.claimer).0),
+        stake_record: params.optional.stake_record,
+        claimer_memo: params
+            .optional
+            .claimer_memo
+            .then(|| pda::claimer_memo(&params.grant, &params.claimer).0),
+    }
+    .to_account_metas(None)
+    .into_iter()
+    .chain(
+        params
+            .optional
+            .fee_recipients
+            .iter()
+            .map(|recipient| AccountMeta::new(*recipient, false)),
+    )
+    .collect()
+}
+
+/// compute budget → (claim intent の ed25519) → PoP の ed25519 → claim の順に並べた命令列
+/// PoP の ed25519 命令は必ず claim 命令の直前に置かれる
+pub fn build_claim_instructions(params: &ClaimParams, proof: &SignedPopMessage) -> Vec<Instruction> {
+    claim_instructions_with_budget(params, Some(proof), params.compute_budget)
+}
+
+/// set_pop_bypass の緊急期間中の claim（ed25519 命令を付けない）
+/// 期間外に送ると PoP 署名の欠落で失敗する
+pub fn build_pop_bypass_claim_instructions(params: &ClaimParams) -> Vec<Instruction> {
+    claim_instructions_with_budget(params, None, params.compute_budget)
+}
+
+/// params.compute_budget の代わりに budget を使う（budget.rs の見積もり用）
+/// proof が None の場合は ed25519 命令を付けない（PoP bypass 期間中）
+pub(crate) fn claim_instructions_with_budget(
+    params: &ClaimParams,
+    proof: Option<&SignedPopMessage>,
+    budget: ComputeBudget,
+) -> Vec<Instruction> {
+    let data = match params.allowlist_proof.as_ref() {
+        Some(allowlist_proof) => grant_program::instruction::ClaimGrantWithProof {
+            period_index: params.period_index,
+            proof: allowlist_proof.clone(),
+        }
+        .data(),
+        None => grant_program::instruction::ClaimGrant {
+            period_index: params.period_index,
+        }
+        .data(),
+    };
+    let claim_ix = Instruction {
+        program_id: grant_program::ID,
+        accounts: claim_account_metas(params),
+        data,
+    };
+
+    let mut ixs = compute_budget_instructions(budget);
+    ixs.extend(
+        params
+            .claim_intent
+            .as_ref()
+            .map(|intent| ed25519_claim_intent_instruction(&params.claimer, intent)),
+    );
+    ixs.extend(proof.map(ed25519_pop_instruction));
+    ixs.push(claim_ix);
+    ixs
+}
+
+/// fee payer を指定した未署名の legacy message（claimer / session key / relayer / keeper の署名が必要）
+pub fn build_claim_transaction(
+    params: &ClaimParams,
+    proof: &SignedPopMessage,
+    payer: &Pubkey,
+) -> Message {
+    Message::new(&build_claim_instructions(params, proof), Some(payer))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(n: u8) -> Pubkey {
+        Pubkey::new_from_array([n; 32])
+    }
+
+    fn pop_message() -> SignedPopMessage {
+        let mut message = vec![0u8; grant_program::POP_MESSAGE_LEN_V2];
+        message[0] = grant_program::POP_MESSAGE_VERSION_V2;
+        message[1..].iter_mut().enumerate().for_each(|(i, byte)| *byte = i as u8);
+        SignedPopMessage {
+            signer: key(9),
+            message,
+            signature: [0xab; 64],
+        }
+    }
+
+    fn params() -> ClaimParams {
+        ClaimParams {
+            grant: key(1),
+            authority: key(2),
+            seed_salt: [5; grant_program::SEED_SALT_LEN],
+            vault: key(3),
+            claimer: key(4),
+            claimer_ata: key(6),
+            period_index: 7,
+            session_key: None,
+            claim_intent: None,
+            scheduled_keeper: None,
+            allowlist_proof: None,
+            optional: ClaimOptionalAccounts::default(),
+            compute_budget: ComputeBudget::default(),
+        }
+    }
+
+    fn read_u16(data: &[u8], offset: usize) -> u16 {
+        u16::from_le_bytes([data[offset], data[offset + 1]])
+    }
+
+    /// プログラムのパーサ（extract_ed25519_signer_and_message）と同じ規則で signer / signature / message を読む
+    fn parse_inline_ed25519(ix: &Instruction) -> (Pubkey, [u8; 64], Vec<u8>) {
+        let data = ix.data.as_slice();
+        assert_eq!(ix.program_id, ed25519_program::ID);
+        assert!(ix.accounts.is_empty());
+        assert_eq!(data[0], 1, "署名数は1");
+        for index_offset in [4, 8, 14] {
+            assert_eq!(read_u16(data, index_offset), u16::MAX, "同一命令内を指す");
+        }
+        let signature_offset = read_u16(data, 2) as usize;
+        let public_key_offset = read_u16(data, 6) as usize;
+        let message_offset = read_u16(data, 10) as usize;
+        let message_len = read_u16(data, 12) as usize;
+        assert_eq!(message_offset + message_len, data.len());
+        (
+            Pubkey::try_from(&data[public_key_offset..public_key_offset + 32]).unwrap(),
+            data[signature_offset..signature_offset + 64].try_into().unwrap(),
+            data[message_offset..].to_vec(),
+        )
+    }
+
+    #[test]
+    fn ed25519_pop_instruction_inlines_signer_signature_and_message() {
+        let proof = pop_message();
+        assert!(proof.has_supported_layout());
+        let (signer, signature, message) = parse_inline_ed25519(&ed25519_pop_instruction(&proof));
+        assert_eq!(signer, proof.signer);
+        assert_eq!(signature, proof.signature);
+        assert_eq!(message, proof.message);
+    }
+
+    #[test]
+    fn has_supported_layout_checks_version_and_length() {
+        let mut proof = pop_message();
+        proof.message.pop();
+        assert!(!proof.has_supported_layout());
+        proof.message = vec![grant_program::POP_MESSAGE_VERSION_V3; grant_program::POP_MESSAGE_LEN_V3];
+        assert!(proof.has_supported_layout());
+        proof.message[0] = 0;
+        assert!(!proof.has_supported_layout());
+    }
+
+    #[test]
+    fn pop_ed25519_is_placed_right_before_claim() {
+        let mut params = params();
+        params.compute_budget = ComputeBudget {
+            unit_limit: Some(300_000),
+            unit_price_micro_lamports: Some(10),
+        };
+        let ixs = build_claim_instructions(&params, &pop_message());
+        let programs: Vec<Pubkey> = ixs.iter().map(|ix| ix.program_id).collect();
+        assert_eq!(
+            programs,
+            [COMPUTE_BUDGET_PROGRAM_ID, COMPUTE_BUDGET_PROGRAM_ID, ed25519_program::ID, grant_program::ID]
+        );
+        assert_eq!(ixs[0].data, [&[COMPUTE_BUDGET_SET_UNIT_LIMIT][..], &300_000u32.to_le_bytes()].concat());
+        assert_eq!(ixs[1].data, [&[COMPUTE_BUDGET_SET_UNIT_PRICE][..], &10u64.to_le_bytes()].concat());
+        assert_eq!(ixs[3].data, grant_program::instruction::ClaimGrant { period_index: 7 }.data());
+
+        let bypass = build_pop_bypass_claim_instructions(&params);
+        assert!(bypass.iter().all(|ix| ix.program_id != ed25519_program::ID));
+        assert_eq!(bypass.last().unwrap().program_id, grant_program::ID);
+    }
+
+    #[test]
+    fn claim_intent_ed25519_precedes_pop_ed25519() {
+        let mut params = params();
+        let message = claim_intent_message(&params.grant, &params.claimer, 7, &key(8), 1_700_000_000);
+        assert_eq!(message.len(), grant_program::CLAIM_INTENT_LEN);
+        params.claim_intent = Some(SignedClaimIntent {
+            relayer: key(8),
+            message: message.clone(),
+            signature: [0xcd; 64],
+        });
+        let ixs = build_claim_instructions(&params, &pop_message());
+        assert_eq!(ixs.len(), 3);
+        let (intent_signer, _, intent_message) = parse_inline_ed25519(&ixs[0]);
+        assert_eq!((intent_signer, intent_message), (params.claimer, message));
+        assert_eq!(parse_inline_ed25519(&ixs[1]).0, key(9));
+
+        // relayer が署名者になり、受給者本人は intent_claimer として渡る
+        let claimer_meta = &ixs[2].accounts[2];
+        assert_eq!(claimer_meta.pubkey, key(8));
+        assert!(claimer_meta.is_signer);
+        assert!(ixs[2].accounts.iter().any(|meta| meta.pubkey == params.claimer && !meta.is_signer));
+    }
+
+    #[test]
+    fn claim_uses_salted_receipt_and_appends_fee_recipients() {
+        let mut params = params();
+        params.allowlist_proof = Some(vec![[1; 32]]);
+        params.optional.fee_recipients = vec![key(20), key(21)];
+        let ixs = build_claim_instructions(&params, &pop_message());
+        let claim = ixs.last().unwrap();
+        assert_eq!(
+            claim.data,
+            grant_program::instruction::ClaimGrantWithProof {
+                period_index: 7,
+                proof: vec![[1; 32]],
+            }
+            .data()
+        );
+        let (receipt, _) = grant_program::receipt::receipt_address(&params.grant, &params.claimer, 7, &params.seed_salt);
+        let (unsalted, _) = pda::receipt(&params.grant, &params.claimer, 7, &[0; grant_program::SEED_SALT_LEN]);
+        assert_ne!(receipt, unsalted);
+        assert_eq!(claim.accounts[4].pubkey, receipt);
+        let tail: Vec<Pubkey> = claim.accounts[claim.accounts.len() - 2..].iter().map(|meta| meta.pubkey).collect();
+        assert_eq!(tail, [key(20), key(21)]);
+    }
+}
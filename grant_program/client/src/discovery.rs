@@ -0,0 +1,106 @@
+// ===== 受給できる Grant の一覧 =====
+// 「このウォレットが今 claim できる Grant はどれか」を、Grant の列挙・受給者の receipt・allowlist の proof から
+// 1回の呼び出しで求める。判定は get_claim_status と同じ Grant::claim_availability を使う。
+// - Grant は GrantScope の範囲を getProgramAccounts で列挙する
+// - receipt は claimer の全 receipt を1回の getProgramAccounts で取得する（Grant ごとに問い合わせない）
+// - proof は allowlist のある Grant についてだけ AllowlistProofSource に問い合わせる
+// PoP・attestation gate・vault 残高は見ない（claim 時に確認される）。
+
+use std::collections::HashSet;
+
+use anchor_lang::prelude::Pubkey;
+use grant_program::{ClaimAvailability, Grant, ReceiptStatus};
+
+use crate::fetch::{
+    fetch_all_grants, fetch_grants_by_authority, fetch_grants_by_category, fetch_receipts_by_claimer, FetchError,
+    ProgramAccountSource,
+};
+
+/// allowlist の proof を返す API（proof API のクライアントなど。呼び出し側が実装する）
+pub trait AllowlistProofSource {
+    type Error;
+
+    /// grant の allowlist に claimer が含まれていれば SortedPair proof を返す（含まれなければ None）
+    fn allowlist_proof(&self, grant: &Pubkey, claimer: &Pubkey) -> Result<Option<Vec<[u8; 32]>>, Self::Error>;
+}
+
+/// 列挙する Grant の範囲
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GrantScope {
+    Authority(Pubkey),
+    Category(u16),
+    All,
+}
+
+#[derive(Debug)]
+pub enum DiscoveryError<A, P> {
+    Accounts(FetchError<A>),
+    Proofs(P),
+}
+
+pub struct GrantAvailability {
+    pub address: Pubkey,
+    pub grant: Grant,
+    /// 判定に使った現在の期間（claim の period_index にそのまま使える）
+    pub period_index: u64,
+    pub availability: ClaimAvailability,
+    /// claim_grant_with_proof に渡す proof（allowlist のない Grant、proof を入手できなかった場合は None）
+    pub allowlist_proof: Option<Vec<[u8; 32]>>,
+}
+
+impl GrantAvailability {
+    pub fn is_claimable(&self) -> bool {
+        self.availability == ClaimAvailability::Claimable
+    }
+}
+
+/// scope の各 Grant について、claimer が now（クラスタの時刻）に claim できるかを返す（列挙した順）
+/// claim できない Grant も理由付きで含める（is_claimable で絞り込む）
+pub fn discover_claimable_grants<A, P>(
+    accounts: &A,
+    proofs: &P,
+    scope: GrantScope,
+    claimer: &Pubkey,
+    now: i64,
+) -> Result<Vec<GrantAvailability>, DiscoveryError<A::Error, P::Error>>
+where
+    A: ProgramAccountSource,
+    P: AllowlistProofSource,
+{
+    let grants = match scope {
+        GrantScope::Authority(authority) => fetch_grants_by_authority(accounts, &authority),
+        GrantScope::Category(category) => fetch_grants_by_category(accounts, category),
+        GrantScope::All => fetch_all_grants(accounts),
+    }
+    .map_err(DiscoveryError::Accounts)?;
+    let claimed: HashSet<(Pubkey, u64)> = fetch_receipts_by_claimer(accounts, claimer)
+        .map_err(DiscoveryError::Accounts)?
+        .into_iter()
+        .filter(|(_, receipt)| receipt.status != ReceiptStatus::Reserved)
+        .map(|(_, receipt)| (receipt.grant, receipt.period_index))
+        .collect();
+
+    let mut results = Vec::with_capacity(grants.len());
+    for (address, grant) in grants {
+        let Ok(period_index) = grant.period_index_at(now) else {
+            continue;
+        };
+        let is_claimed = claimed.contains(&(address, period_index));
+        let allowlist_proof = if grant.merkle_root != [0u8; 32] && !is_claimed {
+            proofs
+                .allowlist_proof(&address, claimer)
+                .map_err(DiscoveryError::Proofs)?
+        } else {
+            None
+        };
+        let availability = grant.claim_availability(claimer, now, is_claimed, allowlist_proof.as_deref());
+        results.push(GrantAvailability {
+            address,
+            grant,
+            period_index,
+            availability,
+            allowlist_proof,
+        });
+    }
+    Ok(results)
+}
@@ -0,0 +1,538 @@
+// ===== イベント購読・デコード =====
+// emit! は "Program data: <base64(discriminator + borsh)>" をログに出す。
+// CPI で他プログラムのログが混ざるため、invoke / success 行から呼び出しスタックを追い、
+// grant_program が実行中の行だけをデコードする。
+// RPC 層には依存せず、過去分は TransactionLogSource（getSignaturesForAddress + getTransaction）、
+// リアルタイム分は logsSubscribe の通知ストリームを呼び出し側が渡す。
+
+use std::collections::{HashSet, VecDeque};
+use std::future::Future;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::{AnchorDeserialize, Discriminator};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use futures::stream::{self, Stream, StreamExt};
+use grant_program::{
+    AdminActionExecuted, AllowlistDeltaAppended, AllowlistRootCommitted, AuditBatchAnchored,
+    AuthorityRecovered, AuthoritySucceeded, ChildStatsRolledUp, ClaimReturned, ClaimScheduled,
+    ClaimerMemoAttached, ClaimerMemoDelivered, DEFAULT_HASH_DOMAIN, DelegateIndexUpdated,
+    DeploymentInitialized, DestinationOptOutChanged, DisbursementAttested, Discrepancy,
+    FundedFromBudget, FundedWithSwap, GrantActivated, GrantArchived, GrantClosed,
+    HistoricalClaimRecorded, KeeperBountyPaid, KpiMilestoneReached, KpiProgress, MatchApplied,
+    MatchPoolExhausted, MintMigrated, MintMigrationInitiated, NotificationPreferenceChanged,
+    OfficerWithdrawal, PeriodClosed, PopAmountAuthorized, PopBypassClaimed, PopBypassSet,
+    PopEntryRecorded, PopSignerHeartbeat, PopSignerSilent, ProgramOfficerRemoved,
+    ProgramOfficerUpdated, ReceiptNftMinted, ReceiptStatusChanged, ReceiptsArchived,
+    ReceiptsReserved, RecoveryCancelled, RecoveryInitiated, ScheduledClaimCancelled,
+    ScheduledClaimExecuted, SeasonStarted, SessionAuthorized, SessionRevoked, SponsoredClaim,
+    SuccessionAnnounced, SuccessionCancelled, VaultRotated, WindDownStarted,
+};
+
+const PROGRAM_DATA_PREFIX: &str = "Program data: ";
+const DISCRIMINATOR_LEN: usize = 8;
+/// getSignaturesForAddress の1ページあたりの最大件数
+const SIGNATURES_PAGE_LIMIT: usize = 1000;
+
+/// grant_program が発行する全イベント
+pub enum GrantEvent {
+    GrantClosed(GrantClosed),
+    GrantActivated(GrantActivated),
+    RecoveryInitiated(RecoveryInitiated),
+    RecoveryCancelled(RecoveryCancelled),
+    AuthorityRecovered(AuthorityRecovered),
+    Discrepancy(Discrepancy),
+    MatchApplied(MatchApplied),
+    MatchPoolExhausted(MatchPoolExhausted),
+    PeriodClosed(PeriodClosed),
+    SeasonStarted(SeasonStarted),
+    MintMigrationInitiated(MintMigrationInitiated),
+    MintMigrated(MintMigrated),
+    VaultRotated(VaultRotated),
+    PopEntryRecorded(PopEntryRecorded),
+    AuditBatchAnchored(AuditBatchAnchored),
+    ReceiptStatusChanged(ReceiptStatusChanged),
+    ClaimReturned(ClaimReturned),
+    AllowlistDeltaAppended(AllowlistDeltaAppended),
+    AllowlistRootCommitted(AllowlistRootCommitted),
+    KeeperBountyPaid(KeeperBountyPaid),
+    SessionAuthorized(SessionAuthorized),
+    SessionRevoked(SessionRevoked),
+    ChildStatsRolledUp(ChildStatsRolledUp),
+    FundedFromBudget(FundedFromBudget),
+    WindDownStarted(WindDownStarted),
+    SponsoredClaim(SponsoredClaim),
+    PopSignerHeartbeat(PopSignerHeartbeat),
+    PopSignerSilent(PopSignerSilent),
+    PopBypassSet(PopBypassSet),
+    PopBypassClaimed(PopBypassClaimed),
+    AdminActionExecuted(AdminActionExecuted),
+    ClaimScheduled(ClaimScheduled),
+    ScheduledClaimExecuted(ScheduledClaimExecuted),
+    ScheduledClaimCancelled(ScheduledClaimCancelled),
+    DisbursementAttested(DisbursementAttested),
+    KpiProgress(KpiProgress),
+    KpiMilestoneReached(KpiMilestoneReached),
+    SuccessionAnnounced(SuccessionAnnounced),
+    SuccessionCancelled(SuccessionCancelled),
+    AuthoritySucceeded(AuthoritySucceeded),
+    ProgramOfficerUpdated(ProgramOfficerUpdated),
+    ProgramOfficerRemoved(ProgramOfficerRemoved),
+    OfficerWithdrawal(OfficerWithdrawal),
+    DelegateIndexUpdated(DelegateIndexUpdated),
+    DestinationOptOutChanged(DestinationOptOutChanged),
+    ReceiptsReserved(ReceiptsReserved),
+    ReceiptsArchived(ReceiptsArchived),
+    GrantArchived(GrantArchived),
+    FundedWithSwap(FundedWithSwap),
+    NotificationPreferenceChanged(NotificationPreferenceChanged),
+    PopAmountAuthorized(PopAmountAuthorized),
+    ClaimerMemoAttached(ClaimerMemoAttached),
+    ClaimerMemoDelivered(ClaimerMemoDelivered),
+    DeploymentInitialized(DeploymentInitialized),
+    ReceiptNftMinted(ReceiptNftMinted),
+    HistoricalClaimRecorded(HistoricalClaimRecorded),
+}
+
+/// イベントと、それを含むトランザクション
+pub struct EventRecord {
+    pub signature: String,
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    pub event: GrantEvent,
+}
+
+/// getSignaturesForAddress の1件
+#[derive(Clone, Debug)]
+pub struct SignatureInfo {
+    pub signature: String,
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    /// トランザクションが失敗している（イベントはロールバック済みなので読まない）
+    pub failed: bool,
+}
+
+/// logsSubscribe の通知1件
+#[derive(Clone, Debug)]
+pub struct LogNotification {
+    pub signature: String,
+    pub slot: u64,
+    pub failed: bool,
+    pub logs: Vec<String>,
+}
+
+/// 過去分のログを取得する RPC 層
+pub trait TransactionLogSource {
+    type Error;
+
+    /// 新しい順。before より古く、until より新しい署名を最大 limit 件
+    fn signatures_for_address(
+        &self,
+        address: &Pubkey,
+        before: Option<&str>,
+        until: Option<&str>,
+        limit: usize,
+    ) -> impl Future<Output = Result<Vec<SignatureInfo>, Self::Error>>;
+
+    /// meta.logMessages（トランザクションが見つからなければ None）
+    fn transaction_logs(&self, signature: &str) -> impl Future<Output = Result<Option<Vec<String>>, Self::Error>>;
+}
+
+fn decode_as<T: AnchorDeserialize>(body: &[u8]) -> Option<T> {
+    T::deserialize(&mut &body[..]).ok()
+}
+
+/// discriminator + borsh を型付きイベントにする（未知の discriminator は None）
+pub fn decode_event(data: &[u8]) -> Option<GrantEvent> {
+    if data.len() < DISCRIMINATOR_LEN {
+        return None;
+    }
+    let (discriminator, body) = data.split_at(DISCRIMINATOR_LEN);
+    match discriminator {
+        d if d == GrantClosed::DISCRIMINATOR => decode_as(body).map(GrantEvent::GrantClosed),
+        d if d == GrantActivated::DISCRIMINATOR => decode_as(body).map(GrantEvent::GrantActivated),
+        d if d == RecoveryInitiated::DISCRIMINATOR => decode_as(body).map(GrantEvent::RecoveryInitiated),
+        d if d == RecoveryCancelled::DISCRIMINATOR => decode_as(body).map(GrantEvent::RecoveryCancelled),
+        d if d == AuthorityRecovered::DISCRIMINATOR => decode_as(body).map(GrantEvent::AuthorityRecovered),
+        d if d == Discrepancy::DISCRIMINATOR => decode_as(body).map(GrantEvent::Discrepancy),
+        d if d == MatchApplied::DISCRIMINATOR => decode_as(body).map(GrantEvent::MatchApplied),
+        d if d == MatchPoolExhausted::DISCRIMINATOR => decode_as(body).map(GrantEvent::MatchPoolExhausted),
+        d if d == PeriodClosed::DISCRIMINATOR => decode_as(body).map(GrantEvent::PeriodClosed),
+        d if d == SeasonStarted::DISCRIMINATOR => decode_as(body).map(GrantEvent::SeasonStarted),
+        d if d == MintMigrationInitiated::DISCRIMINATOR => decode_as(body).map(GrantEvent::MintMigrationInitiated),
+        d if d == MintMigrated::DISCRIMINATOR => decode_as(body).map(GrantEvent::MintMigrated),
+        d if d == VaultRotated::DISCRIMINATOR => decode_as(body).map(GrantEvent::VaultRotated),
+        d if d == PopEntryRecorded::DISCRIMINATOR => decode_as(body).map(GrantEvent::PopEntryRecorded),
+        d if d == AuditBatchAnchored::DISCRIMINATOR => decode_as(body).map(GrantEvent::AuditBatchAnchored),
+        d if d == ReceiptStatusChanged::DISCRIMINATOR => decode_as(body).map(GrantEvent::ReceiptStatusChanged),
+        d if d == ClaimReturned::DISCRIMINATOR => decode_as(body).map(GrantEvent::ClaimReturned),
+        d if d == AllowlistDeltaAppended::DISCRIMINATOR => decode_as(body).map(GrantEvent::AllowlistDeltaAppended),
+        d if d == AllowlistRootCommitted::DISCRIMINATOR => decode_as(body).map(GrantEvent::AllowlistRootCommitted),
+        d if d == KeeperBountyPaid::DISCRIMINATOR => decode_as(body).map(GrantEvent::KeeperBountyPaid),
+        d if d == SessionAuthorized::DISCRIMINATOR => decode_as(body).map(GrantEvent::SessionAuthorized),
+        d if d == SessionRevoked::DISCRIMINATOR => decode_as(body).map(GrantEvent::SessionRevoked),
+        d if d == ChildStatsRolledUp::DISCRIMINATOR => decode_as(body).map(GrantEvent::ChildStatsRolledUp),
+        d if d == FundedFromBudget::DISCRIMINATOR => decode_as(body).map(GrantEvent::FundedFromBudget),
+        d if d == WindDownStarted::DISCRIMINATOR => decode_as(body).map(GrantEvent::WindDownStarted),
+        d if d == SponsoredClaim::DISCRIMINATOR => decode_as(body).map(GrantEvent::SponsoredClaim),
+        d if d == PopSignerHeartbeat::DISCRIMINATOR => decode_as(body).map(GrantEvent::PopSignerHeartbeat),
+        d if d == PopSignerSilent::DISCRIMINATOR => decode_as(body).map(GrantEvent::PopSignerSilent),
+        d if d == PopBypassSet::DISCRIMINATOR => decode_as(body).map(GrantEvent::PopBypassSet),
+        d if d == PopBypassClaimed::DISCRIMINATOR => decode_as(body).map(GrantEvent::PopBypassClaimed),
+        d if d == AdminActionExecuted::DISCRIMINATOR => decode_as(body).map(GrantEvent::AdminActionExecuted),
+        d if d == ClaimScheduled::DISCRIMINATOR => decode_as(body).map(GrantEvent::ClaimScheduled),
+        d if d == ScheduledClaimExecuted::DISCRIMINATOR => decode_as(body).map(GrantEvent::ScheduledClaimExecuted),
+        d if d == ScheduledClaimCancelled::DISCRIMINATOR => decode_as(body).map(GrantEvent::ScheduledClaimCancelled),
+        d if d == DisbursementAttested::DISCRIMINATOR => decode_as(body).map(GrantEvent::DisbursementAttested),
+        d if d == KpiProgress::DISCRIMINATOR => decode_as(body).map(GrantEvent::KpiProgress),
+        d if d == KpiMilestoneReached::DISCRIMINATOR => decode_as(body).map(GrantEvent::KpiMilestoneReached),
+        d if d == SuccessionAnnounced::DISCRIMINATOR => decode_as(body).map(GrantEvent::SuccessionAnnounced),
+        d if d == SuccessionCancelled::DISCRIMINATOR => decode_as(body).map(GrantEvent::SuccessionCancelled),
+        d if d == AuthoritySucceeded::DISCRIMINATOR => decode_as(body).map(GrantEvent::AuthoritySucceeded),
+        d if d == ProgramOfficerUpdated::DISCRIMINATOR => decode_as(body).map(GrantEvent::ProgramOfficerUpdated),
+        d if d == ProgramOfficerRemoved::DISCRIMINATOR => decode_as(body).map(GrantEvent::ProgramOfficerRemoved),
+        d if d == OfficerWithdrawal::DISCRIMINATOR => decode_as(body).map(GrantEvent::OfficerWithdrawal),
+        d if d == DelegateIndexUpdated::DISCRIMINATOR => decode_as(body).map(GrantEvent::DelegateIndexUpdated),
+        d if d == DestinationOptOutChanged::DISCRIMINATOR => decode_as(body).map(GrantEvent::DestinationOptOutChanged),
+        d if d == ReceiptsReserved::DISCRIMINATOR => decode_as(body).map(GrantEvent::ReceiptsReserved),
+        d if d == ReceiptsArchived::DISCRIMINATOR => decode_as(body).map(GrantEvent::ReceiptsArchived),
+        d if d == GrantArchived::DISCRIMINATOR => decode_as(body).map(GrantEvent::GrantArchived),
+        d if d == FundedWithSwap::DISCRIMINATOR => decode_as(body).map(GrantEvent::FundedWithSwap),
+        d if d == NotificationPreferenceChanged::DISCRIMINATOR => decode_as(body).map(GrantEvent::NotificationPreferenceChanged),
+        d if d == PopAmountAuthorized::DISCRIMINATOR => decode_as(body).map(GrantEvent::PopAmountAuthorized),
+        d if d == ClaimerMemoAttached::DISCRIMINATOR => decode_as(body).map(GrantEvent::ClaimerMemoAttached),
+        d if d == ClaimerMemoDelivered::DISCRIMINATOR => decode_as(body).map(GrantEvent::ClaimerMemoDelivered),
+        d if d == DeploymentInitialized::DISCRIMINATOR => decode_as(body).map(GrantEvent::DeploymentInitialized),
+        d if d == ReceiptNftMinted::DISCRIMINATOR => decode_as(body).map(GrantEvent::ReceiptNftMinted),
+        d if d == HistoricalClaimRecorded::DISCRIMINATOR => decode_as(body).map(GrantEvent::HistoricalClaimRecorded),
+        _ => None,
+    }
+}
+
+/// PoP チェーン検証で見つかった不整合
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PopChainIssue {
+    /// entry_hash が入力から再計算した値と一致しない（または未知の version）
+    EntryHashMismatch { index: usize },
+    /// prev_hash が、それより前に現れたどの entry_hash とも一致しない
+    UnknownPrevHash { index: usize },
+}
+
+/// PopEntryRecorded を発行順（古い順）に並べたものを検証する
+/// - 各エントリの entry_hash を再計算する
+/// - 先頭以外の prev_hash が既出の entry_hash を指しているかを確認する
+///   （並行 claim があるため、直前のエントリとの一致までは要求しない）
+///
+/// backfill_events は新しい順に流れるため、並べ替えてから渡すこと
+pub fn verify_pop_chain<'a>(entries: impl IntoIterator<Item = &'a PopEntryRecorded>) -> Vec<PopChainIssue> {
+    verify_pop_chain_in(DEFAULT_HASH_DOMAIN, entries)
+}
+
+/// verify_pop_chain を Grant.hash_domain を指定して行う（initialize_hash_domain 済みのデプロイ用）
+pub fn verify_pop_chain_in<'a>(
+    domain: &[u8],
+    entries: impl IntoIterator<Item = &'a PopEntryRecorded>,
+) -> Vec<PopChainIssue> {
+    let mut seen = HashSet::new();
+    let mut issues = Vec::new();
+    for (index, entry) in entries.into_iter().enumerate() {
+        if entry.computed_entry_hash_in(domain) != Some(entry.entry_hash) {
+            issues.push(PopChainIssue::EntryHashMismatch { index });
+        }
+        if index > 0 && !seen.contains(&entry.prev_hash) {
+            issues.push(PopChainIssue::UnknownPrevHash { index });
+        }
+        seen.insert(entry.entry_hash);
+    }
+    issues
+}
+
+/// "Program <id> invoke [n]" / "Program <id> success" / "Program <id> failed: ..." を解釈する
+fn parse_invocation(line: &str) -> Option<(Pubkey, bool)> {
+    let mut parts = line.strip_prefix("Program ")?.split(' ');
+    let program_id = parts.next()?.parse::<Pubkey>().ok()?;
+    match parts.next()? {
+        "invoke" => Some((program_id, true)),
+        "success" | "failed:" => Some((program_id, false)),
+        _ => None,
+    }
+}
+
+/// 1トランザクション分のログから grant_program のイベントを取り出す
+/// ログが切り詰められている（"Log truncated"）場合、それ以降のイベントは取れない
+pub fn parse_logs<S: AsRef<str>>(logs: &[S]) -> Vec<GrantEvent> {
+    let mut stack: Vec<Pubkey> = Vec::new();
+    let mut events = Vec::new();
+    for line in logs.iter().map(AsRef::as_ref) {
+        if let Some(encoded) = line.strip_prefix(PROGRAM_DATA_PREFIX) {
+            if stack.last() != Some(&grant_program::ID) {
+                continue;
+            }
+            if let Some(event) = STANDARD.decode(encoded).ok().and_then(|data| decode_event(&data)) {
+                events.push(event);
+            }
+        } else if let Some((program_id, is_invoke)) = parse_invocation(line) {
+            if is_invoke {
+                stack.push(program_id);
+            } else if stack.last() == Some(&program_id) {
+                stack.pop();
+            }
+        }
+    }
+    events
+}
+
+/// logsSubscribe（mentions = grant_program::ID）の通知を型付きイベントのストリームにする
+pub fn decode_log_stream<S>(notifications: S) -> impl Stream<Item = EventRecord>
+where
+    S: Stream<Item = LogNotification>,
+{
+    notifications.flat_map(|notification| {
+        let events = if notification.failed {
+            Vec::new()
+        } else {
+            parse_logs(&notification.logs)
+        };
+        let signature = notification.signature;
+        let slot = notification.slot;
+        stream::iter(events.into_iter().map(move |event| EventRecord {
+            signature: signature.clone(),
+            slot,
+            block_time: None,
+            event,
+        }))
+    })
+}
+
+struct BackfillState<'a, S> {
+    source: &'a S,
+    address: Pubkey,
+    until: Option<String>,
+    before: Option<String>,
+    signatures: VecDeque<SignatureInfo>,
+    events: VecDeque<EventRecord>,
+    exhausted: bool,
+}
+
+impl<S: TransactionLogSource> BackfillState<'_, S> {
+    async fn next_event(&mut self) -> Option<Result<EventRecord, S::Error>> {
+        loop {
+            if let Some(record) = self.events.pop_front() {
+                return Some(Ok(record));
+            }
+            if let Some(info) = self.signatures.pop_front() {
+                if info.failed {
+                    continue;
+                }
+                let logs = match self.source.transaction_logs(&info.signature).await {
+                    Ok(logs) => logs.unwrap_or_default(),
+                    Err(err) => return Some(Err(err)),
+                };
+                self.events.extend(parse_logs(&logs).into_iter().map(|event| EventRecord {
+                    signature: info.signature.clone(),
+                    slot: info.slot,
+                    block_time: info.block_time,
+                    event,
+                }));
+                continue;
+            }
+            if self.exhausted {
+                return None;
+            }
+            let page = match self
+                .source
+                .signatures_for_address(
+                    &self.address,
+                    self.before.as_deref(),
+                    self.until.as_deref(),
+                    SIGNATURES_PAGE_LIMIT,
+                )
+                .await
+            {
+                Ok(page) => page,
+                Err(err) => {
+                    self.exhausted = true;
+                    return Some(Err(err));
+                }
+            };
+            self.exhausted = page.len() < SIGNATURES_PAGE_LIMIT;
+            self.before = page.last().map(|info| info.signature.clone());
+            self.signatures.extend(page);
+        }
+    }
+}
+
+/// address（grant / program ID など）に触れた過去のトランザクションからイベントを取り出す
+/// 新しい順に流れ、until（前回処理した最新の署名）に到達すると終了する
+/// ページ取得に失敗した場合はエラーを1件流して終了する
+pub fn backfill_events<S: TransactionLogSource>(
+    source: &S,
+    address: Pubkey,
+    until: Option<String>,
+) -> impl Stream<Item = Result<EventRecord, S::Error>> + '_ {
+    let state = BackfillState {
+        source,
+        address,
+        until,
+        before: None,
+        signatures: VecDeque::new(),
+        events: VecDeque::new(),
+        exhausted: false,
+    };
+    stream::unfold(state, |mut state| async move {
+        state.next_event().await.map(|item| (item, state))
+    })
+}
+
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::AnchorSerialize;
+    use serde_json::Value;
+
+    use super::*;
+
+    /// tests/event_layouts.json（anchor build の IDL から生成したイベントのレイアウト）
+    const EVENT_LAYOUTS: &str = include_str!("../../tests/event_layouts.json");
+
+    macro_rules! event_variants {
+        ($($name:ident),* $(,)?) => {
+            const EVENT_NAMES: &[&str] = &[$(stringify!($name)),*];
+
+            /// 変種名と、中身を borsh で再エンコードしたもの
+            fn describe(event: &GrantEvent) -> (&'static str, Vec<u8>) {
+                let mut body = Vec::new();
+                let name = match event {
+                    $(GrantEvent::$name(inner) => {
+                        inner.serialize(&mut body).unwrap();
+                        stringify!($name)
+                    })*
+                };
+                (name, body)
+            }
+        };
+    }
+
+    event_variants!(
+        GrantClosed, GrantActivated, RecoveryInitiated, RecoveryCancelled, AuthorityRecovered, Discrepancy,
+        MatchApplied, MatchPoolExhausted, PeriodClosed, SeasonStarted, MintMigrationInitiated, MintMigrated,
+        VaultRotated, PopEntryRecorded, AuditBatchAnchored, ReceiptStatusChanged, ClaimReturned,
+        AllowlistDeltaAppended, AllowlistRootCommitted, KeeperBountyPaid, SessionAuthorized, SessionRevoked,
+        ChildStatsRolledUp, FundedFromBudget, WindDownStarted, SponsoredClaim, PopSignerHeartbeat,
+        PopSignerSilent, PopBypassSet, PopBypassClaimed, AdminActionExecuted, ClaimScheduled,
+        ScheduledClaimExecuted, ScheduledClaimCancelled, DisbursementAttested, KpiProgress,
+        KpiMilestoneReached, SuccessionAnnounced, SuccessionCancelled, AuthoritySucceeded,
+        ProgramOfficerUpdated, ProgramOfficerRemoved, OfficerWithdrawal, DelegateIndexUpdated,
+        DestinationOptOutChanged, ReceiptsReserved, ReceiptsArchived, GrantArchived, FundedWithSwap,
+        NotificationPreferenceChanged, PopAmountAuthorized, ClaimerMemoAttached, ClaimerMemoDelivered,
+        DeploymentInitialized, ReceiptNftMinted, HistoricalClaimRecorded,
+    );
+
+    /// レイアウトの型どおりに、フィールドごとに異なるバイト列を borsh で書く
+    fn encode_field(ty: &Value, seed: &mut u8, out: &mut Vec<u8>) {
+        let mut fill = |out: &mut Vec<u8>, len: usize| {
+            for _ in 0..len {
+                *seed = seed.wrapping_add(1);
+                out.push(*seed);
+            }
+        };
+        match ty {
+            Value::String(primitive) => match primitive.as_str() {
+                "u8" => fill(out, 1),
+                "bool" => out.push(1),
+                "u16" => fill(out, 2),
+                "u32" => fill(out, 4),
+                "u64" | "i64" => fill(out, 8),
+                "u128" | "i128" => fill(out, 16),
+                "pubkey" => fill(out, 32),
+                "string" => {
+                    out.extend_from_slice(&3u32.to_le_bytes());
+                    out.extend_from_slice(b"abc");
+                }
+                "bytes" => {
+                    out.extend_from_slice(&2u32.to_le_bytes());
+                    fill(out, 2);
+                }
+                other => panic!("未対応の型: {other}"),
+            },
+            Value::Object(object) if object.contains_key("array") => {
+                let array = object["array"].as_array().unwrap();
+                for _ in 0..array[1].as_u64().unwrap() {
+                    encode_field(&array[0], seed, out);
+                }
+            }
+            // ReceiptStatus（enum は変種の index 1 バイト）
+            Value::Object(object) if object["defined"]["name"] == "ReceiptStatus" => out.push(1),
+            other => panic!("未対応の型: {other}"),
+        }
+    }
+
+    fn layouts() -> serde_json::Map<String, Value> {
+        let layouts: Value = serde_json::from_str(EVENT_LAYOUTS).unwrap();
+        layouts["events"].as_object().unwrap().clone()
+    }
+
+    fn event_data(layout: &Value, seed: &mut u8) -> (Vec<u8>, Vec<u8>) {
+        let discriminator: Vec<u8> = layout["discriminator"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|byte| byte.as_u64().unwrap() as u8)
+            .collect();
+        let mut body = Vec::new();
+        for field in layout["fields"].as_array().unwrap() {
+            encode_field(&field[1], seed, &mut body);
+        }
+        (discriminator, body)
+    }
+
+    #[test]
+    fn every_layout_event_is_decoded() {
+        let layouts = layouts();
+        let mut names: Vec<&str> = layouts.keys().map(String::as_str).collect();
+        let mut variants = EVENT_NAMES.to_vec();
+        names.sort_unstable();
+        variants.sort_unstable();
+        assert_eq!(names, variants, "GrantEvent と event_layouts.json のイベントが一致しない");
+    }
+
+    #[test]
+    fn decode_event_matches_event_layouts() {
+        let mut seed = 0u8;
+        for (name, layout) in layouts() {
+            let (discriminator, body) = event_data(&layout, &mut seed);
+            let event = decode_event(&[discriminator.as_slice(), &body].concat())
+                .unwrap_or_else(|| panic!("{name} をデコードできない"));
+            let (decoded_name, encoded) = describe(&event);
+            assert_eq!(decoded_name, name);
+            assert_eq!(encoded, body, "{name} のフィールドがレイアウトと一致しない");
+        }
+    }
+
+    #[test]
+    fn decode_event_rejects_unknown_and_short_data() {
+        assert!(decode_event(&[0u8; 4]).is_none());
+        assert!(decode_event(&[0u8; 64]).is_none());
+        let (discriminator, mut body) = event_data(&layouts()["GrantClosed"], &mut 0);
+        body.pop();
+        assert!(decode_event(&[discriminator, body].concat()).is_none());
+    }
+
+    #[test]
+    fn parse_logs_skips_data_logged_by_other_programs() {
+        let (discriminator, body) = event_data(&layouts()["GrantClosed"], &mut 0);
+        let data = format!("{PROGRAM_DATA_PREFIX}{}", STANDARD.encode([discriminator, body].concat()));
+        let other = Pubkey::new_from_array([7; 32]);
+        let logs = [
+            format!("Program {} invoke [1]", grant_program::ID),
+            format!("Program {other} invoke [2]"),
+            data.clone(),
+            format!("Program {other} success"),
+            data.clone(),
+            format!("Program {} success", grant_program::ID),
+            data,
+        ];
+        let events = parse_logs(&logs);
+        assert_eq!(events.len(), 1);
+        assert!(matches!(events[0], GrantEvent::GrantClosed(_)));
+    }
+}
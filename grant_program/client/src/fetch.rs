@@ -0,0 +1,228 @@
+// ===== アカウント取得（getProgramAccounts フィルタのプリセット） =====
+// discriminator / offset / dataSize はプログラムの型から導出するため、手で数え直す必要はない。
+// RPC クライアントには依存せず、呼び出し側が ProgramAccountSource を実装する
+// （solana-client の RpcFilterType へはそのまま写せる形にしてある）。
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::{AccountDeserialize, Discriminator, Space};
+use grant_program::{ClaimReceipt, DelegateIndexPage, Grant};
+
+/// memcmp フィルタ（offset はアカウントデータ先頭 = discriminator の位置から）
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Memcmp {
+    pub offset: usize,
+    pub bytes: Vec<u8>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AccountFilters {
+    pub data_size: Option<u64>,
+    pub memcmp: Vec<Memcmp>,
+}
+
+/// getProgramAccounts を実行する RPC 層
+pub trait ProgramAccountSource {
+    type Error;
+
+    fn get_program_accounts(
+        &self,
+        program_id: &Pubkey,
+        filters: &AccountFilters,
+    ) -> Result<Vec<(Pubkey, Vec<u8>)>, Self::Error>;
+}
+
+#[derive(Debug)]
+pub enum FetchError<E> {
+    Source(E),
+    Decode(Pubkey, anchor_lang::error::Error),
+}
+
+const DISCRIMINATOR_LEN: usize = 8;
+
+// Grant: authority は先頭フィールド、category は固定長の先頭ブロック
+// （authority, mint, vault, grant_id, amount/期間4つ, merkle_root, paused, bump）の直後
+const GRANT_AUTHORITY_OFFSET: usize = DISCRIMINATOR_LEN;
+const GRANT_CATEGORY_OFFSET: usize = DISCRIMINATOR_LEN + 32 * 3 + 8 + 8 * 4 + 32 + 1 + 1;
+// ClaimReceipt: grant, claimer, period_index, claimed_at
+const RECEIPT_GRANT_OFFSET: usize = DISCRIMINATOR_LEN;
+const RECEIPT_CLAIMER_OFFSET: usize = RECEIPT_GRANT_OFFSET + 32;
+const RECEIPT_PERIOD_INDEX_OFFSET: usize = RECEIPT_CLAIMER_OFFSET + 32;
+// DelegateIndexPage: grant, delegate（claimers は可変長のため dataSize では絞らない）
+const DELEGATE_INDEX_GRANT_OFFSET: usize = DISCRIMINATOR_LEN;
+const DELEGATE_INDEX_DELEGATE_OFFSET: usize = DELEGATE_INDEX_GRANT_OFFSET + 32;
+
+fn discriminator_filter<T: Discriminator>() -> Memcmp {
+    Memcmp {
+        offset: 0,
+        bytes: T::DISCRIMINATOR.to_vec(),
+    }
+}
+
+/// 全 Grant（authority・分類を問わない）
+pub fn grants_filters() -> AccountFilters {
+    AccountFilters {
+        data_size: Some((DISCRIMINATOR_LEN + Grant::INIT_SPACE) as u64),
+        memcmp: vec![discriminator_filter::<Grant>()],
+    }
+}
+
+/// authority（現在の authority）が管理する全 Grant
+pub fn grants_by_authority_filters(authority: &Pubkey) -> AccountFilters {
+    AccountFilters {
+        data_size: Some((DISCRIMINATOR_LEN + Grant::INIT_SPACE) as u64),
+        memcmp: vec![
+            discriminator_filter::<Grant>(),
+            Memcmp {
+                offset: GRANT_AUTHORITY_OFFSET,
+                bytes: authority.to_bytes().to_vec(),
+            },
+        ],
+    }
+}
+
+/// 分類（GRANT_CATEGORY_*）ごとの全 Grant（authority 横断）
+pub fn grants_by_category_filters(category: u16) -> AccountFilters {
+    AccountFilters {
+        data_size: Some((DISCRIMINATOR_LEN + Grant::INIT_SPACE) as u64),
+        memcmp: vec![
+            discriminator_filter::<Grant>(),
+            Memcmp {
+                offset: GRANT_CATEGORY_OFFSET,
+                bytes: category.to_le_bytes().to_vec(),
+            },
+        ],
+    }
+}
+
+/// claimer の全 receipt（全 Grant 横断）
+pub fn receipts_by_claimer_filters(claimer: &Pubkey) -> AccountFilters {
+    AccountFilters {
+        data_size: Some((DISCRIMINATOR_LEN + ClaimReceipt::INIT_SPACE) as u64),
+        memcmp: vec![
+            discriminator_filter::<ClaimReceipt>(),
+            Memcmp {
+                offset: RECEIPT_CLAIMER_OFFSET,
+                bytes: claimer.to_bytes().to_vec(),
+            },
+        ],
+    }
+}
+
+/// grant の全 receipt（全期間）
+pub fn receipts_by_grant_filters(grant: &Pubkey) -> AccountFilters {
+    AccountFilters {
+        data_size: Some((DISCRIMINATOR_LEN + ClaimReceipt::INIT_SPACE) as u64),
+        memcmp: vec![
+            discriminator_filter::<ClaimReceipt>(),
+            Memcmp {
+                offset: RECEIPT_GRANT_OFFSET,
+                bytes: grant.to_bytes().to_vec(),
+            },
+        ],
+    }
+}
+
+/// grant の period_index における全 receipt
+pub fn receipts_by_grant_period_filters(grant: &Pubkey, period_index: u64) -> AccountFilters {
+    AccountFilters {
+        data_size: Some((DISCRIMINATOR_LEN + ClaimReceipt::INIT_SPACE) as u64),
+        memcmp: vec![
+            discriminator_filter::<ClaimReceipt>(),
+            Memcmp {
+                offset: RECEIPT_GRANT_OFFSET,
+                bytes: grant.to_bytes().to_vec(),
+            },
+            Memcmp {
+                offset: RECEIPT_PERIOD_INDEX_OFFSET,
+                bytes: period_index.to_le_bytes().to_vec(),
+            },
+        ],
+    }
+}
+
+/// delegate（session key）が grant で管理する全索引ページ
+pub fn delegate_index_pages_filters(grant: &Pubkey, delegate: &Pubkey) -> AccountFilters {
+    AccountFilters {
+        data_size: None,
+        memcmp: vec![
+            discriminator_filter::<DelegateIndexPage>(),
+            Memcmp {
+                offset: DELEGATE_INDEX_GRANT_OFFSET,
+                bytes: grant.to_bytes().to_vec(),
+            },
+            Memcmp {
+                offset: DELEGATE_INDEX_DELEGATE_OFFSET,
+                bytes: delegate.to_bytes().to_vec(),
+            },
+        ],
+    }
+}
+
+fn fetch_decoded<S, T>(source: &S, filters: &AccountFilters) -> Result<Vec<(Pubkey, T)>, FetchError<S::Error>>
+where
+    S: ProgramAccountSource,
+    T: AccountDeserialize,
+{
+    source
+        .get_program_accounts(&grant_program::ID, filters)
+        .map_err(FetchError::Source)?
+        .into_iter()
+        .map(|(address, data)| {
+            T::try_deserialize(&mut data.as_slice())
+                .map(|account| (address, account))
+                .map_err(|err| FetchError::Decode(address, err))
+        })
+        .collect()
+}
+
+pub fn fetch_all_grants<S: ProgramAccountSource>(source: &S) -> Result<Vec<(Pubkey, Grant)>, FetchError<S::Error>> {
+    fetch_decoded(source, &grants_filters())
+}
+
+pub fn fetch_grants_by_authority<S: ProgramAccountSource>(
+    source: &S,
+    authority: &Pubkey,
+) -> Result<Vec<(Pubkey, Grant)>, FetchError<S::Error>> {
+    fetch_decoded(source, &grants_by_authority_filters(authority))
+}
+
+pub fn fetch_grants_by_category<S: ProgramAccountSource>(
+    source: &S,
+    category: u16,
+) -> Result<Vec<(Pubkey, Grant)>, FetchError<S::Error>> {
+    fetch_decoded(source, &grants_by_category_filters(category))
+}
+
+pub fn fetch_receipts_by_claimer<S: ProgramAccountSource>(
+    source: &S,
+    claimer: &Pubkey,
+) -> Result<Vec<(Pubkey, ClaimReceipt)>, FetchError<S::Error>> {
+    fetch_decoded(source, &receipts_by_claimer_filters(claimer))
+}
+
+pub fn fetch_receipts_by_grant<S: ProgramAccountSource>(
+    source: &S,
+    grant: &Pubkey,
+) -> Result<Vec<(Pubkey, ClaimReceipt)>, FetchError<S::Error>> {
+    fetch_decoded(source, &receipts_by_grant_filters(grant))
+}
+
+pub fn fetch_receipts_by_grant_period<S: ProgramAccountSource>(
+    source: &S,
+    grant: &Pubkey,
+    period_index: u64,
+) -> Result<Vec<(Pubkey, ClaimReceipt)>, FetchError<S::Error>> {
+    fetch_decoded(source, &receipts_by_grant_period_filters(grant, period_index))
+}
+
+/// delegate が管理する受給者の一覧（page 順に連結。ページをまたいだ重複は除かない）
+pub fn fetch_delegated_claimers<S: ProgramAccountSource>(
+    source: &S,
+    grant: &Pubkey,
+    delegate: &Pubkey,
+) -> Result<Vec<Pubkey>, FetchError<S::Error>> {
+    let mut pages: Vec<(Pubkey, DelegateIndexPage)> =
+        fetch_decoded(source, &delegate_index_pages_filters(grant, delegate))?;
+    pages.sort_by_key(|(_, page)| page.page);
+    Ok(pages.into_iter().flat_map(|(_, page)| page.claimers).collect())
+}
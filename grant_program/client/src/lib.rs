@@ -0,0 +1,35 @@
+// ===== we-ne grant program client =====
+// オフチェーン（バックエンド / CLI / 他言語 SDK の参照実装）向けのヘルパー。
+// 命令データとアカウント順序は grant_program クレート（no-entrypoint）から直接使うため、
+// プログラム側の変更と食い違うことはない。
+
+pub mod budget;
+pub mod claim;
+pub mod discovery;
+pub mod events;
+pub mod fetch;
+pub mod pda;
+pub mod verify;
+
+pub use budget::{
+    build_budgeted_claim_transaction, estimate_claim_compute_budget, priority_fee, unit_limit_with_margin, BudgetOptions,
+    BudgetSource, PriorityFeeStrategy, MAX_COMPUTE_UNIT_LIMIT,
+};
+pub use claim::{
+    build_claim_instructions, build_claim_transaction, build_pop_bypass_claim_instructions, claim_intent_message,
+    ed25519_claim_intent_instruction, ed25519_pop_instruction, ClaimOptionalAccounts, ClaimParams, ComputeBudget,
+    SignedClaimIntent, SignedPopMessage,
+};
+pub use discovery::{discover_claimable_grants, AllowlistProofSource, DiscoveryError, GrantAvailability, GrantScope};
+pub use events::{
+    backfill_events, decode_event, decode_log_stream, parse_logs, verify_pop_chain, verify_pop_chain_in, EventRecord,
+    GrantEvent, LogNotification, PopChainIssue, SignatureInfo, TransactionLogSource,
+};
+pub use fetch::{
+    fetch_all_grants, fetch_delegated_claimers, fetch_grants_by_authority, fetch_grants_by_category, fetch_receipts_by_claimer,
+    fetch_receipts_by_grant, fetch_receipts_by_grant_period, AccountFilters, FetchError, Memcmp, ProgramAccountSource,
+};
+pub use verify::{
+    verify_deployment, AccountSource, ConfiguredAddresses, DeploymentAccount, DeploymentIssue, DeploymentReport,
+    DeploymentTarget, DerivedAccount, FetchedAccount,
+};
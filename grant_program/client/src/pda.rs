@@ -0,0 +1,191 @@
+// ===== PDA 導出 =====
+// seeds はプログラム側の #[account(seeds = ...)] と一致させること。
+
+use anchor_lang::prelude::Pubkey;
+
+/// mint は作成時の mint（complete_mint_migration 後も grant.seed_mint のまま）
+pub fn grant(authority: &Pubkey, mint: &Pubkey, grant_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"grant", authority.as_ref(), mint.as_ref(), &grant_id.to_le_bytes()],
+        &grant_program::ID,
+    )
+}
+
+pub fn vault(grant: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"vault", grant.as_ref()], &grant_program::ID)
+}
+
+/// complete_mint_migration で作成される新 mint の vault
+pub fn migrated_vault(grant: &Pubkey, mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"vault", grant.as_ref(), mint.as_ref()], &grant_program::ID)
+}
+
+/// rotate_vault で作成される vault（vault_nonce は実行後の grant.vault_nonce）
+pub fn rotated_vault(grant: &Pubkey, vault_nonce: u32) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"vault", grant.as_ref(), &vault_nonce.to_le_bytes()],
+        &grant_program::ID,
+    )
+}
+
+/// seed_salt は grant.seed_salt（set_seed_salt 未実行なら [0; SEED_SALT_LEN]）
+pub fn receipt(
+    grant: &Pubkey,
+    claimer: &Pubkey,
+    period_index: u64,
+    seed_salt: &[u8; grant_program::SEED_SALT_LEN],
+) -> (Pubkey, u8) {
+    grant_program::receipt::receipt_address(grant, claimer, period_index, seed_salt)
+}
+
+/// PoP signer 設定（authority 単位）
+pub fn pop_config(authority: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"pop-config", authority.as_ref()], &grant_program::ID)
+}
+
+/// PoP chain state（grant 単位。seed_salt は grant.seed_salt）
+pub fn pop_state(grant: &Pubkey, seed_salt: &[u8; grant_program::SEED_SALT_LEN]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"pop-state", grant.as_ref(), grant_program::seed_salt_seed(seed_salt)],
+        &grant_program::ID,
+    )
+}
+
+/// schedule_claim で予約された claim（claimer = 受給者）
+pub fn pending_claim(grant: &Pubkey, claimer: &Pubkey, period_index: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"pending-claim", grant.as_ref(), claimer.as_ref(), &period_index.to_le_bytes()],
+        &grant_program::ID,
+    )
+}
+
+pub fn claimer_profile(grant: &Pubkey, claimer: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"claimer-profile", grant.as_ref(), claimer.as_ref()],
+        &grant_program::ID,
+    )
+}
+
+pub fn period_stats(grant: &Pubkey, period_index: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"period-stats", grant.as_ref(), &period_index.to_le_bytes()],
+        &grant_program::ID,
+    )
+}
+
+pub fn audit_anchor(pop_config: &Pubkey, batch_root: &[u8; 32]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"audit-anchor", pop_config.as_ref(), batch_root.as_ref()],
+        &grant_program::ID,
+    )
+}
+
+pub fn season_summary(grant: &Pubkey, season: u32) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"season-summary", grant.as_ref(), &season.to_le_bytes()],
+        &grant_program::ID,
+    )
+}
+
+pub fn rate_config(authority: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"rate-config", authority.as_ref()], &grant_program::ID)
+}
+
+pub fn claim_streak(grant: &Pubkey, claimer: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"streak", grant.as_ref(), claimer.as_ref()],
+        &grant_program::ID,
+    )
+}
+
+/// authorize_session で作成される session key の登録
+pub fn session_authorization(grant: &Pubkey, claimer: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"session", grant.as_ref(), claimer.as_ref()], &grant_program::ID)
+}
+
+/// open_delegate_index_page で作成される delegate（session key）の索引ページ
+pub fn delegate_index_page(grant: &Pubkey, delegate: &Pubkey, page: u32) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"delegate-index", grant.as_ref(), delegate.as_ref(), &page.to_le_bytes()],
+        &grant_program::ID,
+    )
+}
+
+/// opt_out_canonical_destination で作成される、ATA 以外の受給先の許可
+pub fn destination_opt_out(grant: &Pubkey, claimer: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"destination-opt-out", grant.as_ref(), claimer.as_ref()], &grant_program::ID)
+}
+
+/// open_claim_index で作成される受給済み期間の索引
+pub fn claim_index(grant: &Pubkey, claimer: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"claim-index", grant.as_ref(), claimer.as_ref()], &grant_program::ID)
+}
+
+/// attach_claimer_memo で作成される受給者宛ての暗号化メモ
+pub fn claimer_memo(grant: &Pubkey, claimer: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"claimer-memo", grant.as_ref(), claimer.as_ref()], &grant_program::ID)
+}
+
+/// open_receipt_nft_collection で作成される受給記録 NFT の設定
+pub fn receipt_nft_collection(grant: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"receipt-nft-collection", grant.as_ref()], &grant_program::ID)
+}
+
+/// 受給記録 NFT の group mint（Token-2022）
+pub fn receipt_nft_group(grant: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"receipt-nft-group", grant.as_ref()], &grant_program::ID)
+}
+
+/// mint_receipt_nft で作成される receipt 1件分の NFT の mint（Token-2022）
+pub fn receipt_nft_mint(receipt: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"receipt-nft", receipt.as_ref()], &grant_program::ID)
+}
+
+/// open_statement_log で作成される受給明細
+pub fn statement_log(grant: &Pubkey, claimer: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"statement", grant.as_ref(), claimer.as_ref()], &grant_program::ID)
+}
+
+pub fn escrow_schedule(grant: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"escrow-schedule", grant.as_ref()], &grant_program::ID)
+}
+
+pub fn match_pool(grant: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"match-pool", grant.as_ref()], &grant_program::ID)
+}
+
+pub fn funding_budget(grant: &Pubkey, operator: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"funding-budget", grant.as_ref(), operator.as_ref()], &grant_program::ID)
+}
+
+/// open_budget_forecast で作成される支出見込み
+pub fn budget_forecast(grant: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"budget-forecast", grant.as_ref()], &grant_program::ID)
+}
+
+/// open_archive で作成される archive（close_grant 後も残る）
+pub fn grant_archive(grant: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"archive", grant.as_ref()], &grant_program::ID)
+}
+
+/// set_notification_preference で受給者が作成する通知先のコミットメント（全 Grant 共通）
+pub fn notification_preference(claimer: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"notification-preference", claimer.as_ref()], &grant_program::ID)
+}
+
+pub fn keeper_config(grant: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"keeper-config", grant.as_ref()], &grant_program::ID)
+}
+
+/// keeper bounty の原資（upsert_keeper_config で作成される token account）
+pub fn keeper_pool(grant: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"keeper-pool", grant.as_ref()], &grant_program::ID)
+}
+
+pub fn feature_flags() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"feature-flags"], &grant_program::ID)
+}
+
+pub fn treasury_config() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"treasury-config"], &grant_program::ID)
+}
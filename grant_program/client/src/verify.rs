@@ -0,0 +1,524 @@
+// ===== デプロイ検証（PDA 導出の監査） =====
+// seed_authority / seed_mint / grant_id から期待される PDA をすべて導出して取得し、
+// 存在・owner・中身の相互参照がずれていないかを報告する。
+// フロントエンドが保持しているアドレスを渡すと、導出結果との食い違いも報告する
+// （古い PDA を指したままの設定を検出するため）。
+// RPC クライアントには依存せず、呼び出し側が AccountSource を実装する。
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::AccountDeserialize;
+use anchor_spl::token::TokenAccount;
+use grant_program::{ClaimReceipt, Grant, PopConfig, PopState, SEED_SALT_LEN};
+
+use crate::pda;
+
+/// getMultipleAccounts の1件
+#[derive(Clone, Debug)]
+pub struct FetchedAccount {
+    pub owner: Pubkey,
+    pub data: Vec<u8>,
+}
+
+/// getMultipleAccounts を実行する RPC 層
+pub trait AccountSource {
+    type Error;
+
+    /// addresses と同じ順（存在しないアカウントは None）
+    fn get_multiple_accounts(&self, addresses: &[Pubkey]) -> Result<Vec<Option<FetchedAccount>>, Self::Error>;
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DeploymentAccount {
+    Grant,
+    Vault,
+    PopConfig,
+    PopState,
+    Receipt,
+}
+
+/// 検証対象（authority / mint は PDA seed に使った作成時の値）
+#[derive(Clone, Debug)]
+pub struct DeploymentTarget {
+    pub seed_authority: Pubkey,
+    pub seed_mint: Pubkey,
+    pub grant_id: u64,
+    /// receipt の導出を確認する claimer と period_index（未作成でも問題にしない）
+    pub receipt_sample: Option<(Pubkey, u64)>,
+    /// フロントエンドが使っているアドレス（指定したものだけ導出結果と照合する）
+    pub configured: ConfiguredAddresses,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct ConfiguredAddresses {
+    pub grant: Option<Pubkey>,
+    pub vault: Option<Pubkey>,
+    pub pop_config: Option<Pubkey>,
+    pub pop_state: Option<Pubkey>,
+}
+
+/// 導出したアカウント（vault は grant.vault の値、bump は PDA の場合のみ）
+#[derive(Clone, Debug)]
+pub struct DerivedAccount {
+    pub kind: DeploymentAccount,
+    pub address: Pubkey,
+    pub bump: Option<u8>,
+    pub exists: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DeploymentIssue {
+    /// 作成済みであるべきアカウントが存在しない
+    Missing(DeploymentAccount, Pubkey),
+    UnexpectedOwner {
+        account: DeploymentAccount,
+        address: Pubkey,
+        owner: Pubkey,
+        expected_owner: Pubkey,
+    },
+    /// 期待する型としてデコードできない
+    Undecodable(DeploymentAccount, Pubkey),
+    /// アカウントの中身が導出結果と合わない（field は比較したフィールド名）
+    FieldMismatch {
+        account: DeploymentAccount,
+        field: &'static str,
+    },
+    /// grant.vault が vault / migrated_vault / rotated_vault のどれとも一致しない
+    VaultNotDerived(Pubkey),
+    /// フロントエンドの設定が導出結果と異なる
+    ConfiguredMismatch {
+        account: DeploymentAccount,
+        configured: Pubkey,
+        derived: Pubkey,
+    },
+}
+
+#[derive(Debug)]
+pub struct DeploymentReport {
+    pub accounts: Vec<DerivedAccount>,
+    pub issues: Vec<DeploymentIssue>,
+}
+
+impl DeploymentReport {
+    pub fn is_ok(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+fn fetch_one<S: AccountSource>(source: &S, address: &Pubkey) -> Result<Option<FetchedAccount>, S::Error> {
+    Ok(source.get_multiple_accounts(&[*address])?.into_iter().next().flatten())
+}
+
+/// owner を確認してデコードする（存在しない・owner 違い・デコード失敗は issues に積んで None）
+fn decode_owned<T: AccountDeserialize>(
+    kind: DeploymentAccount,
+    address: &Pubkey,
+    account: Option<&FetchedAccount>,
+    expected_owner: &Pubkey,
+    required: bool,
+    issues: &mut Vec<DeploymentIssue>,
+) -> Option<T> {
+    let Some(account) = account else {
+        if required {
+            issues.push(DeploymentIssue::Missing(kind, *address));
+        }
+        return None;
+    };
+    if account.owner != *expected_owner {
+        issues.push(DeploymentIssue::UnexpectedOwner {
+            account: kind,
+            address: *address,
+            owner: account.owner,
+            expected_owner: *expected_owner,
+        });
+        return None;
+    }
+    match T::try_deserialize(&mut account.data.as_slice()) {
+        Ok(decoded) => Some(decoded),
+        Err(_) => {
+            issues.push(DeploymentIssue::Undecodable(kind, *address));
+            None
+        }
+    }
+}
+
+fn check_field(condition: bool, account: DeploymentAccount, field: &'static str, issues: &mut Vec<DeploymentIssue>) {
+    if !condition {
+        issues.push(DeploymentIssue::FieldMismatch { account, field });
+    }
+}
+
+fn check_configured(
+    configured: Option<Pubkey>,
+    account: DeploymentAccount,
+    derived: &Pubkey,
+    issues: &mut Vec<DeploymentIssue>,
+) {
+    if let Some(configured) = configured.filter(|configured| configured != derived) {
+        issues.push(DeploymentIssue::ConfiguredMismatch {
+            account,
+            configured,
+            derived: *derived,
+        });
+    }
+}
+
+/// grant / vault / pop_config / pop_state / receipt を導出・取得して突き合わせる
+/// pop_state は最初の claim で作成されるため、未作成は問題にしない
+pub fn verify_deployment<S: AccountSource>(
+    source: &S,
+    target: &DeploymentTarget,
+) -> Result<DeploymentReport, S::Error> {
+    let mut accounts = Vec::new();
+    let mut issues = Vec::new();
+
+    let (grant_address, grant_bump) = pda::grant(&target.seed_authority, &target.seed_mint, target.grant_id);
+    let grant_account = fetch_one(source, &grant_address)?;
+    accounts.push(DerivedAccount {
+        kind: DeploymentAccount::Grant,
+        address: grant_address,
+        bump: Some(grant_bump),
+        exists: grant_account.is_some(),
+    });
+    check_configured(target.configured.grant, DeploymentAccount::Grant, &grant_address, &mut issues);
+    let grant = decode_owned::<Grant>(
+        DeploymentAccount::Grant,
+        &grant_address,
+        grant_account.as_ref(),
+        &grant_program::ID,
+        true,
+        &mut issues,
+    );
+
+    // grant が読めなければ authority / vault / salt は seed から推定する（salt なし）
+    let authority = grant.as_ref().map_or(target.seed_authority, |grant| grant.authority);
+    if let Some(grant) = grant.as_ref() {
+        check_field(grant.grant_id == target.grant_id, DeploymentAccount::Grant, "grant_id", &mut issues);
+        check_field(grant.seed_authority == target.seed_authority, DeploymentAccount::Grant, "seed_authority", &mut issues);
+        check_field(grant.seed_mint == target.seed_mint, DeploymentAccount::Grant, "seed_mint", &mut issues);
+        check_field(grant.bump == grant_bump, DeploymentAccount::Grant, "bump", &mut issues);
+    }
+
+    let (initial_vault, initial_vault_bump) = pda::vault(&grant_address);
+    let (vault_address, vault_bump) = match grant.as_ref() {
+        Some(grant) => {
+            let mut candidates = vec![(initial_vault, initial_vault_bump), pda::migrated_vault(&grant_address, &grant.mint)];
+            if grant.vault_nonce > 0 {
+                candidates.push(pda::rotated_vault(&grant_address, grant.vault_nonce));
+            }
+            let bump = candidates
+                .iter()
+                .find(|(address, _)| *address == grant.vault)
+                .map(|(_, bump)| *bump);
+            if bump.is_none() {
+                issues.push(DeploymentIssue::VaultNotDerived(grant.vault));
+            }
+            (grant.vault, bump)
+        }
+        None => (initial_vault, Some(initial_vault_bump)),
+    };
+    let (pop_config_address, pop_config_bump) = pda::pop_config(&authority);
+    let seed_salt = grant.as_ref().map_or([0u8; SEED_SALT_LEN], |grant| grant.seed_salt);
+    let (pop_state_address, pop_state_bump) = pda::pop_state(&grant_address, &seed_salt);
+    let receipt = target
+        .receipt_sample
+        .map(|(claimer, period_index)| pda::receipt(&grant_address, &claimer, period_index, &seed_salt));
+
+    let mut addresses = vec![vault_address, pop_config_address, pop_state_address];
+    addresses.extend(receipt.map(|(address, _)| address));
+    let mut fetched = source.get_multiple_accounts(&addresses)?.into_iter();
+    let vault_account = fetched.next().flatten();
+    let pop_config_account = fetched.next().flatten();
+    let pop_state_account = fetched.next().flatten();
+    let receipt_account = fetched.next().flatten();
+
+    accounts.push(DerivedAccount {
+        kind: DeploymentAccount::Vault,
+        address: vault_address,
+        bump: vault_bump,
+        exists: vault_account.is_some(),
+    });
+    check_configured(target.configured.vault, DeploymentAccount::Vault, &vault_address, &mut issues);
+    let vault = decode_owned::<TokenAccount>(
+        DeploymentAccount::Vault,
+        &vault_address,
+        vault_account.as_ref(),
+        &anchor_spl::token::ID,
+        true,
+        &mut issues,
+    );
+    if let (Some(vault), Some(grant)) = (vault.as_ref(), grant.as_ref()) {
+        check_field(vault.mint == grant.mint, DeploymentAccount::Vault, "mint", &mut issues);
+        check_field(vault.owner == grant_address, DeploymentAccount::Vault, "owner", &mut issues);
+    }
+
+    accounts.push(DerivedAccount {
+        kind: DeploymentAccount::PopConfig,
+        address: pop_config_address,
+        bump: Some(pop_config_bump),
+        exists: pop_config_account.is_some(),
+    });
+    check_configured(target.configured.pop_config, DeploymentAccount::PopConfig, &pop_config_address, &mut issues);
+    if let Some(pop_config) = decode_owned::<PopConfig>(
+        DeploymentAccount::PopConfig,
+        &pop_config_address,
+        pop_config_account.as_ref(),
+        &grant_program::ID,
+        true,
+        &mut issues,
+    ) {
+        check_field(pop_config.authority == authority, DeploymentAccount::PopConfig, "authority", &mut issues);
+        check_field(pop_config.bump == pop_config_bump, DeploymentAccount::PopConfig, "bump", &mut issues);
+    }
+
+    accounts.push(DerivedAccount {
+        kind: DeploymentAccount::PopState,
+        address: pop_state_address,
+        bump: Some(pop_state_bump),
+        exists: pop_state_account.is_some(),
+    });
+    check_configured(target.configured.pop_state, DeploymentAccount::PopState, &pop_state_address, &mut issues);
+    if let Some(pop_state) = decode_owned::<PopState>(
+        DeploymentAccount::PopState,
+        &pop_state_address,
+        pop_state_account.as_ref(),
+        &grant_program::ID,
+        false,
+        &mut issues,
+    ) {
+        check_field(pop_state.grant == grant_address, DeploymentAccount::PopState, "grant", &mut issues);
+        check_field(pop_state.bump == pop_state_bump, DeploymentAccount::PopState, "bump", &mut issues);
+        check_field(pop_state.seed_salt == seed_salt, DeploymentAccount::PopState, "seed_salt", &mut issues);
+    }
+
+    if let (Some((receipt_address, receipt_bump)), Some((claimer, period_index))) = (receipt, target.receipt_sample) {
+        accounts.push(DerivedAccount {
+            kind: DeploymentAccount::Receipt,
+            address: receipt_address,
+            bump: Some(receipt_bump),
+            exists: receipt_account.is_some(),
+        });
+        if let Some(receipt) = decode_owned::<ClaimReceipt>(
+            DeploymentAccount::Receipt,
+            &receipt_address,
+            receipt_account.as_ref(),
+            &grant_program::ID,
+            false,
+            &mut issues,
+        ) {
+            check_field(receipt.grant == grant_address, DeploymentAccount::Receipt, "grant", &mut issues);
+            check_field(receipt.claimer == claimer, DeploymentAccount::Receipt, "claimer", &mut issues);
+            check_field(receipt.period_index == period_index, DeploymentAccount::Receipt, "period_index", &mut issues);
+            check_field(receipt.seed_salt == seed_salt, DeploymentAccount::Receipt, "seed_salt", &mut issues);
+        }
+    }
+
+    Ok(DeploymentReport { accounts, issues })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::convert::Infallible;
+
+    use anchor_lang::solana_program::program_pack::Pack;
+    use anchor_lang::AccountSerialize;
+    use anchor_spl::token::spl_token;
+    use grant_program::ReceiptStatus;
+
+    use super::*;
+
+    const GRANT_ID: u64 = 3;
+    const PERIOD_INDEX: u64 = 11;
+    const SALT: [u8; SEED_SALT_LEN] = [9; SEED_SALT_LEN];
+
+    fn key(n: u8) -> Pubkey {
+        Pubkey::new_from_array([n; 32])
+    }
+
+    #[derive(Default)]
+    struct Accounts(HashMap<Pubkey, FetchedAccount>);
+
+    impl Accounts {
+        fn put<T: AccountSerialize>(&mut self, address: Pubkey, account: &T) {
+            let mut data = Vec::new();
+            account.try_serialize(&mut data).unwrap();
+            self.0.insert(address, FetchedAccount { owner: grant_program::ID, data });
+        }
+    }
+
+    impl AccountSource for Accounts {
+        type Error = Infallible;
+
+        fn get_multiple_accounts(&self, addresses: &[Pubkey]) -> Result<Vec<Option<FetchedAccount>>, Infallible> {
+            Ok(addresses.iter().map(|address| self.0.get(address).cloned()).collect())
+        }
+    }
+
+    fn target() -> DeploymentTarget {
+        DeploymentTarget {
+            seed_authority: key(1),
+            seed_mint: key(2),
+            grant_id: GRANT_ID,
+            receipt_sample: Some((key(4), PERIOD_INDEX)),
+            configured: ConfiguredAddresses::default(),
+        }
+    }
+
+    fn receipt(grant: Pubkey, bump: u8) -> ClaimReceipt {
+        ClaimReceipt {
+            grant,
+            claimer: key(4),
+            period_index: PERIOD_INDEX,
+            claimed_at: 1_700_000_000,
+            bump,
+            status: ReceiptStatus::Valid,
+            amount: 100,
+            pop_bypassed: false,
+            seed_salt: SALT,
+        }
+    }
+
+    /// set_seed_salt 済みの Grant と、その salt で導出した vault / pop_config / pop_state / receipt
+    fn deployment() -> Accounts {
+        let (grant_address, grant_bump) = pda::grant(&key(1), &key(2), GRANT_ID);
+        let (vault, _) = pda::vault(&grant_address);
+        let (pop_config, pop_config_bump) = pda::pop_config(&key(1));
+        let (pop_state, pop_state_bump) = pda::pop_state(&grant_address, &SALT);
+        let (receipt_address, receipt_bump) = pda::receipt(&grant_address, &key(4), PERIOD_INDEX, &SALT);
+
+        let mut accounts = Accounts::default();
+        accounts.put(
+            grant_address,
+            &Grant {
+                authority: key(1),
+                mint: key(2),
+                vault,
+                grant_id: GRANT_ID,
+                bump: grant_bump,
+                seed_authority: key(1),
+                seed_mint: key(2),
+                seed_salt: SALT,
+                ..Grant::default()
+            },
+        );
+        let mut vault_data = vec![0u8; spl_token::state::Account::LEN];
+        spl_token::state::Account {
+            mint: key(2),
+            owner: grant_address,
+            state: spl_token::state::AccountState::Initialized,
+            ..spl_token::state::Account::default()
+        }
+        .pack_into_slice(&mut vault_data);
+        accounts.0.insert(vault, FetchedAccount { owner: anchor_spl::token::ID, data: vault_data });
+        accounts.put(
+            pop_config,
+            &PopConfig {
+                authority: key(1),
+                signer_pubkey: key(7),
+                bump: pop_config_bump,
+                active_grants: 1,
+                last_heartbeat_at: 0,
+                heartbeat_max_silence_seconds: 0,
+                heartbeat_enforced: false,
+            },
+        );
+        accounts.put(
+            pop_state,
+            &PopState {
+                grant: grant_address,
+                last_global_hash: [0; 32],
+                last_stream_hash: [0; 32],
+                last_period_index: PERIOD_INDEX,
+                last_issued_at: 0,
+                initialized: true,
+                bump: pop_state_bump,
+                payer: key(4),
+                seed_salt: SALT,
+            },
+        );
+        accounts.put(receipt_address, &receipt(grant_address, receipt_bump));
+        accounts
+    }
+
+    fn receipt_address() -> Pubkey {
+        let (grant_address, _) = pda::grant(&key(1), &key(2), GRANT_ID);
+        pda::receipt(&grant_address, &key(4), PERIOD_INDEX, &SALT).0
+    }
+
+    #[test]
+    fn consistent_deployment_has_no_issues() {
+        let report = verify_deployment(&deployment(), &target()).unwrap();
+        assert_eq!(report.issues, []);
+        let receipt = report.accounts.iter().find(|account| account.kind == DeploymentAccount::Receipt).unwrap();
+        assert_eq!(receipt.address, receipt_address());
+        assert!(receipt.exists);
+    }
+
+    #[test]
+    fn receipt_is_looked_up_at_the_salted_pda() {
+        // salt なしのアドレスにしか receipt がなければ、導出先は未作成として扱われる（問題にはしない）
+        let mut accounts = deployment();
+        let moved = accounts.0.remove(&receipt_address()).unwrap();
+        let (grant_address, _) = pda::grant(&key(1), &key(2), GRANT_ID);
+        let (unsalted, _) = pda::receipt(&grant_address, &key(4), PERIOD_INDEX, &[0; SEED_SALT_LEN]);
+        accounts.0.insert(unsalted, moved);
+        let report = verify_deployment(&accounts, &target()).unwrap();
+        let receipt = report.accounts.iter().find(|account| account.kind == DeploymentAccount::Receipt).unwrap();
+        assert_eq!(receipt.address, receipt_address());
+        assert!(!receipt.exists);
+    }
+
+    #[test]
+    fn receipt_fields_are_checked_against_the_pda_seeds() {
+        let (grant_address, _) = pda::grant(&key(1), &key(2), GRANT_ID);
+        for (field, tamper) in [
+            ("grant", (|r| r.grant = key(30)) as fn(&mut ClaimReceipt)),
+            ("claimer", |r| r.claimer = key(31)),
+            ("period_index", |r| r.period_index += 1),
+            ("seed_salt", |r| r.seed_salt = [0; SEED_SALT_LEN]),
+        ] {
+            let mut accounts = deployment();
+            let mut tampered = receipt(grant_address, 0);
+            tamper(&mut tampered);
+            accounts.put(receipt_address(), &tampered);
+            let report = verify_deployment(&accounts, &target()).unwrap();
+            assert_eq!(
+                report.issues,
+                [DeploymentIssue::FieldMismatch { account: DeploymentAccount::Receipt, field }]
+            );
+        }
+    }
+
+    #[test]
+    fn receipt_with_wrong_owner_is_reported() {
+        let mut accounts = deployment();
+        accounts.0.get_mut(&receipt_address()).unwrap().owner = key(40);
+        let report = verify_deployment(&accounts, &target()).unwrap();
+        assert_eq!(
+            report.issues,
+            [DeploymentIssue::UnexpectedOwner {
+                account: DeploymentAccount::Receipt,
+                address: receipt_address(),
+                owner: key(40),
+                expected_owner: grant_program::ID,
+            }]
+        );
+    }
+
+    #[test]
+    fn configured_address_that_differs_from_derivation_is_reported() {
+        let mut target = target();
+        let (grant_address, _) = pda::grant(&key(1), &key(2), GRANT_ID);
+        let (unsalted, _) = pda::pop_state(&grant_address, &[0; SEED_SALT_LEN]);
+        target.configured.pop_state = Some(unsalted);
+        let report = verify_deployment(&deployment(), &target).unwrap();
+        assert_eq!(
+            report.issues,
+            [DeploymentIssue::ConfiguredMismatch {
+                account: DeploymentAccount::PopState,
+                configured: unsalted,
+                derived: pda::pop_state(&grant_address, &SALT).0,
+            }]
+        );
+    }
+}
@@ -0,0 +1,491 @@
+// ===== claim 命令のエンコード =====
+// 命令データ = sha256("global:<name>")[..8] + borsh(args)。
+// claim は実行中のトップレベル命令の直前にある ed25519 precompile 命令から PoP 署名を読むため、
+// CPI で呼ぶ場合も、呼び出し元プログラムの命令の直前に ed25519 命令を置くこと。
+// claimer は署名者（PDA の場合は invoke_signed）で、receipt / pop_state の rent を支払う。
+
+use solana_program::instruction::{AccountMeta, Instruction};
+use solana_program::pubkey::Pubkey;
+use solana_program::sysvar;
+
+use crate::{pda, ID};
+
+pub const CLAIM_GRANT_DISCRIMINATOR: [u8; 8] = [125, 134, 233, 135, 82, 18, 177, 8];
+pub const CLAIM_GRANT_WITH_PROOF_DISCRIMINATOR: [u8; 8] = [2, 28, 65, 86, 182, 174, 167, 53];
+pub const CLAIM_GRANT_UNWRAP_SOL_DISCRIMINATOR: [u8; 8] = [60, 46, 51, 106, 128, 8, 27, 209];
+pub const VERIFY_POP_PROOF_DISCRIMINATOR: [u8; 8] = [64, 86, 233, 64, 253, 41, 44, 56];
+
+pub const TOKEN_PROGRAM_ID: Pubkey = solana_program::pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+pub const SYSTEM_PROGRAM_ID: Pubkey = solana_program::pubkey!("11111111111111111111111111111111");
+
+/// ClaimGrant のアカウント順序（この順で AccountMeta / AccountInfo を並べる）
+pub mod accounts {
+    pub const GRANT: usize = 0;
+    pub const VAULT: usize = 1;
+    pub const CLAIMER: usize = 2;
+    pub const CLAIMER_ATA: usize = 3;
+    pub const RECEIPT: usize = 4;
+    pub const POP_STATE: usize = 5;
+    pub const POP_CONFIG: usize = 6;
+    pub const INSTRUCTIONS_SYSVAR: usize = 7;
+    pub const TOKEN_PROGRAM: usize = 8;
+    pub const SYSTEM_PROGRAM: usize = 9;
+    pub const RENT: usize = 10;
+    pub const CLAIMER_PROFILE: usize = 11;
+    pub const PERIOD_STATS: usize = 12;
+    pub const TREASURY_CONFIG: usize = 13;
+    // 以下 optional（不要なものはプログラム ID を渡す）
+    pub const RATE_CONFIG: usize = 14;
+    pub const GATE_ATTESTATION: usize = 15;
+    pub const PLATFORM_FEE_ACCOUNT: usize = 16;
+    pub const CLAIM_STREAK: usize = 17;
+    pub const ESCROW_SCHEDULE: usize = 18;
+    pub const TREASURY: usize = 19;
+    pub const FEE_PAYER: usize = 20;
+    pub const SESSION_AUTHORIZATION: usize = 21;
+    pub const STATEMENT_LOG: usize = 22;
+    pub const INTENT_CLAIMER: usize = 23;
+    pub const PENDING_CLAIM: usize = 24;
+    pub const ATTESTATION_PROGRAM: usize = 25;
+    pub const ATTESTATION_RECORD: usize = 26;
+    pub const HOLDING_TOKEN_ACCOUNT: usize = 27;
+    pub const ENROLLMENT_PROFILE: usize = 28;
+    pub const MINT: usize = 29;
+    pub const DESTINATION_OPT_OUT: usize = 30;
+    pub const BUDGET_FORECAST: usize = 31;
+    pub const CLAIM_INDEX: usize = 32;
+    pub const STAKE_RECORD: usize = 33;
+    pub const CLAIMER_MEMO: usize = 34;
+    pub const LEN: usize = 35;
+    // LEN 以降に TreasuryConfig.fee_splits の受取先（remaining accounts、writable）
+}
+
+/// claim 系命令に渡すアカウント
+/// optional は Grant の設定（円建て / gate / platform fee / streak・最小間隔 / escrow）に応じて指定する
+pub struct ClaimAccounts {
+    pub grant: Pubkey,
+    /// grant.authority（現在の authority。PoP 設定はこの単位）
+    pub authority: Pubkey,
+    /// grant.seed_salt（receipt / pop_state の seed。set_seed_salt 未実行なら [0; 16]）
+    pub seed_salt: [u8; pda::SEED_SALT_LEN],
+    /// GrantHeader.vault（mint 切り替え後は pda::vault と異なる）
+    pub vault: Pubkey,
+    /// 受給者（session_key / relayer を使う場合も受給者本人のアドレス）
+    pub claimer: Pubkey,
+    pub claimer_ata: Pubkey,
+    pub rate_config: Option<Pubkey>,
+    pub gate_attestation: Option<Pubkey>,
+    pub platform_fee_account: Option<Pubkey>,
+    pub claim_streak: Option<Pubkey>,
+    pub escrow_schedule: Option<Pubkey>,
+    /// TreasuryConfig.treasury（claim 手数料が設定されている場合）
+    pub treasury: Option<Pubkey>,
+    /// claim 手数料を立て替える署名者（省略時は claimer）
+    pub fee_payer: Option<Pubkey>,
+    /// TreasuryConfig.fee_splits の受取先（fee_splits と同じ順）
+    pub fee_recipients: Vec<Pubkey>,
+    /// authorize_session で登録した session key（指定時は claimer の代わりに署名者になる）
+    pub session_key: Option<Pubkey>,
+    /// open_statement_log で作成した受給者の StatementLog（指定時は今回の受給が記録される）
+    pub statement_log: Option<Pubkey>,
+    /// claim intent を送る relayer（指定時は relayer が署名者になり、受給者は署名しない）
+    /// 受給者が署名した intent の ed25519 命令を PoP の ed25519 命令の直前に置くこと
+    pub relayer: Option<Pubkey>,
+    /// schedule_claim で予約された claim を実行する keeper（指定時は keeper が署名者になり、受給者は署名しない）
+    pub keeper: Option<Pubkey>,
+    /// grant.attestation_program と、そのプログラムが決める発行先（受給アテステーションが有効な場合）
+    pub attestation: Option<(Pubkey, Pubkey)>,
+    /// 受給者が保有する grant.holding_mint のトークンアカウント（受給資格の述語に token-holding がある場合）
+    pub holding_token_account: Option<Pubkey>,
+    /// grant.enrollment_grant（受給資格の述語に enrollment がある場合。受給者の ClaimerProfile を渡す）
+    pub enrollment_grant: Option<Pubkey>,
+    /// grant.mint（凍結された受給先を claim 時に解凍する場合。mint の freeze authority が Grant PDA であること）
+    pub mint: Option<Pubkey>,
+    /// 受給者が opt_out_canonical_destination 済みで、claimer_ata に ATA 以外を使う場合 true
+    pub destination_opt_out: bool,
+    /// open_budget_forecast 済みの Grant の場合 true（claim ごとに BudgetForecast が再計算される）
+    pub budget_forecast: bool,
+    /// 受給者が open_claim_index 済みの場合 true（今回の期間が ClaimIndex に記録される）
+    pub claim_index: bool,
+    /// grant.stake_program が所有する受給者のステーク記録（ステーク期間の倍率を受ける場合）
+    pub stake_record: Option<Pubkey>,
+    /// 受給者宛ての ClaimerMemo がある場合 true（未配信なら claim で配信される）
+    pub claimer_memo: bool,
+}
+
+impl ClaimAccounts {
+    /// accounts モジュールの順序どおりの AccountMeta
+    pub fn to_account_metas(&self, period_index: u64) -> Vec<AccountMeta> {
+        let (receipt, _) = pda::receipt(&self.grant, &self.claimer, period_index, &self.seed_salt);
+        let (pop_state, _) = pda::pop_state(&self.grant, &self.seed_salt);
+        let (pop_config, _) = pda::pop_config(&self.authority);
+        let (claimer_profile, _) = pda::claimer_profile(&self.grant, &self.claimer);
+        let (period_stats, _) = pda::period_stats(&self.grant, period_index);
+        let (treasury_config, _) = pda::treasury_config();
+        let signer = self
+            .session_key
+            .or(self.relayer)
+            .or(self.keeper)
+            .unwrap_or(self.claimer);
+        let session_authorization = self
+            .session_key
+            .map(|_| pda::session_authorization(&self.grant, &self.claimer).0);
+        let pending_claim = self
+            .keeper
+            .map(|_| pda::pending_claim(&self.grant, &self.claimer, period_index).0);
+        let destination_opt_out = self
+            .destination_opt_out
+            .then(|| pda::destination_opt_out(&self.grant, &self.claimer).0);
+        let enrollment_profile = self
+            .enrollment_grant
+            .map(|enrollment_grant| pda::claimer_profile(&enrollment_grant, &self.claimer).0);
+
+        // Anchor の optional アカウントは、未指定をプログラム ID（readonly）で表す
+        let optional = |key: Option<Pubkey>, writable: bool| match key {
+            Some(key) if writable => AccountMeta::new(key, false),
+            Some(key) => AccountMeta::new_readonly(key, false),
+            None => AccountMeta::new_readonly(ID, false),
+        };
+
+        let mut metas = vec![
+            AccountMeta::new(self.grant, false),
+            AccountMeta::new(self.vault, false),
+            AccountMeta::new(signer, true),
+            AccountMeta::new(self.claimer_ata, false),
+            AccountMeta::new(receipt, false),
+            AccountMeta::new(pop_state, false),
+            AccountMeta::new_readonly(pop_config, false),
+            AccountMeta::new_readonly(sysvar::instructions::ID, false),
+            AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+            AccountMeta::new_readonly(sysvar::rent::ID, false),
+            AccountMeta::new(claimer_profile, false),
+            AccountMeta::new(period_stats, false),
+            AccountMeta::new_readonly(treasury_config, false),
+            optional(self.rate_config, false),
+            optional(self.gate_attestation, false),
+            optional(self.platform_fee_account, true),
+            optional(self.claim_streak, true),
+            optional(self.escrow_schedule, true),
+            optional(self.treasury, true),
+            match self.fee_payer {
+                Some(fee_payer) => AccountMeta::new(fee_payer, true),
+                None => AccountMeta::new_readonly(ID, false),
+            },
+            optional(session_authorization, false),
+            optional(self.statement_log, true),
+            optional(self.relayer.map(|_| self.claimer), false),
+            optional(pending_claim, true),
+            optional(self.attestation.map(|(program, _)| program), false),
+            optional(self.attestation.map(|(_, record)| record), true),
+            optional(self.holding_token_account, false),
+            optional(enrollment_profile, false),
+            optional(self.mint, false),
+            optional(destination_opt_out, false),
+            optional(self.budget_forecast.then(|| pda::budget_forecast(&self.grant).0), true),
+            optional(self.claim_index.then(|| pda::claim_index(&self.grant, &self.claimer).0), true),
+            optional(self.stake_record, false),
+            optional(self.claimer_memo.then(|| pda::claimer_memo(&self.grant, &self.claimer).0), true),
+        ];
+        metas.extend(self.fee_recipients.iter().map(|recipient| AccountMeta::new(*recipient, false)));
+        metas
+    }
+}
+
+/// borsh の Vec<[u8; 32]>（u32 長 + 要素）
+fn encode_proof(data: &mut Vec<u8>, proof: &[[u8; 32]]) {
+    data.extend_from_slice(&(proof.len() as u32).to_le_bytes());
+    for node in proof {
+        data.extend_from_slice(node);
+    }
+}
+
+pub fn claim_grant_data(period_index: u64) -> Vec<u8> {
+    let mut data = Vec::with_capacity(8 + 8);
+    data.extend_from_slice(&CLAIM_GRANT_DISCRIMINATOR);
+    data.extend_from_slice(&period_index.to_le_bytes());
+    data
+}
+
+pub fn claim_grant_with_proof_data(period_index: u64, proof: &[[u8; 32]]) -> Vec<u8> {
+    let mut data = Vec::with_capacity(8 + 8 + 4 + proof.len() * 32);
+    data.extend_from_slice(&CLAIM_GRANT_WITH_PROOF_DISCRIMINATOR);
+    data.extend_from_slice(&period_index.to_le_bytes());
+    encode_proof(&mut data, proof);
+    data
+}
+
+pub fn claim_grant_unwrap_sol_data(period_index: u64, proof: &[[u8; 32]]) -> Vec<u8> {
+    let mut data = Vec::with_capacity(8 + 8 + 4 + proof.len() * 32);
+    data.extend_from_slice(&CLAIM_GRANT_UNWRAP_SOL_DISCRIMINATOR);
+    data.extend_from_slice(&period_index.to_le_bytes());
+    encode_proof(&mut data, proof);
+    data
+}
+
+/// 受給（allowlist 無効の Grant）
+pub fn claim_grant(accounts: &ClaimAccounts, period_index: u64) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(period_index),
+        data: claim_grant_data(period_index),
+    }
+}
+
+/// 受給（allowlist 有効の Grant、Merkle proof 付き）
+pub fn claim_grant_with_proof(accounts: &ClaimAccounts, period_index: u64, proof: &[[u8; 32]]) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(period_index),
+        data: claim_grant_with_proof_data(period_index, proof),
+    }
+}
+
+/// verify_pop_proof の戻り値（get_return_data で受け取る）
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PopProofVerification {
+    pub version: u8,
+    pub signer: Pubkey,
+    pub entry_hash: [u8; 32],
+    pub issued_at: i64,
+    /// PopState の最新ヘッドと一致する（pop_state を渡さない場合は false）
+    pub is_current_head: bool,
+}
+
+impl PopProofVerification {
+    pub const LEN: usize = 1 + 32 + 32 + 8 + 1;
+
+    pub fn unpack(data: &[u8]) -> Option<Self> {
+        if data.len() < Self::LEN {
+            return None;
+        }
+        Some(Self {
+            version: data[0],
+            signer: Pubkey::new_from_array(data[1..33].try_into().ok()?),
+            entry_hash: data[33..65].try_into().ok()?,
+            issued_at: i64::from_le_bytes(data[65..73].try_into().ok()?),
+            is_current_head: data[73] != 0,
+        })
+    }
+}
+
+pub fn verify_pop_proof_data(claimer: &Pubkey, period_index: u64) -> Vec<u8> {
+    let mut data = Vec::with_capacity(8 + 32 + 8);
+    data.extend_from_slice(&VERIFY_POP_PROOF_DISCRIMINATOR);
+    data.extend_from_slice(claimer.as_ref());
+    data.extend_from_slice(&period_index.to_le_bytes());
+    data
+}
+
+/// PoP proof を claim せずに検証する（直前に ed25519 命令を置くこと）
+/// - authority: grant.authority（PopConfig はこの単位）
+/// - with_pop_state: Some(grant.seed_salt) なら proof が現在のチェーンヘッドかも返す
+pub fn verify_pop_proof(
+    grant: &Pubkey,
+    authority: &Pubkey,
+    claimer: &Pubkey,
+    period_index: u64,
+    with_pop_state: Option<&[u8; pda::SEED_SALT_LEN]>,
+) -> Instruction {
+    let (pop_config, _) = pda::pop_config(authority);
+    let pop_state = with_pop_state.map_or(ID, |seed_salt| pda::pop_state(grant, seed_salt).0);
+    Instruction {
+        program_id: ID,
+        accounts: vec![
+            AccountMeta::new_readonly(*grant, false),
+            AccountMeta::new_readonly(pop_config, false),
+            AccountMeta::new_readonly(sysvar::instructions::ID, false),
+            AccountMeta::new_readonly(pop_state, false),
+        ],
+        data: verify_pop_proof_data(claimer, period_index),
+    }
+}
+
+/// 受給して wSOL をアンラップ（native mint の Grant のみ）
+pub fn claim_grant_unwrap_sol(accounts: &ClaimAccounts, period_index: u64, proof: &[[u8; 32]]) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(period_index),
+        data: claim_grant_unwrap_sol_data(period_index, proof),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::{Discriminator, InstructionData, ToAccountMetas};
+    use grant_program::instruction as ix;
+
+    fn key(n: u8) -> Pubkey {
+        Pubkey::new_from_array([n; 32])
+    }
+
+    /// optional をすべて指定した ClaimAccounts（signer は session key）
+    fn full_claim_accounts() -> ClaimAccounts {
+        ClaimAccounts {
+            grant: key(1),
+            authority: key(2),
+            seed_salt: [7; pda::SEED_SALT_LEN],
+            vault: key(3),
+            claimer: key(4),
+            claimer_ata: key(5),
+            rate_config: Some(key(6)),
+            gate_attestation: Some(key(7)),
+            platform_fee_account: Some(key(8)),
+            claim_streak: Some(key(9)),
+            escrow_schedule: Some(key(10)),
+            treasury: Some(key(11)),
+            fee_payer: Some(key(12)),
+            fee_recipients: vec![key(13), key(14)],
+            session_key: Some(key(15)),
+            statement_log: Some(key(16)),
+            relayer: Some(key(17)),
+            keeper: Some(key(18)),
+            attestation: Some((key(19), key(20))),
+            holding_token_account: Some(key(21)),
+            enrollment_grant: Some(key(22)),
+            mint: Some(key(23)),
+            destination_opt_out: true,
+            budget_forecast: true,
+            claim_index: true,
+            stake_record: Some(key(24)),
+            claimer_memo: true,
+        }
+    }
+
+    fn minimal_claim_accounts() -> ClaimAccounts {
+        ClaimAccounts {
+            rate_config: None,
+            gate_attestation: None,
+            platform_fee_account: None,
+            claim_streak: None,
+            escrow_schedule: None,
+            treasury: None,
+            fee_payer: None,
+            fee_recipients: Vec::new(),
+            session_key: None,
+            statement_log: None,
+            relayer: None,
+            keeper: None,
+            attestation: None,
+            holding_token_account: None,
+            enrollment_grant: None,
+            mint: None,
+            destination_opt_out: false,
+            budget_forecast: false,
+            claim_index: false,
+            stake_record: None,
+            claimer_memo: false,
+            seed_salt: [0; pda::SEED_SALT_LEN],
+            ..full_claim_accounts()
+        }
+    }
+
+    /// プログラム側の ClaimGrant（Anchor の client 用 accounts）で同じアカウントを並べた AccountMeta
+    fn program_metas(metas: &[AccountMeta]) -> Vec<AccountMeta> {
+        let optional = |index: usize| Some(metas[index].pubkey).filter(|key| *key != ID);
+        grant_program::accounts::ClaimGrant {
+            grant: metas[accounts::GRANT].pubkey,
+            vault: metas[accounts::VAULT].pubkey,
+            claimer: metas[accounts::CLAIMER].pubkey,
+            claimer_ata: metas[accounts::CLAIMER_ATA].pubkey,
+            receipt: metas[accounts::RECEIPT].pubkey,
+            pop_state: metas[accounts::POP_STATE].pubkey,
+            pop_config: metas[accounts::POP_CONFIG].pubkey,
+            instructions_sysvar: metas[accounts::INSTRUCTIONS_SYSVAR].pubkey,
+            token_program: metas[accounts::TOKEN_PROGRAM].pubkey,
+            system_program: metas[accounts::SYSTEM_PROGRAM].pubkey,
+            rent: metas[accounts::RENT].pubkey,
+            claimer_profile: metas[accounts::CLAIMER_PROFILE].pubkey,
+            period_stats: metas[accounts::PERIOD_STATS].pubkey,
+            treasury_config: metas[accounts::TREASURY_CONFIG].pubkey,
+            rate_config: optional(accounts::RATE_CONFIG),
+            gate_attestation: optional(accounts::GATE_ATTESTATION),
+            platform_fee_account: optional(accounts::PLATFORM_FEE_ACCOUNT),
+            claim_streak: optional(accounts::CLAIM_STREAK),
+            escrow_schedule: optional(accounts::ESCROW_SCHEDULE),
+            treasury: optional(accounts::TREASURY),
+            fee_payer: optional(accounts::FEE_PAYER),
+            session_authorization: optional(accounts::SESSION_AUTHORIZATION),
+            statement_log: optional(accounts::STATEMENT_LOG),
+            intent_claimer: optional(accounts::INTENT_CLAIMER),
+            pending_claim: optional(accounts::PENDING_CLAIM),
+            attestation_program: optional(accounts::ATTESTATION_PROGRAM),
+            attestation_record: optional(accounts::ATTESTATION_RECORD),
+            holding_token_account: optional(accounts::HOLDING_TOKEN_ACCOUNT),
+            enrollment_profile: optional(accounts::ENROLLMENT_PROFILE),
+            mint: optional(accounts::MINT),
+            destination_opt_out: optional(accounts::DESTINATION_OPT_OUT),
+            budget_forecast: optional(accounts::BUDGET_FORECAST),
+            claim_index: optional(accounts::CLAIM_INDEX),
+            stake_record: optional(accounts::STAKE_RECORD),
+            claimer_memo: optional(accounts::CLAIMER_MEMO),
+        }
+        .to_account_metas(None)
+    }
+
+    #[test]
+    fn discriminators_match_program() {
+        assert_eq!(CLAIM_GRANT_DISCRIMINATOR, ix::ClaimGrant::DISCRIMINATOR);
+        assert_eq!(CLAIM_GRANT_WITH_PROOF_DISCRIMINATOR, ix::ClaimGrantWithProof::DISCRIMINATOR);
+        assert_eq!(CLAIM_GRANT_UNWRAP_SOL_DISCRIMINATOR, ix::ClaimGrantUnwrapSol::DISCRIMINATOR);
+        assert_eq!(VERIFY_POP_PROOF_DISCRIMINATOR, ix::VerifyPopProof::DISCRIMINATOR);
+    }
+
+    #[test]
+    fn instruction_data_matches_program() {
+        let proof = vec![[1u8; 32], [2u8; 32]];
+        assert_eq!(claim_grant_data(5), ix::ClaimGrant { period_index: 5 }.data());
+        assert_eq!(
+            claim_grant_with_proof_data(5, &proof),
+            ix::ClaimGrantWithProof { period_index: 5, proof: proof.clone() }.data()
+        );
+        assert_eq!(
+            claim_grant_with_proof_data(5, &[]),
+            ix::ClaimGrantWithProof { period_index: 5, proof: Vec::new() }.data()
+        );
+        assert_eq!(
+            claim_grant_unwrap_sol_data(u64::MAX, &proof),
+            ix::ClaimGrantUnwrapSol { period_index: u64::MAX, proof }.data()
+        );
+        assert_eq!(
+            verify_pop_proof_data(&key(4), 9),
+            ix::VerifyPopProof { claimer: key(4), period_index: 9 }.data()
+        );
+    }
+
+    #[test]
+    fn claim_account_order_matches_program() {
+        for claim_accounts in [full_claim_accounts(), minimal_claim_accounts()] {
+            let metas = claim_accounts.to_account_metas(3);
+            assert_eq!(metas.len(), accounts::LEN + claim_accounts.fee_recipients.len());
+            assert_eq!(metas[..accounts::LEN], program_metas(&metas)[..]);
+            assert!(metas[accounts::LEN..].iter().all(|meta| meta.is_writable && !meta.is_signer));
+        }
+    }
+
+    #[test]
+    fn claim_accounts_use_salted_pdas() {
+        let claim_accounts = full_claim_accounts();
+        let metas = claim_accounts.to_account_metas(3);
+        let (receipt, _) = grant_program::receipt::receipt_address(
+            &claim_accounts.grant,
+            &claim_accounts.claimer,
+            3,
+            &claim_accounts.seed_salt,
+        );
+        assert_eq!(metas[accounts::RECEIPT].pubkey, receipt);
+        assert_eq!(
+            metas[accounts::POP_STATE].pubkey,
+            Pubkey::find_program_address(
+                &[b"pop-state", claim_accounts.grant.as_ref(), &claim_accounts.seed_salt],
+                &ID
+            )
+            .0
+        );
+        // session key が署名者になり、受給者本人は intent_claimer として渡る
+        assert_eq!(metas[accounts::CLAIMER].pubkey, key(15));
+        assert!(metas[accounts::CLAIMER].is_signer);
+        assert_eq!(metas[accounts::INTENT_CLAIMER].pubkey, claim_accounts.claimer);
+    }
+}
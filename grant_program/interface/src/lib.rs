@@ -0,0 +1,21 @@
+// ===== we-ne grant program interface =====
+// Anchor に依存できないプログラム（Pinocchio など）から claim / verify_pop_proof を CPI し、receipt を読むための最小クレート。
+// 依存は solana-program のみ。discriminator・アカウント順序・レイアウトはプログラム側と手で一致させているため、
+// grant_program の ClaimGrant / ClaimReceipt / Grant 先頭フィールドを変更したらここも更新すること。
+// merkle モジュールだけは例外で、プログラム側がこのクレートの実装をそのまま使う。
+
+pub mod instruction;
+pub mod merkle;
+pub mod pda;
+pub mod state;
+
+solana_program::declare_id!("GZcUoGHk8SfAArTKicL1jiRHZEQa3EuzgYcC2u4yWfSR");
+
+pub use instruction::{
+    claim_grant, claim_grant_unwrap_sol, claim_grant_with_proof, verify_pop_proof, ClaimAccounts, PopProofVerification,
+};
+pub use merkle::{
+    allowlist_leaf, allowlist_leaf_in, audit_anchor_leaf, receipt_leaf, verify_merkle_sorted, MerkleProof,
+    DEFAULT_HASH_DOMAIN,
+};
+pub use state::{ClaimReceipt, GrantHeader, ReceiptStatus};
@@ -0,0 +1,251 @@
+// ===== Merkle 検証 =====
+// grant_program が allowlist / audit anchor の検証に使う実装そのもの（プログラムはこのモジュールを呼ぶ）。
+// 他の distributor が同じ木を検証する場合も、コピーせずにこのモジュールを使うこと。
+// 葉は用途ごとに domain separation したハッシュ、内部ノードは sha256(left || right)。
+// 左右の決め方は MerkleProof の2形式から選ぶ（we-ne の allowlist / audit anchor は SortedPair）。
+
+use solana_program::hash::hashv;
+use solana_program::pubkey::Pubkey;
+
+/// 既定のハッシュの domain（white-label のデプロイは grant_program の initialize_hash_domain で変更する）
+pub const DEFAULT_HASH_DOMAIN: &[u8] = b"we-ne";
+
+/// Domain-separated leaf hash for allowlist membership.
+/// leaf = sha256( "we-ne:allowlist" || claimer_pubkey )
+pub fn allowlist_leaf(claimer: &Pubkey) -> [u8; 32] {
+    allowlist_leaf_in(DEFAULT_HASH_DOMAIN, claimer)
+}
+
+/// allowlist_leaf を Grant.hash_domain を指定して計算する
+/// leaf = sha256( domain || ":allowlist" || claimer_pubkey )
+pub fn allowlist_leaf_in(domain: &[u8], claimer: &Pubkey) -> [u8; 32] {
+    hashv(&[domain, b":allowlist", claimer.as_ref()]).to_bytes()
+}
+
+/// Domain-separated leaf hash for audit anchoring.
+/// leaf = sha256( "we-ne:audit" || audit_hash )
+pub fn audit_anchor_leaf(audit_hash: &[u8; 32]) -> [u8; 32] {
+    hashv(&[b"we-ne:audit", audit_hash.as_ref()]).to_bytes()
+}
+
+/// Domain-separated leaf hash for grant archives (finalize_archive の receipts_root).
+/// leaf = sha256( "we-ne:receipt" || receipt account data without the 8-byte discriminator )
+/// （data は archive_receipts が Archived に書き換える前の内容）
+pub fn receipt_leaf(receipt_data: &[u8]) -> [u8; 32] {
+    hashv(&[b"we-ne:receipt", receipt_data]).to_bytes()
+}
+
+/// proof の形式（葉から root までの兄弟ノードを葉に近い順に並べる点は共通）
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MerkleProof<'a> {
+    /// Sorted pair hashing (no left/right flag).
+    /// Each step: parent = sha256( min(a,b) || max(a,b) )
+    ///
+    /// IMPORTANT: Off-chain Merkle tree builder must use the same sorted-pair rule.
+    SortedPair(&'a [[u8; 32]]),
+    /// 葉の位置で左右を決める（index の下位ビットから1段ずつ、0 = 計算中のノードが左）
+    /// index は proof の段数で表せる範囲に限る（範囲外は verify で不一致になる）
+    Positional { proof: &'a [[u8; 32]], index: u64 },
+}
+
+impl MerkleProof<'_> {
+    /// 葉から計算した root（Positional で index が範囲外の場合は None）
+    pub fn root(&self, leaf: [u8; 32]) -> Option<[u8; 32]> {
+        match *self {
+            MerkleProof::SortedPair(proof) => Some(sorted_pair_root(leaf, proof)),
+            MerkleProof::Positional { proof, index } => {
+                // 上位ビットが残る index を受け付けると、同じ proof が複数の位置として通ってしまう
+                if proof.len() < 64 && index >> proof.len() != 0 {
+                    return None;
+                }
+                let mut computed = leaf;
+                for (depth, sibling) in proof.iter().enumerate() {
+                    let is_left = depth >= 64 || (index >> depth) & 1 == 0;
+                    let (left, right) = if is_left { (computed, *sibling) } else { (*sibling, computed) };
+                    computed = hashv(&[left.as_ref(), right.as_ref()]).to_bytes();
+                }
+                Some(computed)
+            }
+        }
+    }
+
+    pub fn verify(&self, root: [u8; 32], leaf: [u8; 32]) -> bool {
+        self.root(leaf) == Some(root)
+    }
+}
+
+/// SortedPair 形式の proof で root を計算する（1つの proof を複数の root と突き合わせる場合に使う）
+pub fn sorted_pair_root(leaf: [u8; 32], proof: &[[u8; 32]]) -> [u8; 32] {
+    let mut computed = leaf;
+    for p in proof.iter() {
+        let (left, right) = if computed <= *p { (computed, *p) } else { (*p, computed) };
+        computed = hashv(&[left.as_ref(), right.as_ref()]).to_bytes();
+    }
+    computed
+}
+
+/// SortedPair 形式の proof を検証する
+pub fn verify_merkle_sorted(root: [u8; 32], leaf: [u8; 32], proof: &[[u8; 32]]) -> bool {
+    MerkleProof::SortedPair(proof).verify(root, leaf)
+}
+
+// ===== 追記型の Merkle tree =====
+// 深さ ARCHIVE_TREE_DEPTH の木に左から葉を追加し、未使用の葉は [0; 32] とみなす。
+// frontier[level] は、その段で右の兄弟を待っている左ノード（追加1回あたり平均1回のハッシュで済む）。
+// root は MerkleProof::Positional（index = 追加した順番、proof は ARCHIVE_TREE_DEPTH 段）で検証できる。
+
+/// grant archive の receipts_root の木の深さ
+pub const ARCHIVE_TREE_DEPTH: usize = 32;
+
+/// count 番目の葉として leaf を追加する（count は追加前の葉の数）
+pub fn frontier_append(frontier: &mut [[u8; 32]; ARCHIVE_TREE_DEPTH], count: u64, leaf: [u8; 32]) {
+    let mut node = leaf;
+    let mut level = 0;
+    while level < ARCHIVE_TREE_DEPTH - 1 && (count >> level) & 1 == 1 {
+        node = hashv(&[frontier[level].as_ref(), node.as_ref()]).to_bytes();
+        level += 1;
+    }
+    frontier[level] = node;
+}
+
+/// count 個の葉を追加した frontier の root
+pub fn frontier_root(frontier: &[[u8; 32]; ARCHIVE_TREE_DEPTH], count: u64) -> [u8; 32] {
+    let mut node = [0u8; 32];
+    let mut zero = [0u8; 32];
+    for (level, left) in frontier.iter().enumerate() {
+        node = if (count >> level) & 1 == 1 {
+            hashv(&[left.as_ref(), node.as_ref()]).to_bytes()
+        } else {
+            hashv(&[node.as_ref(), zero.as_ref()]).to_bytes()
+        };
+        zero = hashv(&[zero.as_ref(), zero.as_ref()]).to_bytes();
+    }
+    node
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(s: &str) -> [u8; 32] {
+        let mut out = [0u8; 32];
+        for (byte, pair) in out.iter_mut().zip(s.as_bytes().chunks_exact(2)) {
+            *byte = u8::from_str_radix(std::str::from_utf8(pair).unwrap(), 16).unwrap();
+        }
+        out
+    }
+
+    fn leaves() -> [[u8; 32]; 3] {
+        [1, 2, 3].map(|n| allowlist_leaf(&Pubkey::new_from_array([n; 32])))
+    }
+
+    fn pair(a: [u8; 32], b: [u8; 32]) -> [u8; 32] {
+        hashv(&[a.as_ref(), b.as_ref()]).to_bytes()
+    }
+
+    /// 深さ ARCHIVE_TREE_DEPTH の木を素直に組み立てた root（未使用の葉は [0; 32]）
+    fn naive_archive_root(leaves: &[[u8; 32]]) -> [u8; 32] {
+        let mut level = leaves.to_vec();
+        let mut zero = [0u8; 32];
+        for _ in 0..ARCHIVE_TREE_DEPTH {
+            if level.is_empty() {
+                level.push(zero);
+            }
+            if !level.chunks_exact(2).remainder().is_empty() {
+                level.push(zero);
+            }
+            level = level.chunks_exact(2).map(|p| pair(p[0], p[1])).collect();
+            zero = pair(zero, zero);
+        }
+        level[0]
+    }
+
+    #[test]
+    fn allowlist_leaf_known_vector() {
+        assert_eq!(
+            leaves()[0],
+            hex("5b1345c06846324d84da98f51af98925367a9c8340666b5a734dad2d04515958")
+        );
+    }
+
+    #[test]
+    fn sorted_pair_single_leaf_is_its_own_root() {
+        let leaf = leaves()[0];
+        assert!(verify_merkle_sorted(leaf, leaf, &[]));
+        assert!(!verify_merkle_sorted(leaf, leaves()[1], &[]));
+    }
+
+    #[test]
+    fn sorted_pair_odd_leaf_count() {
+        // 3枚の木: 3枚目は兄弟がいないのでそのまま上の段に上がる
+        let [a, b, c] = leaves();
+        let root = hex("8736080aac86852d146610b0b649afdda58eafeb3ac480c53987deb156f3f7ba");
+        let ab = sorted_pair_root(a, &[b]);
+        assert!(verify_merkle_sorted(root, a, &[b, c]));
+        assert!(verify_merkle_sorted(root, b, &[a, c]));
+        assert!(verify_merkle_sorted(root, c, &[ab]));
+        // 兄弟を逆に並べても左右は決まるが、葉に近い順でないので root は変わる
+        assert!(!verify_merkle_sorted(root, a, &[c, b]));
+        assert!(!verify_merkle_sorted(root, c, &[a, b]));
+    }
+
+    #[test]
+    fn positional_rejects_wrong_index_and_out_of_range() {
+        let [a, b, _] = leaves();
+        let root = pair(a, b);
+        assert!(MerkleProof::Positional { proof: &[b], index: 0 }.verify(root, a));
+        assert!(MerkleProof::Positional { proof: &[a], index: 1 }.verify(root, b));
+        assert!(!MerkleProof::Positional { proof: &[b], index: 1 }.verify(root, a));
+        assert_eq!(MerkleProof::Positional { proof: &[b], index: 2 }.root(a), None);
+    }
+
+    #[test]
+    fn frontier_single_leaf_known_vector() {
+        let leaf = receipt_leaf(b"abc");
+        assert_eq!(leaf, hex("69f41255c0aef382d48bfe182b3b0974fadbdb9b334c3193055c949ba7b1dd50"));
+        let mut frontier = [[0u8; 32]; ARCHIVE_TREE_DEPTH];
+        frontier_append(&mut frontier, 0, leaf);
+        assert_eq!(
+            frontier_root(&frontier, 1),
+            hex("06a4431b29757237b7d7b502d54bc6834991fab8fe5e7d189a01c170214506ac")
+        );
+    }
+
+    #[test]
+    fn frontier_matches_naive_tree_and_positional_proofs() {
+        let leaves: Vec<[u8; 32]> = (0..7u8).map(|n| receipt_leaf(&[n])).collect();
+        let mut frontier = [[0u8; 32]; ARCHIVE_TREE_DEPTH];
+        assert_eq!(frontier_root(&frontier, 0), naive_archive_root(&[]));
+        for (count, leaf) in leaves.iter().enumerate() {
+            frontier_append(&mut frontier, count as u64, *leaf);
+            assert_eq!(
+                frontier_root(&frontier, count as u64 + 1),
+                naive_archive_root(&leaves[..=count])
+            );
+        }
+
+        // 奇数枚（7枚）の木で、6番目の葉（兄弟はゼロ葉）の Positional proof を組み立てる
+        let root = frontier_root(&frontier, 7);
+        let mut proof = vec![[0u8; 32], pair(leaves[4], leaves[5]), naive_subtree(&leaves[..4])];
+        let mut zero = pair(pair([0; 32], [0; 32]), pair([0; 32], [0; 32]));
+        zero = pair(zero, zero);
+        while proof.len() < ARCHIVE_TREE_DEPTH {
+            proof.push(zero);
+            zero = pair(zero, zero);
+        }
+        assert!(MerkleProof::Positional { proof: &proof, index: 6 }.verify(root, leaves[6]));
+        assert!(!MerkleProof::Positional { proof: &proof, index: 5 }.verify(root, leaves[6]));
+        // 兄弟の並びを入れ替えた proof は通らない
+        proof.swap(1, 2);
+        assert!(!MerkleProof::Positional { proof: &proof, index: 6 }.verify(root, leaves[6]));
+    }
+
+    /// 葉の数が2の冪の部分木の root
+    fn naive_subtree(leaves: &[[u8; 32]]) -> [u8; 32] {
+        let mut level = leaves.to_vec();
+        while level.len() > 1 {
+            level = level.chunks_exact(2).map(|p| pair(p[0], p[1])).collect();
+        }
+        level[0]
+    }
+}
@@ -0,0 +1,119 @@
+// ===== PDA 導出 =====
+// seeds はプログラム側の #[account(seeds = ...)] と一致させること。
+
+use solana_program::pubkey::Pubkey;
+
+use crate::ID;
+
+/// seed_authority / seed_mint は作成時の値（authority 移譲・mint 切り替え後も変わらない）
+pub fn grant(seed_authority: &Pubkey, seed_mint: &Pubkey, grant_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"grant", seed_authority.as_ref(), seed_mint.as_ref(), &grant_id.to_le_bytes()],
+        &ID,
+    )
+}
+
+/// 作成時の vault（mint 切り替え後は migrated_vault、rotate_vault 後は rotated_vault）
+pub fn vault(grant: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"vault", grant.as_ref()], &ID)
+}
+
+pub fn migrated_vault(grant: &Pubkey, mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"vault", grant.as_ref(), mint.as_ref()], &ID)
+}
+
+pub fn rotated_vault(grant: &Pubkey, vault_nonce: u32) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"vault", grant.as_ref(), &vault_nonce.to_le_bytes()], &ID)
+}
+
+/// Grant.seed_salt（set_seed_salt 未実行なら [0; 16]）が全 0 の場合、salt の seed は空になる
+pub const SEED_SALT_LEN: usize = 16;
+
+fn salt_seed(seed_salt: &[u8; SEED_SALT_LEN]) -> &[u8] {
+    if *seed_salt == [0u8; SEED_SALT_LEN] {
+        &[]
+    } else {
+        seed_salt
+    }
+}
+
+pub fn receipt(grant: &Pubkey, claimer: &Pubkey, period_index: u64, seed_salt: &[u8; SEED_SALT_LEN]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            b"receipt",
+            grant.as_ref(),
+            claimer.as_ref(),
+            &period_index.to_le_bytes(),
+            salt_seed(seed_salt),
+        ],
+        &ID,
+    )
+}
+
+/// PoP signer 設定（現在の authority 単位）
+pub fn pop_config(authority: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"pop-config", authority.as_ref()], &ID)
+}
+
+pub fn pop_state(grant: &Pubkey, seed_salt: &[u8; SEED_SALT_LEN]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"pop-state", grant.as_ref(), salt_seed(seed_salt)], &ID)
+}
+
+pub fn claimer_profile(grant: &Pubkey, claimer: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"claimer-profile", grant.as_ref(), claimer.as_ref()], &ID)
+}
+
+pub fn period_stats(grant: &Pubkey, period_index: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"period-stats", grant.as_ref(), &period_index.to_le_bytes()], &ID)
+}
+
+pub fn treasury_config() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"treasury-config"], &ID)
+}
+
+pub fn rate_config(authority: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"rate-config", authority.as_ref()], &ID)
+}
+
+pub fn claim_streak(grant: &Pubkey, claimer: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"streak", grant.as_ref(), claimer.as_ref()], &ID)
+}
+
+pub fn session_authorization(grant: &Pubkey, claimer: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"session", grant.as_ref(), claimer.as_ref()], &ID)
+}
+
+/// 受給者が ATA 以外の受給先を許可した記録（claim に destination_opt_out を渡す場合）
+pub fn destination_opt_out(grant: &Pubkey, claimer: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"destination-opt-out", grant.as_ref(), claimer.as_ref()], &ID)
+}
+
+pub fn pending_claim(grant: &Pubkey, claimer: &Pubkey, period_index: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"pending-claim", grant.as_ref(), claimer.as_ref(), &period_index.to_le_bytes()],
+        &ID,
+    )
+}
+
+pub fn statement_log(grant: &Pubkey, claimer: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"statement", grant.as_ref(), claimer.as_ref()], &ID)
+}
+
+pub fn escrow_schedule(grant: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"escrow-schedule", grant.as_ref()], &ID)
+}
+
+/// 受給済み期間の索引（open_claim_index 済みの受給者の claim に渡す）
+pub fn claim_index(grant: &Pubkey, claimer: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"claim-index", grant.as_ref(), claimer.as_ref()], &ID)
+}
+
+/// authority から受給者への暗号化メモ（attach_claimer_memo 済みの受給者の claim に渡す）
+pub fn claimer_memo(grant: &Pubkey, claimer: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"claimer-memo", grant.as_ref(), claimer.as_ref()], &ID)
+}
+
+/// 支出見込み（open_budget_forecast 済みの Grant の claim に渡す）
+pub fn budget_forecast(grant: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"budget-forecast", grant.as_ref()], &ID)
+}
@@ -0,0 +1,346 @@
+// ===== state レイアウト =====
+// アカウントデータ = discriminator（sha256("account:<Name>")[..8]）+ borsh（固定長フィールドのみ）。
+// unpack はバイト列だけを見るため、呼び出し側で owner == crate::ID を確認すること。
+
+use solana_program::program_error::ProgramError;
+use solana_program::pubkey::Pubkey;
+
+pub const CLAIM_RECEIPT_DISCRIMINATOR: [u8; 8] = [223, 233, 11, 229, 124, 165, 207, 28];
+pub const GRANT_DISCRIMINATOR: [u8; 8] = [161, 166, 11, 205, 204, 135, 205, 54];
+
+const DISCRIMINATOR_LEN: usize = 8;
+
+/// 固定オフセットのフィールド読み出し
+struct Reader<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: &'a [u8], discriminator: &[u8; 8], len: usize) -> Result<Self, ProgramError> {
+        if data.len() < DISCRIMINATOR_LEN + len || &data[..DISCRIMINATOR_LEN] != discriminator {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        Ok(Self {
+            data,
+            offset: DISCRIMINATOR_LEN,
+        })
+    }
+
+    fn bytes<const N: usize>(&mut self) -> [u8; N] {
+        let mut out = [0u8; N];
+        out.copy_from_slice(&self.data[self.offset..self.offset + N]);
+        self.offset += N;
+        out
+    }
+
+    fn pubkey(&mut self) -> Pubkey {
+        Pubkey::new_from_array(self.bytes())
+    }
+
+    fn u64(&mut self) -> u64 {
+        u64::from_le_bytes(self.bytes())
+    }
+
+    fn i64(&mut self) -> i64 {
+        i64::from_le_bytes(self.bytes())
+    }
+
+    fn bool(&mut self) -> bool {
+        self.bytes::<1>()[0] != 0
+    }
+}
+
+/// 受給記録の状態（プログラム側の ReceiptStatus と同じ並び）
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReceiptStatus {
+    Valid,
+    Disputed,
+    Revoked,
+    Refunded,
+    /// 期間前に予約された未受給の receipt（受給記録ではない）
+    Reserved,
+    /// archive_receipts で grant archive に記録された（finalize 後に close される）
+    Archived,
+    /// 移行前にプログラムの外で支給済み（record_historical_claim で記録。送金はない）
+    OffChainSettled,
+}
+
+impl ReceiptStatus {
+    fn from_u8(value: u8) -> Result<Self, ProgramError> {
+        match value {
+            0 => Ok(Self::Valid),
+            1 => Ok(Self::Disputed),
+            2 => Ok(Self::Revoked),
+            3 => Ok(Self::Refunded),
+            4 => Ok(Self::Reserved),
+            5 => Ok(Self::Archived),
+            6 => Ok(Self::OffChainSettled),
+            _ => Err(ProgramError::InvalidAccountData),
+        }
+    }
+}
+
+/// 受給記録（status が Reserved 以外で存在する = その期間は受給済み。ただし後から取り消されることがある）
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ClaimReceipt {
+    pub grant: Pubkey,
+    pub claimer: Pubkey,
+    pub period_index: u64,
+    pub claimed_at: i64,
+    pub bump: u8,
+    pub status: ReceiptStatus,
+    /// claimer に支給した額（platform fee は含まない）
+    pub amount: u64,
+    /// PoP signer 障害時の緊急期間中に PoP なしで受給した
+    pub pop_bypassed: bool,
+    /// 作成時の Grant.seed_salt（PDA の seed）
+    pub seed_salt: [u8; 16],
+}
+
+impl ClaimReceipt {
+    pub const LEN: usize = 32 + 32 + 8 + 8 + 1 + 1 + 8 + 1 + 16;
+
+    pub fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        let mut reader = Reader::new(data, &CLAIM_RECEIPT_DISCRIMINATOR, Self::LEN)?;
+        Ok(Self {
+            grant: reader.pubkey(),
+            claimer: reader.pubkey(),
+            period_index: reader.u64(),
+            claimed_at: reader.i64(),
+            bump: reader.bytes::<1>()[0],
+            status: ReceiptStatus::from_u8(reader.bytes::<1>()[0])?,
+            amount: reader.u64(),
+            pop_bypassed: reader.bool(),
+            seed_salt: reader.bytes(),
+        })
+    }
+
+    /// 支給が有効（予約のみ・異議申し立て中・取り消し済みでない）
+    pub fn is_valid(&self) -> bool {
+        self.status == ReceiptStatus::Valid
+    }
+
+    /// owner・PDA アドレス・内容が (grant, claimer, period_index) と一致することを確認して読み出す
+    /// 他プログラムで「この期間に受給済み」を条件にする場合に使う（status は is_valid で確認する）
+    pub fn verify(
+        address: &Pubkey,
+        owner: &Pubkey,
+        data: &[u8],
+        grant: &Pubkey,
+        claimer: &Pubkey,
+        period_index: u64,
+    ) -> Result<Self, ProgramError> {
+        if *owner != crate::ID {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let receipt = Self::unpack(data)?;
+        if receipt.grant != *grant || receipt.claimer != *claimer || receipt.period_index != period_index {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        // 保存済みの salt / bump で再計算する（find_program_address の探索を省く）
+        let salt: &[u8] = if receipt.seed_salt == [0u8; 16] { &[] } else { &receipt.seed_salt };
+        let expected = Pubkey::create_program_address(
+            &[
+                b"receipt",
+                grant.as_ref(),
+                claimer.as_ref(),
+                &period_index.to_le_bytes(),
+                salt,
+                &[receipt.bump],
+            ],
+            &crate::ID,
+        )
+        .map_err(|_| ProgramError::InvalidSeeds)?;
+        if *address != expected {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        Ok(receipt)
+    }
+}
+
+/// Grant の先頭フィールド（レイアウトが固定されている範囲のみ）
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GrantHeader {
+    pub authority: Pubkey,
+    pub mint: Pubkey,
+    pub vault: Pubkey,
+    pub grant_id: u64,
+    pub amount_per_period: u64,
+    pub period_seconds: i64,
+    pub start_ts: i64,
+    /// 0 = 期限なし
+    pub expires_at: i64,
+    /// [0;32] = allowlist 無効
+    pub merkle_root: [u8; 32],
+    pub paused: bool,
+    pub bump: u8,
+    /// GRANT_CATEGORY_*（0 = 未分類）
+    pub category: u16,
+}
+
+impl GrantHeader {
+    pub const LEN: usize = 32 + 32 + 32 + 8 + 8 + 8 + 8 + 8 + 32 + 1 + 1 + 2;
+
+    pub fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        let mut reader = Reader::new(data, &GRANT_DISCRIMINATOR, Self::LEN)?;
+        Ok(Self {
+            authority: reader.pubkey(),
+            mint: reader.pubkey(),
+            vault: reader.pubkey(),
+            grant_id: reader.u64(),
+            amount_per_period: reader.u64(),
+            period_seconds: reader.i64(),
+            start_ts: reader.i64(),
+            expires_at: reader.i64(),
+            merkle_root: reader.bytes(),
+            paused: reader.bool(),
+            bump: reader.bytes::<1>()[0],
+            category: u16::from_le_bytes(reader.bytes()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::{AccountSerialize, Discriminator, Space};
+
+    const ALL_STATUSES: [(grant_program::ReceiptStatus, ReceiptStatus); 7] = [
+        (grant_program::ReceiptStatus::Valid, ReceiptStatus::Valid),
+        (grant_program::ReceiptStatus::Disputed, ReceiptStatus::Disputed),
+        (grant_program::ReceiptStatus::Revoked, ReceiptStatus::Revoked),
+        (grant_program::ReceiptStatus::Refunded, ReceiptStatus::Refunded),
+        (grant_program::ReceiptStatus::Reserved, ReceiptStatus::Reserved),
+        (grant_program::ReceiptStatus::Archived, ReceiptStatus::Archived),
+        (grant_program::ReceiptStatus::OffChainSettled, ReceiptStatus::OffChainSettled),
+    ];
+
+    fn program_receipt(status: grant_program::ReceiptStatus, seed_salt: [u8; 16]) -> grant_program::ClaimReceipt {
+        grant_program::ClaimReceipt {
+            grant: Pubkey::new_from_array([1; 32]),
+            claimer: Pubkey::new_from_array([2; 32]),
+            period_index: 0x0102_0304_0506_0708,
+            claimed_at: -42,
+            bump: 254,
+            status,
+            amount: u64::MAX - 7,
+            pop_bypassed: true,
+            seed_salt,
+        }
+    }
+
+    fn serialize<T: AccountSerialize>(account: &T) -> Vec<u8> {
+        let mut data = Vec::new();
+        account.try_serialize(&mut data).unwrap();
+        data
+    }
+
+    #[test]
+    fn discriminators_match_program() {
+        assert_eq!(CLAIM_RECEIPT_DISCRIMINATOR, grant_program::ClaimReceipt::DISCRIMINATOR);
+        assert_eq!(GRANT_DISCRIMINATOR, grant_program::Grant::DISCRIMINATOR);
+    }
+
+    #[test]
+    fn receipt_len_matches_program() {
+        assert_eq!(DISCRIMINATOR_LEN + ClaimReceipt::LEN, grant_program::receipt::RECEIPT_ACCOUNT_LEN);
+        assert_eq!(ClaimReceipt::LEN, grant_program::ClaimReceipt::INIT_SPACE);
+    }
+
+    #[test]
+    fn receipt_round_trips_every_status() {
+        let salt = [9u8; 16];
+        for (program_status, status) in ALL_STATUSES {
+            let data = serialize(&program_receipt(program_status, salt));
+            assert_eq!(data.len(), grant_program::receipt::RECEIPT_ACCOUNT_LEN);
+            let receipt = ClaimReceipt::unpack(&data).unwrap();
+            assert_eq!(
+                receipt,
+                ClaimReceipt {
+                    grant: Pubkey::new_from_array([1; 32]),
+                    claimer: Pubkey::new_from_array([2; 32]),
+                    period_index: 0x0102_0304_0506_0708,
+                    claimed_at: -42,
+                    bump: 254,
+                    status,
+                    amount: u64::MAX - 7,
+                    pop_bypassed: true,
+                    seed_salt: salt,
+                }
+            );
+            assert_eq!(receipt.is_valid(), status == ReceiptStatus::Valid);
+        }
+    }
+
+    #[test]
+    fn receipt_rejects_wrong_discriminator_and_short_data() {
+        let data = serialize(&program_receipt(grant_program::ReceiptStatus::Valid, [0; 16]));
+        let mut wrong = data.clone();
+        wrong[0] ^= 1;
+        assert_eq!(ClaimReceipt::unpack(&wrong), Err(ProgramError::InvalidAccountData));
+        assert_eq!(ClaimReceipt::unpack(&data[..data.len() - 1]), Err(ProgramError::InvalidAccountData));
+    }
+
+    #[test]
+    fn verify_matches_program_receipt_address() {
+        let grant = Pubkey::new_from_array([1; 32]);
+        let claimer = Pubkey::new_from_array([2; 32]);
+        for salt in [[0u8; 16], [9u8; 16]] {
+            let (address, bump) = grant_program::receipt::receipt_address(&grant, &claimer, 3, &salt);
+            let mut receipt = program_receipt(grant_program::ReceiptStatus::Valid, salt);
+            receipt.period_index = 3;
+            receipt.bump = bump;
+            let data = serialize(&receipt);
+
+            let verified = ClaimReceipt::verify(&address, &crate::ID, &data, &grant, &claimer, 3).unwrap();
+            assert_eq!(verified.seed_salt, salt);
+            assert_eq!(
+                ClaimReceipt::verify(&address, &Pubkey::default(), &data, &grant, &claimer, 3),
+                Err(ProgramError::IllegalOwner)
+            );
+            assert!(ClaimReceipt::verify(&address, &crate::ID, &data, &grant, &claimer, 4).is_err());
+            assert_eq!(
+                ClaimReceipt::verify(&Pubkey::new_unique(), &crate::ID, &data, &grant, &claimer, 3),
+                Err(ProgramError::InvalidSeeds)
+            );
+        }
+    }
+
+    #[test]
+    fn grant_header_matches_program_layout() {
+        let grant = grant_program::Grant {
+            authority: Pubkey::new_from_array([1; 32]),
+            mint: Pubkey::new_from_array([2; 32]),
+            vault: Pubkey::new_from_array([3; 32]),
+            grant_id: 11,
+            amount_per_period: 1_000,
+            period_seconds: 86_400,
+            start_ts: 1_700_000_000,
+            expires_at: -1,
+            merkle_root: [4; 32],
+            paused: true,
+            bump: 253,
+            category: 0x0102,
+            ..Default::default()
+        };
+        let header = GrantHeader::unpack(&serialize(&grant)).unwrap();
+        assert_eq!(
+            header,
+            GrantHeader {
+                authority: grant.authority,
+                mint: grant.mint,
+                vault: grant.vault,
+                grant_id: 11,
+                amount_per_period: 1_000,
+                period_seconds: 86_400,
+                start_ts: 1_700_000_000,
+                expires_at: -1,
+                merkle_root: [4; 32],
+                paused: true,
+                bump: 253,
+                category: 0x0102,
+            }
+        );
+    }
+}
@@ -0,0 +1,38 @@
+// ===== 現在時刻 =====
+// 命令が使う現在時刻はすべてここから取る。
+// 通常のビルドでは Clock sysvar の unix_timestamp をそのまま返す。
+// test-clock feature 付きのビルド（統合テスト専用。devnet / mainnet 向けのビルドでは有効にしないこと）では、
+// set_clock_override で作成した ClockOverride アカウントを remaining_accounts の末尾に渡した命令に限り、
+// その unix_timestamp（0 = 上書きなし）を現在時刻として使う。
+// 期間の切り替わり・期限切れ・PoP の時刻ずれを、validator の時計を進めずに決定的に再現するためのもの。
+
+use anchor_lang::prelude::*;
+
+#[cfg(not(feature = "test-clock"))]
+pub fn now(_remaining_accounts: &[AccountInfo]) -> Result<i64> {
+    Ok(Clock::get()?.unix_timestamp)
+}
+
+#[cfg(feature = "test-clock")]
+pub fn now(remaining_accounts: &[AccountInfo]) -> Result<i64> {
+    use anchor_lang::Discriminator;
+
+    use crate::ClockOverride;
+
+    let clock_now = Clock::get()?.unix_timestamp;
+    let Some(info) = remaining_accounts.last() else {
+        return Ok(clock_now);
+    };
+    if info.owner != &crate::ID {
+        return Ok(clock_now);
+    }
+    let data = info.try_borrow_data()?;
+    if !data.starts_with(ClockOverride::DISCRIMINATOR) {
+        return Ok(clock_now);
+    }
+    let clock_override = ClockOverride::try_deserialize(&mut &data[..])?;
+    Ok(match clock_override.unix_timestamp {
+        0 => clock_now,
+        mocked => mocked,
+    })
+}
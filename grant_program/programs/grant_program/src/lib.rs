@@ -3,21 +3,68 @@
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program::{
     ed25519_program,
+    entrypoint::MAX_PERMITTED_DATA_INCREASE,
     hash::hashv,
-    instruction::Instruction,
+    instruction::{AccountMeta, Instruction},
+    program::{invoke, invoke_signed},
     sysvar::instructions::{load_current_index_checked, load_instruction_at_checked},
 };
 
 declare_id!("GZcUoGHk8SfAArTKicL1jiRHZEQa3EuzgYcC2u4yWfSR");
+use anchor_lang::system_program::{allocate, assign, create_account, Allocate, Assign, CreateAccount};
+use anchor_spl::associated_token::{get_associated_token_address, get_associated_token_address_with_program_id, AssociatedToken};
+use anchor_spl::token::spl_token::native_mint;
 use anchor_spl::token::{
+    approve,
     close_account,
+    initialize_account3,
+    revoke,
+    thaw_account,
+    Approve,
+    InitializeAccount3,
+    Revoke,
+    ThawAccount,
+    transfer,
     transfer_checked,
     CloseAccount,
     Mint,
     Token,
     TokenAccount,
+    Transfer,
     TransferChecked,
 };
+use anchor_spl::token_2022::{self, Token2022};
+use anchor_spl::token_interface::spl_token_2022::extension::ExtensionType;
+use anchor_spl::token_interface::spl_token_metadata_interface::state::{Field, TokenMetadata};
+use anchor_spl::token_interface::{
+    group_member_pointer_initialize,
+    group_pointer_initialize,
+    metadata_pointer_initialize,
+    non_transferable_mint_initialize,
+    token_group_initialize,
+    token_member_initialize,
+    token_metadata_initialize,
+    token_metadata_update_field,
+    GroupMemberPointerInitialize,
+    GroupPointerInitialize,
+    MetadataPointerInitialize,
+    NonTransferableMintInitialize,
+    TokenGroupInitialize,
+    TokenMemberInitialize,
+    TokenMetadataInitialize,
+    TokenMetadataUpdateField,
+};
+use std::cell::RefMut;
+
+mod clock;
+mod math;
+pub mod receipt;
+pub mod structured_log;
+/// allowlist / audit anchor の Merkle 検証（他の distributor からも再利用できるよう interface クレートに置いている）
+pub use grant_program_interface::merkle;
+pub use merkle::{allowlist_leaf, audit_anchor_leaf, verify_merkle_sorted, MerkleProof, DEFAULT_HASH_DOMAIN};
+use math::{add_amounts, add_to_total, mul_amount, mul_div, replace_in_total, to_u64};
+use structured_log::LogLine;
 
 // ===== we-ne (MVP) =====
 // SPLトークン限定 / 固定レート方式
@@ -28,17 +75,170 @@ use anchor_spl::token::{
 pub const DEFAULT_MONTH_SECONDS: i64 = 2_592_000; // 30 days
 const POP_HASH_LEN: usize = 32;
 const POP_MESSAGE_VERSION_V1: u8 = 1;
-const POP_MESSAGE_VERSION_V2: u8 = 2;
+pub const POP_MESSAGE_VERSION_V2: u8 = 2;
 const POP_MESSAGE_LEN_V1: usize = 1 + 32 + 32 + 8 + 32 + 32 + 32 + 8;
-const POP_MESSAGE_LEN_V2: usize = 1 + 32 + 32 + 8 + 32 + 32 + 32 + 32 + 8;
+pub const POP_MESSAGE_LEN_V2: usize = 1 + 32 + 32 + 8 + 32 + 32 + 32 + 32 + 8;
+/// v2 の末尾に authorized_amount(8) を加えた形式。署名者がこの claim の支給額を指定する
+/// （Grant.pop_amount_ceiling 以下。entry_hash は v2 と同じ式で、金額はハッシュチェーンに含めない）
+pub const POP_MESSAGE_VERSION_V3: u8 = 3;
+pub const POP_MESSAGE_LEN_V3: usize = POP_MESSAGE_LEN_V2 + 8;
 const POP_MAX_SKEW_SECONDS: i64 = 600; // 10 minutes
+/// 受給者が署名する claim intent（relayer が受給者の署名なしで claim を送るための承認）
+/// domain + grant(32) + claimer(32) + period_index(8) + relayer(32, default = 誰でも可) + expires_at(8)
+pub const CLAIM_INTENT_DOMAIN: &[u8] = b"we-ne:claim-intent:v1";
+pub const CLAIM_INTENT_LEN: usize = CLAIM_INTENT_DOMAIN.len() + 32 + 32 + 8 + 32 + 8;
+/// 期間の切り替わり直後は、直前の期間の period_index も受け付ける（クライアント側の計算と境界のずれを吸収）
+pub const PERIOD_BOUNDARY_GRACE_SECONDS: i64 = 60;
+const MAX_GRANTS_PER_BATCH: u8 = 10;
+/// reserve_receipts / release_receipt_reservations 1回で扱う receipt 数の上限
+pub const MAX_RECEIPT_RESERVATIONS_PER_BATCH: usize = 16;
+/// archive_receipts / close_archived_receipts 1回で扱う receipt 数の上限
+pub const MAX_ARCHIVE_RECEIPTS_PER_BATCH: usize = 16;
+const BPS_DENOMINATOR: u64 = 10_000;
+pub const RECOVERY_DELAY_SECONDS: i64 = 7 * 24 * 60 * 60; // 7 days
+pub const MINT_MIGRATION_DELAY_SECONDS: i64 = 7 * 24 * 60 * 60; // 7 days
+/// dead-man switch: authority の無操作期間として設定できる下限
+pub const SUCCESSION_MIN_INACTIVITY_SECONDS: i64 = 30 * 24 * 60 * 60; // 30 days
+/// dead-man switch: successor の引き継ぎ表明から実行までの待機期間
+pub const SUCCESSION_DELAY_SECONDS: i64 = 7 * 24 * 60 * 60; // 7 days
+pub const MAX_STREAK_BONUS_STEPS: usize = 4;
+pub const MAX_STAKE_MULTIPLIER_STEPS: usize = 4;
+pub const MAX_BLACKOUT_WINDOWS: usize = 4;
+pub const MAX_FEE_SPLITS: usize = 4;
+pub const MAX_ALLOWLIST_DELTAS: usize = 12;
+pub const MAX_KEEPERS: usize = 8;
+/// session key の有効期間の上限
+pub const SESSION_MAX_DURATION_SECONDS: i64 = 90 * 24 * 60 * 60; // 90 days
+/// session key に許可する操作（現在は claim のみ）
+pub const SESSION_SCOPE_CLAIM: u8 = 1;
+/// DelegateIndexPage 1ページに載せる受給者数の上限
+pub const DELEGATE_INDEX_PAGE_CAPACITY: usize = 64;
+/// sponsor のロゴ URI の最大バイト数
+pub const MAX_SPONSOR_URI_LEN: usize = 128;
+/// ClaimerMemo の暗号文の最大バイト数
+pub const MAX_CLAIMER_MEMO_LEN: usize = 512;
+/// ReceiptNftCollection の名前・シンボルの最大バイト数（URI は MAX_SPONSOR_URI_LEN）
+pub const MAX_RECEIPT_NFT_NAME_LEN: usize = 32;
+pub const MAX_RECEIPT_NFT_SYMBOL_LEN: usize = 10;
+/// HashDomainConfig で設定できる domain の最大バイト数
+pub const MAX_HASH_DOMAIN_LEN: usize = 16;
+/// StatementLog に残す直近の受給件数
+pub const STATEMENT_LOG_ENTRIES: usize = 8;
+/// ClaimIndex が受給の有無を保持する直近の期間数（ビットセットの幅）
+pub const CLAIM_INDEX_PERIODS: usize = 512;
+const CLAIM_INDEX_WORDS: usize = CLAIM_INDEX_PERIODS / 64;
+/// FundingBudget の上限を適用する期間（月次）
+pub const FUNDING_BUDGET_WINDOW_SECONDS: i64 = 30 * 24 * 60 * 60; // 30 days
+pub const MAX_ESCROW_TRANCHES: usize = 36;
+/// 期限前に close_grant する場合の wind-down（受給者が残りを受給できる猶予）の下限
+pub const WIND_DOWN_MIN_SECONDS: i64 = 14 * 24 * 60 * 60; // 14 days
+/// receipt / pop_state の seed に加える Grant ごとの salt（[0; SEED_SALT_LEN] = salt なし）
+pub const SEED_SALT_LEN: usize = 16;
+/// PoP signer 障害時に PoP なしの claim を許可する緊急期間の上限（延長は再設定で行う）
+pub const POP_BYPASS_MAX_SECONDS: i64 = 72 * 60 * 60; // 72 hours
+/// Grant.kpi_milestones のビット（目標を初めて達成した時点で立つ）
+pub const KPI_MILESTONE_TAKE_UP: u8 = 1 << 0;
+pub const KPI_MILESTONE_UNIQUE_CLAIMERS: u8 = 1 << 1;
+/// schedule_claim で予約できる実行開始時刻の上限（予約時点からの遅延）
+pub const SCHEDULED_CLAIM_MAX_DELAY_SECONDS: i64 = 31 * 24 * 60 * 60; // 31 days
+/// 受給資格の述語で使う gate のビット（Grant.eligibility_clauses の各要素はこれらの AND）
+pub const ELIGIBILITY_ALLOWLIST: u8 = 1 << 0;
+pub const ELIGIBILITY_TOKEN_HOLDING: u8 = 1 << 1;
+pub const ELIGIBILITY_ATTESTATION: u8 = 1 << 2;
+pub const ELIGIBILITY_ENROLLMENT: u8 = 1 << 3;
+pub const ELIGIBILITY_ALL_GATES: u8 =
+    ELIGIBILITY_ALLOWLIST | ELIGIBILITY_TOKEN_HOLDING | ELIGIBILITY_ATTESTATION | ELIGIBILITY_ENROLLMENT;
+/// 受給資格の述語の clause 数の上限（clause 同士は OR）
+pub const MAX_ELIGIBILITY_CLAUSES: usize = 4;
+
+// Grant.category（ダッシュボードでの横断集計用）
+// 1..=GRANT_CATEGORY_MAX_RESERVED は予約済みの分類、GRANT_CATEGORY_CUSTOM_START 以降は運用者が自由に使える
+pub const GRANT_CATEGORY_UNSPECIFIED: u16 = 0;
+pub const GRANT_CATEGORY_HOUSING: u16 = 1;
+pub const GRANT_CATEGORY_FOOD: u16 = 2;
+pub const GRANT_CATEGORY_CHILDCARE: u16 = 3;
+pub const GRANT_CATEGORY_EDUCATION: u16 = 4;
+pub const GRANT_CATEGORY_HEALTHCARE: u16 = 5;
+pub const GRANT_CATEGORY_TRANSPORT: u16 = 6;
+pub const GRANT_CATEGORY_DISASTER_RELIEF: u16 = 7;
+pub const GRANT_CATEGORY_EMPLOYMENT: u16 = 8;
+pub const GRANT_CATEGORY_MAX_RESERVED: u16 = GRANT_CATEGORY_EMPLOYMENT;
+pub const GRANT_CATEGORY_CUSTOM_START: u16 = 0x8000;
+
+/// FeatureFlags のビット。ステージングされた機能の命令パスごとに1ビット
+/// （IDL の constants に載せ、デプロイ用のスクリプトが名前で指定できるようにする）
+#[constant]
+pub const FEATURE_BATCH_CREATE: u64 = 1 << 0;
+#[constant]
+pub const FEATURE_TEMPLATES: u64 = 1 << 1;
+#[constant]
+pub const FEATURE_PLATFORM_CPI: u64 = 1 << 2;
+
+/// プログラムのバージョン（major, minor, patch）。get_version で返す
+/// アカウント・命令レイアウトを変更した場合は MIN_CLIENT_VERSION も合わせて上げること
+#[constant]
+pub const PROGRAM_VERSION: [u16; 3] = [0, 2, 0];
+/// このプログラムと通信できるクライアントの最小バージョン
+#[constant]
+pub const MIN_CLIENT_VERSION: [u16; 3] = [0, 2, 0];
+/// イベントのレイアウトのバージョン（全イベントの先頭フィールド schema_version に入る）
+/// 公開済みイベントのフィールドを変更した場合に上げる（docs/EVENT_SCHEMA.md の changelog も更新すること）
+#[constant]
+pub const EVENT_SCHEMA_VERSION: u8 = 1;
+
+/// platform プログラムが CPI で署名する PDA の seed
+/// platform_signer = find_program_address([PLATFORM_SIGNER_SEED], platform_program)
+pub const PLATFORM_SIGNER_SEED: &[u8] = b"we-ne-platform";
+
+// ===== Attestation gate（標準アテステーション・レイアウト）=====
+// gate_program が所有するアカウントに以下のレイアウトで発行されていること:
+//   [0..8]   discriminator = b"wenegate"
+//   [8..40]  config        = Grant.gate_config
+//   [40..72] subject       = claimer
+//   [72..80] expires_at    (i64 LE, 0 = 無期限)
+//   [80]     approved      (1 = 受給資格あり)
+// KYC / uniqueness プロバイダはこのレイアウトで発行すれば、claim ロジックを fork せずに差し替えられる。
+pub const GATE_ATTESTATION_DISCRIMINATOR: [u8; 8] = *b"wenegate";
+pub const GATE_ATTESTATION_LEN: usize = 8 + 32 + 32 + 8 + 1;
+
+// ===== Stake-duration multiplier（標準ステーク記録レイアウト）=====
+// stake_program が所有するアカウントに以下のレイアウトで記録されていること:
+//   [0..8]   discriminator = b"wenestak"
+//   [8..40]  owner         = claimer
+//   [40..72] mint          = Grant.stake_mint
+//   [72..80] amount        (u64 LE, 現在ステーク / 保有している量)
+//   [80..88] staked_since  (i64 LE, amount 以上を継続して保持し始めた時刻。減らした場合はステーク側で更新する)
+// ステーキングプログラムはこのレイアウトで記録すれば、claim 時に保持期間に応じた倍率が適用される。
+pub const STAKE_RECORD_DISCRIMINATOR: [u8; 8] = *b"wenestak";
+pub const STAKE_RECORD_LEN: usize = 8 + 32 + 32 + 8 + 8;
+
+// ===== Disbursement attestation（受給アテステーションの標準 CPI）=====
+// attestation_program を設定した Grant では、claim ごとに以下の命令を CPI で呼ぶ:
+//   data:
+//     [0..8]     discriminator = b"weneattn"
+//     [8..40]    config        = Grant.attestation_config
+//     [40..72]   subject       = 受給者
+//     [72..104]  receipt       = 今回の ClaimReceipt
+//     [104..112] period_index  (u64 LE)
+//     [112..120] amount        (u64 LE)
+//     [120..128] claimed_at    (i64 LE)
+//   accounts:
+//     0. grant              （signer。issuer = この grant。owner がこのプログラムであることで真正性を確認できる）
+//     1. attestation_record （writable。発行先。導出方法は attestation_program 側で決める）
+//     2. payer = claimer    （signer, writable。record の rent を支払う）
+//     3. system_program
+// 与信スコアリングなどの利用者は独自 PDA ではなく発行済みアテステーションを読む。
+// SAS など既存の attestation service へは、この命令を受けて発行する adapter プログラムを挟む。
+pub const DISBURSEMENT_ATTESTATION_DISCRIMINATOR: [u8; 8] = *b"weneattn";
+pub const DISBURSEMENT_ATTESTATION_DATA_LEN: usize = 8 + 32 + 32 + 32 + 8 + 8 + 8;
 
 // ===== PoP（Proof of Process）が保証すること =====
 // PoP は「特定の signer が認証したプロセスレシートが、当該 claim に binding されている」こと
 // ——すなわち signer-authenticated process receipt binding——を保証する。
 // 「オンチェーン canonical chain の連続性」は PoP の要求仕様に含まない。
 // chain head（last_global_hash / last_stream_hash）はベスト・エフォートで最新値に追従するが、
-// 並行 claim を拒否しない。唯一の二重受給ガードは receipt PDA の init-dup である。
+// 並行 claim を拒否しない。唯一の二重受給ガードは receipt PDA の一意性である
+// （予約済み receipt 以外が既に存在する期間には受給できない）。
 
 #[program]
 pub mod grant_program {
@@ -51,9 +251,19 @@ pub mod grant_program {
     /// - start_ts: 期間計算の起点（unix timestamp）
     /// - expires_at: 期限（0なら無期限）
     ///
+    /// - allow_freeze_authority: freeze authority を持つ mint を明示的に許容する場合 true
+    ///   （発行者が vault を凍結すると claim が止まるため、既定では拒否する）
+    /// - will_use_allowlist: allowlist を後から設定する場合 true
+    ///   （set_allowlist_root / commit_allowlist_root を実行するまで claim を NotYetOpen で拒否する。
+    ///   false の場合、root を設定するまでは誰でも受給できる）
+    /// - ui_amount: 1期間あたりの配布量を表示上の額で指定する場合に渡す（amount_per_period は 0 にする）
+    ///   mint の decimals が ui_amount.expected_decimals と一致しない場合は DecimalsMismatch
+    ///   （decimals 6 と 9 の取り違えで 1000 倍の額を設定する事故を防ぐ）。保存されるのは最小単位の額
+    ///
     /// NOTE: 固定レート方式のため「円換算」はオフチェーン運用ルール。
     /// SECURITY: init を使うため、同一 PDA への再呼び出しは Anchor がエラーとする。
     ///           パラメータを変更する場合は update_grant を使うこと。
+    #[allow(clippy::too_many_arguments)]
     pub fn create_grant(
         ctx: Context<CreateGrant>,
         grant_id: u64,
@@ -61,29 +271,477 @@ pub mod grant_program {
         period_seconds: i64,
         start_ts: i64,
         expires_at: i64,
+        allow_freeze_authority: bool,
+        category: u16,
+        will_use_allowlist: bool,
+        ui_amount: Option<UiAmount>,
     ) -> Result<()> {
-        require!(amount_per_period > 0, ErrorCode::InvalidAmount);
-        require!(period_seconds > 0, ErrorCode::InvalidPeriod);
+        let amount_per_period = match ui_amount {
+            Some(ui_amount) => {
+                require!(amount_per_period == 0, ErrorCode::InvalidAmount);
+                ui_amount.to_raw(ctx.accounts.mint.decimals)?
+            }
+            None => amount_per_period,
+        };
+        let params = GrantParams {
+            amount_per_period,
+            period_seconds,
+            start_ts,
+            expires_at,
+            allow_freeze_authority,
+            category,
+        };
+        validate_grant_params(&params, &ctx.accounts.mint, clock::now(ctx.remaining_accounts)?)?;
+
+        let grant = &mut ctx.accounts.grant;
+        init_grant(
+            grant,
+            GrantKeys {
+                authority: ctx.accounts.authority.key(),
+                mint: ctx.accounts.mint.key(),
+                decimals: ctx.accounts.mint.decimals,
+                vault: ctx.accounts.vault.key(),
+                grant_id,
+                bump: ctx.bumps.grant,
+            },
+            &params,
+        );
+        grant.allowlist_pending = will_use_allowlist;
+        grant.hash_domain = deployment_hash_domain(ctx.accounts.hash_domain_config.as_deref());
+        // recovery guardian は任意（渡された場合のみ登録）
+        if let Some(recovery) = ctx.accounts.recovery.as_ref() {
+            grant.recovery = recovery.key();
+        }
+        // PopConfig が渡された場合は依存 Grant として数える（close_pop_config の安全弁）
+        if let Some(pop_config) = ctx.accounts.pop_config.as_mut() {
+            pop_config.active_grants = pop_config
+                .active_grants
+                .checked_add(1)
+                .ok_or(ErrorCode::MathOverflow)?;
+            grant.pop_config_tracked = true;
+        }
+
+        Ok(())
+    }
+
+    /// 同一 authority / mint の Grant を連番 grant_id でまとめて作成する
+    /// - grant_id は first_grant_id から count 件の連番
+    /// - remaining_accounts に [grant_0, vault_0, grant_1, vault_1, ...] の順で PDA を渡す
+    /// - パラメータは全 Grant 共通（allowlist の root などは作成後に個別設定する）
+    /// - allow_freeze_authority / will_use_allowlist / ui_amount は create_grant と同じ
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_grants_batch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CreateGrantsBatch<'info>>,
+        first_grant_id: u64,
+        count: u8,
+        amount_per_period: u64,
+        period_seconds: i64,
+        start_ts: i64,
+        expires_at: i64,
+        allow_freeze_authority: bool,
+        category: u16,
+        will_use_allowlist: bool,
+        ui_amount: Option<UiAmount>,
+    ) -> Result<()> {
+        require_feature(&ctx.accounts.feature_flags, FEATURE_BATCH_CREATE)?;
+        let amount_per_period = match ui_amount {
+            Some(ui_amount) => {
+                require!(amount_per_period == 0, ErrorCode::InvalidAmount);
+                ui_amount.to_raw(ctx.accounts.mint.decimals)?
+            }
+            None => amount_per_period,
+        };
+        require!(
+            count > 0 && count <= MAX_GRANTS_PER_BATCH,
+            ErrorCode::InvalidBatchSize
+        );
+        require!(
+            ctx.remaining_accounts.len() == count as usize * 2,
+            ErrorCode::InvalidBatchSize
+        );
+
+        let params = GrantParams {
+            amount_per_period,
+            period_seconds,
+            start_ts,
+            expires_at,
+            allow_freeze_authority,
+            category,
+        };
+        // remaining_accounts は grant / vault の組で使うため、ClockOverride は受け付けない
+        validate_grant_params(&params, &ctx.accounts.mint, clock::now(&[])?)?;
+
+        let rent = Rent::get()?;
+        let authority_key = ctx.accounts.authority.key();
+        let mint_key = ctx.accounts.mint.key();
+        let track_pop_config = ctx.accounts.pop_config.is_some();
+        let hash_domain = deployment_hash_domain(ctx.accounts.hash_domain_config.as_deref());
+
+        for (i, pair) in ctx.remaining_accounts.chunks(2).enumerate() {
+            let (grant_info, vault_info) = (&pair[0], &pair[1]);
+            let grant_id = first_grant_id
+                .checked_add(i as u64)
+                .ok_or(ErrorCode::MathOverflow)?;
+            let grant_id_bytes = grant_id.to_le_bytes();
+
+            let (grant_key, grant_bump) = Pubkey::find_program_address(
+                &[b"grant", authority_key.as_ref(), mint_key.as_ref(), &grant_id_bytes],
+                ctx.program_id,
+            );
+            require_keys_eq!(grant_info.key(), grant_key, ErrorCode::InvalidBatchAccount);
+            let (vault_key, vault_bump) =
+                Pubkey::find_program_address(&[b"vault", grant_key.as_ref()], ctx.program_id);
+            require_keys_eq!(vault_info.key(), vault_key, ErrorCode::InvalidBatchAccount);
+
+            // Grant PDA（program 所有）
+            let grant_space = 8 + Grant::INIT_SPACE;
+            create_pda_account(
+                &ctx.accounts.authority.to_account_info(),
+                grant_info,
+                &ctx.accounts.system_program.to_account_info(),
+                &[
+                    b"grant",
+                    authority_key.as_ref(),
+                    mint_key.as_ref(),
+                    &grant_id_bytes,
+                    &[grant_bump],
+                ],
+                rent.minimum_balance(grant_space),
+                grant_space,
+                ctx.program_id,
+            )?;
+
+            // vault PDA（token program 所有、authority は grant PDA）
+            create_pda_account(
+                &ctx.accounts.authority.to_account_info(),
+                vault_info,
+                &ctx.accounts.system_program.to_account_info(),
+                &[b"vault", grant_key.as_ref(), &[vault_bump]],
+                rent.minimum_balance(TokenAccount::LEN),
+                TokenAccount::LEN,
+                &ctx.accounts.token_program.key(),
+            )?;
+            initialize_account3(CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                InitializeAccount3 {
+                    account: vault_info.clone(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    authority: grant_info.clone(),
+                },
+            ))?;
+
+            let mut grant = Grant::default();
+            init_grant(
+                &mut grant,
+                GrantKeys {
+                    authority: authority_key,
+                    mint: mint_key,
+                    decimals: ctx.accounts.mint.decimals,
+                    vault: vault_key,
+                    grant_id,
+                    bump: grant_bump,
+                },
+                &params,
+            );
+            grant.allowlist_pending = will_use_allowlist;
+            grant.pop_config_tracked = track_pop_config;
+            grant.hash_domain = hash_domain;
+            let mut data = grant_info.try_borrow_mut_data()?;
+            let mut writer: &mut [u8] = &mut data;
+            grant.try_serialize(&mut writer)?;
+        }
+
+        if let Some(pop_config) = ctx.accounts.pop_config.as_mut() {
+            pop_config.active_grants = pop_config
+                .active_grants
+                .checked_add(count as u64)
+                .ok_or(ErrorCode::MathOverflow)?;
+        }
+
+        Ok(())
+    }
+
+    /// Grant テンプレート（既定パラメータ）を作成/更新する（authority 限定）
+    /// - 手入力による period_seconds などの設定ミスを防ぐため、検証済みの値を保存しておく
+    pub fn upsert_grant_template(
+        ctx: Context<UpsertGrantTemplate>,
+        template_id: u64,
+        params: GrantTemplateParams,
+    ) -> Result<()> {
+        require!(params.amount_per_period > 0, ErrorCode::InvalidAmount);
+        require!(params.period_seconds > 0, ErrorCode::InvalidPeriod);
+        require!(params.duration_seconds >= 0, ErrorCode::InvalidPeriod);
+        require_valid_category(params.category)?;
+
+        let template = &mut ctx.accounts.template;
+        template.authority = ctx.accounts.authority.key();
+        template.template_id = template_id;
+        template.amount_per_period = params.amount_per_period;
+        template.period_seconds = params.period_seconds;
+        template.duration_seconds = params.duration_seconds;
+        template.yen_per_period = params.yen_per_period;
+        template.gate_program = params.gate_program;
+        template.gate_config = params.gate_config;
+        template.require_pop_config = params.require_pop_config;
+        template.category = params.category;
+        template.bump = ctx.bumps.template;
+        Ok(())
+    }
 
-        let now = Clock::get()?.unix_timestamp;
-        // start_tsは未来でも良い（開始前にfundしておく想定）
-        // ただし極端に昔すぎるのはミスの可能性があるので軽く制限
-        require!(start_ts <= now + 365_i64 * 24 * 60 * 60, ErrorCode::InvalidStartTs);
+    /// テンプレートから Grant を作成する
+    /// - 起点（start_ts）だけを指定し、それ以外はテンプレートの値を使う
+    /// - expires_at = start_ts + duration_seconds（duration_seconds が 0 なら無期限）
+    pub fn create_from_template(
+        ctx: Context<CreateFromTemplate>,
+        grant_id: u64,
+        start_ts: i64,
+    ) -> Result<()> {
+        require_feature(&ctx.accounts.feature_flags, FEATURE_TEMPLATES)?;
+        let template = &ctx.accounts.template;
+        let expires_at = if template.duration_seconds == 0 {
+            0
+        } else {
+            start_ts
+                .checked_add(template.duration_seconds)
+                .ok_or(ErrorCode::MathOverflow)?
+        };
+        let params = GrantParams {
+            amount_per_period: template.amount_per_period,
+            period_seconds: template.period_seconds,
+            start_ts,
+            expires_at,
+            allow_freeze_authority: false,
+            category: template.category,
+        };
+        validate_grant_params(&params, &ctx.accounts.mint, clock::now(ctx.remaining_accounts)?)?;
+        require!(
+            !template.require_pop_config || ctx.accounts.pop_config.is_some(),
+            ErrorCode::PopConfigRequired
+        );
 
         let grant = &mut ctx.accounts.grant;
-        grant.authority = ctx.accounts.authority.key();
-        grant.mint = ctx.accounts.mint.key();
-        grant.vault = ctx.accounts.vault.key();
-        grant.grant_id = grant_id;
-        grant.amount_per_period = amount_per_period;
-        grant.period_seconds = period_seconds;
-        grant.start_ts = start_ts;
-        grant.expires_at = expires_at;
-        // allowlist is optional; default is disabled
-        grant.merkle_root = [0u8; 32];
-        grant.paused = false;
-        grant.bump = ctx.bumps.grant;
+        init_grant(
+            grant,
+            GrantKeys {
+                authority: ctx.accounts.authority.key(),
+                mint: ctx.accounts.mint.key(),
+                decimals: ctx.accounts.mint.decimals,
+                vault: ctx.accounts.vault.key(),
+                grant_id,
+                bump: ctx.bumps.grant,
+            },
+            &params,
+        );
+        grant.yen_per_period = template.yen_per_period;
+        grant.gate_program = template.gate_program;
+        grant.gate_config = template.gate_config;
+        grant.hash_domain = deployment_hash_domain(ctx.accounts.hash_domain_config.as_deref());
+        if let Some(pop_config) = ctx.accounts.pop_config.as_mut() {
+            pop_config.active_grants = pop_config
+                .active_grants
+                .checked_add(1)
+                .ok_or(ErrorCode::MathOverflow)?;
+            grant.pop_config_tracked = true;
+        }
+
+        Ok(())
+    }
+
+    /// 親 Grant の下に地域別などの子 Grant を作成する（親の authority 限定）
+    /// - 子は親と同じ mint で、vault・allowlist は子ごとに独立する
+    /// - 期間（period_seconds / start_ts / expires_at）・分類・円建て・gate 設定は親の値を引き継ぐ
+    /// - amount_per_period: 子の1期間あたりの支給額（0 = 親と同じ）
+    /// - region_code: 運用側で定める地域などの区分（集計用のラベル）
+    /// - 子の受給実績は roll_up_child_stats で親の children_* に集計する（子の子は作れない）
+    pub fn create_child_grant(
+        ctx: Context<CreateChildGrant>,
+        grant_id: u64,
+        region_code: u16,
+        amount_per_period: u64,
+    ) -> Result<()> {
+        let parent = &ctx.accounts.parent;
+        require!(parent.parent == Pubkey::default(), ErrorCode::InvalidParentGrant);
+        let params = GrantParams {
+            amount_per_period: if amount_per_period == 0 {
+                parent.amount_per_period
+            } else {
+                amount_per_period
+            },
+            period_seconds: parent.period_seconds,
+            start_ts: parent.start_ts,
+            expires_at: parent.expires_at,
+            allow_freeze_authority: parent.freeze_authority_acknowledged,
+            category: parent.category,
+        };
+        validate_grant_params(&params, &ctx.accounts.mint, clock::now(ctx.remaining_accounts)?)?;
+
+        let grant = &mut ctx.accounts.grant;
+        init_grant(
+            grant,
+            GrantKeys {
+                authority: ctx.accounts.authority.key(),
+                mint: ctx.accounts.mint.key(),
+                decimals: ctx.accounts.mint.decimals,
+                vault: ctx.accounts.vault.key(),
+                grant_id,
+                bump: ctx.bumps.grant,
+            },
+            &params,
+        );
+        grant.yen_per_period = parent.yen_per_period;
+        grant.gate_program = parent.gate_program;
+        grant.gate_config = parent.gate_config;
+        grant.hash_domain = parent.hash_domain;
+        grant.claim_cpi_guard = parent.claim_cpi_guard;
+        grant.claim_cpi_allowed_caller = parent.claim_cpi_allowed_caller;
+        grant.eligibility_clauses = parent.eligibility_clauses;
+        grant.holding_mint = parent.holding_mint;
+        grant.holding_min_amount = parent.holding_min_amount;
+        grant.enrollment_grant = parent.enrollment_grant;
+        grant.parent = parent.key();
+        grant.region_code = region_code;
+        grant.sponsor_name_hash = parent.sponsor_name_hash;
+        grant.sponsor_logo_uri = parent.sponsor_logo_uri.clone();
+        if let Some(pop_config) = ctx.accounts.pop_config.as_mut() {
+            pop_config.active_grants = pop_config
+                .active_grants
+                .checked_add(1)
+                .ok_or(ErrorCode::MathOverflow)?;
+            grant.pop_config_tracked = true;
+        }
+
+        let parent = &mut ctx.accounts.parent;
+        parent.child_count = parent
+            .child_count
+            .checked_add(1)
+            .ok_or(ErrorCode::MathOverflow)?;
+        Ok(())
+    }
+
+    /// 子 Grant の受給実績を親 Grant に集計する（誰でも実行可能）
+    /// - 前回の集計以降の増減（return_claim による減少を含む）を親の children_claims_count / children_total_claimed に反映する
+    pub fn roll_up_child_stats(ctx: Context<RollUpChildStats>) -> Result<()> {
+        let child = &mut ctx.accounts.child;
+        let parent = &mut ctx.accounts.parent;
+
+        parent.children_claims_count = to_u64(replace_in_total(
+            parent.children_claims_count as u128,
+            child.rolled_up_claims_count as u128,
+            child.claims_count as u128,
+        )?)?;
+        parent.children_total_claimed = replace_in_total(
+            parent.children_total_claimed,
+            child.rolled_up_total_claimed,
+            child.total_claimed,
+        )?;
+        child.rolled_up_claims_count = child.claims_count;
+        child.rolled_up_total_claimed = child.total_claimed;
+
+        emit!(ChildStatsRolledUp {
+            schema_version: EVENT_SCHEMA_VERSION,
+            parent: parent.key(),
+            child: child.key(),
+            region_code: child.region_code,
+            child_claims_count: child.claims_count,
+            child_total_claimed: child.total_claimed,
+            children_claims_count: parent.children_claims_count,
+            children_total_claimed: parent.children_total_claimed,
+        });
+        Ok(())
+    }
+
+    /// platform（SaaS 再販事業者のプログラム）に Grant 作成を委任する（organization 署名）
+    /// - 以後 platform_program は CPI 経由で organization 名義の Grant を作成できる
+    /// - max_fee_bps: platform が設定できる claim 手数料の上限（basis points）
+    pub fn approve_platform(
+        ctx: Context<ApprovePlatform>,
+        platform_program: Pubkey,
+        max_fee_bps: u16,
+    ) -> Result<()> {
+        require!(max_fee_bps as u64 <= BPS_DENOMINATOR, ErrorCode::InvalidFeeBps);
+
+        let (platform_signer, _) =
+            Pubkey::find_program_address(&[PLATFORM_SIGNER_SEED], &platform_program);
+        let approval = &mut ctx.accounts.approval;
+        approval.organization = ctx.accounts.organization.key();
+        approval.platform_program = platform_program;
+        approval.platform_signer = platform_signer;
+        approval.max_fee_bps = max_fee_bps;
+        approval.bump = ctx.bumps.approval;
+        Ok(())
+    }
+
+    /// platform が organization に代わって Grant を作成する（platform_program からの CPI 限定）
+    /// - platform_signer は platform_program の PDA であり、CPI でのみ署名できる
+    /// - platform_signer は co-admin として Grant に記録され、claim ごとに fee_bps の手数料を得る
+    /// - organization の鍵を platform が預かる必要はない
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_grant_via_platform(
+        ctx: Context<CreateGrantViaPlatform>,
+        grant_id: u64,
+        amount_per_period: u64,
+        period_seconds: i64,
+        start_ts: i64,
+        expires_at: i64,
+        fee_bps: u16,
+        category: u16,
+    ) -> Result<()> {
+        require_feature(&ctx.accounts.feature_flags, FEATURE_PLATFORM_CPI)?;
+        require!(
+            fee_bps <= ctx.accounts.approval.max_fee_bps,
+            ErrorCode::InvalidFeeBps
+        );
+
+        let params = GrantParams {
+            amount_per_period,
+            period_seconds,
+            start_ts,
+            expires_at,
+            // platform は organization に代わって mint のリスクを許容できない
+            allow_freeze_authority: false,
+            category,
+        };
+        validate_grant_params(&params, &ctx.accounts.mint, clock::now(ctx.remaining_accounts)?)?;
+
+        let grant = &mut ctx.accounts.grant;
+        init_grant(
+            grant,
+            GrantKeys {
+                authority: ctx.accounts.organization.key(),
+                mint: ctx.accounts.mint.key(),
+                decimals: ctx.accounts.mint.decimals,
+                vault: ctx.accounts.vault.key(),
+                grant_id,
+                bump: ctx.bumps.grant,
+            },
+            &params,
+        );
+        grant.co_admin = ctx.accounts.platform_signer.key();
+        grant.platform_fee_bps = fee_bps;
+        grant.platform_fee_account = ctx.accounts.platform_fee_account.key();
+        grant.hash_domain = deployment_hash_domain(ctx.accounts.hash_domain_config.as_deref());
+        if let Some(pop_config) = ctx.accounts.pop_config.as_mut() {
+            pop_config.active_grants = pop_config
+                .active_grants
+                .checked_add(1)
+                .ok_or(ErrorCode::MathOverflow)?;
+            grant.pop_config_tracked = true;
+        }
+
+        Ok(())
+    }
 
+    /// platform 手数料の受取口座を変更する（co-admin 限定）
+    pub fn set_platform_fee_account(ctx: Context<SetPlatformFeeAccount>, admin_nonce: u64) -> Result<()> {
+        consume_admin_nonce(
+            &mut ctx.accounts.grant,
+            &ctx.accounts.co_admin,
+            admin_nonce,
+            "set_platform_fee_account",
+            ctx.remaining_accounts,
+        )?;
+        let grant = &mut ctx.accounts.grant;
+        grant.platform_fee_account = ctx.accounts.platform_fee_account.key();
         Ok(())
     }
 
@@ -91,128 +749,436 @@ pub mod grant_program {
     /// create_grant とは別命令として分離することで、更新意図を明示的かつ監査可能にする。
     /// - amount_per_period / period_seconds は既に受給が始まっている場合は変更注意。
     /// - mint / vault / grant_id は変更不可（PDA seed に含まれるため）。
+    /// - 受給開始後に expires_at を早める場合は WIND_DOWN_MIN_SECONDS 以上先の時刻にすること（wind-down 中は変更不可）
     pub fn update_grant(
         ctx: Context<UpdateGrant>,
         amount_per_period: u64,
         period_seconds: i64,
         expires_at: i64,
+        admin_nonce: u64,
     ) -> Result<()> {
+        consume_admin_nonce(
+            &mut ctx.accounts.grant,
+            &ctx.accounts.authority,
+            admin_nonce,
+            "update_grant",
+            ctx.remaining_accounts,
+        )?;
         require!(amount_per_period > 0, ErrorCode::InvalidAmount);
         require!(period_seconds > 0, ErrorCode::InvalidPeriod);
 
         let grant = &mut ctx.accounts.grant;
+        require!(grant.wind_down_ends_at == 0, ErrorCode::WindDownActive);
+        require_no_abrupt_expiry(grant, expires_at, clock::now(ctx.remaining_accounts)?)?;
         grant.amount_per_period = amount_per_period;
         grant.period_seconds = period_seconds;
         grant.expires_at = expires_at;
+        invalidate_review(grant);
 
         Ok(())
     }
 
-    /// 原資入金（追加入金も可能）
-    pub fn fund_grant(ctx: Context<FundGrant>, amount: u64) -> Result<()> {
-        require!(amount > 0, ErrorCode::InvalidAmount);
-
-        // mint整合性
-        require!(ctx.accounts.grant.mint == ctx.accounts.mint.key(), ErrorCode::MintMismatch);
-        require!(ctx.accounts.vault.mint == ctx.accounts.mint.key(), ErrorCode::MintMismatch);
+    /// recovery guardian を登録/変更する（authority 限定、default で解除）
+    pub fn set_recovery(ctx: Context<SetRecovery>, recovery: Pubkey, admin_nonce: u64) -> Result<()> {
+        consume_admin_nonce(
+            &mut ctx.accounts.grant,
+            &ctx.accounts.authority,
+            admin_nonce,
+            "set_recovery",
+            ctx.remaining_accounts,
+        )?;
+        let grant = &mut ctx.accounts.grant;
+        grant.recovery = recovery;
+        grant.recovery_pending_authority = Pubkey::default();
+        grant.recovery_unlocks_at = 0;
+        Ok(())
+    }
 
-        let decimals = ctx.accounts.mint.decimals;
+    /// authority 鍵の復旧を開始する（recovery 限定）
+    /// - RECOVERY_DELAY_SECONDS 経過後に complete_recovery で authority を置き換えられる
+    /// - 待機期間中は現 authority が cancel_recovery で取り消せる
+    pub fn initiate_recovery(ctx: Context<RecoveryAction>, new_authority: Pubkey) -> Result<()> {
+        require!(new_authority != Pubkey::default(), ErrorCode::InvalidRecovery);
 
-        let cpi_accounts = TransferChecked {
-            from: ctx.accounts.from_ata.to_account_info(),
-            mint: ctx.accounts.mint.to_account_info(),
-            to: ctx.accounts.vault.to_account_info(),
-            authority: ctx.accounts.funder.to_account_info(),
-        };
-        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
-        transfer_checked(cpi_ctx, amount, decimals)
+        let now = clock::now(ctx.remaining_accounts)?;
+        let unlocks_at = now
+            .checked_add(RECOVERY_DELAY_SECONDS)
+            .ok_or(ErrorCode::MathOverflow)?;
+        let grant = &mut ctx.accounts.grant;
+        grant.recovery_pending_authority = new_authority;
+        grant.recovery_unlocks_at = unlocks_at;
+
+        emit!(RecoveryInitiated {
+            schema_version: EVENT_SCHEMA_VERSION,
+            grant: grant.key(),
+            recovery: grant.recovery,
+            new_authority,
+            unlocks_at,
+        });
+        Ok(())
     }
 
-    /// 受給（期間内1回のみ）
-    pub fn claim_grant(ctx: Context<ClaimGrant>, period_index: u64) -> Result<()> {
-        let now = Clock::get()?.unix_timestamp;
+    /// 進行中の復旧を取り消す（現 authority 限定）
+    pub fn cancel_recovery(ctx: Context<CancelRecovery>, admin_nonce: u64) -> Result<()> {
+        consume_admin_nonce(
+            &mut ctx.accounts.grant,
+            &ctx.accounts.authority,
+            admin_nonce,
+            "cancel_recovery",
+            ctx.remaining_accounts,
+        )?;
+        let grant = &mut ctx.accounts.grant;
+        require!(
+            grant.recovery_pending_authority != Pubkey::default(),
+            ErrorCode::NoPendingRecovery
+        );
+        grant.recovery_pending_authority = Pubkey::default();
+        grant.recovery_unlocks_at = 0;
+
+        emit!(RecoveryCancelled {
+            schema_version: EVENT_SCHEMA_VERSION,
+            grant: grant.key(),
+            authority: grant.authority,
+        });
+        Ok(())
+    }
 
-        require!(!ctx.accounts.grant.paused, ErrorCode::Paused);
-        // allowlist が有効な場合は proof 付きの claim を要求
+    /// 待機期間経過後に authority を置き換える（recovery 限定）
+    /// NOTE: PDA seed は seed_authority（作成時の authority）を使い続けるため、Grant / vault のアドレスは変わらない。
+    ///       旧 authority の PopConfig への依存登録は解除される（新 authority は自身の PopConfig を用意すること）。
+    pub fn complete_recovery(ctx: Context<RecoveryAction>) -> Result<()> {
+        let now = clock::now(ctx.remaining_accounts)?;
+        let grant = &mut ctx.accounts.grant;
         require!(
-            ctx.accounts.grant.merkle_root == [0u8; 32],
-            ErrorCode::AllowlistRequired
+            grant.recovery_pending_authority != Pubkey::default(),
+            ErrorCode::NoPendingRecovery
         );
+        require!(now >= grant.recovery_unlocks_at, ErrorCode::RecoveryLocked);
+
+        let old_authority = grant.authority;
+        grant.authority = grant.recovery_pending_authority;
+        grant.recovery_pending_authority = Pubkey::default();
+        grant.recovery_unlocks_at = 0;
+        grant.pop_config_tracked = false;
+        // 新 authority の活動として扱い、進行中の dead-man switch の引き継ぎ表明は取り消す
+        grant.last_admin_action_at = now;
+        grant.succession_unlocks_at = 0;
+
+        emit!(AuthorityRecovered {
+            schema_version: EVENT_SCHEMA_VERSION,
+            grant: grant.key(),
+            old_authority,
+            new_authority: grant.authority,
+            recovered_at: now,
+        });
+        Ok(())
+    }
 
-        verify_and_record_pop_proof(ctx.accounts, period_index, now, ctx.bumps.pop_state)?;
-        let grant = &ctx.accounts.grant;
-        require_claim_timing(grant, now, period_index)?;
-        // receipt PDA の seed に period_index が含まれているため
-        // 同じ期間に2回目のclaimをしようとすると init が失敗し、二重受給が防げる
-        // （receipt作成は Accounts 側で init される）
+    /// dead-man switch の successor と無操作期間を設定する（authority 限定、successor = default で解除）
+    /// - authority が inactivity_seconds 以上管理命令を実行しなければ、successor が引き継ぎを表明できる
+    /// - inactivity_seconds は SUCCESSION_MIN_INACTIVITY_SECONDS 以上（解除時は 0）
+    /// - 進行中の引き継ぎ表明は取り消される
+    pub fn set_successor(
+        ctx: Context<SetSuccessor>,
+        successor: Pubkey,
+        inactivity_seconds: i64,
+        admin_nonce: u64,
+    ) -> Result<()> {
+        consume_admin_nonce(
+            &mut ctx.accounts.grant,
+            &ctx.accounts.authority,
+            admin_nonce,
+            "set_successor",
+            ctx.remaining_accounts,
+        )?;
+        let grant = &mut ctx.accounts.grant;
+        if successor == Pubkey::default() {
+            require!(inactivity_seconds == 0, ErrorCode::InvalidSuccession);
+        } else {
+            require!(successor != grant.authority, ErrorCode::InvalidSuccession);
+            require!(
+                inactivity_seconds >= SUCCESSION_MIN_INACTIVITY_SECONDS,
+                ErrorCode::InvalidSuccession
+            );
+        }
+        grant.successor = successor;
+        grant.succession_inactivity_seconds = inactivity_seconds;
+        grant.succession_unlocks_at = 0;
+        Ok(())
+    }
 
-        transfer_from_vault(
-            &ctx.accounts.grant,
-            &ctx.accounts.vault,
-            &ctx.accounts.mint,
-            &ctx.accounts.claimer_ata,
-            &ctx.accounts.token_program,
-            grant.amount_per_period,
+    /// authority の無操作期間が経過した後、successor が引き継ぎを表明する（successor 限定）
+    /// - SUCCESSION_DELAY_SECONDS 経過後に complete_succession で authority を引き継げる
+    /// - 待機期間中に authority が管理命令を1つでも実行すると、表明は取り消される
+    pub fn announce_succession(ctx: Context<SuccessionAction>) -> Result<()> {
+        let now = clock::now(ctx.remaining_accounts)?;
+        let grant = &mut ctx.accounts.grant;
+        require!(grant.succession_unlocks_at == 0, ErrorCode::InvalidSuccession);
+        let inactive_until = grant
+            .last_admin_action_at
+            .checked_add(grant.succession_inactivity_seconds)
+            .ok_or(ErrorCode::MathOverflow)?;
+        require!(now >= inactive_until, ErrorCode::AuthorityStillActive);
+        let unlocks_at = now
+            .checked_add(SUCCESSION_DELAY_SECONDS)
+            .ok_or(ErrorCode::MathOverflow)?;
+        grant.succession_unlocks_at = unlocks_at;
+
+        emit!(SuccessionAnnounced {
+            schema_version: EVENT_SCHEMA_VERSION,
+            grant: grant.key(),
+            authority: grant.authority,
+            successor: grant.successor,
+            last_admin_action_at: grant.last_admin_action_at,
+            unlocks_at,
+        });
+        Ok(())
+    }
+
+    /// 待機期間経過後に successor が authority を引き継ぐ（successor 限定）
+    /// - 引き継ぎ後の successor は未設定になる（新 authority が改めて set_successor する）
+    /// NOTE: complete_recovery と同様、PDA seed は変わらず、旧 authority の PopConfig への依存登録は解除される。
+    pub fn complete_succession(ctx: Context<SuccessionAction>) -> Result<()> {
+        let now = clock::now(ctx.remaining_accounts)?;
+        let grant = &mut ctx.accounts.grant;
+        require!(grant.succession_unlocks_at != 0, ErrorCode::NoPendingSuccession);
+        require!(now >= grant.succession_unlocks_at, ErrorCode::SuccessionLocked);
+
+        let old_authority = grant.authority;
+        grant.authority = grant.successor;
+        grant.successor = Pubkey::default();
+        grant.succession_inactivity_seconds = 0;
+        grant.succession_unlocks_at = 0;
+        grant.last_admin_action_at = now;
+        grant.pop_config_tracked = false;
+
+        emit!(AuthoritySucceeded {
+            schema_version: EVENT_SCHEMA_VERSION,
+            grant: grant.key(),
+            old_authority,
+            new_authority: grant.authority,
+            succeeded_at: now,
+        });
+        Ok(())
+    }
+
+    /// 支給トークンの切り替えを予約する（authority 限定）
+    /// - MINT_MIGRATION_DELAY_SECONDS 経過後に complete_mint_migration で切り替えられる
+    /// - 待機期間中も旧 mint での claim は続く。取り消しは cancel_mint_migration
+    /// - amount_per_period は新 mint の最小単位で指定する
+    pub fn initiate_mint_migration(
+        ctx: Context<InitiateMintMigration>,
+        amount_per_period: u64,
+        admin_nonce: u64,
+    ) -> Result<()> {
+        consume_admin_nonce(
+            &mut ctx.accounts.grant,
+            &ctx.accounts.authority,
+            admin_nonce,
+            "initiate_mint_migration",
+            ctx.remaining_accounts,
         )?;
-        record_receipt(
-            &mut ctx.accounts.receipt,
-            grant.key(),
-            ctx.accounts.claimer.key(),
-            period_index,
-            now,
-        );
+        require!(amount_per_period > 0, ErrorCode::InvalidAmount);
+        let new_mint = ctx.accounts.new_mint.key();
+        require!(new_mint != ctx.accounts.grant.mint, ErrorCode::InvalidMintMigration);
+        require_supported_mint(
+            &ctx.accounts.new_mint,
+            ctx.accounts.grant.freeze_authority_acknowledged,
+        )?;
+        // earmark とマッチングプールは旧 mint 建てのため、先に解消しておくこと
+        require!(!ctx.accounts.grant.escrow_earmarked, ErrorCode::InvalidMintMigration);
+        require!(!ctx.accounts.grant.has_match_pool, ErrorCode::MatchPoolActive);
+        require!(!ctx.accounts.grant.has_keeper_config, ErrorCode::KeeperConfigActive);
+
+        let now = clock::now(ctx.remaining_accounts)?;
+        let unlocks_at = now
+            .checked_add(MINT_MIGRATION_DELAY_SECONDS)
+            .ok_or(ErrorCode::MathOverflow)?;
+        let grant = &mut ctx.accounts.grant;
+        grant.mint_migration_pending_mint = new_mint;
+        grant.mint_migration_amount_per_period = amount_per_period;
+        grant.mint_migration_unlocks_at = unlocks_at;
+
+        emit!(MintMigrationInitiated {
+            schema_version: EVENT_SCHEMA_VERSION,
+            grant: grant.key(),
+            old_mint: grant.mint,
+            new_mint,
+            amount_per_period,
+            unlocks_at,
+        });
+        Ok(())
+    }
 
+    /// 予約中の mint 切り替えを取り消す（authority 限定）
+    pub fn cancel_mint_migration(ctx: Context<CancelMintMigration>, admin_nonce: u64) -> Result<()> {
+        consume_admin_nonce(
+            &mut ctx.accounts.grant,
+            &ctx.accounts.authority,
+            admin_nonce,
+            "cancel_mint_migration",
+            ctx.remaining_accounts,
+        )?;
+        let grant = &mut ctx.accounts.grant;
+        require!(
+            grant.mint_migration_pending_mint != Pubkey::default(),
+            ErrorCode::NoPendingMintMigration
+        );
+        grant.mint_migration_pending_mint = Pubkey::default();
+        grant.mint_migration_amount_per_period = 0;
+        grant.mint_migration_unlocks_at = 0;
         Ok(())
     }
 
-    /// 終了・返金（vaultの残高を回収し、vaultをcloseする）
-    pub fn close_grant(ctx: Context<CloseGrant>) -> Result<()> {
+    /// 待機期間経過後に支給トークンを切り替える（authority 限定）
+    /// - 旧 vault の残高は authority の旧 mint ATA に返金し、旧 vault は close する
+    ///   （交換が必要な場合はオフチェーンで行い、新 mint を fund_grant で入金する）
+    /// - 新 vault は [b"vault", grant, new_mint] に作成する。以降は grant.vault を参照すること
+    /// - Grant のアドレスは seed_mint（作成時の mint）で固定されるため変わらない
+    /// - total_funded は返金分を差し引き、新 vault の残高と突き合わせられるようにする
+    ///   （累計値は旧 mint と新 mint の単位が混在する）
+    /// NOTE: 円建て（yen_per_period > 0）の Grant は、新 mint に合わせて RateConfig も更新すること
+    pub fn complete_mint_migration(ctx: Context<CompleteMintMigration>, admin_nonce: u64) -> Result<()> {
+        consume_admin_nonce(
+            &mut ctx.accounts.grant,
+            &ctx.accounts.authority,
+            admin_nonce,
+            "complete_mint_migration",
+            ctx.remaining_accounts,
+        )?;
+        let now = clock::now(ctx.remaining_accounts)?;
         let grant = &ctx.accounts.grant;
+        require!(
+            grant.mint_migration_pending_mint != Pubkey::default(),
+            ErrorCode::NoPendingMintMigration
+        );
+        require!(now >= grant.mint_migration_unlocks_at, ErrorCode::MintMigrationLocked);
+        require!(!grant.escrow_earmarked, ErrorCode::InvalidMintMigration);
+        require!(!grant.has_match_pool, ErrorCode::MatchPoolActive);
+        require!(!grant.has_keeper_config, ErrorCode::KeeperConfigActive);
+        require!(grant.wind_down_ends_at == 0, ErrorCode::WindDownActive);
+
+        let grant_id_bytes = grant.grant_id.to_le_bytes();
+        let grant_seeds: &[&[u8]] = &[
+            b"grant",
+            grant.seed_authority.as_ref(),
+            grant.seed_mint.as_ref(),
+            &grant_id_bytes,
+            &[grant.bump],
+        ];
+        let signer_seeds: &[&[&[u8]]] = &[grant_seeds];
+
+        // 旧 vault の残高を返金
+        let refunded = ctx.accounts.old_vault.amount;
+        if refunded > 0 {
+            let cpi_accounts = TransferChecked {
+                from: ctx.accounts.old_vault.to_account_info(),
+                mint: ctx.accounts.old_mint.to_account_info(),
+                to: ctx.accounts.authority_ata.to_account_info(),
+                authority: ctx.accounts.grant.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                cpi_accounts,
+                signer_seeds,
+            );
+            transfer_checked(cpi_ctx, refunded, ctx.accounts.old_mint.decimals)?;
+        }
 
-        // 残高があるなら返金
-        let remaining = ctx.accounts.vault.amount;
-        if remaining > 0 {
-            let grant_seeds: &[&[u8]] = &[
-                b"grant",
-                grant.authority.as_ref(),
-                grant.mint.as_ref(),
-                &grant.grant_id.to_le_bytes(),
-                &[grant.bump],
-            ];
+        // 旧 vault を close（rent回収）
+        let cpi_accounts = CloseAccount {
+            account: ctx.accounts.old_vault.to_account_info(),
+            destination: ctx.accounts.authority.to_account_info(),
+            authority: ctx.accounts.grant.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer_seeds,
+        );
+        close_account(cpi_ctx)?;
 
-            let decimals = ctx.accounts.mint.decimals;
+        let new_vault = ctx.accounts.new_vault.key();
+        let new_decimals = ctx.accounts.new_mint.decimals;
+        let grant = &mut ctx.accounts.grant;
+        let old_mint = grant.mint;
+        grant.mint = grant.mint_migration_pending_mint;
+        grant.vault = new_vault;
+        grant.decimals = new_decimals;
+        grant.amount_per_period = grant.mint_migration_amount_per_period;
+        grant.total_funded = grant
+            .total_funded
+            .checked_sub(refunded as u128)
+            .ok_or(ErrorCode::MathOverflow)?;
+        grant.mint_migration_pending_mint = Pubkey::default();
+        grant.mint_migration_amount_per_period = 0;
+        grant.mint_migration_unlocks_at = 0;
+        invalidate_review(grant);
+
+        emit!(MintMigrated {
+            schema_version: EVENT_SCHEMA_VERSION,
+            grant: grant.key(),
+            old_mint,
+            new_mint: grant.mint,
+            new_vault,
+            amount_per_period: grant.amount_per_period,
+            refunded,
+            migrated_at: now,
+        });
+        Ok(())
+    }
+
+    /// vault を作り直す（authority 限定）
+    /// - 残高を [b"vault", grant, vault_nonce] の新しい vault に移し、grant.vault を差し替える
+    /// - delegate / close authority が付いて fund_grant できなくなった vault を、Grant を close せずに復旧する
+    /// - 旧 vault は close authority が付いていなければ close する（付いている場合は rent ごと放置）
+    /// NOTE: 凍結された vault は Grant PDA からも動かせないため対象外
+    pub fn rotate_vault(ctx: Context<RotateVault>, admin_nonce: u64) -> Result<()> {
+        consume_admin_nonce(
+            &mut ctx.accounts.grant,
+            &ctx.accounts.authority,
+            admin_nonce,
+            "rotate_vault",
+            ctx.remaining_accounts,
+        )?;
+        let old_vault = &ctx.accounts.old_vault;
+        require!(!old_vault.is_frozen(), ErrorCode::VaultFrozen);
 
+        let grant = &ctx.accounts.grant;
+        let grant_id_bytes = grant.grant_id.to_le_bytes();
+        let grant_seeds: &[&[u8]] = &[
+            b"grant",
+            grant.seed_authority.as_ref(),
+            grant.seed_mint.as_ref(),
+            &grant_id_bytes,
+            &[grant.bump],
+        ];
+        let signer_seeds: &[&[&[u8]]] = &[grant_seeds];
+
+        // delegate が付いていても、owner である Grant PDA は全額を動かせる
+        let moved = old_vault.amount;
+        if moved > 0 {
             let cpi_accounts = TransferChecked {
-                from: ctx.accounts.vault.to_account_info(),
+                from: ctx.accounts.old_vault.to_account_info(),
                 mint: ctx.accounts.mint.to_account_info(),
-                to: ctx.accounts.authority_ata.to_account_info(),
+                to: ctx.accounts.new_vault.to_account_info(),
                 authority: ctx.accounts.grant.to_account_info(),
             };
-            let signer_seeds: &[&[&[u8]]] = &[grant_seeds];
             let cpi_ctx = CpiContext::new_with_signer(
                 ctx.accounts.token_program.to_account_info(),
                 cpi_accounts,
                 signer_seeds,
             );
-            transfer_checked(cpi_ctx, remaining, decimals)?;
+            transfer_checked(cpi_ctx, moved, ctx.accounts.mint.decimals)?;
         }
 
-        // vault を close（rent回収）
-        {
-            let grant_seeds: &[&[u8]] = &[
-                b"grant",
-                grant.authority.as_ref(),
-                grant.mint.as_ref(),
-                &grant.grant_id.to_le_bytes(),
-                &[grant.bump],
-            ];
-
+        let old_vault_closed = ctx.accounts.old_vault.close_authority.is_none();
+        if old_vault_closed {
             let cpi_accounts = CloseAccount {
-                account: ctx.accounts.vault.to_account_info(),
+                account: ctx.accounts.old_vault.to_account_info(),
                 destination: ctx.accounts.authority.to_account_info(),
                 authority: ctx.accounts.grant.to_account_info(),
             };
-            let signer_seeds: &[&[&[u8]]] = &[grant_seeds];
             let cpi_ctx = CpiContext::new_with_signer(
                 ctx.accounts.token_program.to_account_info(),
                 cpi_accounts,
@@ -221,412 +1187,9156 @@ pub mod grant_program {
             close_account(cpi_ctx)?;
         }
 
-        // Grantアカウント自体は Accounts で close される
+        let old_vault = ctx.accounts.old_vault.key();
+        let new_vault = ctx.accounts.new_vault.key();
+        let grant = &mut ctx.accounts.grant;
+        grant.vault_nonce = grant.vault_nonce.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+        grant.vault = new_vault;
+
+        emit!(VaultRotated {
+            schema_version: EVENT_SCHEMA_VERSION,
+            grant: grant.key(),
+            old_vault,
+            new_vault,
+            vault_nonce: grant.vault_nonce,
+            moved,
+            old_vault_closed,
+            rotated_at: clock::now(ctx.remaining_accounts)?,
+        });
         Ok(())
     }
 
-    /// 一時停止/再開（運用自由度）
-    pub fn set_paused(ctx: Context<SetPaused>, paused: bool) -> Result<()> {
+    /// reviewer（承認者）を設定し、Grant をドラフト状態にする（authority 限定）
+    /// - reviewer が設定された Grant は activate_grant されるまで claim できない
+    /// - 四者確認のため reviewer は authority と別の鍵であること
+    /// - 受給開始後（receipt が1つでもある場合）は設定できない
+    pub fn set_reviewer(ctx: Context<SetReviewer>, reviewer: Pubkey, admin_nonce: u64) -> Result<()> {
+        consume_admin_nonce(
+            &mut ctx.accounts.grant,
+            &ctx.accounts.authority,
+            admin_nonce,
+            "set_reviewer",
+            ctx.remaining_accounts,
+        )?;
         let grant = &mut ctx.accounts.grant;
-        grant.paused = paused;
+        require!(!grant.has_receipts(), ErrorCode::GrantAlreadyClaimed);
+        require!(reviewer != grant.authority, ErrorCode::ReviewerMustDiffer);
+
+        grant.reviewer = reviewer;
+        grant.activated = false;
+        grant.activated_at = 0;
         Ok(())
     }
 
-    /// allowlist を設定（任意）
-    /// - merkle_root が [0;32] の場合は allowlist 無効（誰でも受給可能）
-    /// - それ以外の場合は allowlist 有効（proof を伴う claim が必要）
-    pub fn set_allowlist_root(ctx: Context<SetAllowlistRoot>, merkle_root: [u8; 32]) -> Result<()> {
+    /// ドラフト状態の Grant を reviewer が承認し、claim 可能にする
+    pub fn activate_grant(ctx: Context<ActivateGrant>, admin_nonce: u64) -> Result<()> {
+        consume_admin_nonce(
+            &mut ctx.accounts.grant,
+            &ctx.accounts.reviewer,
+            admin_nonce,
+            "activate_grant",
+            ctx.remaining_accounts,
+        )?;
+        let now = clock::now(ctx.remaining_accounts)?;
         let grant = &mut ctx.accounts.grant;
-        grant.merkle_root = merkle_root;
+        require!(!grant.activated, ErrorCode::GrantAlreadyActivated);
+
+        grant.activated = true;
+        grant.activated_at = now;
+        emit!(GrantActivated {
+            schema_version: EVENT_SCHEMA_VERSION,
+            grant: grant.key(),
+            reviewer: grant.reviewer,
+            activated_at: now,
+        });
         Ok(())
     }
 
-    /// PoP（Proof of Process）署名者を設定/更新
-    pub fn upsert_pop_config(ctx: Context<UpsertPopConfig>, signer_pubkey: Pubkey) -> Result<()> {
-        let pop_config = &mut ctx.accounts.pop_config;
-        pop_config.authority = ctx.accounts.authority.key();
-        pop_config.signer_pubkey = signer_pubkey;
-        pop_config.bump = ctx.bumps.pop_config;
-        Ok(())
+    /// 原資入金（追加入金も可能）
+    pub fn fund_grant(ctx: Context<FundGrant>, amount: u64) -> Result<()> {
+        let result = (|| -> Result<()> {
+            require!(amount > 0, ErrorCode::InvalidAmount);
+
+            // mint整合性
+            require!(ctx.accounts.grant.mint == ctx.accounts.mint.key(), ErrorCode::MintMismatch);
+            require!(ctx.accounts.vault.mint == ctx.accounts.mint.key(), ErrorCode::MintMismatch);
+
+            // 作成後に freeze authority が付与されることはないが、作成時の判断を入金ごとに再確認する
+            require_supported_mint(
+                &ctx.accounts.mint,
+                ctx.accounts.grant.freeze_authority_acknowledged,
+            )?;
+            require_clean_vault(&ctx.accounts.vault)?;
+
+            // マッチングプールが渡され、第三者（authority / sponsor 以外）の入金であれば上乗せする
+            let funder = ctx.accounts.funder.key();
+            let matched = match ctx.accounts.match_pool.as_deref() {
+                Some(pool)
+                    if funder != ctx.accounts.grant.authority && funder != pool.sponsor =>
+                {
+                    let match_vault = ctx
+                        .accounts
+                        .match_vault
+                        .as_ref()
+                        .ok_or(ErrorCode::MatchVaultMismatch)?;
+                    match_amount(pool, match_vault.amount, amount)?
+                }
+                _ => 0,
+            };
+
+            // 期間別 earmark が有効な場合、入金は未充足の tranche に先頭から割り当てる
+            if ctx.accounts.grant.escrow_earmarked {
+                let schedule = ctx
+                    .accounts
+                    .escrow_schedule
+                    .as_mut()
+                    .ok_or(ErrorCode::EscrowScheduleRequired)?;
+                allocate_to_tranches(schedule, add_amounts(amount, matched)?)?;
+            }
+
+            let decimals = ctx.accounts.mint.decimals;
+
+            let cpi_accounts = TransferChecked {
+                from: ctx.accounts.from_ata.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.vault.to_account_info(),
+                authority: ctx.accounts.funder.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+            transfer_checked(cpi_ctx, amount, decimals)?;
+
+            if matched > 0 {
+                let match_vault = ctx
+                    .accounts
+                    .match_vault
+                    .as_ref()
+                    .ok_or(ErrorCode::MatchVaultMismatch)?;
+                transfer_from_vault(
+                    &ctx.accounts.grant,
+                    match_vault,
+                    &ctx.accounts.vault,
+                    &ctx.accounts.token_program,
+                    matched,
+                )?;
+                let remaining_in_vault = match_vault.amount - matched;
+
+                let pool = ctx
+                    .accounts
+                    .match_pool
+                    .as_deref_mut()
+                    .ok_or(ErrorCode::MatchVaultMismatch)?;
+                pool.total_matched = add_amounts(pool.total_matched, matched)?;
+                emit!(MatchApplied {
+                    schema_version: EVENT_SCHEMA_VERSION,
+                    grant: pool.grant,
+                    funder,
+                    contribution: amount,
+                    matched,
+                    total_matched: pool.total_matched,
+                });
+                if !pool.exhausted && (pool.total_matched >= pool.cap || remaining_in_vault == 0) {
+                    pool.exhausted = true;
+                    emit!(MatchPoolExhausted {
+                        schema_version: EVENT_SCHEMA_VERSION,
+                        grant: pool.grant,
+                        sponsor: pool.sponsor,
+                        total_matched: pool.total_matched,
+                        cap_reached: pool.total_matched >= pool.cap,
+                    });
+                }
+            }
+
+            let grant = &mut ctx.accounts.grant;
+            grant.total_funded = add_to_total(grant.total_funded, amount)?;
+            grant.total_funded = add_to_total(grant.total_funded, matched)?;
+            let now = clock::now(ctx.remaining_accounts)?;
+            update_budget_forecast_if_present(
+                ctx.accounts.budget_forecast.as_deref_mut(),
+                &ctx.accounts.grant,
+                None,
+                None,
+                now,
+            )
+        })();
+        structured_log::emit(&result, || LogLine {
+            instruction: "fund_grant",
+            grant: ctx.accounts.grant.key(),
+            claimer: None,
+            period: None,
+            amount: Some(amount),
+        });
+        result
     }
 
-    /// allowlist（Merkle）を用いた受給
-    /// - Grant に merkle_root が設定されている場合はこちらを使用
-    pub fn claim_grant_with_proof(
-        ctx: Context<ClaimGrant>,
-        period_index: u64,
-        proof: Vec<[u8; 32]>,
+    /// 財務担当（operator）向けの入金枠を作成/更新する（authority 限定）
+    /// - source: 入金元の token account（authority 所有）。FundingBudget PDA を allowance の範囲で delegate に設定する
+    /// - monthly_cap: FUNDING_BUDGET_WINDOW_SECONDS ごとに operator が入金できる上限
+    /// - operator は fund_grant_from_budget で source から vault へ移すことだけができる（source の鍵は持たない）
+    /// NOTE: SPL token account の delegate は1つのため、source は FundingBudget ごとに分けること
+    pub fn upsert_funding_budget(
+        ctx: Context<UpsertFundingBudget>,
+        operator: Pubkey,
+        monthly_cap: u64,
+        allowance: u64,
+        admin_nonce: u64,
     ) -> Result<()> {
-        let now = Clock::get()?.unix_timestamp;
+        consume_admin_nonce(
+            &mut ctx.accounts.grant,
+            &ctx.accounts.authority,
+            admin_nonce,
+            "upsert_funding_budget",
+            ctx.remaining_accounts,
+        )?;
+        require!(monthly_cap > 0, ErrorCode::InvalidFundingBudget);
+        require!(operator != Pubkey::default(), ErrorCode::InvalidFundingBudget);
+
+        let budget = &mut ctx.accounts.funding_budget;
+        if budget.grant == Pubkey::default() {
+            budget.grant = ctx.accounts.grant.key();
+            budget.operator = operator;
+            budget.window_start = clock::now(ctx.remaining_accounts)?;
+            budget.window_used = 0;
+            budget.total_funded = 0;
+            budget.bump = ctx.bumps.funding_budget;
+        }
+        budget.source = ctx.accounts.source.key();
+        budget.monthly_cap = monthly_cap;
+
+        let cpi_accounts = Approve {
+            to: ctx.accounts.source.to_account_info(),
+            delegate: ctx.accounts.funding_budget.to_account_info(),
+            authority: ctx.accounts.authority.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        approve(cpi_ctx, allowance)
+    }
 
-        require!(!ctx.accounts.grant.paused, ErrorCode::Paused);
+    /// FundingBudget の枠内で source から vault へ入金する（operator 限定）
+    /// - 期間（FUNDING_BUDGET_WINDOW_SECONDS）内の合計が monthly_cap を超える入金は拒否する
+    /// - 期間別 earmark・total_funded の扱いは fund_grant と同じ（マッチングの対象にはならない）
+    pub fn fund_grant_from_budget(ctx: Context<FundGrantFromBudget>, amount: u64) -> Result<()> {
+        let result = (|| -> Result<()> {
+            require!(amount > 0, ErrorCode::InvalidAmount);
+            require_supported_mint(
+                &ctx.accounts.mint,
+                ctx.accounts.grant.freeze_authority_acknowledged,
+            )?;
+            require_clean_vault(&ctx.accounts.vault)?;
+
+            let now = clock::now(ctx.remaining_accounts)?;
+            let budget = &mut ctx.accounts.funding_budget;
+            (budget.window_start, budget.window_used) =
+                roll_budget_window(budget.window_start, budget.window_used, now);
+            let window_used = add_amounts(budget.window_used, amount)?;
+            require!(window_used <= budget.monthly_cap, ErrorCode::FundingBudgetExceeded);
+            budget.window_used = window_used;
+            budget.total_funded = add_amounts(budget.total_funded, amount)?;
+
+            if ctx.accounts.grant.escrow_earmarked {
+                let schedule = ctx
+                    .accounts
+                    .escrow_schedule
+                    .as_mut()
+                    .ok_or(ErrorCode::EscrowScheduleRequired)?;
+                allocate_to_tranches(schedule, amount)?;
+            }
+
+            let grant_key = ctx.accounts.grant.key();
+            let operator_key = ctx.accounts.operator.key();
+            let budget_seeds: &[&[u8]] = &[
+                b"funding-budget",
+                grant_key.as_ref(),
+                operator_key.as_ref(),
+                &[ctx.accounts.funding_budget.bump],
+            ];
+            let cpi_accounts = TransferChecked {
+                from: ctx.accounts.source.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.vault.to_account_info(),
+                authority: ctx.accounts.funding_budget.to_account_info(),
+            };
+            let signer_seeds: &[&[&[u8]]] = &[budget_seeds];
+            let cpi_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                cpi_accounts,
+                signer_seeds,
+            );
+            transfer_checked(cpi_ctx, amount, ctx.accounts.mint.decimals)?;
+
+            let grant = &mut ctx.accounts.grant;
+            grant.total_funded = add_to_total(grant.total_funded, amount)?;
+
+            let budget = &ctx.accounts.funding_budget;
+            emit!(FundedFromBudget {
+                schema_version: EVENT_SCHEMA_VERSION,
+                grant: grant_key,
+                operator: operator_key,
+                amount,
+                window_start: budget.window_start,
+                window_used: budget.window_used,
+                monthly_cap: budget.monthly_cap,
+            });
+            update_budget_forecast_if_present(
+                ctx.accounts.budget_forecast.as_deref_mut(),
+                &ctx.accounts.grant,
+                None,
+                None,
+                now,
+            )
+        })();
+        structured_log::emit(&result, || LogLine {
+            instruction: "fund_grant_from_budget",
+            grant: ctx.accounts.grant.key(),
+            claimer: None,
+            period: None,
+            amount: Some(amount),
+        });
+        result
+    }
 
-        // allowlist が無効なら通常の claim を使えばよい
+    /// fund_with_swap で受け付ける別 mint と、両替に使う AMM を登録/更新する（authority 限定）
+    /// - input_mint ごとに1つ（USDC など）。grant の mint と同じ mint は登録できない
+    /// - amm_program: 両替で CPI するプログラム（実行可能なプログラムであること。これ以外は呼ばない）
+    pub fn upsert_swap_config(ctx: Context<UpsertSwapConfig>, admin_nonce: u64) -> Result<()> {
+        consume_admin_nonce(
+            &mut ctx.accounts.grant,
+            &ctx.accounts.authority,
+            admin_nonce,
+            "upsert_swap_config",
+            ctx.remaining_accounts,
+        )?;
+        let amm_program = &ctx.accounts.amm_program;
         require!(
-            ctx.accounts.grant.merkle_root != [0u8; 32],
-            ErrorCode::AllowlistNotEnabled
+            ctx.accounts.input_mint.key() != ctx.accounts.grant.mint,
+            ErrorCode::InvalidSwapConfig
         );
-
-        // Merkle allowlist verify
-        let leaf = allowlist_leaf(ctx.accounts.claimer.key());
         require!(
-            verify_merkle_sorted(ctx.accounts.grant.merkle_root, leaf, &proof),
-            ErrorCode::NotInAllowlist
+            amm_program.executable && amm_program.key() != crate::ID && amm_program.key() != anchor_spl::token::ID,
+            ErrorCode::InvalidSwapConfig
         );
 
-        verify_and_record_pop_proof(ctx.accounts, period_index, now, ctx.bumps.pop_state)?;
-        let grant = &ctx.accounts.grant;
-        require_claim_timing(grant, now, period_index)?;
-        transfer_from_vault(
-            &ctx.accounts.grant,
-            &ctx.accounts.vault,
-            &ctx.accounts.mint,
-            &ctx.accounts.claimer_ata,
-            &ctx.accounts.token_program,
-            grant.amount_per_period,
+        let config = &mut ctx.accounts.swap_config;
+        config.grant = ctx.accounts.grant.key();
+        config.input_mint = ctx.accounts.input_mint.key();
+        config.amm_program = amm_program.key();
+        config.bump = ctx.bumps.swap_config;
+        Ok(())
+    }
+
+    /// SwapConfig を削除する（authority 限定）
+    pub fn close_swap_config(ctx: Context<CloseSwapConfig>, admin_nonce: u64) -> Result<()> {
+        consume_admin_nonce(
+            &mut ctx.accounts.grant,
+            &ctx.accounts.authority,
+            admin_nonce,
+            "close_swap_config",
+            ctx.remaining_accounts,
         )?;
-        record_receipt(
-            &mut ctx.accounts.receipt,
-            grant.key(),
-            ctx.accounts.claimer.key(),
+        // SwapConfig 自体は Accounts で close される
+        Ok(())
+    }
+
+    /// 別 mint（SwapConfig.input_mint）での入金を、登録済みの AMM で grant の mint に両替して vault に入れる（誰でも実行可）
+    /// - swap_data / remaining_accounts: AMM の swap 命令のデータとアカウント（並びは AMM の仕様どおり）。
+    ///   出力先は vault、入力元は from_ata とし、funder の署名はそのまま AMM に渡る（Grant PDA は署名しない）
+    /// - vault の増加分を入金額とし、min_amount_out 未満なら拒否する。from_ata からの支出は amount_in まで
+    /// - 期間別 earmark・total_funded の扱いは fund_grant と同じ（マッチングの対象にはならない）
+    pub fn fund_with_swap<'info>(
+        ctx: Context<'_, '_, 'info, 'info, FundWithSwap<'info>>,
+        amount_in: u64,
+        min_amount_out: u64,
+        swap_data: Vec<u8>,
+    ) -> Result<()> {
+        let result = (|| -> Result<u64> {
+            require!(amount_in > 0 && min_amount_out > 0, ErrorCode::InvalidAmount);
+            require_supported_mint(
+                &ctx.accounts.mint,
+                ctx.accounts.grant.freeze_authority_acknowledged,
+            )?;
+            require_clean_vault(&ctx.accounts.vault)?;
+            // remaining_accounts は AMM の命令に使うため、ClockOverride は受け付けない
+            let now = clock::now(&[])?;
+
+            let vault_before = ctx.accounts.vault.amount;
+            let input_before = ctx.accounts.from_ata.amount;
+            let swap_ix = Instruction {
+                program_id: ctx.accounts.amm_program.key(),
+                accounts: ctx
+                    .remaining_accounts
+                    .iter()
+                    .map(|account| AccountMeta {
+                        pubkey: account.key(),
+                        is_signer: account.is_signer,
+                        is_writable: account.is_writable,
+                    })
+                    .collect(),
+                data: swap_data,
+            };
+            let mut swap_accounts = ctx.remaining_accounts.to_vec();
+            swap_accounts.push(ctx.accounts.amm_program.to_account_info());
+            invoke(&swap_ix, &swap_accounts)?;
+
+            ctx.accounts.vault.reload()?;
+            ctx.accounts.from_ata.reload()?;
+            let amount_out = ctx
+                .accounts
+                .vault
+                .amount
+                .checked_sub(vault_before)
+                .ok_or(ErrorCode::SwapSlippageExceeded)?;
+            require!(amount_out >= min_amount_out, ErrorCode::SwapSlippageExceeded);
+            let spent = input_before.saturating_sub(ctx.accounts.from_ata.amount);
+            require!(spent <= amount_in, ErrorCode::SwapInputExceeded);
+
+            if ctx.accounts.grant.escrow_earmarked {
+                let schedule = ctx
+                    .accounts
+                    .escrow_schedule
+                    .as_mut()
+                    .ok_or(ErrorCode::EscrowScheduleRequired)?;
+                allocate_to_tranches(schedule, amount_out)?;
+            }
+
+            let grant = &mut ctx.accounts.grant;
+            grant.total_funded = add_to_total(grant.total_funded, amount_out)?;
+            emit!(FundedWithSwap {
+                schema_version: EVENT_SCHEMA_VERSION,
+                grant: grant.key(),
+                funder: ctx.accounts.funder.key(),
+                input_mint: ctx.accounts.swap_config.input_mint,
+                amm_program: ctx.accounts.swap_config.amm_program,
+                amount_in: spent,
+                amount_out,
+            });
+            update_budget_forecast_if_present(
+                ctx.accounts.budget_forecast.as_deref_mut(),
+                &ctx.accounts.grant,
+                None,
+                None,
+                now,
+            )?;
+            Ok(amount_out)
+        })();
+        structured_log::emit(&result, || LogLine {
+            instruction: "fund_with_swap",
+            grant: ctx.accounts.grant.key(),
+            claimer: None,
+            period: None,
+            amount: result.as_ref().ok().copied(),
+        });
+        result.map(|_| ())
+    }
+
+    /// FundingBudget を削除し、source の delegate を取り消す（authority 限定）
+    pub fn close_funding_budget(ctx: Context<CloseFundingBudget>, admin_nonce: u64) -> Result<()> {
+        consume_admin_nonce(
+            &mut ctx.accounts.grant,
+            &ctx.accounts.authority,
+            admin_nonce,
+            "close_funding_budget",
+            ctx.remaining_accounts,
+        )?;
+        let cpi_accounts = Revoke {
+            source: ctx.accounts.source.to_account_info(),
+            authority: ctx.accounts.authority.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        revoke(cpi_ctx)?;
+        // FundingBudget 自体は Accounts で close される
+        Ok(())
+    }
+
+    /// program officer（日常管理を委任する鍵）を登録/更新する（authority 限定）
+    /// - withdrawal_cap: FUNDING_BUDGET_WINDOW_SECONDS ごとに officer_withdraw で vault から引き出せる上限（0 = 不可）
+    /// - withdrawal_destination: 引き出し先（authority が固定する。officer は変更できない）
+    /// - max_keeper_bounty: officer_set_keeper_bounty で設定できる close_period 報酬の上限（0 = 変更不可）
+    pub fn upsert_program_officer(
+        ctx: Context<UpsertProgramOfficer>,
+        officer: Pubkey,
+        withdrawal_cap: u64,
+        max_keeper_bounty: u64,
+        admin_nonce: u64,
+    ) -> Result<()> {
+        consume_admin_nonce(
+            &mut ctx.accounts.grant,
+            &ctx.accounts.authority,
+            admin_nonce,
+            "upsert_program_officer",
+            ctx.remaining_accounts,
+        )?;
+        require!(
+            officer != Pubkey::default() && officer != ctx.accounts.grant.authority,
+            ErrorCode::InvalidProgramOfficer
+        );
+
+        let record = &mut ctx.accounts.program_officer;
+        if record.grant == Pubkey::default() {
+            record.grant = ctx.accounts.grant.key();
+            record.officer = officer;
+            record.window_start = clock::now(ctx.remaining_accounts)?;
+            record.window_used = 0;
+            record.total_withdrawn = 0;
+            record.bump = ctx.bumps.program_officer;
+        }
+        record.withdrawal_destination = ctx.accounts.withdrawal_destination.key();
+        record.withdrawal_cap = withdrawal_cap;
+        record.max_keeper_bounty = max_keeper_bounty;
+
+        emit!(ProgramOfficerUpdated {
+            schema_version: EVENT_SCHEMA_VERSION,
+            grant: record.grant,
+            officer,
+            withdrawal_destination: record.withdrawal_destination,
+            withdrawal_cap,
+            max_keeper_bounty,
+        });
+        Ok(())
+    }
+
+    /// program officer の登録を削除する（authority 限定、rent は authority に戻る）
+    pub fn remove_program_officer(ctx: Context<RemoveProgramOfficer>, admin_nonce: u64) -> Result<()> {
+        consume_admin_nonce(
+            &mut ctx.accounts.grant,
+            &ctx.accounts.authority,
+            admin_nonce,
+            "remove_program_officer",
+            ctx.remaining_accounts,
+        )?;
+        emit!(ProgramOfficerRemoved {
+            schema_version: EVENT_SCHEMA_VERSION,
+            grant: ctx.accounts.grant.key(),
+            officer: ctx.accounts.program_officer.officer,
+        });
+        // ProgramOfficer 自体は Accounts で close される
+        Ok(())
+    }
+
+    /// vault から withdrawal_destination へ引き出す（officer 限定）
+    /// - 期間（FUNDING_BUDGET_WINDOW_SECONDS）内の合計が withdrawal_cap を超える引き出しは拒否する
+    /// - wind-down 中（残高は受給者のための escrow）と期間別 earmark が有効な Grant では引き出せない
+    /// - 引き出した分は total_funded から差し引く
+    pub fn officer_withdraw(ctx: Context<OfficerWithdraw>, amount: u64, admin_nonce: u64) -> Result<()> {
+        consume_admin_nonce(
+            &mut ctx.accounts.grant,
+            &ctx.accounts.officer,
+            admin_nonce,
+            "officer_withdraw",
+            ctx.remaining_accounts,
+        )?;
+        require!(amount > 0, ErrorCode::InvalidAmount);
+        let grant = &ctx.accounts.grant;
+        require!(grant.wind_down_ends_at == 0, ErrorCode::WindDownActive);
+        require!(!grant.escrow_earmarked, ErrorCode::OfficerWithdrawalNotAllowed);
+
+        let now = clock::now(ctx.remaining_accounts)?;
+        let record = &mut ctx.accounts.program_officer;
+        (record.window_start, record.window_used) =
+            roll_budget_window(record.window_start, record.window_used, now);
+        let window_used = add_amounts(record.window_used, amount)?;
+        require!(window_used <= record.withdrawal_cap, ErrorCode::OfficerLimitExceeded);
+        record.window_used = window_used;
+        record.total_withdrawn = add_amounts(record.total_withdrawn, amount)?;
+
+        transfer_from_vault(
+            &ctx.accounts.grant,
+            &ctx.accounts.vault,
+            &ctx.accounts.withdrawal_destination,
+            &ctx.accounts.token_program,
+            amount,
+        )?;
+        let grant = &mut ctx.accounts.grant;
+        // vault の会計（verify_invariants）と整合させる
+        grant.total_funded = grant
+            .total_funded
+            .checked_sub(amount as u128)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        emit!(OfficerWithdrawal {
+            schema_version: EVENT_SCHEMA_VERSION,
+            grant: grant.key(),
+            officer: ctx.accounts.officer.key(),
+            destination: ctx.accounts.withdrawal_destination.key(),
+            amount,
+            window_used,
+            withdrawal_cap: ctx.accounts.program_officer.withdrawal_cap,
+        });
+        Ok(())
+    }
+
+    /// KeeperConfig.period_close_bounty を max_keeper_bounty の範囲で変更する（officer 限定）
+    pub fn officer_set_keeper_bounty(
+        ctx: Context<OfficerSetKeeperBounty>,
+        period_close_bounty: u64,
+        admin_nonce: u64,
+    ) -> Result<()> {
+        consume_admin_nonce(
+            &mut ctx.accounts.grant,
+            &ctx.accounts.officer,
+            admin_nonce,
+            "officer_set_keeper_bounty",
+            ctx.remaining_accounts,
+        )?;
+        require!(
+            period_close_bounty <= ctx.accounts.program_officer.max_keeper_bounty,
+            ErrorCode::OfficerLimitExceeded
+        );
+        ctx.accounts.keeper_config.period_close_bounty = period_close_bounty;
+        Ok(())
+    }
+
+    /// マッチングプールを作成する（authority と sponsor の両方が署名）
+    /// - ratio_bps: 第三者の入金 1 に対して上乗せする割合（10_000 = 1:1）
+    /// - cap: 上乗せの累計上限
+    /// - 原資は fund_match_pool で sponsor が match_vault に入金する
+    pub fn create_match_pool(
+        ctx: Context<CreateMatchPool>,
+        ratio_bps: u16,
+        cap: u64,
+        admin_nonce: u64,
+    ) -> Result<()> {
+        consume_admin_nonce(
+            &mut ctx.accounts.grant,
+            &ctx.accounts.authority,
+            admin_nonce,
+            "create_match_pool",
+            ctx.remaining_accounts,
+        )?;
+        require!(ratio_bps > 0 && cap > 0, ErrorCode::InvalidMatchPool);
+
+        let pool = &mut ctx.accounts.match_pool;
+        pool.grant = ctx.accounts.grant.key();
+        pool.sponsor = ctx.accounts.sponsor.key();
+        pool.match_vault = ctx.accounts.match_vault.key();
+        pool.ratio_bps = ratio_bps;
+        pool.cap = cap;
+        pool.total_matched = 0;
+        pool.exhausted = false;
+        pool.bump = ctx.bumps.match_pool;
+
+        ctx.accounts.grant.has_match_pool = true;
+        Ok(())
+    }
+
+    /// マッチングプールへの原資入金（sponsor 限定）
+    pub fn fund_match_pool(ctx: Context<FundMatchPool>, amount: u64) -> Result<()> {
+        require!(amount > 0, ErrorCode::InvalidAmount);
+
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.sponsor_ata.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.match_vault.to_account_info(),
+            authority: ctx.accounts.sponsor.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        transfer_checked(cpi_ctx, amount, ctx.accounts.mint.decimals)?;
+
+        // 再入金された場合は上乗せを再開できる（cap 到達済みの場合を除く）
+        let pool = &mut ctx.accounts.match_pool;
+        pool.exhausted = pool.total_matched >= pool.cap;
+        Ok(())
+    }
+
+    /// マッチングプールを終了し、未使用の原資を sponsor に返す（sponsor または authority）
+    /// - close_grant の前に実行すること（match_vault の authority は Grant PDA のため）
+    pub fn close_match_pool(ctx: Context<CloseMatchPool>) -> Result<()> {
+        let remaining = ctx.accounts.match_vault.amount;
+        if remaining > 0 {
+            transfer_from_vault(
+                &ctx.accounts.grant,
+                &ctx.accounts.match_vault,
+                &ctx.accounts.sponsor_ata,
+                &ctx.accounts.token_program,
+                remaining,
+            )?;
+        }
+
+        let grant = &ctx.accounts.grant;
+        let grant_id_bytes = grant.grant_id.to_le_bytes();
+        let grant_seeds: &[&[u8]] = &[
+            b"grant",
+            grant.seed_authority.as_ref(),
+            grant.seed_mint.as_ref(),
+            &grant_id_bytes,
+            &[grant.bump],
+        ];
+        let cpi_accounts = CloseAccount {
+            account: ctx.accounts.match_vault.to_account_info(),
+            destination: ctx.accounts.sponsor.to_account_info(),
+            authority: ctx.accounts.grant.to_account_info(),
+        };
+        let signer_seeds: &[&[&[u8]]] = &[grant_seeds];
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer_seeds,
+        );
+        close_account(cpi_ctx)?;
+
+        ctx.accounts.grant.has_match_pool = false;
+        // MatchPool 自体は Accounts で close される
+        Ok(())
+    }
+
+    /// 期間別の earmark（tranche）を設定する（authority 限定、入金前のみ）
+    /// - commitments[i] は period_index = i の期間に確保する額（支給額 + platform 手数料の合計上限）
+    /// - 以後の fund_grant は未充足の tranche に先頭から割り当てられ、合計を超える入金は拒否される
+    /// - tranche が全額充足されていない期間、および commitments の範囲外の期間は claim できない
+    pub fn set_escrow_schedule(
+        ctx: Context<SetEscrowSchedule>,
+        commitments: Vec<u64>,
+        admin_nonce: u64,
+    ) -> Result<()> {
+        consume_admin_nonce(
+            &mut ctx.accounts.grant,
+            &ctx.accounts.authority,
+            admin_nonce,
+            "set_escrow_schedule",
+            ctx.remaining_accounts,
+        )?;
+        require!(
+            !commitments.is_empty() && commitments.len() <= MAX_ESCROW_TRANCHES,
+            ErrorCode::InvalidEscrowSchedule
+        );
+        let grant = &mut ctx.accounts.grant;
+        require!(grant.total_funded == 0, ErrorCode::GrantAlreadyFunded);
+        require!(!grant.has_receipts(), ErrorCode::GrantAlreadyClaimed);
+
+        let schedule = &mut ctx.accounts.escrow_schedule;
+        schedule.grant = grant.key();
+        schedule.tranches = commitments
+            .into_iter()
+            .map(|committed| EscrowTranche {
+                committed,
+                funded: 0,
+                claimed: 0,
+            })
+            .collect();
+        schedule.bump = ctx.bumps.escrow_schedule;
+
+        grant.escrow_earmarked = true;
+        invalidate_review(grant);
+        Ok(())
+    }
+
+    /// 受給（期間内1回のみ）
+    pub fn claim_grant<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ClaimGrant<'info>>,
+        period_index: u64,
+    ) -> Result<()> {
+        let result = (|| -> Result<()> {
+            let now = clock::now(ctx.remaining_accounts)?;
+
+            require!(!ctx.accounts.grant.paused, ErrorCode::Paused);
+            if ctx.accounts.grant.has_eligibility_predicate() {
+                require_eligibility(ctx.accounts, false, now)?;
+            } else {
+                // allowlist が有効な場合は proof 付きの claim を要求
+                require!(
+                    ctx.accounts.grant.merkle_root == [0u8; 32],
+                    ErrorCode::AllowlistRequired
+                );
+            }
+            require_canonical_destination(ctx.accounts)?;
+
+            execute_claim(ctx.accounts, &ctx.bumps, ctx.remaining_accounts, period_index, now)
+        })();
+        structured_log::emit(&result, || claim_log_line("claim_grant", ctx.accounts, period_index));
+        result
+    }
+
+    /// 期限前の終了に向けて wind-down を開始する（authority 限定）
+    /// - window_seconds（WIND_DOWN_MIN_SECONDS 以上）の間、vault の残高は受給者のための escrow として扱う
+    ///   expires_at を wind-down の終了時刻に前倒しし、それまで close_grant できない
+    /// - wind-down 中は allowlist の置き換え・一時停止・パラメータ変更・シーズン開始・mint 切り替えを受け付けない
+    ///   （受給途中の claimer を締め出せないようにする。append_allowlist_delta による追加は可能）
+    /// - 一時停止中の Grant では開始できない（再開してから wind-down に入る）
+    pub fn begin_wind_down(ctx: Context<BeginWindDown>, window_seconds: i64, admin_nonce: u64) -> Result<()> {
+        consume_admin_nonce(
+            &mut ctx.accounts.grant,
+            &ctx.accounts.authority,
+            admin_nonce,
+            "begin_wind_down",
+            ctx.remaining_accounts,
+        )?;
+        let now = clock::now(ctx.remaining_accounts)?;
+        let grant = &mut ctx.accounts.grant;
+        require!(grant.wind_down_ends_at == 0, ErrorCode::WindDownActive);
+        require!(!grant.paused, ErrorCode::Paused);
+        require!(window_seconds >= WIND_DOWN_MIN_SECONDS, ErrorCode::InvalidWindDown);
+        let ends_at = now.checked_add(window_seconds).ok_or(ErrorCode::MathOverflow)?;
+        // 既に wind-down より前に期限を迎える Grant は、期限後にそのまま close できる
+        require!(
+            grant.expires_at == 0 || grant.expires_at > ends_at,
+            ErrorCode::InvalidWindDown
+        );
+
+        grant.wind_down_ends_at = ends_at;
+        grant.expires_at = ends_at;
+
+        emit!(WindDownStarted {
+            schema_version: EVENT_SCHEMA_VERSION,
+            grant: grant.key(),
+            authority: grant.authority,
+            vault_balance: ctx.accounts.vault.amount,
+            started_at: now,
+            ends_at,
+        });
+        Ok(())
+    }
+
+    /// 終了・返金（vaultの残高を回収し、vaultをcloseする）
+    /// - 受給可能な Grant を期限前に close する場合は、先に begin_wind_down で wind-down を終えること
+    ///   （vault が空の場合、受給がまだ始まっていない場合は不要）
+    pub fn close_grant(ctx: Context<CloseGrant>, admin_nonce: u64) -> Result<()> {
+        consume_admin_nonce(
+            &mut ctx.accounts.grant,
+            &ctx.accounts.authority,
+            admin_nonce,
+            "close_grant",
+            ctx.remaining_accounts,
+        )?;
+        let grant = &ctx.accounts.grant;
+        require!(!grant.has_match_pool, ErrorCode::MatchPoolActive);
+        require!(!grant.has_keeper_config, ErrorCode::KeeperConfigActive);
+        require!(!grant.has_budget_forecast, ErrorCode::BudgetForecastActive);
+        require!(
+            grant.archive_opened_at == 0 || grant.archived_at != 0,
+            ErrorCode::ArchiveInProgress
+        );
+
+        // 残高があるなら返金
+        let remaining = ctx.accounts.vault.amount;
+        require!(
+            !requires_wind_down(grant, remaining, clock::now(ctx.remaining_accounts)?),
+            ErrorCode::WindDownRequired
+        );
+        if remaining > 0 {
+            let grant_seeds: &[&[u8]] = &[
+                b"grant",
+                grant.seed_authority.as_ref(),
+                grant.seed_mint.as_ref(),
+                &grant.grant_id.to_le_bytes(),
+                &[grant.bump],
+            ];
+
+            let decimals = ctx.accounts.mint.decimals;
+
+            let cpi_accounts = TransferChecked {
+                from: ctx.accounts.vault.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.authority_ata.to_account_info(),
+                authority: ctx.accounts.grant.to_account_info(),
+            };
+            let signer_seeds: &[&[&[u8]]] = &[grant_seeds];
+            let cpi_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                cpi_accounts,
+                signer_seeds,
+            );
+            transfer_checked(cpi_ctx, remaining, decimals)?;
+        }
+
+        // vault を close（rent回収）
+        {
+            let grant_seeds: &[&[u8]] = &[
+                b"grant",
+                grant.seed_authority.as_ref(),
+                grant.seed_mint.as_ref(),
+                &grant.grant_id.to_le_bytes(),
+                &[grant.bump],
+            ];
+
+            let cpi_accounts = CloseAccount {
+                account: ctx.accounts.vault.to_account_info(),
+                destination: ctx.accounts.authority.to_account_info(),
+                authority: ctx.accounts.grant.to_account_info(),
+            };
+            let signer_seeds: &[&[&[u8]]] = &[grant_seeds];
+            let cpi_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                cpi_accounts,
+                signer_seeds,
+            );
+            close_account(cpi_ctx)?;
+        }
+
+        // PopConfig の依存 Grant 数を戻す
+        if ctx.accounts.grant.pop_config_tracked {
+            let pop_config = ctx
+                .accounts
+                .pop_config
+                .as_mut()
+                .ok_or(ErrorCode::PopConfigRequired)?;
+            pop_config.active_grants = pop_config
+                .active_grants
+                .checked_sub(1)
+                .ok_or(ErrorCode::MathOverflow)?;
+        }
+
+        // 任意: 同一トランザクションで PopState も close する（rent は立て替えた payer へ）
+        if let Some(pop_state) = ctx.accounts.pop_state.as_ref() {
+            let payer = ctx
+                .accounts
+                .pop_state_payer
+                .as_ref()
+                .ok_or(ErrorCode::PopStatePayerMismatch)?;
+            require!(payer.key() == pop_state.payer, ErrorCode::PopStatePayerMismatch);
+            pop_state.close(payer.to_account_info())?;
+        }
+
+        let grant = &ctx.accounts.grant;
+        emit!(GrantClosed {
+            schema_version: EVENT_SCHEMA_VERSION,
+            grant: grant.key(),
+            authority: grant.authority,
+            mint: grant.mint,
+            grant_id: grant.grant_id,
+            total_funded: grant.total_funded,
+            total_claimed: grant.total_claimed,
+            claims_count: grant.claims_count,
+            refunded: remaining,
+            pop_state_closed: ctx.accounts.pop_state.is_some(),
+            closed_at: clock::now(ctx.remaining_accounts)?,
+        });
+
+        // Grantアカウント自体は Accounts で close される
+        Ok(())
+    }
+
+    /// PopState を close して rent を立て替えた payer に返す（Grant close 後のみ）
+    /// - 返金先は pop_state.payer に固定されるため、誰が呼んでもよい
+    pub fn close_pop_state(ctx: Context<ClosePopState>) -> Result<()> {
+        let grant_info = ctx.accounts.grant.to_account_info();
+        require!(
+            grant_info.data_is_empty() && grant_info.lamports() == 0,
+            ErrorCode::GrantStillActive
+        );
+        // PopState 自体は Accounts で close される
+        Ok(())
+    }
+
+    /// PopConfig を close して rent を authority に返す（authority 限定）
+    /// - この PopConfig に依存する Grant が残っている場合は拒否する
+    pub fn close_pop_config(ctx: Context<ClosePopConfig>) -> Result<()> {
+        require!(
+            ctx.accounts.pop_config.active_grants == 0,
+            ErrorCode::PopConfigInUse
+        );
+        // PopConfig 自体は Accounts で close される
+        Ok(())
+    }
+
+    /// 会計の不変条件を検証する（誰でも実行可能）
+    /// - vault 残高 == total_funded - total_claimed - total_platform_fees
+    /// - 一致しない場合は Discrepancy を発行する（トランザクション自体は成功させ、イベントを残す）
+    /// - fund_grant を経由しない直接送金も「差分」として検出される
+    pub fn verify_invariants(ctx: Context<VerifyInvariants>) -> Result<()> {
+        let grant = &ctx.accounts.grant;
+        let vault_balance = ctx.accounts.vault.amount;
+        let expected_balance = grant.total_funded as i128
+            - grant.total_claimed as i128
+            - grant.total_platform_fees as i128;
+        let difference = vault_balance as i128 - expected_balance;
+
+        if difference != 0 {
+            emit!(Discrepancy {
+                schema_version: EVENT_SCHEMA_VERSION,
+                grant: grant.key(),
+                vault_balance,
+                expected_balance,
+                difference,
+                total_funded: grant.total_funded,
+                total_claimed: grant.total_claimed,
+                total_platform_fees: grant.total_platform_fees,
+                checked_at: clock::now(ctx.remaining_accounts)?,
+            });
+        }
+        Ok(())
+    }
+
+    /// 期間の締め（誰でも実行可能）
+    /// - period_index の期間が終了し、境界の猶予（PERIOD_BOUNDARY_GRACE_SECONDS）も過ぎていること
+    /// - PeriodStats を確定させ、受給率の集計を PeriodClosed として発行する（1期間1回）
+    /// - 受給ゼロの期間でも実行できる（PeriodStats は実行者負担で作成）
+    /// - 締め済みの PeriodStats（closed / closed_at / unclaimed_count）は以後変更されないため、
+    ///   未受給分の繰り越し・回収は締め済みであることを前提に集計できる
+    /// - keeper_config を渡した場合、実行者（payer）に KeeperConfig.period_close_bounty を支払う
+    ///   （keepers が設定されている場合は登録済みの keeper のみ。原資が不足する場合は残高の範囲で支払う）
+    /// - KPI 目標（set_kpi_targets）が設定されていれば、目標に対する進捗を KpiProgress として発行する
+    pub fn close_period(ctx: Context<ClosePeriod>, period_index: u64) -> Result<()> {
+        let now = clock::now(ctx.remaining_accounts)?;
+        let grant = &ctx.accounts.grant;
+        let period_end = period_start_ts(grant, period_index.checked_add(1).ok_or(ErrorCode::MathOverflow)?)?;
+        // 境界の猶予中はまだ claim されうるため、猶予が明けてから締める
+        require!(
+            now > period_end.saturating_add(PERIOD_BOUNDARY_GRACE_SECONDS),
+            ErrorCode::PeriodNotEnded
+        );
+
+        let mut stats = load_period_stats(&ctx.accounts.period_stats)?;
+        touch_period_stats(&mut stats, grant, period_index, ctx.bumps.period_stats);
+        require!(!stats.is_closed(), ErrorCode::PeriodAlreadyClosed);
+        stats.closed = 1;
+        stats.closed_at = now;
+        stats.unclaimed_count = stats.eligible_count.saturating_sub(stats.claims_count);
+
+        emit!(PeriodClosed {
+            schema_version: EVENT_SCHEMA_VERSION,
+            grant: grant.key(),
             period_index,
-            now,
+            eligible_count: stats.eligible_count,
+            claims_count: stats.claims_count,
+            total_claimed: stats.total_claimed,
+            unclaimed_count: stats.unclaimed_count,
+            closed_at: now,
+        });
+        let take_up_bps = take_up_bps(stats.claims_count, stats.eligible_count)?;
+        drop(stats);
+        record_kpi_progress(&mut ctx.accounts.grant, period_index, take_up_bps, now);
+
+        if ctx.accounts.keeper_config.is_some() {
+            pay_period_close_bounty(ctx.accounts, period_index)?;
+        }
+        Ok(())
+    }
+
+    /// 期間の receipt を事前に作成しておく（authority または KeeperConfig.keepers に登録された keeper、rent は実行者負担）
+    /// - 期間開始直後の claim からアカウント作成（rent の支払い）を外し、集中時の失敗を減らすため
+    /// - remaining_accounts: claimers と同じ順に receipt PDA と ReceiptReservation PDA の組
+    ///   （いずれも writable、最大 MAX_RECEIPT_RESERVATIONS_PER_BATCH 組）
+    /// - 作成した receipt は status = Reserved（受給記録ではない。is_valid_receipt は false）で、
+    ///   claim 時に Valid の受給記録として上書きされる
+    /// - ReceiptReservation に rent の支払者を記録し、release_receipt_reservations はその支払者に rent を戻す
+    /// - 既に存在する receipt（予約済み・受給済み）は飛ばす。先に lamports だけ送られたアドレスにも作成できる
+    /// - 終了前の期間（現在または将来）に限る。受給されなかった予約は期間終了後に release_receipt_reservations で回収する
+    pub fn reserve_receipts<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ReserveReceipts<'info>>,
+        period_index: u64,
+        claimers: Vec<Pubkey>,
+    ) -> Result<()> {
+        require!(
+            !claimers.is_empty() && claimers.len() <= MAX_RECEIPT_RESERVATIONS_PER_BATCH,
+            ErrorCode::InvalidBatchSize
+        );
+        require!(
+            ctx.remaining_accounts.len() == claimers.len() * 2,
+            ErrorCode::InvalidBatchSize
         );
+        let grant = &ctx.accounts.grant;
+        let reserver = ctx.accounts.reserver.key();
+        let is_keeper = ctx
+            .accounts
+            .keeper_config
+            .as_ref()
+            .is_some_and(|config| config.keepers.contains(&reserver));
+        require!(reserver == grant.authority || is_keeper, ErrorCode::Unauthorized);
+
+        // remaining_accounts は receipt に使うため、ClockOverride は受け付けない
+        let now = clock::now(&[])?;
+        let period_end = period_start_ts(grant, period_index.checked_add(1).ok_or(ErrorCode::MathOverflow)?)?;
+        require!(now < period_end, ErrorCode::PeriodAlreadyEnded);
+
+        let rent = Rent::get()?;
+        let space = 8 + ClaimReceipt::INIT_SPACE;
+        let reservation_space = 8 + ReceiptReservation::INIT_SPACE;
+        let grant_key = grant.key();
+        let period_index_bytes = period_index.to_le_bytes();
+        let reserver_info = ctx.accounts.reserver.to_account_info();
+        let system_program = ctx.accounts.system_program.to_account_info();
+        let mut reserved = 0u32;
+        for (claimer, accounts) in claimers.iter().zip(ctx.remaining_accounts.chunks_exact(2)) {
+            let (receipt_info, reservation_info) = (&accounts[0], &accounts[1]);
+            let (receipt_key, bump) =
+                receipt::receipt_address(&grant_key, claimer, period_index, &grant.seed_salt);
+            require_keys_eq!(receipt_info.key(), receipt_key, ErrorCode::InvalidBatchAccount);
+            let (reservation_key, reservation_bump) =
+                Pubkey::find_program_address(&[b"receipt-reservation", receipt_key.as_ref()], ctx.program_id);
+            require_keys_eq!(reservation_info.key(), reservation_key, ErrorCode::InvalidBatchAccount);
+            if *receipt_info.owner == crate::ID || *reservation_info.owner == crate::ID {
+                continue;
+            }
+
+            let receipt_seeds: &[&[u8]] = &[
+                b"receipt",
+                grant_key.as_ref(),
+                claimer.as_ref(),
+                &period_index_bytes,
+                seed_salt_seed(&grant.seed_salt),
+                &[bump],
+            ];
+            create_pda_account(
+                &reserver_info,
+                receipt_info,
+                &system_program,
+                receipt_seeds,
+                rent.minimum_balance(space),
+                space,
+                ctx.program_id,
+            )?;
+            create_pda_account(
+                &reserver_info,
+                reservation_info,
+                &system_program,
+                &[b"receipt-reservation", receipt_key.as_ref(), &[reservation_bump]],
+                rent.minimum_balance(reservation_space),
+                reservation_space,
+                ctx.program_id,
+            )?;
+            let reservation = ReceiptReservation {
+                grant: grant_key,
+                receipt: receipt_key,
+                payer: reserver,
+                bump: reservation_bump,
+            };
+            let mut data = reservation_info.try_borrow_mut_data()?;
+            let mut writer: &mut [u8] = &mut data;
+            reservation.try_serialize(&mut writer)?;
+            drop(data);
+
+            let placeholder = ClaimReceipt {
+                grant: grant_key,
+                claimer: *claimer,
+                period_index,
+                claimed_at: 0,
+                bump,
+                status: ReceiptStatus::Reserved,
+                amount: 0,
+                pop_bypassed: false,
+                seed_salt: grant.seed_salt,
+            };
+            let mut data = receipt_info.try_borrow_mut_data()?;
+            let mut writer: &mut [u8] = &mut data;
+            placeholder.try_serialize(&mut writer)?;
+            reserved += 1;
+        }
 
+        let grant = &mut ctx.accounts.grant;
+        grant.uncounted_receipts = grant
+            .uncounted_receipts
+            .checked_add(reserved as u64)
+            .ok_or(ErrorCode::MathOverflow)?;
+        emit!(ReceiptsReserved {
+            schema_version: EVENT_SCHEMA_VERSION,
+            grant: grant_key,
+            period_index,
+            reserver,
+            reserved,
+        });
         Ok(())
     }
-}
 
-// ===== Accounts =====
+    /// 予約の記録（ReceiptReservation）を close し、rent を予約時の支払者に戻す（authority 限定）
+    /// - remaining_accounts: receipt・ReceiptReservation・支払者（ReceiptReservation.payer）の組
+    ///   （いずれも writable、最大 MAX_RECEIPT_RESERVATIONS_PER_BATCH 組）
+    /// - receipt が受給されずに Reserved のままなら receipt も close する。
+    ///   予約した期間が終了し、境界の猶予（PERIOD_BOUNDARY_GRACE_SECONDS）も過ぎていること
+    /// - 受給済みの receipt は受給記録として残し、ReceiptReservation だけを close する（期間の終了を待たない）
+    pub fn release_receipt_reservations<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ReleaseReceiptReservations<'info>>,
+    ) -> Result<()> {
+        require!(
+            !ctx.remaining_accounts.is_empty()
+                && ctx.remaining_accounts.chunks_exact(3).remainder().is_empty()
+                && ctx.remaining_accounts.len() <= MAX_RECEIPT_RESERVATIONS_PER_BATCH * 3,
+            ErrorCode::InvalidBatchSize
+        );
+        // remaining_accounts は receipt に使うため、ClockOverride は受け付けない
+        let now = clock::now(&[])?;
+        let grant_key = ctx.accounts.grant.key();
+        let mut released = 0u64;
+        for accounts in ctx.remaining_accounts.chunks_exact(3) {
+            let (receipt_info, reservation_info, payer_info) = (&accounts[0], &accounts[1], &accounts[2]);
+            let reservation = Account::<ReceiptReservation>::try_from(reservation_info)?;
+            require_keys_eq!(reservation.grant, grant_key, ErrorCode::InvalidReceipt);
+            require_keys_eq!(reservation.receipt, receipt_info.key(), ErrorCode::InvalidReceipt);
+            require_keys_eq!(reservation.payer, payer_info.key(), ErrorCode::InvalidBatchAccount);
+
+            // claim 後に archive・close 済みの receipt は存在しない
+            if *receipt_info.owner == crate::ID {
+                let receipt = Account::<ClaimReceipt>::try_from(receipt_info)?;
+                if receipt.status == ReceiptStatus::Reserved {
+                    let period_end = period_start_ts(
+                        &ctx.accounts.grant,
+                        receipt.period_index.checked_add(1).ok_or(ErrorCode::MathOverflow)?,
+                    )?;
+                    require!(
+                        now > period_end.saturating_add(PERIOD_BOUNDARY_GRACE_SECONDS),
+                        ErrorCode::PeriodNotEnded
+                    );
+                    receipt.close(payer_info.clone())?;
+                    released += 1;
+                }
+            }
+            reservation.close(payer_info.clone())?;
+        }
 
-#[derive(Accounts)]
-#[instruction(grant_id: u64)]
-pub struct CreateGrant<'info> {
-    /// init: 同一 PDA への再呼び出しは Anchor がエラーとする（隠れ更新を防ぐ）。
-    /// パラメータ変更は update_grant 命令を使うこと。
-    #[account(
-        init,
-        payer = authority,
-        space = 8 + Grant::INIT_SPACE,
-        seeds = [b"grant", authority.key().as_ref(), mint.key().as_ref(), &grant_id.to_le_bytes()],
-        bump
-    )]
-    pub grant: Account<'info, Grant>,
+        // 予約の仕組みの導入前に作成された Reserved の receipt は数えていないため、0 で止める
+        let grant = &mut ctx.accounts.grant;
+        grant.uncounted_receipts = grant.uncounted_receipts.saturating_sub(released);
+        Ok(())
+    }
 
-    pub mint: Account<'info, Mint>,
+    /// keeper への報酬（bounty）を設定する（authority 限定、作成または更新）
+    /// - period_close_bounty: close_period 1回あたりの報酬（0 = 支払わない）
+    /// - bounty_from_pool: true なら bounty_pool（keeper-pool PDA）から、false なら vault から支払う
+    ///   bounty_pool へは誰でも直接送金して補充できる。vault から支払った分は total_funded から差し引く
+    /// - keepers: 報酬を受け取れる keeper（空 = 誰でも。最大 MAX_KEEPERS）
+    pub fn upsert_keeper_config(
+        ctx: Context<UpsertKeeperConfig>,
+        period_close_bounty: u64,
+        bounty_from_pool: bool,
+        keepers: Vec<Pubkey>,
+        admin_nonce: u64,
+    ) -> Result<()> {
+        consume_admin_nonce(
+            &mut ctx.accounts.grant,
+            &ctx.accounts.authority,
+            admin_nonce,
+            "upsert_keeper_config",
+            ctx.remaining_accounts,
+        )?;
+        require!(keepers.len() <= MAX_KEEPERS, ErrorCode::InvalidKeeperConfig);
+        for (i, keeper) in keepers.iter().enumerate() {
+            require!(*keeper != Pubkey::default(), ErrorCode::InvalidKeeperConfig);
+            require!(!keepers[..i].contains(keeper), ErrorCode::InvalidKeeperConfig);
+        }
 
-    /// Program-owned vault (TokenAccount). Authority is the grant PDA.
-    #[account(
-        init,
-        payer = authority,
-        token::mint = mint,
-        token::authority = grant,
-        seeds = [b"vault", grant.key().as_ref()],
-        bump,
-    )]
-    pub vault: Account<'info, TokenAccount>,
+        let config = &mut ctx.accounts.keeper_config;
+        config.grant = ctx.accounts.grant.key();
+        config.period_close_bounty = period_close_bounty;
+        config.bounty_from_pool = bounty_from_pool;
+        config.bounty_pool = ctx.accounts.bounty_pool.key();
+        config.keepers = [Pubkey::default(); MAX_KEEPERS];
+        config.keepers[..keepers.len()].copy_from_slice(&keepers);
+        config.bump = ctx.bumps.keeper_config;
+
+        ctx.accounts.grant.has_keeper_config = true;
+        Ok(())
+    }
+
+    /// KeeperConfig を削除し、bounty_pool の残高を authority に返す（authority 限定）
+    /// - close_grant / mint の切り替えの前に実行すること（bounty_pool の authority は Grant PDA のため）
+    pub fn close_keeper_config(ctx: Context<CloseKeeperConfig>, admin_nonce: u64) -> Result<()> {
+        consume_admin_nonce(
+            &mut ctx.accounts.grant,
+            &ctx.accounts.authority,
+            admin_nonce,
+            "close_keeper_config",
+            ctx.remaining_accounts,
+        )?;
+        let remaining = ctx.accounts.bounty_pool.amount;
+        if remaining > 0 {
+            transfer_from_vault(
+                &ctx.accounts.grant,
+                &ctx.accounts.bounty_pool,
+                &ctx.accounts.authority_ata,
+                &ctx.accounts.token_program,
+                remaining,
+            )?;
+        }
+
+        let grant = &ctx.accounts.grant;
+        let grant_id_bytes = grant.grant_id.to_le_bytes();
+        let grant_seeds: &[&[u8]] = &[
+            b"grant",
+            grant.seed_authority.as_ref(),
+            grant.seed_mint.as_ref(),
+            &grant_id_bytes,
+            &[grant.bump],
+        ];
+        let cpi_accounts = CloseAccount {
+            account: ctx.accounts.bounty_pool.to_account_info(),
+            destination: ctx.accounts.authority.to_account_info(),
+            authority: ctx.accounts.grant.to_account_info(),
+        };
+        let signer_seeds: &[&[&[u8]]] = &[grant_seeds];
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer_seeds,
+        );
+        close_account(cpi_ctx)?;
+
+        ctx.accounts.grant.has_keeper_config = false;
+        // KeeperConfig 自体は Accounts で close される
+        Ok(())
+    }
+
+    /// このプログラムへの移行前に支給済みの受給を receipt として記録する（authority 限定、rent は authority 負担、送金なし）
+    /// 既存の給付制度を移行したときに受給履歴と受給者数の上限が途切れないようにするための命令。
+    /// receipt は status = OffChainSettled で作成し、同じ期間の claim は二重受給として拒否される。
+    /// - ClaimerProfile の累計と unique_claimers（max_unique_claimers の判定）に加える
+    /// - vault から支出していないため、Grant.total_claimed / claims_count と PeriodStats は変更しない
+    ///   （代わりに uncounted_receipts に数え、set_seed_salt など receipt がある間は変更できない設定を固定する）
+    /// - period_index は現在の期間以前、claimed_at は現在時刻以前に限る
+    pub fn record_historical_claim(
+        ctx: Context<RecordHistoricalClaim>,
+        claimer: Pubkey,
+        period_index: u64,
+        amount: u64,
+        claimed_at: i64,
+        admin_nonce: u64,
+    ) -> Result<()> {
+        consume_admin_nonce(
+            &mut ctx.accounts.grant,
+            &ctx.accounts.authority,
+            admin_nonce,
+            "record_historical_claim",
+            ctx.remaining_accounts,
+        )?;
+        let now = clock::now(ctx.remaining_accounts)?;
+        let grant = &ctx.accounts.grant;
+        require!(
+            claimed_at > 0 && claimed_at <= now && period_index <= current_period_index(grant, now)?,
+            ErrorCode::InvalidHistoricalClaim
+        );
+
+        let profile = &mut ctx.accounts.claimer_profile;
+        let is_new_claimer = profile.claims_count == 0;
+        if is_new_claimer {
+            require!(
+                grant.max_unique_claimers == 0 || grant.unique_claimers < grant.max_unique_claimers,
+                ErrorCode::MaxUniqueClaimersReached
+            );
+        }
+        let last_claim_period = profile.last_claim_period;
+        record_claimer_profile(
+            profile,
+            grant.key(),
+            claimer,
+            period_index,
+            amount,
+            ctx.bumps.claimer_profile,
+        )?;
+        // 過去の期間を後から記録するため、直近に受給した期間は戻さない
+        if !is_new_claimer {
+            profile.last_claim_period = last_claim_period.max(period_index);
+        }
+        match ctx.accounts.claim_index.as_ref() {
+            Some(claim_index) => claim_index.load_mut()?.record(period_index),
+            None => require!(!profile.has_claim_index, ErrorCode::ClaimIndexRequired),
+        }
+
+        let receipt = &mut ctx.accounts.receipt;
+        record_receipt(
+            receipt,
+            grant,
+            claimer,
+            period_index,
+            claimed_at,
+            amount,
+            ctx.bumps.receipt,
+            false,
+        );
+        receipt.status = ReceiptStatus::OffChainSettled;
+
+        let grant = &mut ctx.accounts.grant;
+        grant.uncounted_receipts = grant
+            .uncounted_receipts
+            .checked_add(1)
+            .ok_or(ErrorCode::MathOverflow)?;
+        if is_new_claimer {
+            grant.unique_claimers = grant
+                .unique_claimers
+                .checked_add(1)
+                .ok_or(ErrorCode::MathOverflow)?;
+        }
+        emit!(HistoricalClaimRecorded {
+            schema_version: EVENT_SCHEMA_VERSION,
+            grant: ctx.accounts.grant.key(),
+            claimer,
+            period_index,
+            receipt: ctx.accounts.receipt.key(),
+            amount,
+            claimed_at,
+            recorded_at: now,
+        });
+        Ok(())
+    }
+
+    /// receipt の状態を遷移させる（異議申し立て・取り消し・返還確認）
+    /// - 許可される遷移とロールは require_receipt_transition を参照
+    /// - reason_code は運用側の事由コード（イベントにのみ残す）
+    /// NOTE: Grant の集計（total_claimed など）は変更しない。返還されたトークンは fund_grant で戻す
+    pub fn set_receipt_status(
+        ctx: Context<SetReceiptStatus>,
+        status: ReceiptStatus,
+        reason_code: u16,
+        admin_nonce: u64,
+    ) -> Result<()> {
+        consume_admin_nonce(
+            &mut ctx.accounts.grant,
+            &ctx.accounts.actor,
+            admin_nonce,
+            "set_receipt_status",
+            ctx.remaining_accounts,
+        )?;
+        let actor = ctx.accounts.actor.key();
+        let receipt = &mut ctx.accounts.receipt;
+        let from = receipt.status;
+        require_receipt_transition(&ctx.accounts.grant, actor, from, status)?;
+        receipt.status = status;
+
+        emit!(ReceiptStatusChanged {
+            schema_version: EVENT_SCHEMA_VERSION,
+            grant: receipt.grant,
+            claimer: receipt.claimer,
+            period_index: receipt.period_index,
+            receipt: receipt.key(),
+            from,
+            to: status,
+            actor,
+            reason_code,
+            changed_at: clock::now(ctx.remaining_accounts)?,
+        });
+        Ok(())
+    }
+
+    /// 受給記録を譲渡不可の Token-2022 NFT として発行できるようにする（authority 限定、rent は authority 負担）
+    /// Grant ごとに1つの group mint（TokenGroup + TokenMetadata）を作成し、mint_receipt_nft の NFT はその member になる。
+    /// wallet が受給履歴を token として表示するための任意の機能で、ClaimReceipt の扱いは変わらない。
+    /// - name / symbol / uri: group と各 NFT の metadata（NFT の name は "<name> #<period_index>"）
+    /// - max_size: group に入れられる NFT の上限（TokenGroup.max_size）
+    pub fn open_receipt_nft_collection(
+        ctx: Context<OpenReceiptNftCollection>,
+        name: String,
+        symbol: String,
+        uri: String,
+        max_size: u64,
+        admin_nonce: u64,
+    ) -> Result<()> {
+        consume_admin_nonce(
+            &mut ctx.accounts.grant,
+            &ctx.accounts.authority,
+            admin_nonce,
+            "open_receipt_nft_collection",
+            ctx.remaining_accounts,
+        )?;
+        require!(
+            !name.is_empty()
+                && name.len() <= MAX_RECEIPT_NFT_NAME_LEN
+                && symbol.len() <= MAX_RECEIPT_NFT_SYMBOL_LEN
+                && uri.len() <= MAX_SPONSOR_URI_LEN
+                && max_size > 0,
+            ErrorCode::InvalidReceiptNftCollection
+        );
+
+        let grant = &ctx.accounts.grant;
+        let grant_key = grant.key();
+        let group_mint = ctx.accounts.group_mint.to_account_info();
+        let token_program = ctx.accounts.token_2022_program.to_account_info();
+        let metadata = TokenMetadata {
+            mint: group_mint.key(),
+            name: name.clone(),
+            symbol: symbol.clone(),
+            uri: uri.clone(),
+            ..Default::default()
+        };
+        let group_bump = ctx.bumps.group_mint;
+        create_token_2022_mint_account(
+            &ctx.accounts.authority,
+            &group_mint,
+            &ctx.accounts.system_program,
+            &[b"receipt-nft-group", grant_key.as_ref(), &[group_bump]],
+            &[ExtensionType::GroupPointer, ExtensionType::MetadataPointer],
+            &[ExtensionType::TokenGroup],
+            metadata.tlv_size_of()?,
+        )?;
+        group_pointer_initialize(
+            CpiContext::new(
+                token_program.clone(),
+                GroupPointerInitialize {
+                    token_program_id: token_program.clone(),
+                    mint: group_mint.clone(),
+                },
+            ),
+            Some(grant_key),
+            Some(group_mint.key()),
+        )?;
+        metadata_pointer_initialize(
+            CpiContext::new(
+                token_program.clone(),
+                MetadataPointerInitialize {
+                    token_program_id: token_program.clone(),
+                    mint: group_mint.clone(),
+                },
+            ),
+            Some(grant_key),
+            Some(group_mint.key()),
+        )?;
+        token_2022::initialize_mint2(
+            CpiContext::new(token_program.clone(), token_2022::InitializeMint2 { mint: group_mint.clone() }),
+            0,
+            &grant_key,
+            None,
+        )?;
+
+        let grant_id_bytes = grant.grant_id.to_le_bytes();
+        let grant_seeds: &[&[u8]] = &[
+            b"grant",
+            grant.seed_authority.as_ref(),
+            grant.seed_mint.as_ref(),
+            &grant_id_bytes,
+            &[grant.bump],
+        ];
+        let grant_info = grant.to_account_info();
+        token_metadata_initialize(
+            CpiContext::new_with_signer(
+                token_program.clone(),
+                TokenMetadataInitialize {
+                    program_id: token_program.clone(),
+                    metadata: group_mint.clone(),
+                    update_authority: grant_info.clone(),
+                    mint_authority: grant_info.clone(),
+                    mint: group_mint.clone(),
+                },
+                &[grant_seeds],
+            ),
+            name.clone(),
+            symbol.clone(),
+            uri.clone(),
+        )?;
+        token_group_initialize(
+            CpiContext::new_with_signer(
+                token_program.clone(),
+                TokenGroupInitialize {
+                    program_id: token_program.clone(),
+                    group: group_mint.clone(),
+                    mint: group_mint.clone(),
+                    mint_authority: grant_info,
+                },
+                &[grant_seeds],
+            ),
+            Some(grant_key),
+            max_size,
+        )?;
+
+        let collection = &mut ctx.accounts.collection;
+        collection.grant = grant_key;
+        collection.group_mint = group_mint.key();
+        collection.name = name;
+        collection.symbol = symbol;
+        collection.uri = uri;
+        collection.max_size = max_size;
+        collection.minted_count = 0;
+        collection.bump = ctx.bumps.collection;
+        collection.group_mint_bump = group_bump;
+        Ok(())
+    }
+
+    /// 受給記録（Valid の receipt）1件を譲渡不可の Token-2022 NFT として claimer に発行する（誰でも実行可能、rent は payer 負担）
+    /// claim の直後に同じトランザクションで実行する想定。NFT の mint は receipt ごとの PDA のため、1件につき1回だけ発行できる。
+    /// - metadata の additional_metadata に grant / period_index / amount / claimed_at を記録する
+    /// - 発行後は mint authority を外す（供給量は 1 で固定）
+    /// - NonTransferable のため、claimer の token account から移動できない
+    pub fn mint_receipt_nft(ctx: Context<MintReceiptNft>) -> Result<()> {
+        let receipt = &ctx.accounts.receipt;
+        require!(receipt.status == ReceiptStatus::Valid, ErrorCode::InvalidReceipt);
+        let collection = &ctx.accounts.collection;
+        require!(collection.minted_count < collection.max_size, ErrorCode::ReceiptNftCollectionFull);
+
+        let grant = &ctx.accounts.grant;
+        let grant_key = grant.key();
+        let receipt_key = receipt.key();
+        let nft_mint = ctx.accounts.receipt_nft_mint.to_account_info();
+        let token_program = ctx.accounts.token_2022_program.to_account_info();
+        let name = format!("{} #{}", collection.name, receipt.period_index);
+        let fields = [
+            ("grant", grant_key.to_string()),
+            ("period_index", receipt.period_index.to_string()),
+            ("amount", receipt.amount.to_string()),
+            ("claimed_at", receipt.claimed_at.to_string()),
+        ];
+        let metadata = TokenMetadata {
+            mint: nft_mint.key(),
+            name: name.clone(),
+            symbol: collection.symbol.clone(),
+            uri: collection.uri.clone(),
+            additional_metadata: fields.iter().map(|(key, value)| (key.to_string(), value.clone())).collect(),
+            ..Default::default()
+        };
+        create_token_2022_mint_account(
+            &ctx.accounts.payer,
+            &nft_mint,
+            &ctx.accounts.system_program,
+            &[b"receipt-nft", receipt_key.as_ref(), &[ctx.bumps.receipt_nft_mint]],
+            &[
+                ExtensionType::NonTransferable,
+                ExtensionType::MetadataPointer,
+                ExtensionType::GroupMemberPointer,
+            ],
+            &[ExtensionType::TokenGroupMember],
+            metadata.tlv_size_of()?,
+        )?;
+        non_transferable_mint_initialize(CpiContext::new(
+            token_program.clone(),
+            NonTransferableMintInitialize {
+                token_program_id: token_program.clone(),
+                mint: nft_mint.clone(),
+            },
+        ))?;
+        metadata_pointer_initialize(
+            CpiContext::new(
+                token_program.clone(),
+                MetadataPointerInitialize {
+                    token_program_id: token_program.clone(),
+                    mint: nft_mint.clone(),
+                },
+            ),
+            Some(grant_key),
+            Some(nft_mint.key()),
+        )?;
+        group_member_pointer_initialize(
+            CpiContext::new(
+                token_program.clone(),
+                GroupMemberPointerInitialize {
+                    token_program_id: token_program.clone(),
+                    mint: nft_mint.clone(),
+                },
+            ),
+            Some(grant_key),
+            Some(nft_mint.key()),
+        )?;
+        token_2022::initialize_mint2(
+            CpiContext::new(token_program.clone(), token_2022::InitializeMint2 { mint: nft_mint.clone() }),
+            0,
+            &grant_key,
+            None,
+        )?;
+
+        let grant_id_bytes = grant.grant_id.to_le_bytes();
+        let grant_seeds: &[&[u8]] = &[
+            b"grant",
+            grant.seed_authority.as_ref(),
+            grant.seed_mint.as_ref(),
+            &grant_id_bytes,
+            &[grant.bump],
+        ];
+        let signer_seeds: &[&[&[u8]]] = &[grant_seeds];
+        let grant_info = grant.to_account_info();
+        token_metadata_initialize(
+            CpiContext::new_with_signer(
+                token_program.clone(),
+                TokenMetadataInitialize {
+                    program_id: token_program.clone(),
+                    metadata: nft_mint.clone(),
+                    update_authority: grant_info.clone(),
+                    mint_authority: grant_info.clone(),
+                    mint: nft_mint.clone(),
+                },
+                signer_seeds,
+            ),
+            name,
+            collection.symbol.clone(),
+            collection.uri.clone(),
+        )?;
+        for (key, value) in fields {
+            token_metadata_update_field(
+                CpiContext::new_with_signer(
+                    token_program.clone(),
+                    TokenMetadataUpdateField {
+                        program_id: token_program.clone(),
+                        metadata: nft_mint.clone(),
+                        update_authority: grant_info.clone(),
+                    },
+                    signer_seeds,
+                ),
+                Field::Key(key.to_string()),
+                value,
+            )?;
+        }
+        token_member_initialize(CpiContext::new_with_signer(
+            token_program.clone(),
+            TokenMemberInitialize {
+                program_id: token_program.clone(),
+                member: nft_mint.clone(),
+                member_mint: nft_mint.clone(),
+                member_mint_authority: grant_info.clone(),
+                group: ctx.accounts.group_mint.to_account_info(),
+                group_update_authority: grant_info.clone(),
+            },
+            signer_seeds,
+        ))?;
+
+        anchor_spl::associated_token::create(CpiContext::new(
+            ctx.accounts.associated_token_program.to_account_info(),
+            anchor_spl::associated_token::Create {
+                payer: ctx.accounts.payer.to_account_info(),
+                associated_token: ctx.accounts.claimer_nft_account.to_account_info(),
+                authority: ctx.accounts.claimer.to_account_info(),
+                mint: nft_mint.clone(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+                token_program: token_program.clone(),
+            },
+        ))?;
+        token_2022::mint_to(
+            CpiContext::new_with_signer(
+                token_program.clone(),
+                token_2022::MintTo {
+                    mint: nft_mint.clone(),
+                    to: ctx.accounts.claimer_nft_account.to_account_info(),
+                    authority: grant_info.clone(),
+                },
+                signer_seeds,
+            ),
+            1,
+        )?;
+        token_2022::set_authority(
+            CpiContext::new_with_signer(
+                token_program,
+                token_2022::SetAuthority {
+                    current_authority: grant_info,
+                    account_or_mint: nft_mint.clone(),
+                },
+                signer_seeds,
+            ),
+            token_2022::spl_token_2022::instruction::AuthorityType::MintTokens,
+            None,
+        )?;
+
+        let collection = &mut ctx.accounts.collection;
+        collection.minted_count = collection
+            .minted_count
+            .checked_add(1)
+            .ok_or(ErrorCode::MathOverflow)?;
+        emit!(ReceiptNftMinted {
+            schema_version: EVENT_SCHEMA_VERSION,
+            grant: grant_key,
+            claimer: receipt.claimer,
+            period_index: receipt.period_index,
+            receipt: receipt_key,
+            mint: nft_mint.key(),
+            amount: receipt.amount,
+            member_number: collection.minted_count,
+        });
+        Ok(())
+    }
+
+    /// claim 専用の session key を登録する（claimer 本人が署名、既存の登録は置き換える）
+    /// - 以後 expires_at まで、session key の署名で claim 系命令を実行できる（受給先は claimer のまま）
+    /// - session key の claim では receipt などの rent を session key が支払うため、少額の SOL を持たせること
+    /// - 有効期間は SESSION_MAX_DURATION_SECONDS まで。wSOL のアンラップ（claim_grant_unwrap_sol）は対象外
+    pub fn authorize_session(
+        ctx: Context<AuthorizeSession>,
+        session_key: Pubkey,
+        expires_at: i64,
+    ) -> Result<()> {
+        let now = clock::now(ctx.remaining_accounts)?;
+        let claimer = ctx.accounts.claimer.key();
+        require!(
+            session_key != Pubkey::default() && session_key != claimer,
+            ErrorCode::InvalidSession
+        );
+        require!(
+            expires_at > now && expires_at - now <= SESSION_MAX_DURATION_SECONDS,
+            ErrorCode::InvalidSession
+        );
+
+        let session = &mut ctx.accounts.session_authorization;
+        session.grant = ctx.accounts.grant.key();
+        session.claimer = claimer;
+        session.session_key = session_key;
+        session.scope = SESSION_SCOPE_CLAIM;
+        session.expires_at = expires_at;
+        session.bump = ctx.bumps.session_authorization;
+
+        emit!(SessionAuthorized {
+            schema_version: EVENT_SCHEMA_VERSION,
+            grant: session.grant,
+            claimer,
+            session_key,
+            scope: session.scope,
+            expires_at,
+        });
+        Ok(())
+    }
+
+    /// session key の登録を取り消す（claimer 本人が署名、rent は claimer に戻る）
+    pub fn revoke_session(ctx: Context<RevokeSession>) -> Result<()> {
+        let session = &ctx.accounts.session_authorization;
+        emit!(SessionRevoked {
+            schema_version: EVENT_SCHEMA_VERSION,
+            grant: session.grant,
+            claimer: session.claimer,
+            session_key: session.session_key,
+        });
+        // SessionAuthorization 自体は Accounts で close される
+        Ok(())
+    }
+
+    /// 受給先を受給者の ATA に限る検査から外す（claimer 本人が署名、rent は claimer 負担）
+    /// - custodial な運用で、ATA 以外の token account（owner は受給者）で受け取る場合に使う
+    /// - 既定では claim_grant / claim_grant_with_proof の受給先は get_associated_token_address(受給者, mint) に限る
+    ///   （受給者の鍵が owner の別アカウントへ受給させるフィッシングを防ぐ）
+    /// - claim 時に destination_opt_out を渡した場合のみ検査を外す
+    pub fn opt_out_canonical_destination(ctx: Context<OptOutCanonicalDestination>) -> Result<()> {
+        let opt_out = &mut ctx.accounts.destination_opt_out;
+        opt_out.grant = ctx.accounts.grant.key();
+        opt_out.claimer = ctx.accounts.claimer.key();
+        opt_out.bump = ctx.bumps.destination_opt_out;
+
+        emit!(DestinationOptOutChanged {
+            schema_version: EVENT_SCHEMA_VERSION,
+            grant: opt_out.grant,
+            claimer: opt_out.claimer,
+            opted_out: true,
+        });
+        Ok(())
+    }
+
+    /// opt_out_canonical_destination を取り消す（claimer 本人が署名、rent は claimer に戻る）
+    pub fn revoke_destination_opt_out(ctx: Context<RevokeDestinationOptOut>) -> Result<()> {
+        let opt_out = &ctx.accounts.destination_opt_out;
+        emit!(DestinationOptOutChanged {
+            schema_version: EVENT_SCHEMA_VERSION,
+            grant: opt_out.grant,
+            claimer: opt_out.claimer,
+            opted_out: false,
+        });
+        // DestinationOptOut 自体は Accounts で close される
+        Ok(())
+    }
+
+    /// 通知先の設定のコミットメントを登録/更新する（claimer 本人が署名、rent は claimer 負担。全 Grant 共通）
+    /// - preference_hash: 連絡先と通知の希望をオフチェーンでハッシュ化した値（連絡先そのものはオンチェーンに置かない）
+    ///   通知サービスは受け取った設定のハッシュがこの値と一致する場合だけ、この wallet への通知に使う
+    /// - 更新のたびに revision が増える（古い設定の再提示を見分けるため）
+    pub fn set_notification_preference(
+        ctx: Context<SetNotificationPreference>,
+        preference_hash: [u8; 32],
+    ) -> Result<()> {
+        require!(preference_hash != [0u8; 32], ErrorCode::InvalidNotificationPreference);
+        let now = clock::now(ctx.remaining_accounts)?;
+        let preference = &mut ctx.accounts.notification_preference;
+        if preference.claimer == Pubkey::default() {
+            preference.claimer = ctx.accounts.claimer.key();
+            preference.bump = ctx.bumps.notification_preference;
+        }
+        preference.preference_hash = preference_hash;
+        preference.revision = preference.revision.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+        preference.updated_at = now;
+
+        emit!(NotificationPreferenceChanged {
+            schema_version: EVENT_SCHEMA_VERSION,
+            claimer: preference.claimer,
+            preference_hash,
+            revision: preference.revision,
+            updated_at: now,
+        });
+        Ok(())
+    }
+
+    /// 通知先の設定を削除する（claimer 本人が署名、rent は claimer に戻る。通知への同意の撤回）
+    pub fn clear_notification_preference(ctx: Context<ClearNotificationPreference>) -> Result<()> {
+        let now = clock::now(ctx.remaining_accounts)?;
+        let preference = &ctx.accounts.notification_preference;
+        emit!(NotificationPreferenceChanged {
+            schema_version: EVENT_SCHEMA_VERSION,
+            claimer: preference.claimer,
+            preference_hash: [0u8; 32],
+            revision: preference.revision,
+            updated_at: now,
+        });
+        // NotificationPreference 自体は Accounts で close される
+        Ok(())
+    }
+
+    /// 受給者宛ての暗号化メモを添付/差し替える（authority 限定、rent は authority 負担）
+    /// 暗号化はオフチェーンで行う（claimer の ed25519 鍵を X25519 に変換し、ephemeral_pubkey との nacl box 等）。
+    /// プログラムは中身を見ず、次の claim の ClaimerMemoDelivered で ciphertext をそのまま受給者に渡す。
+    /// - ephemeral_pubkey / nonce: 復号に必要な公開値
+    /// - ciphertext: 1〜MAX_CLAIMER_MEMO_LEN バイト（平文はオンチェーンに置かない）
+    /// - 差し替えると revision が増え、未配信に戻る（配信済みのメモも次の claim で再び配信される）
+    pub fn attach_claimer_memo(
+        ctx: Context<AttachClaimerMemo>,
+        claimer: Pubkey,
+        ephemeral_pubkey: [u8; 32],
+        nonce: [u8; 24],
+        ciphertext: Vec<u8>,
+        admin_nonce: u64,
+    ) -> Result<()> {
+        consume_admin_nonce(
+            &mut ctx.accounts.grant,
+            &ctx.accounts.authority,
+            admin_nonce,
+            "attach_claimer_memo",
+            ctx.remaining_accounts,
+        )?;
+        require!(claimer != Pubkey::default(), ErrorCode::InvalidClaimerMemo);
+        require!(
+            !ciphertext.is_empty() && ciphertext.len() <= MAX_CLAIMER_MEMO_LEN,
+            ErrorCode::InvalidClaimerMemo
+        );
+        let now = clock::now(ctx.remaining_accounts)?;
+        let memo = &mut ctx.accounts.claimer_memo;
+        if memo.grant == Pubkey::default() {
+            memo.grant = ctx.accounts.grant.key();
+            memo.claimer = claimer;
+            memo.bump = ctx.bumps.claimer_memo;
+        }
+        memo.ephemeral_pubkey = ephemeral_pubkey;
+        memo.nonce = nonce;
+        memo.ciphertext = ciphertext;
+        memo.revision = memo.revision.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+        memo.attached_at = now;
+        memo.delivered_at = 0;
+        memo.delivered_period = 0;
+
+        emit!(ClaimerMemoAttached {
+            schema_version: EVENT_SCHEMA_VERSION,
+            grant: memo.grant,
+            claimer: memo.claimer,
+            memo_hash: memo.memo_hash(),
+            revision: memo.revision,
+            attached_at: now,
+        });
+        Ok(())
+    }
+
+    /// 受給者宛ての暗号化メモを削除する（authority 限定、rent は authority に戻る。未配信でも削除できる）
+    pub fn close_claimer_memo(ctx: Context<CloseClaimerMemo>, admin_nonce: u64) -> Result<()> {
+        consume_admin_nonce(
+            &mut ctx.accounts.grant,
+            &ctx.accounts.authority,
+            admin_nonce,
+            "close_claimer_memo",
+            ctx.remaining_accounts,
+        )?;
+        // ClaimerMemo 自体は Accounts で close される
+        Ok(())
+    }
+
+    /// delegate（session key）の索引ページを作成する（delegate が署名、rent は delegate 負担）
+    /// - page は delegate が自由に割り当てる通し番号（0 から順に使う想定）
+    pub fn open_delegate_index_page(ctx: Context<OpenDelegateIndexPage>, page: u32) -> Result<()> {
+        let index = &mut ctx.accounts.delegate_index;
+        index.grant = ctx.accounts.grant.key();
+        index.delegate = ctx.accounts.delegate.key();
+        index.page = page;
+        index.bump = ctx.bumps.delegate_index;
+        index.claimers = Vec::new();
+        Ok(())
+    }
+
+    /// 自分に session を登録した受給者を索引ページに追加する（delegate が署名、増えた分の rent は delegate 負担）
+    /// - 有効期限内の SessionAuthorization（session_key = delegate）がある受給者のみ追加できる
+    /// - 重複はページ内でのみ検査する（ページをまたいだ重複は delegate 側で避けること）
+    pub fn add_delegated_claimer(ctx: Context<AddDelegatedClaimer>, page: u32) -> Result<()> {
+        let now = clock::now(ctx.remaining_accounts)?;
+        let session = &ctx.accounts.session_authorization;
+        require_valid_session(session, &ctx.accounts.delegate.key(), SESSION_SCOPE_CLAIM, now)?;
+
+        let index = &mut ctx.accounts.delegate_index;
+        require!(
+            index.claimers.len() < DELEGATE_INDEX_PAGE_CAPACITY,
+            ErrorCode::DelegateIndexPageFull
+        );
+        require!(
+            !index.claimers.contains(&session.claimer),
+            ErrorCode::DelegatedClaimerAlreadyIndexed
+        );
+        index.claimers.push(session.claimer);
+
+        emit!(DelegateIndexUpdated {
+            schema_version: EVENT_SCHEMA_VERSION,
+            grant: index.grant,
+            delegate: index.delegate,
+            page,
+            claimer: session.claimer,
+            added: true,
+            len: index.claimers.len() as u32,
+        });
+        Ok(())
+    }
+
+    /// 索引ページから受給者を外す（delegate が署名、減った分の rent は delegate に戻る）
+    /// - session の取り消し・期限切れ後も外せる
+    /// - 末尾の要素を外した位置に移すため、ページ内の順序は保たれない
+    pub fn remove_delegated_claimer(ctx: Context<RemoveDelegatedClaimer>, page: u32, claimer: Pubkey) -> Result<()> {
+        let index = &mut ctx.accounts.delegate_index;
+        let position = index
+            .claimers
+            .iter()
+            .position(|c| *c == claimer)
+            .ok_or(ErrorCode::DelegatedClaimerNotIndexed)?;
+        index.claimers.swap_remove(position);
+
+        emit!(DelegateIndexUpdated {
+            schema_version: EVENT_SCHEMA_VERSION,
+            grant: index.grant,
+            delegate: index.delegate,
+            page,
+            claimer,
+            added: false,
+            len: index.claimers.len() as u32,
+        });
+        Ok(())
+    }
+
+    /// 空の索引ページを閉じる（delegate が署名、rent は delegate に戻る）
+    pub fn close_delegate_index_page(ctx: Context<CloseDelegateIndexPage>, _page: u32) -> Result<()> {
+        require!(
+            ctx.accounts.delegate_index.claimers.is_empty(),
+            ErrorCode::DelegateIndexPageNotEmpty
+        );
+        Ok(())
+    }
+
+    /// claim を予約する（claimer 本人が署名、PendingClaim の rent は claimer 負担）
+    /// - execute_after 以降、keeper が claim 系命令に pending_claim を渡して代わりに実行する
+    ///   （claimer アカウント = keeper が receipt などの rent を支払い、PendingClaim の rent を受け取る）
+    /// - keeper を指定した場合はその keeper だけが実行できる（Pubkey::default() = 誰でも）
+    /// - 実行時も通常の claim と同じ検証（期間・PoP・allowlist など）を行う。period_index の期間内に
+    ///   実行されなかった予約は cancel_scheduled_claim で取り消す
+    pub fn schedule_claim(
+        ctx: Context<ScheduleClaim>,
+        period_index: u64,
+        execute_after: i64,
+        keeper: Pubkey,
+    ) -> Result<()> {
+        let now = clock::now(ctx.remaining_accounts)?;
+        let claimer = ctx.accounts.claimer.key();
+        require!(keeper != claimer, ErrorCode::InvalidScheduledClaim);
+        require!(
+            execute_after >= now && execute_after - now <= SCHEDULED_CLAIM_MAX_DELAY_SECONDS,
+            ErrorCode::InvalidScheduledClaim
+        );
+
+        let pending = &mut ctx.accounts.pending_claim;
+        pending.grant = ctx.accounts.grant.key();
+        pending.claimer = claimer;
+        pending.period_index = period_index;
+        pending.execute_after = execute_after;
+        pending.keeper = keeper;
+        pending.scheduled_at = now;
+        pending.bump = ctx.bumps.pending_claim;
+
+        emit!(ClaimScheduled {
+            schema_version: EVENT_SCHEMA_VERSION,
+            grant: pending.grant,
+            claimer,
+            period_index,
+            execute_after,
+            keeper,
+        });
+        Ok(())
+    }
+
+    /// 予約した claim を取り消す（claimer 本人が署名、rent は claimer に戻る）
+    pub fn cancel_scheduled_claim(ctx: Context<CancelScheduledClaim>) -> Result<()> {
+        let pending = &ctx.accounts.pending_claim;
+        emit!(ScheduledClaimCancelled {
+            schema_version: EVENT_SCHEMA_VERSION,
+            grant: pending.grant,
+            claimer: pending.claimer,
+            period_index: pending.period_index,
+        });
+        // PendingClaim 自体は Accounts で close される
+        Ok(())
+    }
+
+    /// 受給明細（StatementLog）を作成する（claimer 本人が署名、rent は claimer 負担）
+    /// - 作成後、claim 系命令に statement_log を渡すと直近 STATEMENT_LOG_ENTRIES 件の受給が記録される
+    ///   （indexer なしで wallet が明細を表示できるようにするため。渡さない claim は記録されない）
+    pub fn open_statement_log(ctx: Context<OpenStatementLog>) -> Result<()> {
+        let mut log = ctx.accounts.statement_log.load_init()?;
+        log.grant = ctx.accounts.grant.key();
+        log.claimer = ctx.accounts.claimer.key();
+        log.bump = ctx.bumps.statement_log;
+        Ok(())
+    }
+
+    /// StatementLog を close して rent を claimer に戻す
+    pub fn close_statement_log(_ctx: Context<CloseStatementLog>) -> Result<()> {
+        // StatementLog 自体は Accounts で close される
+        Ok(())
+    }
+
+    /// 受給済み期間の索引（ClaimIndex）を作成する（claimer 本人が署名、rent は claimer 負担）
+    /// - 作成後は claim 系命令と return_claim に claim_index を渡すことが必須になり（渡さない場合は ClaimIndexRequired）、
+    ///   直近 CLAIM_INDEX_PERIODS 期間の受給の有無を receipt を期間ごとに取得せずに引ける
+    /// - 記録は現在の期間（tracked_from）から。現在の期間に受給済みであればその分も記録する
+    /// - 二重受給の判定は引き続き receipt で行う（ClaimIndex は照会用の写し）
+    pub fn open_claim_index(ctx: Context<OpenClaimIndex>) -> Result<()> {
+        let now = clock::now(ctx.remaining_accounts)?;
+        let grant = &ctx.accounts.grant;
+        let claimer = ctx.accounts.claimer.key();
+        let period_index = current_period_index(grant, now)?;
+
+        // 未受給の claimer は ClaimerProfile もここで作成する（has_claim_index を claim 側で確認するため）
+        let profile = &mut ctx.accounts.claimer_profile;
+        profile.grant = grant.key();
+        profile.claimer = claimer;
+        profile.bump = ctx.bumps.claimer_profile;
+        profile.has_claim_index = true;
+
+        let mut index = ctx.accounts.claim_index.load_init()?;
+        index.grant = grant.key();
+        index.claimer = claimer;
+        index.tracked_from = period_index;
+        index.last_period = period_index;
+        index.bump = ctx.bumps.claim_index;
+        if profile.claims_count > 0 && profile.last_claim_period == period_index {
+            index.record(period_index);
+        }
+        Ok(())
+    }
+
+    /// ClaimIndex を close して rent を claimer に戻す（以降の claim に claim_index は不要になる）
+    pub fn close_claim_index(ctx: Context<CloseClaimIndex>) -> Result<()> {
+        ctx.accounts.claimer_profile.has_claim_index = false;
+        // ClaimIndex 自体は Accounts で close される
+        Ok(())
+    }
+
+    /// 受給した額を claimer が自発的に vault へ返す（誤った wallet で受給した場合など）
+    /// - receipt.amount を claimer_ata から vault に戻し、receipt を Refunded にする
+    /// - Grant / PeriodStats / ClaimerProfile の集計から差し引き、初回受給だった場合は unique_claimers の枠も戻す
+    /// - platform fee は返らない。receipt は残るため、同じ claimer が同じ期間に再受給することはできない
+    /// - 締め済みの期間（PeriodStats.closed）と過去シーズンの期間は対象外
+    pub fn return_claim(ctx: Context<ReturnClaim>) -> Result<()> {
+        let result = (|| -> Result<()> {
+            let receipt = &ctx.accounts.receipt;
+            require!(
+                receipt.status != ReceiptStatus::Refunded
+                    && receipt.status != ReceiptStatus::Reserved
+                    && receipt.status != ReceiptStatus::Archived
+                    && receipt.status != ReceiptStatus::OffChainSettled,
+                ErrorCode::InvalidReceiptTransition
+            );
+            require!(!ctx.accounts.period_stats.load()?.is_closed(), ErrorCode::PeriodAlreadyClosed);
+            require!(
+                receipt.period_index >= ctx.accounts.grant.season_period_offset,
+                ErrorCode::InvalidPeriodIndex
+            );
+            let amount = receipt.amount;
+            let from = receipt.status;
+
+            if amount > 0 {
+                let cpi_accounts = Transfer {
+                    from: ctx.accounts.claimer_ata.to_account_info(),
+                    to: ctx.accounts.vault.to_account_info(),
+                    authority: ctx.accounts.claimer.to_account_info(),
+                };
+                let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+                transfer(cpi_ctx, amount)?;
+            }
+
+            // earmark 済みの Grant は tranche の引き当てを戻す
+            if ctx.accounts.grant.escrow_earmarked {
+                let schedule = ctx
+                    .accounts
+                    .escrow_schedule
+                    .as_deref_mut()
+                    .ok_or(ErrorCode::EscrowScheduleRequired)?;
+                let tranche = usize::try_from(receipt.period_index)
+                    .ok()
+                    .and_then(|index| schedule.tranches.get_mut(index))
+                    .ok_or(ErrorCode::PeriodNotFunded)?;
+                tranche.claimed = tranche.claimed.checked_sub(amount).ok_or(ErrorCode::MathOverflow)?;
+            }
+
+            {
+                let mut stats = ctx.accounts.period_stats.load_mut()?;
+                stats.claims_count = stats.claims_count.checked_sub(1).ok_or(ErrorCode::MathOverflow)?;
+                stats.total_claimed = stats
+                    .total_claimed
+                    .checked_sub(amount as u128)
+                    .ok_or(ErrorCode::MathOverflow)?;
+            }
+
+            let profile = &mut ctx.accounts.claimer_profile;
+            profile.claims_count = profile.claims_count.checked_sub(1).ok_or(ErrorCode::MathOverflow)?;
+            profile.total_received = profile
+                .total_received
+                .checked_sub(amount as u128)
+                .ok_or(ErrorCode::MathOverflow)?;
+            let freed_claimer_slot = profile.claims_count == 0;
+            match ctx.accounts.claim_index.as_ref() {
+                Some(claim_index) => claim_index.load_mut()?.clear(ctx.accounts.receipt.period_index),
+                None => require!(!profile.has_claim_index, ErrorCode::ClaimIndexRequired),
+            }
+
+            let grant = &mut ctx.accounts.grant;
+            grant.total_claimed = grant
+                .total_claimed
+                .checked_sub(amount as u128)
+                .ok_or(ErrorCode::MathOverflow)?;
+            grant.claims_count = grant.claims_count.checked_sub(1).ok_or(ErrorCode::MathOverflow)?;
+            // receipt は Refunded として残るため、claims_count の代わりに数えて set_seed_salt などの固定を保つ
+            grant.uncounted_receipts = grant
+                .uncounted_receipts
+                .checked_add(1)
+                .ok_or(ErrorCode::MathOverflow)?;
+            if freed_claimer_slot {
+                grant.unique_claimers = grant.unique_claimers.saturating_sub(1);
+            }
+
+            let receipt = &mut ctx.accounts.receipt;
+            receipt.status = ReceiptStatus::Refunded;
+
+            let now = clock::now(ctx.remaining_accounts)?;
+            emit!(ReceiptStatusChanged {
+                schema_version: EVENT_SCHEMA_VERSION,
+                grant: receipt.grant,
+                claimer: receipt.claimer,
+                period_index: receipt.period_index,
+                receipt: receipt.key(),
+                from,
+                to: ReceiptStatus::Refunded,
+                actor: receipt.claimer,
+                reason_code: 0,
+                changed_at: now,
+            });
+            emit!(ClaimReturned {
+                schema_version: EVENT_SCHEMA_VERSION,
+                grant: receipt.grant,
+                claimer: receipt.claimer,
+                period_index: receipt.period_index,
+                amount,
+                freed_claimer_slot,
+                returned_at: now,
+            });
+            Ok(())
+        })();
+        structured_log::emit(&result, || LogLine {
+            instruction: "return_claim",
+            grant: ctx.accounts.grant.key(),
+            claimer: Some(ctx.accounts.claimer.key()),
+            period: Some(ctx.accounts.receipt.period_index),
+            amount: Some(ctx.accounts.receipt.amount),
+        });
+        result
+    }
+
+    /// 新しいシーズンを開始する（authority 限定）
+    /// - 現シーズンの集計を SeasonSummary に保存し、start_ts / expires_at / 期間の起点を差し替える
+    /// - Grant / vault のアドレスはそのまま（claimer 向け URL や集計を引き継ぐ）
+    /// - period_index は通し番号のまま続ける（receipt PDA が過去シーズンと衝突しないように）
+    /// - total_funded / total_claimed などの累計は vault の会計に使うためリセットしない
+    pub fn start_new_season(
+        ctx: Context<StartNewSeason>,
+        start_ts: i64,
+        expires_at: i64,
+        admin_nonce: u64,
+    ) -> Result<()> {
+        consume_admin_nonce(
+            &mut ctx.accounts.grant,
+            &ctx.accounts.authority,
+            admin_nonce,
+            "start_new_season",
+            ctx.remaining_accounts,
+        )?;
+        let now = clock::now(ctx.remaining_accounts)?;
+        let grant = &mut ctx.accounts.grant;
+        require!(grant.wind_down_ends_at == 0, ErrorCode::WindDownActive);
+        require_no_abrupt_expiry(grant, expires_at, now)?;
+        require!(start_ts >= now, ErrorCode::InvalidStartTs);
+        require!(expires_at == 0 || expires_at > start_ts, ErrorCode::InvalidStartTs);
+        // tranche は period_index で引き当てるため、シーズンをまたいで使えない
+        require!(!grant.escrow_earmarked, ErrorCode::InvalidEscrowSchedule);
+
+        // 現シーズンで使われた（または現在進行中の）期間の次から新シーズンを始める
+        let next_period_index = if now < grant.period_anchor_ts {
+            grant.season_period_offset
+        } else {
+            let elapsed_periods = ((now - grant.period_anchor_ts) / grant.period_seconds) as u64;
+            grant
+                .season_period_offset
+                .checked_add(elapsed_periods)
+                .and_then(|index| index.checked_add(1))
+                .ok_or(ErrorCode::MathOverflow)?
+        };
+
+        let summary = &mut ctx.accounts.season_summary;
+        summary.grant = grant.key();
+        summary.season = grant.season;
+        summary.start_ts = grant.start_ts;
+        summary.ended_at = now;
+        summary.first_period_index = grant.season_period_offset;
+        summary.next_period_index = next_period_index;
+        summary.claims_count = grant.claims_count - grant.season_base_claims_count;
+        summary.total_claimed = grant.total_claimed - grant.season_base_total_claimed;
+        summary.bump = ctx.bumps.season_summary;
+
+        grant.season = grant.season.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+        grant.season_period_offset = next_period_index;
+        grant.season_base_claims_count = grant.claims_count;
+        grant.season_base_total_claimed = grant.total_claimed;
+        grant.start_ts = start_ts;
+        grant.expires_at = expires_at;
+        grant.period_anchor_ts = start_ts;
+        invalidate_review(grant);
+
+        emit!(SeasonStarted {
+            schema_version: EVENT_SCHEMA_VERSION,
+            grant: grant.key(),
+            season: grant.season,
+            start_ts,
+            expires_at,
+            first_period_index: next_period_index,
+        });
+        Ok(())
+    }
+
+    /// 一時停止/再開（運用自由度）
+    /// - wind-down 中は一時停止できない（再開は可能）
+    pub fn set_paused(ctx: Context<SetPaused>, paused: bool, admin_nonce: u64) -> Result<()> {
+        consume_admin_nonce(
+            &mut ctx.accounts.grant,
+            &ctx.accounts.authority,
+            admin_nonce,
+            "set_paused",
+            ctx.remaining_accounts,
+        )?;
+        let grant = &mut ctx.accounts.grant;
+        require!(!paused || grant.wind_down_ends_at == 0, ErrorCode::WindDownActive);
+        grant.paused = paused;
+        Ok(())
+    }
+
+    /// allowlist を設定（任意）
+    /// - merkle_root が [0;32] の場合は allowlist 無効（誰でも受給可能）
+    /// - それ以外の場合は allowlist 有効（proof を伴う claim が必要）
+    /// - eligible_count は allowlist の人数（allowlist 無効の場合は想定受給者数。0 = 未設定）
+    ///   期間ごとの受給率（take-up）の分母として PeriodStats に記録される
+    /// - append_allowlist_delta で追加した差分 root はすべて破棄される（全体を作り直した root に統合する想定）
+    /// - 出所の記録（snapshot_hash）は消える。allowlist_summary_required が有効な場合は commit_allowlist_root を使う
+    pub fn set_allowlist_root(
+        ctx: Context<SetAllowlistRoot>,
+        merkle_root: [u8; 32],
+        eligible_count: u64,
+        admin_nonce: u64,
+    ) -> Result<()> {
+        consume_admin_nonce(
+            &mut ctx.accounts.grant,
+            &ctx.accounts.authority,
+            admin_nonce,
+            "set_allowlist_root",
+            ctx.remaining_accounts,
+        )?;
+        let grant = &mut ctx.accounts.grant;
+        require!(!grant.allowlist_summary_required, ErrorCode::AllowlistSummaryRequired);
+        require!(grant.wind_down_ends_at == 0, ErrorCode::WindDownActive);
+        replace_allowlist_root(grant, merkle_root, eligible_count, [0u8; 32], 0);
+        let now = clock::now(ctx.remaining_accounts)?;
+        update_budget_forecast_if_present(
+            ctx.accounts.budget_forecast.as_deref_mut(),
+            &ctx.accounts.grant,
+            None,
+            None,
+            now,
+        )
+    }
+
+    /// allowlist を、元になったリストの要約付きで設定する（authority が署名して記録する）
+    /// - leaf_count: Merkle tree の葉の数（eligible_count として記録される）
+    /// - snapshot_hash: root を作ったリストのスナップショットのハッシュ（オフチェーンの元データとの突き合わせ用）
+    /// - Grant に snapshot_hash・記録時刻を保存し、AllowlistRootCommitted を発行する
+    /// - それ以外は set_allowlist_root と同じ（差分 root は破棄される）
+    pub fn commit_allowlist_root(
+        ctx: Context<SetAllowlistRoot>,
+        merkle_root: [u8; 32],
+        leaf_count: u64,
+        snapshot_hash: [u8; 32],
+        admin_nonce: u64,
+    ) -> Result<()> {
+        consume_admin_nonce(
+            &mut ctx.accounts.grant,
+            &ctx.accounts.authority,
+            admin_nonce,
+            "commit_allowlist_root",
+            ctx.remaining_accounts,
+        )?;
+        require!(snapshot_hash != [0u8; 32], ErrorCode::InvalidAllowlistSummary);
+        require!(
+            (merkle_root == [0u8; 32]) == (leaf_count == 0),
+            ErrorCode::InvalidAllowlistSummary
+        );
+        let now = clock::now(ctx.remaining_accounts)?;
+        let grant = &mut ctx.accounts.grant;
+        require!(grant.wind_down_ends_at == 0, ErrorCode::WindDownActive);
+        replace_allowlist_root(grant, merkle_root, leaf_count, snapshot_hash, now);
+
+        emit!(AllowlistRootCommitted {
+            schema_version: EVENT_SCHEMA_VERSION,
+            grant: grant.key(),
+            merkle_root,
+            leaf_count,
+            snapshot_hash,
+            committed_by: ctx.accounts.authority.key(),
+            committed_at: now,
+        });
+        update_budget_forecast_if_present(
+            ctx.accounts.budget_forecast.as_deref_mut(),
+            &ctx.accounts.grant,
+            None,
+            None,
+            now,
+        )
+    }
+
+    /// allowlist の更新に要約（commit_allowlist_root）を必須にするか（authority 限定）
+    /// - 有効な場合、set_allowlist_root は AllowlistSummaryRequired で拒否される
+    pub fn set_allowlist_summary_required(ctx: Context<SetAllowlistRoot>, required: bool, admin_nonce: u64) -> Result<()> {
+        consume_admin_nonce(
+            &mut ctx.accounts.grant,
+            &ctx.accounts.authority,
+            admin_nonce,
+            "set_allowlist_summary_required",
+            ctx.remaining_accounts,
+        )?;
+        ctx.accounts.grant.allowlist_summary_required = required;
+        Ok(())
+    }
+
+    /// allowlist に差分（追加分だけの Merkle root）を積む（authority 限定、追加のみ）
+    /// - 既存の受給者の proof はそのまま使える。追加分の受給者には差分 root に対する proof を配布する
+    /// - expected_delta_count は現在の差分数（二重適用・適用順の取り違えを防ぐ）
+    /// - 除外（資格喪失）は差分では表現できないため、set_allowlist_root で全体を作り直すこと
+    /// - MAX_ALLOWLIST_DELTAS 個に達したら set_allowlist_root で統合する
+    pub fn append_allowlist_delta(
+        ctx: Context<AppendAllowlistDelta>,
+        delta_root: [u8; 32],
+        added_count: u64,
+        expected_delta_count: u8,
+        admin_nonce: u64,
+    ) -> Result<()> {
+        consume_admin_nonce(
+            &mut ctx.accounts.grant,
+            &ctx.accounts.authority,
+            admin_nonce,
+            "append_allowlist_delta",
+            ctx.remaining_accounts,
+        )?;
+        let grant = &mut ctx.accounts.grant;
+        require!(grant.merkle_root != [0u8; 32], ErrorCode::AllowlistNotEnabled);
+        require!(delta_root != [0u8; 32], ErrorCode::InvalidAllowlistDelta);
+        require!(
+            expected_delta_count == grant.allowlist_delta_count,
+            ErrorCode::InvalidAllowlistDelta
+        );
+        let index = grant.allowlist_delta_count as usize;
+        require!(index < MAX_ALLOWLIST_DELTAS, ErrorCode::AllowlistDeltasFull);
+        require!(
+            delta_root != grant.merkle_root && !grant.allowlist_delta_roots[..index].contains(&delta_root),
+            ErrorCode::InvalidAllowlistDelta
+        );
+
+        grant.allowlist_delta_roots[index] = delta_root;
+        grant.allowlist_delta_count += 1;
+        grant.eligible_count = grant
+            .eligible_count
+            .checked_add(added_count)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        emit!(AllowlistDeltaAppended {
+            schema_version: EVENT_SCHEMA_VERSION,
+            grant: grant.key(),
+            base_root: grant.merkle_root,
+            delta_root,
+            delta_index: index as u8,
+            added_count,
+            eligible_count: grant.eligible_count,
+        });
+        let now = clock::now(ctx.remaining_accounts)?;
+        update_budget_forecast_if_present(
+            ctx.accounts.budget_forecast.as_deref_mut(),
+            &ctx.accounts.grant,
+            None,
+            None,
+            now,
+        )
+    }
+
+    /// PoP（Proof of Process）署名者を設定/更新
+    pub fn upsert_pop_config(ctx: Context<UpsertPopConfig>, signer_pubkey: Pubkey) -> Result<()> {
+        let pop_config = &mut ctx.accounts.pop_config;
+        pop_config.authority = ctx.accounts.authority.key();
+        pop_config.signer_pubkey = signer_pubkey;
+        pop_config.bump = ctx.bumps.pop_config;
+        Ok(())
+    }
+
+    /// PoP signer の死活通知（PopConfig の signer 限定、定期的に実行する）
+    /// - last_heartbeat_at を現在時刻に更新し、監視用に PopSignerHeartbeat を emit する
+    pub fn pop_heartbeat(ctx: Context<PopHeartbeat>) -> Result<()> {
+        let now = clock::now(ctx.remaining_accounts)?;
+        let pop_config = &mut ctx.accounts.pop_config;
+        let previous_heartbeat_at = pop_config.last_heartbeat_at;
+        pop_config.last_heartbeat_at = now;
+
+        emit!(PopSignerHeartbeat {
+            schema_version: EVENT_SCHEMA_VERSION,
+            pop_config: pop_config.key(),
+            signer: pop_config.signer_pubkey,
+            heartbeat_at: now,
+            previous_heartbeat_at,
+        });
+        Ok(())
+    }
+
+    /// claim 時に PoP signer の heartbeat 途絶を確認する設定（authority 限定）
+    /// - max_silence_seconds: 最後の heartbeat からの許容秒数（0 = 確認しない）
+    /// - enforced: true なら途絶時に claim を拒否、false なら PopSignerSilent を emit して続行する
+    /// - 有効化は heartbeat を1回以上受信してから（未受信のままだと直ちに途絶扱いになるため）
+    pub fn set_pop_heartbeat_policy(
+        ctx: Context<SetPopHeartbeatPolicy>,
+        max_silence_seconds: i64,
+        enforced: bool,
+    ) -> Result<()> {
+        let pop_config = &mut ctx.accounts.pop_config;
+        require!(max_silence_seconds >= 0, ErrorCode::InvalidHeartbeatPolicy);
+        require!(
+            max_silence_seconds == 0 || pop_config.last_heartbeat_at > 0,
+            ErrorCode::InvalidHeartbeatPolicy
+        );
+        pop_config.heartbeat_max_silence_seconds = max_silence_seconds;
+        pop_config.heartbeat_enforced = enforced;
+        Ok(())
+    }
+
+    /// PoP の audit_hash をまとめて記録する（PopConfig の signer 限定、claim とは独立）
+    /// - batch_root: audit_anchor_leaf(audit_hash) を葉とする Merkle root（sorted pair、1件なら葉そのもの）
+    /// - first_issued_at / last_issued_at: バッチに含まれる PoP message の issued_at の範囲
+    /// - 同じ batch_root は1回だけ記録できる（AuditAnchor の seeds に含まれる）
+    /// - rent は payer が負担する（signer 鍵に SOL を持たせなくてよい）
+    pub fn anchor_audit_batch(
+        ctx: Context<AnchorAuditBatch>,
+        batch_root: [u8; 32],
+        entry_count: u32,
+        first_issued_at: i64,
+        last_issued_at: i64,
+    ) -> Result<()> {
+        require!(batch_root != [0u8; 32], ErrorCode::InvalidAuditBatch);
+        require!(entry_count > 0, ErrorCode::InvalidAuditBatch);
+        require!(first_issued_at <= last_issued_at, ErrorCode::InvalidAuditBatch);
+
+        let clock = Clock::get()?;
+        let anchor = &mut ctx.accounts.audit_anchor;
+        anchor.pop_config = ctx.accounts.pop_config.key();
+        anchor.signer = ctx.accounts.signer.key();
+        anchor.batch_root = batch_root;
+        anchor.entry_count = entry_count;
+        anchor.first_issued_at = first_issued_at;
+        anchor.last_issued_at = last_issued_at;
+        anchor.anchored_at = clock.unix_timestamp;
+        anchor.anchored_slot = clock.slot;
+        anchor.bump = ctx.bumps.audit_anchor;
+
+        emit!(AuditBatchAnchored {
+            schema_version: EVENT_SCHEMA_VERSION,
+            pop_config: anchor.pop_config,
+            signer: anchor.signer,
+            audit_anchor: anchor.key(),
+            batch_root,
+            entry_count,
+            first_issued_at,
+            last_issued_at,
+            anchored_at: anchor.anchored_at,
+        });
+        Ok(())
+    }
+
+    /// audit_hash が記録済みのバッチに含まれることを確認する（view、状態は変更しない）
+    /// - proof: audit_anchor_leaf(audit_hash) から batch_root までの Merkle proof
+    pub fn lookup_audit_hash(
+        ctx: Context<LookupAuditHash>,
+        audit_hash: [u8; 32],
+        proof: Vec<[u8; 32]>,
+    ) -> Result<AuditAnchorLookup> {
+        let anchor = &ctx.accounts.audit_anchor;
+        require!(
+            verify_merkle_sorted(anchor.batch_root, audit_anchor_leaf(&audit_hash), &proof),
+            ErrorCode::AuditHashNotAnchored
+        );
+        Ok(AuditAnchorLookup {
+            pop_config: anchor.pop_config,
+            signer: anchor.signer,
+            batch_root: anchor.batch_root,
+            first_issued_at: anchor.first_issued_at,
+            last_issued_at: anchor.last_issued_at,
+            anchored_at: anchor.anchored_at,
+            anchored_slot: anchor.anchored_slot,
+        })
+    }
+
+    /// vault の残高で何期間分の支給を賄えるかを返す（view、状態は変更しない）
+    /// - 想定受給者数は eligible_count（未設定なら unique_claimers）
+    /// - 1期間の支出 = 1回あたりの支給額（円建ての場合は rate_config で換算）× 想定受給者数 + platform fee
+    ///   （streak ボーナス・日割りは含まない概算）
+    /// - depletion_ts = 現在時刻 + periods_remaining × period_seconds（支出が 0 の場合は 0）
+    pub fn get_runway(ctx: Context<GetRunway>) -> Result<RunwayEstimate> {
+        let grant = &ctx.accounts.grant;
+        let vault_balance = ctx.accounts.vault.amount;
+        let active_claimers = if grant.eligible_count > 0 {
+            grant.eligible_count
+        } else {
+            grant.unique_claimers
+        };
+        let payout = claim_payout_amount(grant, ctx.accounts.rate_config.as_deref())?;
+        let per_claim = add_amounts(payout, bps_of(payout, grant.platform_fee_bps)?)?;
+        let per_period_outflow = (per_claim as u128) * (active_claimers as u128);
+
+        let (periods_remaining, depletion_ts) = match (vault_balance as u128).checked_div(per_period_outflow) {
+            Some(periods) => {
+                let periods = periods as u64;
+                let now = clock::now(ctx.remaining_accounts)?;
+                let horizon = (periods as i128) * (grant.period_seconds as i128) + now as i128;
+                (periods, horizon.min(i64::MAX as i128) as i64)
+            }
+            None => (u64::MAX, 0),
+        };
+
+        Ok(RunwayEstimate {
+            vault_balance,
+            active_claimers,
+            per_period_outflow,
+            periods_remaining,
+            depletion_ts,
+            expires_at: grant.expires_at,
+        })
+    }
+
+    /// BudgetForecast（残りの期間に受給者全員が受給した場合の支出見込み）を作成する（authority 限定）
+    /// - 作成後は fund_grant / fund_grant_from_budget / allowlist の更新 / claim に budget_forecast を渡すことが必須になり、
+    ///   各命令の最後に再計算される（渡さない場合は BudgetForecastRequired）
+    /// - 円建て Grant の場合は rate_config が必要（以降は claim 時の実際の支給額で更新される）
+    pub fn open_budget_forecast(ctx: Context<OpenBudgetForecast>, admin_nonce: u64) -> Result<()> {
+        consume_admin_nonce(
+            &mut ctx.accounts.grant,
+            &ctx.accounts.authority,
+            admin_nonce,
+            "open_budget_forecast",
+            ctx.remaining_accounts,
+        )?;
+        let now = clock::now(ctx.remaining_accounts)?;
+        let grant = &ctx.accounts.grant;
+        let payout = claim_payout_amount(grant, ctx.accounts.rate_config.as_deref())?;
+        let per_claim_outflow = add_amounts(payout, bps_of(payout, grant.platform_fee_bps)?)?;
+
+        let forecast = &mut ctx.accounts.budget_forecast;
+        forecast.grant = grant.key();
+        forecast.bump = ctx.bumps.budget_forecast;
+        update_budget_forecast(forecast, grant, Some(per_claim_outflow), None, now)?;
+
+        ctx.accounts.grant.has_budget_forecast = true;
+        Ok(())
+    }
+
+    /// BudgetForecast を現在時刻で再計算する（誰でも実行可。期間が切り替わった後の残り期間数の更新に使う）
+    /// - 円建て Grant で rate_config を渡した場合は、現在のレートで1回あたりの支出を更新する
+    pub fn refresh_budget_forecast(ctx: Context<RefreshBudgetForecast>) -> Result<()> {
+        let now = clock::now(ctx.remaining_accounts)?;
+        let grant = &ctx.accounts.grant;
+        let per_claim_outflow = match ctx.accounts.rate_config.as_deref() {
+            Some(rate_config) => {
+                let payout = claim_payout_amount(grant, Some(rate_config))?;
+                Some(add_amounts(payout, bps_of(payout, grant.platform_fee_bps)?)?)
+            }
+            None => None,
+        };
+        update_budget_forecast(&mut ctx.accounts.budget_forecast, grant, per_claim_outflow, None, now)
+    }
+
+    /// 現在時刻で再計算した BudgetForecast を返す（view、状態は変更しない）
+    pub fn get_budget_forecast(ctx: Context<GetBudgetForecast>) -> Result<BudgetForecast> {
+        let now = clock::now(ctx.remaining_accounts)?;
+        let mut forecast = (*ctx.accounts.budget_forecast).clone();
+        update_budget_forecast(&mut forecast, &ctx.accounts.grant, None, None, now)?;
+        Ok(forecast)
+    }
+
+    /// BudgetForecast を削除する（authority 限定。close_grant の前に実行すること）
+    pub fn close_budget_forecast(ctx: Context<CloseBudgetForecast>, admin_nonce: u64) -> Result<()> {
+        consume_admin_nonce(
+            &mut ctx.accounts.grant,
+            &ctx.accounts.authority,
+            admin_nonce,
+            "close_budget_forecast",
+            ctx.remaining_accounts,
+        )?;
+        ctx.accounts.grant.has_budget_forecast = false;
+        // BudgetForecast 自体は Accounts で close される
+        Ok(())
+    }
+
+    /// 期限を過ぎた Grant の archive（GrantArchive）を作成する（authority 限定）
+    /// - expires_at を過ぎていること（期限なしの Grant は archive できない。wind-down の場合は終了後）
+    /// - 以降の手順: archive_receipts（誰でも実行可）で receipt を記録 → finalize_archive で root と最終集計を確定
+    ///   → close_archived_receipts（誰でも実行可）で receipt を close
+    /// - open から finalize までの間は close_grant できない
+    pub fn open_archive(ctx: Context<OpenArchive>, admin_nonce: u64) -> Result<()> {
+        consume_admin_nonce(
+            &mut ctx.accounts.grant,
+            &ctx.accounts.authority,
+            admin_nonce,
+            "open_archive",
+            ctx.remaining_accounts,
+        )?;
+        let now = clock::now(ctx.remaining_accounts)?;
+        let grant = &mut ctx.accounts.grant;
+        require!(grant.expires_at != 0 && now > grant.expires_at, ErrorCode::GrantNotExpired);
+
+        let archive = &mut ctx.accounts.archive;
+        archive.grant = grant.key();
+        archive.opened_at = now;
+        archive.bump = ctx.bumps.archive;
+
+        grant.archive_opened_at = now;
+        Ok(())
+    }
+
+    /// receipt を archive の Merkle tree に記録し、status を Archived にする（誰でも実行可）
+    /// - remaining_accounts: この Grant の receipt（writable、最大 MAX_ARCHIVE_RECEIPTS_PER_BATCH 件）。渡した順が葉の位置になる
+    /// - 葉は Archived に書き換える前のアカウントデータの receipt_leaf。オフチェーンで export する場合は、
+    ///   実行前のデータを葉の順に保存しておく（ReceiptsArchived の first_leaf_index から位置がわかる）
+    /// - Reserved の receipt は記録できない（先に release_receipt_reservations で close する）
+    pub fn archive_receipts<'info>(ctx: Context<'_, '_, 'info, 'info, ArchiveReceipts<'info>>) -> Result<()> {
+        require!(
+            !ctx.remaining_accounts.is_empty() && ctx.remaining_accounts.len() <= MAX_ARCHIVE_RECEIPTS_PER_BATCH,
+            ErrorCode::InvalidBatchSize
+        );
+        let grant_key = ctx.accounts.grant.key();
+        let archive = &mut ctx.accounts.archive;
+        require!(archive.finalized_at == 0, ErrorCode::ArchiveFinalized);
+        let first_leaf_index = archive.leaf_count;
+        for receipt_info in ctx.remaining_accounts.iter() {
+            require!(receipt_info.is_writable, ErrorCode::InvalidReceipt);
+            let mut receipt = Account::<ClaimReceipt>::try_from(receipt_info)?;
+            require_keys_eq!(receipt.grant, grant_key, ErrorCode::InvalidReceipt);
+            require!(
+                receipt.status != ReceiptStatus::Reserved && receipt.status != ReceiptStatus::Archived,
+                ErrorCode::InvalidReceiptTransition
+            );
+            require!(
+                archive.leaf_count < 1u64 << merkle::ARCHIVE_TREE_DEPTH,
+                ErrorCode::MathOverflow
+            );
+            let leaf = merkle::receipt_leaf(&receipt_info.try_borrow_data()?[8..receipt::RECEIPT_ACCOUNT_LEN]);
+            let leaf_index = archive.leaf_count;
+            merkle::frontier_append(&mut archive.frontier, leaf_index, leaf);
+            archive.leaf_count = leaf_index + 1;
+
+            receipt.status = ReceiptStatus::Archived;
+            receipt.exit(&crate::ID)?;
+        }
+
+        emit!(ReceiptsArchived {
+            schema_version: EVENT_SCHEMA_VERSION,
+            grant: grant_key,
+            first_leaf_index,
+            count: ctx.remaining_accounts.len() as u32,
+            leaf_count: archive.leaf_count,
+        });
+        Ok(())
+    }
+
+    /// archive を確定する（authority 限定）
+    /// - receipts_root（記録した receipt の Merkle root）と Grant の最終集計を GrantArchive に保存し、GrantArchived を emit する
+    /// - 確定後は archive_receipts できず、close_archived_receipts と close_grant ができるようになる
+    pub fn finalize_archive(ctx: Context<FinalizeArchive>, admin_nonce: u64) -> Result<()> {
+        consume_admin_nonce(
+            &mut ctx.accounts.grant,
+            &ctx.accounts.authority,
+            admin_nonce,
+            "finalize_archive",
+            ctx.remaining_accounts,
+        )?;
+        let now = clock::now(ctx.remaining_accounts)?;
+        let grant = &mut ctx.accounts.grant;
+        let archive = &mut ctx.accounts.archive;
+        require!(archive.finalized_at == 0, ErrorCode::ArchiveFinalized);
+
+        archive.receipts_root = merkle::frontier_root(&archive.frontier, archive.leaf_count);
+        archive.total_funded = grant.total_funded;
+        archive.total_claimed = grant.total_claimed;
+        archive.total_platform_fees = grant.total_platform_fees;
+        archive.claims_count = grant.claims_count;
+        archive.unique_claimers = grant.unique_claimers;
+        archive.finalized_at = now;
+        grant.archived_at = now;
+
+        emit!(GrantArchived {
+            schema_version: EVENT_SCHEMA_VERSION,
+            grant: grant.key(),
+            receipts_root: archive.receipts_root,
+            leaf_count: archive.leaf_count,
+            tree_depth: merkle::ARCHIVE_TREE_DEPTH as u8,
+            total_funded: archive.total_funded,
+            total_claimed: archive.total_claimed,
+            total_platform_fees: archive.total_platform_fees,
+            claims_count: archive.claims_count,
+            unique_claimers: archive.unique_claimers,
+            archived_at: now,
+        });
+        Ok(())
+    }
+
+    /// 確定済みの archive に記録された receipt を close する（誰でも実行可、rent は各 receipt の claimer に戻る）
+    /// - remaining_accounts: [receipt, claimer] の組（すべて writable、最大 MAX_ARCHIVE_RECEIPTS_PER_BATCH 組）
+    /// - close_grant の後でも実行できる
+    pub fn close_archived_receipts<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CloseArchivedReceipts<'info>>,
+    ) -> Result<()> {
+        let pairs = ctx.remaining_accounts.len() / 2;
+        require!(
+            ctx.remaining_accounts.len().is_multiple_of(2) && pairs > 0 && pairs <= MAX_ARCHIVE_RECEIPTS_PER_BATCH,
+            ErrorCode::InvalidBatchSize
+        );
+        let archive = &mut ctx.accounts.archive;
+        require!(archive.finalized_at != 0, ErrorCode::ArchiveNotFinalized);
+        for pair in ctx.remaining_accounts.chunks(2) {
+            let receipt = Account::<ClaimReceipt>::try_from(&pair[0])?;
+            require_keys_eq!(receipt.grant, archive.grant, ErrorCode::InvalidReceipt);
+            require!(receipt.status == ReceiptStatus::Archived, ErrorCode::InvalidReceiptTransition);
+            require_keys_eq!(receipt.claimer, pair[1].key(), ErrorCode::InvalidReceipt);
+            receipt.close(pair[1].clone())?;
+        }
+        archive.closed_count = archive.closed_count.checked_add(pairs as u64).ok_or(ErrorCode::MathOverflow)?;
+        Ok(())
+    }
+
+    /// 固定レート表（tokens-per-yen）を設定/更新（authority 限定）
+    /// - tokens_per_yen: 1円あたりのトークン最小単位数（decimals=6 で 1 token = 1円なら 1_000_000）
+    /// - 円建て額を持つ Grant の claim はこのレートで支給額を算出する。
+    ///   レートを変えるだけで Grant を作り直さずに再ペッグできる。
+    pub fn upsert_rate_config(ctx: Context<UpsertRateConfig>, tokens_per_yen: u64) -> Result<()> {
+        require!(tokens_per_yen > 0, ErrorCode::InvalidRate);
+
+        let rate_config = &mut ctx.accounts.rate_config;
+        rate_config.authority = ctx.accounts.authority.key();
+        rate_config.tokens_per_yen = tokens_per_yen;
+        rate_config.last_updated = clock::now(ctx.remaining_accounts)?;
+        rate_config.bump = ctx.bumps.rate_config;
+        Ok(())
+    }
+
+    /// Grant の円建て支給額を設定（authority 限定）
+    /// - yen_per_period が 0 の場合は円建て無効（amount_per_period をそのまま支給）
+    /// - それ以外の場合は claim 時に RateConfig を参照して支給額を算出する
+    pub fn set_yen_per_period(ctx: Context<SetYenPerPeriod>, yen_per_period: u64, admin_nonce: u64) -> Result<()> {
+        consume_admin_nonce(
+            &mut ctx.accounts.grant,
+            &ctx.accounts.authority,
+            admin_nonce,
+            "set_yen_per_period",
+            ctx.remaining_accounts,
+        )?;
+        let grant = &mut ctx.accounts.grant;
+        grant.yen_per_period = yen_per_period;
+        invalidate_review(grant);
+        Ok(())
+    }
+
+    /// 期間の区切りと日割りを設定（authority 限定、受給開始前のみ）
+    /// - period_anchor_ts: 期間計算の起点（月初などの暦の区切り）。start_ts 以前であること
+    /// - prorate: true の場合、start_ts が期間途中にある最初の期間と、
+    ///   expires_at が期間途中にある最後の期間の支給額を受給可能秒数で按分する
+    pub fn set_proration(
+        ctx: Context<SetProration>,
+        prorate: bool,
+        period_anchor_ts: i64,
+        admin_nonce: u64,
+    ) -> Result<()> {
+        consume_admin_nonce(
+            &mut ctx.accounts.grant,
+            &ctx.accounts.authority,
+            admin_nonce,
+            "set_proration",
+            ctx.remaining_accounts,
+        )?;
+        let grant = &mut ctx.accounts.grant;
+        require!(!grant.has_receipts(), ErrorCode::GrantAlreadyClaimed);
+        require!(period_anchor_ts <= grant.start_ts, ErrorCode::InvalidStartTs);
+
+        grant.prorate_partial_periods = prorate;
+        grant.period_anchor_ts = period_anchor_ts;
+        invalidate_review(grant);
+        Ok(())
+    }
+
+    /// 連続受給ボーナスのスケジュールを設定（authority 限定）
+    /// - 各ステップは「今回の claim を含む連続期間数 >= min_streak なら支給額に bonus_bps を上乗せ」
+    /// - 条件を満たすステップのうち min_streak が最大のものを適用する
+    /// - 空配列でボーナス無効
+    pub fn set_streak_bonus(ctx: Context<SetStreakBonus>, steps: Vec<StreakBonusStep>, admin_nonce: u64) -> Result<()> {
+        consume_admin_nonce(
+            &mut ctx.accounts.grant,
+            &ctx.accounts.authority,
+            admin_nonce,
+            "set_streak_bonus",
+            ctx.remaining_accounts,
+        )?;
+        require!(
+            steps.len() <= MAX_STREAK_BONUS_STEPS,
+            ErrorCode::InvalidStreakBonus
+        );
+        for step in steps.iter() {
+            require!(step.min_streak > 0, ErrorCode::InvalidStreakBonus);
+            require!(
+                step.bonus_bps as u64 <= BPS_DENOMINATOR,
+                ErrorCode::InvalidStreakBonus
+            );
+        }
+
+        let grant = &mut ctx.accounts.grant;
+        grant.streak_bonus_steps = [StreakBonusStep::default(); MAX_STREAK_BONUS_STEPS];
+        grant.streak_bonus_steps[..steps.len()].copy_from_slice(&steps);
+        invalidate_review(grant);
+        Ok(())
+    }
+
+    /// ステーク期間による支給額の倍率を設定（authority 限定）
+    /// - stake_program が所有するステーク記録（STAKE_RECORD_* のレイアウト）を claim に渡した受給者は、
+    ///   stake_mint を min_amount 以上保持し続けた期間に応じて支給額に multiplier_bps / 10_000 を掛ける
+    /// - 条件を満たすステップのうち min_stake_seconds が最大のものを適用する（満たさなければ等倍）
+    /// - ステーク記録を渡さない claim は等倍のまま受け付ける
+    /// - stake_program = Pubkey::default() で無効（steps は空にすること）
+    pub fn set_stake_multiplier(
+        ctx: Context<SetStakeMultiplier>,
+        stake_program: Pubkey,
+        stake_mint: Pubkey,
+        min_amount: u64,
+        steps: Vec<StakeMultiplierStep>,
+        admin_nonce: u64,
+    ) -> Result<()> {
+        consume_admin_nonce(
+            &mut ctx.accounts.grant,
+            &ctx.accounts.authority,
+            admin_nonce,
+            "set_stake_multiplier",
+            ctx.remaining_accounts,
+        )?;
+        require!(stake_program != crate::ID, ErrorCode::InvalidStakeMultiplier);
+        require!(
+            steps.len() <= MAX_STAKE_MULTIPLIER_STEPS
+                && (stake_program != Pubkey::default() || steps.is_empty()),
+            ErrorCode::InvalidStakeMultiplier
+        );
+        for step in steps.iter() {
+            require!(step.min_stake_seconds > 0, ErrorCode::InvalidStakeMultiplier);
+            require!(
+                step.multiplier_bps as u64 >= BPS_DENOMINATOR,
+                ErrorCode::InvalidStakeMultiplier
+            );
+        }
+
+        let grant = &mut ctx.accounts.grant;
+        let enabled = stake_program != Pubkey::default();
+        grant.stake_program = stake_program;
+        grant.stake_mint = if enabled { stake_mint } else { Pubkey::default() };
+        grant.stake_min_amount = if enabled { min_amount } else { 0 };
+        grant.stake_multiplier_steps = [StakeMultiplierStep::default(); MAX_STAKE_MULTIPLIER_STEPS];
+        grant.stake_multiplier_steps[..steps.len()].copy_from_slice(&steps);
+        invalidate_review(grant);
+        Ok(())
+    }
+
+    /// 同一 claimer の claim 間隔の下限を設定（authority 限定）
+    /// - 期間の区切りとは独立に、前回 claim から min_claim_interval_seconds 経過するまで次の claim を拒否する
+    ///   （期間 N の末尾と期間 N+1 の先頭で続けて受給するのを防ぐ）
+    /// - 0 で無効。有効な場合、claim には claimer ごとの ClaimStreak アカウントが必要
+    pub fn set_min_claim_interval(
+        ctx: Context<SetMinClaimInterval>,
+        min_claim_interval_seconds: i64,
+        admin_nonce: u64,
+    ) -> Result<()> {
+        consume_admin_nonce(
+            &mut ctx.accounts.grant,
+            &ctx.accounts.authority,
+            admin_nonce,
+            "set_min_claim_interval",
+            ctx.remaining_accounts,
+        )?;
+        require!(min_claim_interval_seconds >= 0, ErrorCode::InvalidPeriod);
+        ctx.accounts.grant.min_claim_interval_seconds = min_claim_interval_seconds;
+        Ok(())
+    }
+
+    /// 受給者数（ユニーク claimer 数）の上限を設定（authority 限定）
+    /// - 上限に達すると新規の claimer は拒否されるが、既存の claimer は引き続き受給できる
+    /// - 0 で無制限。現在の受給者数未満には設定できない
+    pub fn set_max_unique_claimers(
+        ctx: Context<SetMaxUniqueClaimers>,
+        max_unique_claimers: u64,
+        admin_nonce: u64,
+    ) -> Result<()> {
+        consume_admin_nonce(
+            &mut ctx.accounts.grant,
+            &ctx.accounts.authority,
+            admin_nonce,
+            "set_max_unique_claimers",
+            ctx.remaining_accounts,
+        )?;
+        let grant = &mut ctx.accounts.grant;
+        require!(
+            max_unique_claimers == 0 || max_unique_claimers >= grant.unique_claimers,
+            ErrorCode::InvalidMaxUniqueClaimers
+        );
+        grant.max_unique_claimers = max_unique_claimers;
+        Ok(())
+    }
+
+    /// KPI 目標を設定（authority 限定）
+    /// - target_take_up_bps: 期間ごとの受給率（claims_count / eligible_count）の目標（0 = なし、最大 10_000）
+    /// - target_unique_claimers: ユニーク受給者数の目標（0 = なし）
+    /// - close_period のたびに KpiProgress を発行し、初めて達成した目標は kpi_milestones に記録する
+    /// - 再設定すると kpi_milestones はリセットされる
+    pub fn set_kpi_targets(
+        ctx: Context<SetKpiTargets>,
+        target_take_up_bps: u16,
+        target_unique_claimers: u64,
+        admin_nonce: u64,
+    ) -> Result<()> {
+        consume_admin_nonce(
+            &mut ctx.accounts.grant,
+            &ctx.accounts.authority,
+            admin_nonce,
+            "set_kpi_targets",
+            ctx.remaining_accounts,
+        )?;
+        require!(
+            target_take_up_bps as u64 <= BPS_DENOMINATOR,
+            ErrorCode::InvalidKpiTargets
+        );
+        let grant = &mut ctx.accounts.grant;
+        grant.target_take_up_bps = target_take_up_bps;
+        grant.target_unique_claimers = target_unique_claimers;
+        grant.kpi_milestones = 0;
+        Ok(())
+    }
+
+    /// PoP message の issued_at の単調増加を要求するか（authority 限定）
+    /// - 有効な場合、issued_at が PopState.last_issued_at 以下の proof を拒否する
+    ///   （順序が入れ替わった、または重複した signer のバッチを受け付けない）
+    /// - 複数 claimer の proof が同時に発行される運用では無効のままにすること
+    pub fn set_pop_monotonic_issued_at(ctx: Context<SetPopMonotonicIssuedAt>, enabled: bool, admin_nonce: u64) -> Result<()> {
+        consume_admin_nonce(
+            &mut ctx.accounts.grant,
+            &ctx.accounts.authority,
+            admin_nonce,
+            "set_pop_monotonic_issued_at",
+            ctx.remaining_accounts,
+        )?;
+        ctx.accounts.grant.pop_monotonic_issued_at = enabled;
+        Ok(())
+    }
+
+    /// v2 形式（audit_hash 付き）の PoP message のみ受け付けるか（authority 限定）
+    /// - 作成時は有効。無効にすると audit_hash を含まない v1 形式の proof でも claim できる
+    /// - 規制対象のキャンペーンなど、オフチェーンのレシートとの突き合わせが必要な Grant では有効のままにすること
+    /// - verify_pop_proof の検証結果にも同じ条件が適用される
+    pub fn set_pop_require_audit_hash(ctx: Context<SetPopRequireAuditHash>, enabled: bool, admin_nonce: u64) -> Result<()> {
+        consume_admin_nonce(
+            &mut ctx.accounts.grant,
+            &ctx.accounts.authority,
+            admin_nonce,
+            "set_pop_require_audit_hash",
+            ctx.remaining_accounts,
+        )?;
+        ctx.accounts.grant.pop_require_audit_hash = enabled;
+        Ok(())
+    }
+
+    /// v3 形式の PoP message で署名者が指定できる支給額の上限を設定する（authority 限定、0 = v3 を受け付けない）
+    /// - 調整分や未払い分など、オフチェーンで計算した額をそのまま支給する場合に使う
+    /// - 指定された額は amount_per_period / rate_config / streak ボーナスの計算結果の代わりに支給される
+    ///   （platform fee は指定額に対して別途かかる）
+    pub fn set_pop_amount_ceiling(ctx: Context<SetPopAmountCeiling>, ceiling: u64, admin_nonce: u64) -> Result<()> {
+        consume_admin_nonce(
+            &mut ctx.accounts.grant,
+            &ctx.accounts.authority,
+            admin_nonce,
+            "set_pop_amount_ceiling",
+            ctx.remaining_accounts,
+        )?;
+        ctx.accounts.grant.pop_amount_ceiling = ceiling;
+        Ok(())
+    }
+
+    /// receipt / pop_state の PDA seed に加える salt を設定する（authority 限定、1回のみ）
+    /// - 同じ authority の下で staging / production などを分ける場合に使う（環境ごとに異なる salt にする）
+    /// - 既存の receipt / pop_state のアドレスが変わるため、receipt が1つもない（record_historical_claim で記録した receipt を含む）
+    ///   pop_state 未作成の間に限る
+    pub fn set_seed_salt(ctx: Context<SetSeedSalt>, salt: [u8; SEED_SALT_LEN], admin_nonce: u64) -> Result<()> {
+        consume_admin_nonce(
+            &mut ctx.accounts.grant,
+            &ctx.accounts.authority,
+            admin_nonce,
+            "set_seed_salt",
+            ctx.remaining_accounts,
+        )?;
+        require!(salt != [0u8; SEED_SALT_LEN], ErrorCode::InvalidSeedSalt);
+        let grant = &mut ctx.accounts.grant;
+        require!(
+            grant.seed_salt == [0u8; SEED_SALT_LEN]
+                && !grant.has_receipts()
+                && ctx.accounts.pop_state.data_is_empty(),
+            ErrorCode::SeedSaltLocked
+        );
+        grant.seed_salt = salt;
+        Ok(())
+    }
+
+    /// PoP signer 障害時の緊急措置として、until まで PoP なしの claim を許可する（authority 限定）
+    /// - until: 現在時刻より後、POP_BYPASS_MAX_SECONDS 以内。0 で即時解除
+    /// - 期間中の claim は ed25519 命令を要求せず、PopState のチェーンヘッドも更新しない
+    ///   receipt の pop_bypassed が立ち、PopBypassClaimed を発行する（事後の突き合わせ用）
+    pub fn set_pop_bypass(ctx: Context<SetPopBypass>, until: i64, admin_nonce: u64) -> Result<()> {
+        consume_admin_nonce(
+            &mut ctx.accounts.grant,
+            &ctx.accounts.authority,
+            admin_nonce,
+            "set_pop_bypass",
+            ctx.remaining_accounts,
+        )?;
+        let now = clock::now(ctx.remaining_accounts)?;
+        require!(
+            until == 0 || (until > now && until - now <= POP_BYPASS_MAX_SECONDS),
+            ErrorCode::InvalidPopBypass
+        );
+        let grant = &mut ctx.accounts.grant;
+        grant.pop_bypass_until = until;
+
+        emit!(PopBypassSet {
+            schema_version: EVENT_SCHEMA_VERSION,
+            grant: grant.key(),
+            authority: ctx.accounts.authority.key(),
+            until,
+            set_at: now,
+        });
+        Ok(())
+    }
+
+    /// sponsor の表示情報を設定する（authority 限定）
+    /// - name_hash: sponsor 名のハッシュ（名前自体はオフチェーンで管理し、表示時に突き合わせる）。[0;32] で解除
+    /// - logo_uri: ロゴの URI（MAX_SPONSOR_URI_LEN バイトまで、空でもよい）
+    /// - 設定中の Grant の claim では SponsoredClaim を発行する（claim の記録に sponsor を残すため）
+    ///   ClaimReceipt は固定長のため可変長の sponsor 情報を含めない（receipt の表示側は Grant から読む）
+    pub fn set_sponsor(ctx: Context<SetSponsor>, name_hash: [u8; 32], logo_uri: String, admin_nonce: u64) -> Result<()> {
+        consume_admin_nonce(
+            &mut ctx.accounts.grant,
+            &ctx.accounts.authority,
+            admin_nonce,
+            "set_sponsor",
+            ctx.remaining_accounts,
+        )?;
+        require!(logo_uri.len() <= MAX_SPONSOR_URI_LEN, ErrorCode::InvalidSponsor);
+        require!(
+            name_hash != [0u8; 32] || logo_uri.is_empty(),
+            ErrorCode::InvalidSponsor
+        );
+        let grant = &mut ctx.accounts.grant;
+        grant.sponsor_name_hash = name_hash;
+        grant.sponsor_logo_uri = logo_uri;
+        Ok(())
+    }
+
+    /// PopState.last_issued_at を巻き戻す（authority 限定）
+    /// - signer の時計が進んでいた場合の補正用。以降は issued_at > last_issued_at の proof を受け付ける
+    pub fn reset_pop_issued_at(ctx: Context<ResetPopIssuedAt>, last_issued_at: i64, admin_nonce: u64) -> Result<()> {
+        consume_admin_nonce(
+            &mut ctx.accounts.grant,
+            &ctx.accounts.authority,
+            admin_nonce,
+            "reset_pop_issued_at",
+            ctx.remaining_accounts,
+        )?;
+        let pop_state = &mut ctx.accounts.pop_state;
+        require!(
+            last_issued_at <= pop_state.last_issued_at,
+            ErrorCode::PopIssuedAtNotIncreasing
+        );
+        pop_state.last_issued_at = last_issued_at;
+        Ok(())
+    }
+
+    /// claim 停止期間（blackout）を設定（authority 限定）
+    /// - 期間中の claim は ClaimBlackout で拒否されるが、期間計算（period_index）はそのまま進む
+    /// - recurrence_seconds > 0 の場合、start_ts から recurrence_seconds ごとに繰り返す
+    /// - 空配列で blackout 無効
+    pub fn set_blackout_windows(
+        ctx: Context<SetBlackoutWindows>,
+        windows: Vec<BlackoutWindow>,
+        admin_nonce: u64,
+    ) -> Result<()> {
+        consume_admin_nonce(
+            &mut ctx.accounts.grant,
+            &ctx.accounts.authority,
+            admin_nonce,
+            "set_blackout_windows",
+            ctx.remaining_accounts,
+        )?;
+        require!(
+            windows.len() <= MAX_BLACKOUT_WINDOWS,
+            ErrorCode::InvalidBlackoutWindow
+        );
+        for window in windows.iter() {
+            require!(window.end_ts > window.start_ts, ErrorCode::InvalidBlackoutWindow);
+            if window.recurrence_seconds != 0 {
+                let duration = window
+                    .end_ts
+                    .checked_sub(window.start_ts)
+                    .ok_or(ErrorCode::MathOverflow)?;
+                require!(
+                    window.recurrence_seconds > duration,
+                    ErrorCode::InvalidBlackoutWindow
+                );
+            }
+        }
+
+        let grant = &mut ctx.accounts.grant;
+        grant.blackout_windows = [BlackoutWindow::default(); MAX_BLACKOUT_WINDOWS];
+        grant.blackout_windows[..windows.len()].copy_from_slice(&windows);
+        Ok(())
+    }
+
+    /// attestation gate（KYC / uniqueness プロバイダ）を設定（authority 限定）
+    /// - gate_program が Pubkey::default() の場合は gate 無効
+    /// - それ以外の場合、claim には gate_program 所有の標準アテステーションが必要
+    pub fn set_gate(ctx: Context<SetGate>, gate_program: Pubkey, gate_config: Pubkey, admin_nonce: u64) -> Result<()> {
+        consume_admin_nonce(
+            &mut ctx.accounts.grant,
+            &ctx.accounts.authority,
+            admin_nonce,
+            "set_gate",
+            ctx.remaining_accounts,
+        )?;
+        let grant = &mut ctx.accounts.grant;
+        grant.gate_program = gate_program;
+        grant.gate_config = gate_config;
+        invalidate_review(grant);
+        Ok(())
+    }
+
+    /// 他プログラムから CPI で claim を呼ぶこと（claim のラップ）を制限する（authority 限定、既定は無制限）
+    /// - enabled = true の場合、トランザクションの最上位の命令が grant_program 自身（= 直接の呼び出し）か、
+    ///   allowed_caller（Pubkey::default() = なし）のプログラムである claim だけを受け付ける
+    /// - 最上位の命令は instructions sysvar で確認する（allowed_caller がさらに別のプログラムを経由する場合も通る）
+    pub fn set_claim_cpi_guard(
+        ctx: Context<SetClaimCpiGuard>,
+        enabled: bool,
+        allowed_caller: Pubkey,
+        admin_nonce: u64,
+    ) -> Result<()> {
+        consume_admin_nonce(
+            &mut ctx.accounts.grant,
+            &ctx.accounts.authority,
+            admin_nonce,
+            "set_claim_cpi_guard",
+            ctx.remaining_accounts,
+        )?;
+        require!(allowed_caller != crate::ID, ErrorCode::InvalidClaimCaller);
+        let grant = &mut ctx.accounts.grant;
+        grant.claim_cpi_guard = enabled;
+        grant.claim_cpi_allowed_caller = if enabled { allowed_caller } else { Pubkey::default() };
+        Ok(())
+    }
+
+    /// 受給資格の述語を設定（authority 限定）
+    /// - clauses の各要素は ELIGIBILITY_* のビットの AND、要素同士は OR（0 の要素は無視）
+    ///   例: [ELIGIBILITY_ALLOWLIST | ELIGIBILITY_TOKEN_HOLDING, 0, 0, 0] = 住民名簿に含まれ、かつ会員 NFT を保有
+    /// - すべて 0 の場合は述語なし（従来どおり allowlist / attestation gate をそれぞれ必須にする）
+    /// - 述語がある場合、allowlist と attestation gate は述語の条件としてのみ評価する
+    ///   （proof なしの claim_grant も受け付け、allowlist の条件は満たさないものとして扱う）
+    /// - token-holding: 受給者が holding_mint を holding_min_amount 以上保有していること
+    /// - enrollment: 受給者が enrollment_grant で1回以上受給していること（ClaimerProfile で確認）
+    /// - wind-down 中は変更できない
+    pub fn set_eligibility_predicate(
+        ctx: Context<SetEligibilityPredicate>,
+        clauses: [u8; MAX_ELIGIBILITY_CLAUSES],
+        holding_mint: Pubkey,
+        holding_min_amount: u64,
+        enrollment_grant: Pubkey,
+        admin_nonce: u64,
+    ) -> Result<()> {
+        consume_admin_nonce(
+            &mut ctx.accounts.grant,
+            &ctx.accounts.authority,
+            admin_nonce,
+            "set_eligibility_predicate",
+            ctx.remaining_accounts,
+        )?;
+        let grant_key = ctx.accounts.grant.key();
+        let grant = &mut ctx.accounts.grant;
+        require!(grant.wind_down_ends_at == 0, ErrorCode::WindDownActive);
+
+        let used = clauses.iter().fold(0u8, |acc, clause| acc | clause);
+        require!(used & !ELIGIBILITY_ALL_GATES == 0, ErrorCode::InvalidEligibilityPredicate);
+        if used & ELIGIBILITY_TOKEN_HOLDING != 0 {
+            require!(
+                holding_mint != Pubkey::default() && holding_min_amount > 0,
+                ErrorCode::InvalidEligibilityPredicate
+            );
+        }
+        if used & ELIGIBILITY_ENROLLMENT != 0 {
+            require!(
+                enrollment_grant != Pubkey::default() && enrollment_grant != grant_key,
+                ErrorCode::InvalidEligibilityPredicate
+            );
+        }
+
+        // 使わない gate のパラメータは残さない
+        let (holding_mint, holding_min_amount) = if used & ELIGIBILITY_TOKEN_HOLDING != 0 {
+            (holding_mint, holding_min_amount)
+        } else {
+            (Pubkey::default(), 0)
+        };
+        let enrollment_grant = if used & ELIGIBILITY_ENROLLMENT != 0 {
+            enrollment_grant
+        } else {
+            Pubkey::default()
+        };
+        grant.eligibility_clauses = clauses;
+        grant.holding_mint = holding_mint;
+        grant.holding_min_amount = holding_min_amount;
+        grant.enrollment_grant = enrollment_grant;
+        invalidate_review(grant);
+        Ok(())
+    }
+
+    /// 受給アテステーションの発行先を設定（authority 限定）
+    /// - attestation_program が Pubkey::default() の場合は無効（attestation_config も default にする）
+    /// - それ以外の場合、claim ごとに attestation_program へ標準の disbursement attestation を CPI で発行し、
+    ///   claim には attestation_program / attestation_record アカウントが必要になる
+    pub fn set_attestation_program(
+        ctx: Context<SetAttestationProgram>,
+        attestation_program: Pubkey,
+        attestation_config: Pubkey,
+        admin_nonce: u64,
+    ) -> Result<()> {
+        consume_admin_nonce(
+            &mut ctx.accounts.grant,
+            &ctx.accounts.authority,
+            admin_nonce,
+            "set_attestation_program",
+            ctx.remaining_accounts,
+        )?;
+        require!(attestation_program != crate::ID, ErrorCode::InvalidAttestationProgram);
+        require!(
+            attestation_program != Pubkey::default() || attestation_config == Pubkey::default(),
+            ErrorCode::InvalidAttestationProgram
+        );
+        let grant = &mut ctx.accounts.grant;
+        grant.attestation_program = attestation_program;
+        grant.attestation_config = attestation_config;
+        Ok(())
+    }
+
+    /// allowlist（Merkle）を用いた受給
+    /// - Grant に merkle_root が設定されている場合はこちらを使用
+    pub fn claim_grant_with_proof<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ClaimGrant<'info>>,
+        period_index: u64,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        let result = (|| -> Result<()> {
+            let now = clock::now(ctx.remaining_accounts)?;
+
+            require!(!ctx.accounts.grant.paused, ErrorCode::Paused);
+
+            // allowlist が無効なら通常の claim を使えばよい
+            require!(
+                ctx.accounts.grant.merkle_root != [0u8; 32],
+                ErrorCode::AllowlistNotEnabled
+            );
+
+            // Merkle allowlist verify
+            let leaf = ctx.accounts.grant.allowlist_leaf(&ctx.accounts.beneficiary());
+            require!(
+                allowlist_contains(&ctx.accounts.grant, leaf, &proof),
+                ErrorCode::NotInAllowlist
+            );
+            if ctx.accounts.grant.has_eligibility_predicate() {
+                require_eligibility(ctx.accounts, true, now)?;
+            }
+            require_canonical_destination(ctx.accounts)?;
+
+            execute_claim(ctx.accounts, &ctx.bumps, ctx.remaining_accounts, period_index, now)
+        })();
+        structured_log::emit(&result, || claim_log_line("claim_grant_with_proof", ctx.accounts, period_index));
+        result
+    }
+
+    /// 受給して wSOL をネイティブ SOL へ戻す（mint が wrapped SOL の Grant 限定）
+    /// - 受給先の wSOL アカウント（claimer_ata）は受給後に close され、
+    ///   残高と rent がネイティブ SOL として claimer に戻る
+    /// - allowlist が有効な場合は proof を検証する（無効な場合は空配列でよい）
+    /// - claimer_ata は一時アカウントでよい（受給後すぐ claimer 本人に close されるため ATA の検査はしない）
+    pub fn claim_grant_unwrap_sol<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ClaimGrant<'info>>,
+        period_index: u64,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        let result = (|| -> Result<()> {
+            let now = clock::now(ctx.remaining_accounts)?;
+
+            require!(!ctx.accounts.grant.paused, ErrorCode::Paused);
+            require!(
+                ctx.accounts.grant.mint == native_mint::ID,
+                ErrorCode::NotNativeMint
+            );
+            // claimer_ata の close には受給者本人の署名が必要
+            require!(
+                ctx.accounts.session_authorization.is_none(),
+                ErrorCode::SessionScopeNotAllowed
+            );
+            require!(ctx.accounts.intent_claimer.is_none(), ErrorCode::InvalidClaimIntent);
+            require!(ctx.accounts.pending_claim.is_none(), ErrorCode::InvalidScheduledClaim);
+
+            // 述語がある場合、proof は allowlist の条件を満たすために渡すときだけでよい
+            let prove_allowlist = ctx.accounts.grant.merkle_root != [0u8; 32]
+                && (!ctx.accounts.grant.has_eligibility_predicate() || !proof.is_empty());
+            if prove_allowlist {
+                let leaf = ctx.accounts.grant.allowlist_leaf(&ctx.accounts.beneficiary());
+                require!(
+                    allowlist_contains(&ctx.accounts.grant, leaf, &proof),
+                    ErrorCode::NotInAllowlist
+                );
+            }
+            if ctx.accounts.grant.has_eligibility_predicate() {
+                require_eligibility(ctx.accounts, prove_allowlist, now)?;
+            }
+
+            execute_claim(ctx.accounts, &ctx.bumps, ctx.remaining_accounts, period_index, now)?;
+
+            // 一時 wSOL アカウントを close してネイティブ SOL として受け取る
+            let cpi_accounts = CloseAccount {
+                account: ctx.accounts.claimer_ata.to_account_info(),
+                destination: ctx.accounts.claimer.to_account_info(),
+                authority: ctx.accounts.claimer.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+            close_account(cpi_ctx)
+        })();
+        structured_log::emit(&result, || claim_log_line("claim_grant_unwrap_sol", ctx.accounts, period_index));
+        result
+    }
+
+    /// claimer が現在の期間に claim できるかを返す（view、状態は変更しない）
+    /// - receipt は現在の期間の receipt PDA（未作成でよい。period_index は戻り値で確認できる）
+    /// - proof は allowlist の proof（allowlist のない Grant では空配列でよい）
+    /// - 判定の範囲は Grant::claim_availability を参照（PoP・gate・vault 残高は見ない）
+    /// - 複数の Grant をまとめて調べる場合は client::discover_claimable_grants を使う（同じ判定をオフチェーンで行う）
+    pub fn get_claim_status(
+        ctx: Context<GetClaimStatus>,
+        claimer: Pubkey,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<ClaimStatus> {
+        let now = clock::now(ctx.remaining_accounts)?;
+        let grant = &ctx.accounts.grant;
+        let period_index = current_period_index(grant, now)?;
+        let (expected_receipt, _) = receipt::receipt_address(&grant.key(), &claimer, period_index, &grant.seed_salt);
+        require_keys_eq!(ctx.accounts.receipt.key(), expected_receipt, ErrorCode::InvalidReceipt);
+
+        let receipt_info = ctx.accounts.receipt.to_account_info();
+        let claimed = if *receipt_info.owner == crate::ID {
+            let receipt = ClaimReceipt::try_deserialize(&mut &receipt_info.try_borrow_data()?[..])?;
+            receipt.status != ReceiptStatus::Reserved
+        } else {
+            false
+        };
+
+        Ok(ClaimStatus {
+            period_index,
+            availability: grant.claim_availability(&claimer, now, claimed, Some(&proof)),
+            evaluated_at: now,
+        })
+    }
+
+    /// プログラムのバージョンとクライアント最小バージョンを返す（view、状態は変更しない）
+    /// SDK は起動時に simulate して互換性を確認する
+    pub fn get_version(_ctx: Context<GetVersion>) -> Result<ProgramVersion> {
+        Ok(ProgramVersion {
+            version: PROGRAM_VERSION,
+            min_client_version: MIN_CLIENT_VERSION,
+        })
+    }
+
+    /// PoP proof を claim せずに検証する（view、状態は変更しない）
+    /// - 直前の ed25519 命令の署名者・対象・entry_hash を claim と同じ手順で確認する
+    /// - 発行時刻の許容幅（POP_MAX_SKEW_SECONDS）は見ないため、過去の proof も検証できる
+    /// - 署名者は現在の PopConfig と比較する（signer を差し替える前の proof は InvalidPopSigner になる）
+    /// - v1 形式（audit_hash なし）は Grant.pop_require_audit_hash が無効な場合のみ検証できる
+    /// 他プログラムから CPI する場合も、ed25519 命令は呼び出し元のトップレベル命令の直前に置くこと
+    pub fn verify_pop_proof(
+        ctx: Context<VerifyPopProof>,
+        claimer: Pubkey,
+        period_index: u64,
+    ) -> Result<PopProofVerification> {
+        let (signer, message) = load_verified_pop_message(
+            &ctx.accounts.instructions_sysvar.to_account_info(),
+            &ctx.accounts.pop_config,
+            &ctx.accounts.grant,
+            claimer,
+            period_index,
+        )?;
+        let is_current_head = ctx
+            .accounts
+            .pop_state
+            .as_ref()
+            .is_some_and(|pop_state| pop_state.initialized && pop_state.last_global_hash == message.entry_hash);
+
+        Ok(PopProofVerification {
+            version: message.version,
+            signer,
+            entry_hash: message.entry_hash,
+            issued_at: message.issued_at,
+            is_current_head,
+            authorized_amount: message.authorized_amount,
+        })
+    }
+
+    /// 新しいクラスタへのデプロイ直後に、FeatureFlags と TreasuryConfig を1つの命令で作成する
+    /// （プログラムの upgrade authority 限定。どちらかが既にあれば失敗する）
+    /// 途中まで設定された状態のクラスタを作らないための命令で、個別の initialize_feature_flags /
+    /// upsert_treasury_config / set_treasury_fee_splits を順に実行した結果と同じになる。
+    /// 手順とデプロイ manifest の出力は tests/initialize_deployment.ts を使う
+    pub fn initialize_deployment(ctx: Context<InitializeDeployment>, params: DeploymentParams) -> Result<()> {
+        require!(params.admin != Pubkey::default(), ErrorCode::Unauthorized);
+        require!(
+            params.claim_fee_lamports == 0 || params.treasury != Pubkey::default(),
+            ErrorCode::InvalidTreasury
+        );
+        let flags = &mut ctx.accounts.feature_flags;
+        flags.admin = params.admin;
+        flags.enabled = params.enabled_features;
+        flags.bump = ctx.bumps.feature_flags;
+
+        let config = &mut ctx.accounts.treasury_config;
+        config.treasury = params.treasury;
+        config.claim_fee_lamports = params.claim_fee_lamports;
+        config.bump = ctx.bumps.treasury_config;
+        write_fee_splits(config, &params.fee_splits)?;
+
+        emit!(DeploymentInitialized {
+            schema_version: EVENT_SCHEMA_VERSION,
+            program_version: PROGRAM_VERSION,
+            upgrade_authority: ctx.accounts.upgrade_authority.key(),
+            admin: params.admin,
+            enabled_features: params.enabled_features,
+            treasury: params.treasury,
+            claim_fee_lamports: params.claim_fee_lamports,
+            fee_split_count: params.fee_splits.len() as u8,
+            initialized_at: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    /// PoP / allowlist のハッシュに使う domain をデプロイ単位で設定する（プログラムの upgrade authority 限定、1回のみ）
+    /// white-label のデプロイで、we-ne と衝突しない proof の domain を使うための命令。
+    /// 設定後に作成した Grant は作成時に domain を写し取り、"<domain>:pop:v1" / "<domain>:allowlist" などを使う。
+    /// 未設定のデプロイと、設定前に作成された Grant は DEFAULT_HASH_DOMAIN（"we-ne"）のまま。
+    /// - domain: 1〜MAX_HASH_DOMAIN_LEN バイトの [a-z0-9-]
+    /// - 既存の proof が検証できなくなるため、変更・削除はできない
+    pub fn initialize_hash_domain(ctx: Context<InitializeHashDomain>, domain: Vec<u8>) -> Result<()> {
+        let hash_domain = HashDomain::new(&domain)?;
+        let config = &mut ctx.accounts.hash_domain_config;
+        config.domain = hash_domain;
+        config.bump = ctx.bumps.hash_domain_config;
+        Ok(())
+    }
+
+    /// デプロイ単位の FeatureFlags を作成する（プログラムの upgrade authority 限定）
+    /// - admin: 以後 set_feature_flags を実行できる protocol admin
+    /// - 作成時点ではすべての機能が無効
+    pub fn initialize_feature_flags(
+        ctx: Context<InitializeFeatureFlags>,
+        admin: Pubkey,
+    ) -> Result<()> {
+        let flags = &mut ctx.accounts.feature_flags;
+        flags.admin = admin;
+        flags.enabled = 0;
+        flags.bump = ctx.bumps.feature_flags;
+        Ok(())
+    }
+
+    /// 有効な機能のビット集合を設定する（protocol admin 限定）
+    /// - 既に作成済みの Grant には影響しない。命令パスの実行可否のみを切り替える
+    pub fn set_feature_flags(ctx: Context<SetFeatureFlags>, enabled: u64) -> Result<()> {
+        ctx.accounts.feature_flags.enabled = enabled;
+        Ok(())
+    }
+
+    /// protocol admin を変更する（現 admin 限定）
+    pub fn set_feature_admin(ctx: Context<SetFeatureFlags>, new_admin: Pubkey) -> Result<()> {
+        ctx.accounts.feature_flags.admin = new_admin;
+        Ok(())
+    }
+
+    /// claim ごとの SOL 手数料と送金先 treasury を設定/更新（protocol admin 限定）
+    /// - claim_fee_lamports が 0 の場合は手数料なし
+    /// - トークン建ての platform fee とは独立（運営者独自トークンの Grant でも収益化できる）
+    pub fn upsert_treasury_config(
+        ctx: Context<UpsertTreasuryConfig>,
+        treasury: Pubkey,
+        claim_fee_lamports: u64,
+    ) -> Result<()> {
+        require!(
+            claim_fee_lamports == 0 || treasury != Pubkey::default(),
+            ErrorCode::InvalidTreasury
+        );
+        let config = &mut ctx.accounts.treasury_config;
+        config.treasury = treasury;
+        config.claim_fee_lamports = claim_fee_lamports;
+        config.bump = ctx.bumps.treasury_config;
+        Ok(())
+    }
+
+    /// claim 手数料の分配先を設定する（protocol admin 限定、空で分配なし）
+    /// - splits の bps 合計は 10_000 以下。残りは treasury が受け取る
+    /// - claim では受取先を splits と同じ順で remaining_accounts（writable）に渡す
+    pub fn set_treasury_fee_splits(ctx: Context<SetTreasuryFeeSplits>, splits: Vec<FeeSplit>) -> Result<()> {
+        write_fee_splits(&mut ctx.accounts.treasury_config, &splits)
+    }
+
+    /// テスト用の現在時刻を設定する（test-clock feature 付きのビルドのみ。誰でも実行可能）
+    /// - 以後、ClockOverride を remaining_accounts の末尾に渡した命令は unix_timestamp を現在時刻として使う
+    /// - 0 で上書きを解除する
+    #[cfg(feature = "test-clock")]
+    pub fn set_clock_override(ctx: Context<SetClockOverride>, unix_timestamp: i64) -> Result<()> {
+        let clock_override = &mut ctx.accounts.clock_override;
+        clock_override.unix_timestamp = unix_timestamp;
+        clock_override.bump = ctx.bumps.clock_override;
+        Ok(())
+    }
+}
+
+// ===== Accounts =====
+
+#[derive(Accounts)]
+#[instruction(grant_id: u64)]
+pub struct CreateGrant<'info> {
+    /// init: 同一 PDA への再呼び出しは Anchor がエラーとする（隠れ更新を防ぐ）。
+    /// パラメータ変更は update_grant 命令を使うこと。
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Grant::INIT_SPACE,
+        seeds = [b"grant", authority.key().as_ref(), mint.key().as_ref(), &grant_id.to_le_bytes()],
+        bump
+    )]
+    pub grant: Account<'info, Grant>,
+
+    pub mint: Account<'info, Mint>,
+
+    /// Program-owned vault (TokenAccount). Authority is the grant PDA.
+    #[account(
+        init,
+        payer = authority,
+        token::mint = mint,
+        token::authority = grant,
+        seeds = [b"vault", grant.key().as_ref()],
+        bump,
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+
+    /// 渡された場合は依存 Grant 数（active_grants）に加算する（末尾の optional アカウント）
+    #[account(
+        mut,
+        seeds = [b"pop-config", authority.key().as_ref()],
+        bump = pop_config.bump
+    )]
+    pub pop_config: Option<Account<'info, PopConfig>>,
+
+    /// CHECK: 任意の recovery guardian（鍵のみ記録する）
+    pub recovery: Option<UncheckedAccount<'info>>,
+
+    /// initialize_hash_domain 済みのデプロイでは渡す（Grant が domain を写し取る。未指定は DEFAULT_HASH_DOMAIN）
+    #[account(seeds = [b"hash-domain"], bump = hash_domain_config.bump)]
+    pub hash_domain_config: Option<Account<'info, HashDomainConfig>>,
+}
+
+#[derive(Accounts)]
+#[instruction(template_id: u64)]
+pub struct UpsertGrantTemplate<'info> {
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + GrantTemplate::INIT_SPACE,
+        seeds = [b"grant-template", authority.key().as_ref(), &template_id.to_le_bytes()],
+        bump
+    )]
+    pub template: Account<'info, GrantTemplate>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(grant_id: u64)]
+pub struct CreateFromTemplate<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Grant::INIT_SPACE,
+        seeds = [b"grant", authority.key().as_ref(), mint.key().as_ref(), &grant_id.to_le_bytes()],
+        bump
+    )]
+    pub grant: Account<'info, Grant>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = authority,
+        token::mint = mint,
+        token::authority = grant,
+        seeds = [b"vault", grant.key().as_ref()],
+        bump,
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(
+        has_one = authority,
+        seeds = [b"grant-template", authority.key().as_ref(), &template.template_id.to_le_bytes()],
+        bump = template.bump
+    )]
+    pub template: Account<'info, GrantTemplate>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(seeds = [b"feature-flags"], bump = feature_flags.bump)]
+    pub feature_flags: Account<'info, FeatureFlags>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+
+    /// template.require_pop_config の場合は必須。渡された場合は active_grants に加算する
+    #[account(
+        mut,
+        seeds = [b"pop-config", authority.key().as_ref()],
+        bump = pop_config.bump
+    )]
+    pub pop_config: Option<Account<'info, PopConfig>>,
+
+    /// initialize_hash_domain 済みのデプロイでは渡す（Grant が domain を写し取る。未指定は DEFAULT_HASH_DOMAIN）
+    #[account(seeds = [b"hash-domain"], bump = hash_domain_config.bump)]
+    pub hash_domain_config: Option<Account<'info, HashDomainConfig>>,
+}
+
+#[derive(Accounts)]
+#[instruction(grant_id: u64)]
+pub struct CreateChildGrant<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        has_one = mint @ ErrorCode::MintMismatch,
+        seeds = [b"grant", parent.seed_authority.as_ref(), parent.seed_mint.as_ref(), &parent.grant_id.to_le_bytes()],
+        bump = parent.bump
+    )]
+    pub parent: Box<Account<'info, Grant>>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Grant::INIT_SPACE,
+        seeds = [b"grant", authority.key().as_ref(), mint.key().as_ref(), &grant_id.to_le_bytes()],
+        bump
+    )]
+    pub grant: Box<Account<'info, Grant>>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = authority,
+        token::mint = mint,
+        token::authority = grant,
+        seeds = [b"vault", grant.key().as_ref()],
+        bump,
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+
+    /// 渡された場合は active_grants に加算する
+    #[account(
+        mut,
+        seeds = [b"pop-config", authority.key().as_ref()],
+        bump = pop_config.bump
+    )]
+    pub pop_config: Option<Account<'info, PopConfig>>,
+}
+
+#[derive(Accounts)]
+pub struct RollUpChildStats<'info> {
+    #[account(
+        mut,
+        seeds = [b"grant", parent.seed_authority.as_ref(), parent.seed_mint.as_ref(), &parent.grant_id.to_le_bytes()],
+        bump = parent.bump
+    )]
+    pub parent: Box<Account<'info, Grant>>,
+
+    #[account(
+        mut,
+        has_one = parent @ ErrorCode::InvalidParentGrant,
+        seeds = [b"grant", child.seed_authority.as_ref(), child.seed_mint.as_ref(), &child.grant_id.to_le_bytes()],
+        bump = child.bump
+    )]
+    pub child: Box<Account<'info, Grant>>,
+}
+
+#[derive(Accounts)]
+#[instruction(platform_program: Pubkey)]
+pub struct ApprovePlatform<'info> {
+    #[account(
+        init_if_needed,
+        payer = organization,
+        space = 8 + PlatformApproval::INIT_SPACE,
+        seeds = [b"platform", organization.key().as_ref(), platform_program.as_ref()],
+        bump
+    )]
+    pub approval: Account<'info, PlatformApproval>,
+
+    #[account(mut)]
+    pub organization: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(grant_id: u64)]
+pub struct CreateGrantViaPlatform<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + Grant::INIT_SPACE,
+        seeds = [b"grant", organization.key().as_ref(), mint.key().as_ref(), &grant_id.to_le_bytes()],
+        bump
+    )]
+    pub grant: Account<'info, Grant>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = payer,
+        token::mint = mint,
+        token::authority = grant,
+        seeds = [b"vault", grant.key().as_ref()],
+        bump,
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(
+        has_one = organization,
+        has_one = platform_signer,
+        seeds = [b"platform", organization.key().as_ref(), approval.platform_program.as_ref()],
+        bump = approval.bump
+    )]
+    pub approval: Account<'info, PlatformApproval>,
+
+    /// platform_program の PDA（CPI でのみ署名可能）
+    pub platform_signer: Signer<'info>,
+
+    /// Grant の authority となる組織（署名不要、approve_platform で事前に委任済み）
+    pub organization: SystemAccount<'info>,
+
+    /// platform 手数料の受取口座
+    #[account(constraint = platform_fee_account.mint == mint.key() @ ErrorCode::MintMismatch)]
+    pub platform_fee_account: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(seeds = [b"feature-flags"], bump = feature_flags.bump)]
+    pub feature_flags: Account<'info, FeatureFlags>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+
+    /// initialize_hash_domain 済みのデプロイでは渡す（Grant が domain を写し取る。未指定は DEFAULT_HASH_DOMAIN）
+    #[account(seeds = [b"hash-domain"], bump = hash_domain_config.bump)]
+    pub hash_domain_config: Option<Account<'info, HashDomainConfig>>,
+
+    /// organization の PopConfig。渡された場合は active_grants に加算する
+    #[account(
+        mut,
+        seeds = [b"pop-config", organization.key().as_ref()],
+        bump = pop_config.bump
+    )]
+    pub pop_config: Option<Account<'info, PopConfig>>,
+}
+
+#[derive(Accounts)]
+pub struct SetPlatformFeeAccount<'info> {
+    #[account(
+        mut,
+        has_one = co_admin,
+        seeds = [b"grant", grant.seed_authority.as_ref(), grant.seed_mint.as_ref(), &grant.grant_id.to_le_bytes()],
+        bump = grant.bump
+    )]
+    pub grant: Account<'info, Grant>,
+
+    pub co_admin: Signer<'info>,
+
+    #[account(constraint = platform_fee_account.mint == grant.mint @ ErrorCode::MintMismatch)]
+    pub platform_fee_account: Account<'info, TokenAccount>,
+}
+
+#[derive(Accounts)]
+pub struct ReserveReceipts<'info> {
+    #[account(
+        mut,
+        seeds = [b"grant", grant.seed_authority.as_ref(), grant.seed_mint.as_ref(), &grant.grant_id.to_le_bytes()],
+        bump = grant.bump
+    )]
+    pub grant: Account<'info, Grant>,
+
+    /// grant.authority または KeeperConfig.keepers に登録された keeper（rent を支払う）
+    #[account(mut)]
+    pub reserver: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    /// keeper が実行する場合のみ必要
+    #[account(
+        has_one = grant,
+        seeds = [b"keeper-config", grant.key().as_ref()],
+        bump = keeper_config.bump
+    )]
+    pub keeper_config: Option<Account<'info, KeeperConfig>>,
+}
+
+#[derive(Accounts)]
+pub struct ReleaseReceiptReservations<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [b"grant", grant.seed_authority.as_ref(), grant.seed_mint.as_ref(), &grant.grant_id.to_le_bytes()],
+        bump = grant.bump
+    )]
+    pub grant: Account<'info, Grant>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CreateGrantsBatch<'info> {
+    pub mint: Account<'info, Mint>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(seeds = [b"feature-flags"], bump = feature_flags.bump)]
+    pub feature_flags: Account<'info, FeatureFlags>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+
+    /// 渡された場合は作成件数を依存 Grant 数（active_grants）に加算する
+    #[account(
+        mut,
+        seeds = [b"pop-config", authority.key().as_ref()],
+        bump = pop_config.bump
+    )]
+    pub pop_config: Option<Account<'info, PopConfig>>,
+
+    /// initialize_hash_domain 済みのデプロイでは渡す（Grant が domain を写し取る。未指定は DEFAULT_HASH_DOMAIN）
+    #[account(seeds = [b"hash-domain"], bump = hash_domain_config.bump)]
+    pub hash_domain_config: Option<Account<'info, HashDomainConfig>>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateGrant<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [b"grant", grant.seed_authority.as_ref(), grant.seed_mint.as_ref(), &grant.grant_id.to_le_bytes()],
+        bump = grant.bump
+    )]
+    pub grant: Account<'info, Grant>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetRecovery<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [b"grant", grant.seed_authority.as_ref(), grant.seed_mint.as_ref(), &grant.grant_id.to_le_bytes()],
+        bump = grant.bump
+    )]
+    pub grant: Account<'info, Grant>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RecoveryAction<'info> {
+    #[account(
+        mut,
+        has_one = recovery,
+        seeds = [b"grant", grant.seed_authority.as_ref(), grant.seed_mint.as_ref(), &grant.grant_id.to_le_bytes()],
+        bump = grant.bump
+    )]
+    pub grant: Account<'info, Grant>,
+
+    pub recovery: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetSuccessor<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [b"grant", grant.seed_authority.as_ref(), grant.seed_mint.as_ref(), &grant.grant_id.to_le_bytes()],
+        bump = grant.bump
+    )]
+    pub grant: Account<'info, Grant>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SuccessionAction<'info> {
+    #[account(
+        mut,
+        has_one = successor,
+        seeds = [b"grant", grant.seed_authority.as_ref(), grant.seed_mint.as_ref(), &grant.grant_id.to_le_bytes()],
+        bump = grant.bump
+    )]
+    pub grant: Account<'info, Grant>,
+
+    pub successor: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CancelRecovery<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [b"grant", grant.seed_authority.as_ref(), grant.seed_mint.as_ref(), &grant.grant_id.to_le_bytes()],
+        bump = grant.bump
+    )]
+    pub grant: Account<'info, Grant>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InitiateMintMigration<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [b"grant", grant.seed_authority.as_ref(), grant.seed_mint.as_ref(), &grant.grant_id.to_le_bytes()],
+        bump = grant.bump
+    )]
+    pub grant: Account<'info, Grant>,
+
+    /// 切り替え先の mint（SPL Token のみ）
+    pub new_mint: Account<'info, Mint>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CancelMintMigration<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [b"grant", grant.seed_authority.as_ref(), grant.seed_mint.as_ref(), &grant.grant_id.to_le_bytes()],
+        bump = grant.bump
+    )]
+    pub grant: Account<'info, Grant>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CompleteMintMigration<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [b"grant", grant.seed_authority.as_ref(), grant.seed_mint.as_ref(), &grant.grant_id.to_le_bytes()],
+        bump = grant.bump
+    )]
+    pub grant: Account<'info, Grant>,
+
+    #[account(address = grant.mint @ ErrorCode::MintMismatch)]
+    pub old_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        address = grant.vault @ ErrorCode::VaultMismatch
+    )]
+    pub old_vault: Account<'info, TokenAccount>,
+
+    /// 旧 vault 残高の返金先（authorityの旧 mint ATA）
+    #[account(
+        mut,
+        constraint = authority_ata.mint == old_mint.key() @ ErrorCode::MintMismatch,
+        constraint = authority_ata.owner == authority.key() @ ErrorCode::Unauthorized
+    )]
+    pub authority_ata: Account<'info, TokenAccount>,
+
+    #[account(address = grant.mint_migration_pending_mint @ ErrorCode::MintMismatch)]
+    pub new_mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = authority,
+        token::mint = new_mint,
+        token::authority = grant,
+        seeds = [b"vault", grant.key().as_ref(), new_mint.key().as_ref()],
+        bump,
+    )]
+    pub new_vault: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct RotateVault<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        has_one = mint @ ErrorCode::MintMismatch,
+        seeds = [b"grant", grant.seed_authority.as_ref(), grant.seed_mint.as_ref(), &grant.grant_id.to_le_bytes()],
+        bump = grant.bump
+    )]
+    pub grant: Account<'info, Grant>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        address = grant.vault @ ErrorCode::VaultMismatch
+    )]
+    pub old_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = authority,
+        token::mint = mint,
+        token::authority = grant,
+        seeds = [b"vault", grant.key().as_ref(), &grant.vault_nonce.wrapping_add(1).to_le_bytes()],
+        bump,
+    )]
+    pub new_vault: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct SetReviewer<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [b"grant", grant.seed_authority.as_ref(), grant.seed_mint.as_ref(), &grant.grant_id.to_le_bytes()],
+        bump = grant.bump
+    )]
+    pub grant: Account<'info, Grant>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ActivateGrant<'info> {
+    #[account(
+        mut,
+        has_one = reviewer,
+        seeds = [b"grant", grant.seed_authority.as_ref(), grant.seed_mint.as_ref(), &grant.grant_id.to_le_bytes()],
+        bump = grant.bump
+    )]
+    pub grant: Account<'info, Grant>,
+
+    pub reviewer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct FundGrant<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        has_one = mint @ ErrorCode::MintMismatch,
+        seeds = [b"grant", grant.seed_authority.as_ref(), grant.seed_mint.as_ref(), &grant.grant_id.to_le_bytes()],
+        bump = grant.bump
+    )]
+    pub grant: Account<'info, Grant>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        address = grant.vault @ ErrorCode::VaultMismatch
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    /// 入金元（ATAなど）
+    #[account(
+        mut,
+        constraint = from_ata.mint == mint.key() @ ErrorCode::MintMismatch,
+        constraint = from_ata.owner == funder.key() @ ErrorCode::Unauthorized
+    )]
+    pub from_ata: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub funder: Signer<'info>,
+
+    /// Grant作成者（has_oneのため）
+    pub authority: SystemAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+
+    /// 期間別 earmark が有効な Grant の場合のみ必要（末尾の optional アカウント）
+    #[account(
+        mut,
+        seeds = [b"escrow-schedule", grant.key().as_ref()],
+        bump = escrow_schedule.bump
+    )]
+    pub escrow_schedule: Option<Account<'info, EscrowSchedule>>,
+
+    /// 任意: 第三者の入金に上乗せするマッチングプール
+    #[account(
+        mut,
+        has_one = grant,
+        has_one = match_vault @ ErrorCode::MatchVaultMismatch,
+        seeds = [b"match-pool", grant.key().as_ref()],
+        bump = match_pool.bump
+    )]
+    pub match_pool: Option<Box<Account<'info, MatchPool>>>,
+
+    #[account(mut)]
+    pub match_vault: Option<Account<'info, TokenAccount>>,
+
+    /// BudgetForecast がある Grant の場合のみ必要（この命令の結果で再計算する）
+    #[account(
+        mut,
+        has_one = grant,
+        seeds = [b"budget-forecast", grant.key().as_ref()],
+        bump = budget_forecast.bump
+    )]
+    pub budget_forecast: Option<Account<'info, BudgetForecast>>,
+}
+
+#[derive(Accounts)]
+#[instruction(operator: Pubkey)]
+pub struct UpsertFundingBudget<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [b"grant", grant.seed_authority.as_ref(), grant.seed_mint.as_ref(), &grant.grant_id.to_le_bytes()],
+        bump = grant.bump
+    )]
+    pub grant: Account<'info, Grant>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + FundingBudget::INIT_SPACE,
+        seeds = [b"funding-budget", grant.key().as_ref(), operator.as_ref()],
+        bump
+    )]
+    pub funding_budget: Account<'info, FundingBudget>,
+
+    /// 入金元（authority 所有。FundingBudget を delegate に設定する）
+    #[account(
+        mut,
+        constraint = source.mint == grant.mint @ ErrorCode::MintMismatch,
+        constraint = source.owner == authority.key() @ ErrorCode::Unauthorized
+    )]
+    pub source: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FundGrantFromBudget<'info> {
+    #[account(
+        mut,
+        has_one = mint @ ErrorCode::MintMismatch,
+        seeds = [b"grant", grant.seed_authority.as_ref(), grant.seed_mint.as_ref(), &grant.grant_id.to_le_bytes()],
+        bump = grant.bump
+    )]
+    pub grant: Account<'info, Grant>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        address = grant.vault @ ErrorCode::VaultMismatch
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        has_one = grant,
+        has_one = operator @ ErrorCode::Unauthorized,
+        has_one = source @ ErrorCode::FundingSourceMismatch,
+        seeds = [b"funding-budget", grant.key().as_ref(), operator.key().as_ref()],
+        bump = funding_budget.bump
+    )]
+    pub funding_budget: Account<'info, FundingBudget>,
+
+    #[account(mut)]
+    pub source: Account<'info, TokenAccount>,
+
+    pub operator: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+
+    /// 期間別 earmark が有効な Grant の場合のみ必要（末尾の optional アカウント）
+    #[account(
+        mut,
+        seeds = [b"escrow-schedule", grant.key().as_ref()],
+        bump = escrow_schedule.bump
+    )]
+    pub escrow_schedule: Option<Account<'info, EscrowSchedule>>,
+
+    /// BudgetForecast がある Grant の場合のみ必要（この命令の結果で再計算する）
+    #[account(
+        mut,
+        has_one = grant,
+        seeds = [b"budget-forecast", grant.key().as_ref()],
+        bump = budget_forecast.bump
+    )]
+    pub budget_forecast: Option<Account<'info, BudgetForecast>>,
+}
+
+#[derive(Accounts)]
+pub struct UpsertSwapConfig<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [b"grant", grant.seed_authority.as_ref(), grant.seed_mint.as_ref(), &grant.grant_id.to_le_bytes()],
+        bump = grant.bump
+    )]
+    pub grant: Account<'info, Grant>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + SwapConfig::INIT_SPACE,
+        seeds = [b"swap-config", grant.key().as_ref(), input_mint.key().as_ref()],
+        bump
+    )]
+    pub swap_config: Account<'info, SwapConfig>,
+
+    pub input_mint: Account<'info, Mint>,
+
+    /// CHECK: 実行可能なプログラムであることを命令内で確認し、アドレスだけを記録する
+    pub amm_program: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CloseSwapConfig<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [b"grant", grant.seed_authority.as_ref(), grant.seed_mint.as_ref(), &grant.grant_id.to_le_bytes()],
+        bump = grant.bump
+    )]
+    pub grant: Account<'info, Grant>,
+
+    #[account(
+        mut,
+        has_one = grant,
+        close = authority,
+        seeds = [b"swap-config", grant.key().as_ref(), swap_config.input_mint.as_ref()],
+        bump = swap_config.bump
+    )]
+    pub swap_config: Account<'info, SwapConfig>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct FundWithSwap<'info> {
+    #[account(
+        mut,
+        has_one = mint @ ErrorCode::MintMismatch,
+        seeds = [b"grant", grant.seed_authority.as_ref(), grant.seed_mint.as_ref(), &grant.grant_id.to_le_bytes()],
+        bump = grant.bump
+    )]
+    pub grant: Account<'info, Grant>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        address = grant.vault @ ErrorCode::VaultMismatch
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(
+        has_one = grant,
+        seeds = [b"swap-config", grant.key().as_ref(), swap_config.input_mint.as_ref()],
+        bump = swap_config.bump
+    )]
+    pub swap_config: Account<'info, SwapConfig>,
+
+    /// 入金元（input_mint の funder 所有の token account）
+    #[account(
+        mut,
+        constraint = from_ata.mint == swap_config.input_mint @ ErrorCode::MintMismatch,
+        constraint = from_ata.owner == funder.key() @ ErrorCode::Unauthorized
+    )]
+    pub from_ata: Account<'info, TokenAccount>,
+
+    pub funder: Signer<'info>,
+
+    /// CHECK: SwapConfig に登録された AMM であることをアドレスで確認する
+    #[account(address = swap_config.amm_program @ ErrorCode::InvalidSwapConfig)]
+    pub amm_program: UncheckedAccount<'info>,
+
+    /// 期間別 earmark が有効な Grant の場合のみ必要（末尾の optional アカウント）
+    #[account(
+        mut,
+        seeds = [b"escrow-schedule", grant.key().as_ref()],
+        bump = escrow_schedule.bump
+    )]
+    pub escrow_schedule: Option<Account<'info, EscrowSchedule>>,
+
+    /// BudgetForecast がある Grant の場合のみ必要（この命令の結果で再計算する）
+    #[account(
+        mut,
+        has_one = grant,
+        seeds = [b"budget-forecast", grant.key().as_ref()],
+        bump = budget_forecast.bump
+    )]
+    pub budget_forecast: Option<Account<'info, BudgetForecast>>,
+}
+
+#[derive(Accounts)]
+pub struct CloseFundingBudget<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [b"grant", grant.seed_authority.as_ref(), grant.seed_mint.as_ref(), &grant.grant_id.to_le_bytes()],
+        bump = grant.bump
+    )]
+    pub grant: Account<'info, Grant>,
+
+    #[account(
+        mut,
+        has_one = grant,
+        has_one = source @ ErrorCode::FundingSourceMismatch,
+        close = authority,
+        seeds = [b"funding-budget", grant.key().as_ref(), funding_budget.operator.as_ref()],
+        bump = funding_budget.bump
+    )]
+    pub funding_budget: Account<'info, FundingBudget>,
+
+    #[account(mut)]
+    pub source: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+#[instruction(officer: Pubkey)]
+pub struct UpsertProgramOfficer<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [b"grant", grant.seed_authority.as_ref(), grant.seed_mint.as_ref(), &grant.grant_id.to_le_bytes()],
+        bump = grant.bump
+    )]
+    pub grant: Account<'info, Grant>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + ProgramOfficer::INIT_SPACE,
+        seeds = [b"officer", grant.key().as_ref(), officer.as_ref()],
+        bump
+    )]
+    pub program_officer: Account<'info, ProgramOfficer>,
+
+    /// officer_withdraw の引き出し先
+    #[account(constraint = withdrawal_destination.mint == grant.mint @ ErrorCode::MintMismatch)]
+    pub withdrawal_destination: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveProgramOfficer<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [b"grant", grant.seed_authority.as_ref(), grant.seed_mint.as_ref(), &grant.grant_id.to_le_bytes()],
+        bump = grant.bump
+    )]
+    pub grant: Account<'info, Grant>,
+
+    #[account(
+        mut,
+        has_one = grant,
+        close = authority,
+        seeds = [b"officer", grant.key().as_ref(), program_officer.officer.as_ref()],
+        bump = program_officer.bump
+    )]
+    pub program_officer: Account<'info, ProgramOfficer>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct OfficerWithdraw<'info> {
+    #[account(
+        mut,
+        seeds = [b"grant", grant.seed_authority.as_ref(), grant.seed_mint.as_ref(), &grant.grant_id.to_le_bytes()],
+        bump = grant.bump
+    )]
+    pub grant: Account<'info, Grant>,
+
+    #[account(
+        mut,
+        address = grant.vault @ ErrorCode::VaultMismatch
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        has_one = grant,
+        has_one = officer @ ErrorCode::Unauthorized,
+        has_one = withdrawal_destination @ ErrorCode::OfficerDestinationMismatch,
+        seeds = [b"officer", grant.key().as_ref(), officer.key().as_ref()],
+        bump = program_officer.bump
+    )]
+    pub program_officer: Account<'info, ProgramOfficer>,
+
+    #[account(
+        mut,
+        constraint = withdrawal_destination.mint == grant.mint @ ErrorCode::MintMismatch
+    )]
+    pub withdrawal_destination: Account<'info, TokenAccount>,
+
+    pub officer: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct OfficerSetKeeperBounty<'info> {
+    #[account(
+        mut,
+        seeds = [b"grant", grant.seed_authority.as_ref(), grant.seed_mint.as_ref(), &grant.grant_id.to_le_bytes()],
+        bump = grant.bump
+    )]
+    pub grant: Account<'info, Grant>,
+
+    #[account(
+        has_one = grant,
+        has_one = officer @ ErrorCode::Unauthorized,
+        seeds = [b"officer", grant.key().as_ref(), officer.key().as_ref()],
+        bump = program_officer.bump
+    )]
+    pub program_officer: Account<'info, ProgramOfficer>,
+
+    #[account(
+        mut,
+        has_one = grant,
+        seeds = [b"keeper-config", grant.key().as_ref()],
+        bump = keeper_config.bump
+    )]
+    pub keeper_config: Account<'info, KeeperConfig>,
+
+    pub officer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CreateMatchPool<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        has_one = mint,
+        seeds = [b"grant", grant.seed_authority.as_ref(), grant.seed_mint.as_ref(), &grant.grant_id.to_le_bytes()],
+        bump = grant.bump
+    )]
+    pub grant: Account<'info, Grant>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = sponsor,
+        space = 8 + MatchPool::INIT_SPACE,
+        seeds = [b"match-pool", grant.key().as_ref()],
+        bump
+    )]
+    pub match_pool: Account<'info, MatchPool>,
+
+    /// マッチング原資の vault。authority は Grant PDA（fund_grant で vault へ移す）
+    #[account(
+        init,
+        payer = sponsor,
+        token::mint = mint,
+        token::authority = grant,
+        seeds = [b"match-vault", grant.key().as_ref()],
+        bump,
+    )]
+    pub match_vault: Account<'info, TokenAccount>,
+
+    pub authority: Signer<'info>,
+
+    #[account(mut)]
+    pub sponsor: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct FundMatchPool<'info> {
+    #[account(
+        mut,
+        has_one = sponsor,
+        has_one = match_vault @ ErrorCode::MatchVaultMismatch,
+        seeds = [b"match-pool", match_pool.grant.as_ref()],
+        bump = match_pool.bump
+    )]
+    pub match_pool: Account<'info, MatchPool>,
+
+    #[account(mut)]
+    pub match_vault: Account<'info, TokenAccount>,
+
+    #[account(address = match_vault.mint @ ErrorCode::MintMismatch)]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        constraint = sponsor_ata.mint == mint.key() @ ErrorCode::MintMismatch,
+        constraint = sponsor_ata.owner == sponsor.key() @ ErrorCode::Unauthorized
+    )]
+    pub sponsor_ata: Account<'info, TokenAccount>,
+
+    pub sponsor: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct CloseMatchPool<'info> {
+    #[account(
+        mut,
+        seeds = [b"grant", grant.seed_authority.as_ref(), grant.seed_mint.as_ref(), &grant.grant_id.to_le_bytes()],
+        bump = grant.bump
+    )]
+    pub grant: Account<'info, Grant>,
+
+    #[account(
+        mut,
+        has_one = grant,
+        has_one = sponsor,
+        has_one = match_vault @ ErrorCode::MatchVaultMismatch,
+        close = sponsor,
+        seeds = [b"match-pool", grant.key().as_ref()],
+        bump = match_pool.bump
+    )]
+    pub match_pool: Account<'info, MatchPool>,
+
+    #[account(mut)]
+    pub match_vault: Account<'info, TokenAccount>,
+
+    /// 未使用原資の返金先（sponsor の ATA など）
+    #[account(
+        mut,
+        constraint = sponsor_ata.mint == grant.mint @ ErrorCode::MintMismatch,
+        constraint = sponsor_ata.owner == sponsor.key() @ ErrorCode::Unauthorized
+    )]
+    pub sponsor_ata: Account<'info, TokenAccount>,
+
+    /// CHECK: rent の返金先（has_one で検証）
+    #[account(mut)]
+    pub sponsor: UncheckedAccount<'info>,
+
+    /// sponsor または grant.authority
+    #[account(
+        constraint = closer.key() == sponsor.key() || closer.key() == grant.authority @ ErrorCode::Unauthorized
+    )]
+    pub closer: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+#[instruction(period_index: u64)]
+pub struct ClaimGrant<'info> {
+    #[account(
+        mut,
+        seeds = [b"grant", grant.seed_authority.as_ref(), grant.seed_mint.as_ref(), &grant.grant_id.to_le_bytes()],
+        bump = grant.bump
+    )]
+    pub grant: Account<'info, Grant>,
+
+    /// vault の mint は作成時に grant.mint で固定されているため、claim では Mint アカウントを受け取らない
+    #[account(
+        mut,
+        address = grant.vault @ ErrorCode::VaultMismatch
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    /// 受給者（session_authorization を渡す場合は登録済みの session key、intent_claimer を渡す場合は relayer、
+    /// pending_claim を渡す場合は keeper）。rent を支払う
+    #[account(mut)]
+    pub claimer: Signer<'info>,
+
+    /// 受給先（ATAなど）。owner は受給者（session 経由の場合は SessionAuthorization.claimer、intent の場合は intent_claimer、
+    /// 予約実行の場合は PendingClaim.claimer）
+    #[account(
+        mut,
+        constraint = claimer_ata.mint == grant.mint @ ErrorCode::MintMismatch,
+        constraint = claimer_ata.owner == claim_beneficiary(&claimer, &session_authorization, &intent_claimer, &pending_claim) @ ErrorCode::Unauthorized
+    )]
+    pub claimer_ata: Account<'info, TokenAccount>,
+
+    /// 期間内1回の受給を保証するレシート（reserve_receipts で予約済みの場合はそれを使う）
+    /// 同一期間の二重 claim は execute_claim で ReceiptAlreadyClaimed になる
+    #[account(
+        init_if_needed,
+        payer = claimer,
+        space = 8 + ClaimReceipt::INIT_SPACE,
+        seeds = [
+            b"receipt",
+            grant.key().as_ref(),
+            claim_beneficiary(&claimer, &session_authorization, &intent_claimer, &pending_claim).as_ref(),
+            &period_index.to_le_bytes(),
+            seed_salt_seed(&grant.seed_salt),
+        ],
+        bump
+    )]
+    pub receipt: Account<'info, ClaimReceipt>,
+
+    #[account(
+        init_if_needed,
+        payer = claimer,
+        space = 8 + PopState::INIT_SPACE,
+        seeds = [b"pop-state", grant.key().as_ref(), seed_salt_seed(&grant.seed_salt)],
+        bump
+    )]
+    pub pop_state: Account<'info, PopState>,
+
+    #[account(
+        seeds = [b"pop-config", grant.authority.as_ref()],
+        bump = pop_config.bump,
+        constraint = pop_config.authority == grant.authority @ ErrorCode::InvalidPopConfigAuthority
+    )]
+    pub pop_config: Account<'info, PopConfig>,
+
+    /// CHECK: Instructions Sysvar account (required for Ed25519 proof verification)
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+
+    /// claimer ごとの受給累計（初回 claim 時に claimer 負担で作成）
+    #[account(
+        init_if_needed,
+        payer = claimer,
+        space = 8 + ClaimerProfile::INIT_SPACE,
+        seeds = [b"claimer-profile", grant.key().as_ref(), claim_beneficiary(&claimer, &session_authorization, &intent_claimer, &pending_claim).as_ref()],
+        bump
+    )]
+    pub claimer_profile: Box<Account<'info, ClaimerProfile>>,
+
+    /// 期間ごとの受給集計（期間の初回 claim 時に claimer 負担で作成）
+    #[account(
+        init_if_needed,
+        payer = claimer,
+        space = 8 + PeriodStats::INIT_SPACE,
+        seeds = [b"period-stats", grant.key().as_ref(), &period_index.to_le_bytes()],
+        bump
+    )]
+    pub period_stats: AccountLoader<'info, PeriodStats>,
+
+    /// CHECK: protocol の TreasuryConfig PDA（アドレスのみ検証。未初期化 = claim 手数料なし）
+    #[account(seeds = [b"treasury-config"], bump)]
+    pub treasury_config: UncheckedAccount<'info>,
+
+    /// 円建て Grant の場合のみ必要な固定レート表（末尾の optional アカウント）
+    #[account(
+        seeds = [b"rate-config", grant.authority.as_ref()],
+        bump = rate_config.bump,
+        constraint = rate_config.authority == grant.authority @ ErrorCode::Unauthorized
+    )]
+    pub rate_config: Option<Account<'info, RateConfig>>,
+
+    /// CHECK: gate 有効時のみ必要。所有者とレイアウトは require_gate_attestation で検証する
+    pub gate_attestation: Option<UncheckedAccount<'info>>,
+
+    /// platform 手数料が設定された Grant の場合のみ必要
+    #[account(
+        mut,
+        address = grant.platform_fee_account @ ErrorCode::PlatformFeeAccountMismatch
+    )]
+    pub platform_fee_account: Option<Account<'info, TokenAccount>>,
+
+    /// 連続受給・前回 claim 時刻の記録（ボーナス / claim 間隔制限の対象にする場合のみ。初回は claimer 負担で作成）
+    #[account(
+        init_if_needed,
+        payer = claimer,
+        space = 8 + ClaimStreak::INIT_SPACE,
+        seeds = [b"streak", grant.key().as_ref(), claim_beneficiary(&claimer, &session_authorization, &intent_claimer, &pending_claim).as_ref()],
+        bump
+    )]
+    pub claim_streak: Option<Box<Account<'info, ClaimStreak>>>,
+
+    /// 期間別 earmark が有効な Grant の場合のみ必要
+    #[account(
+        mut,
+        seeds = [b"escrow-schedule", grant.key().as_ref()],
+        bump = escrow_schedule.bump
+    )]
+    pub escrow_schedule: Option<Box<Account<'info, EscrowSchedule>>>,
+
+    /// CHECK: claim 手数料の送金先（TreasuryConfig.treasury と一致することを execute_claim で検証）
+    #[account(mut)]
+    pub treasury: Option<UncheckedAccount<'info>>,
+
+    /// relayer（gasless claim で手数料・rent とは別に SOL 手数料を立て替える署名者）。省略時は claimer が支払う
+    #[account(mut)]
+    pub fee_payer: Option<Signer<'info>>,
+
+    /// session key で claim する場合のみ必要（claimer = session key、受給者 = SessionAuthorization.claimer）
+    #[account(
+        has_one = grant,
+        seeds = [b"session", grant.key().as_ref(), session_authorization.claimer.as_ref()],
+        bump = session_authorization.bump
+    )]
+    pub session_authorization: Option<Box<Account<'info, SessionAuthorization>>>,
+
+    /// 任意: 受給者の StatementLog（渡した場合は今回の受給を記録する）
+    #[account(
+        mut,
+        seeds = [b"statement", grant.key().as_ref(), claim_beneficiary(&claimer, &session_authorization, &intent_claimer, &pending_claim).as_ref()],
+        bump = statement_log.load()?.bump
+    )]
+    pub statement_log: Option<AccountLoader<'info, StatementLog>>,
+
+    /// CHECK: claim intent で受給する場合の受給者（署名者ではない。ed25519 命令の intent 署名で検証する）
+    /// 渡した場合、claimer は relayer として rent を支払うだけで、受給者にはならない
+    pub intent_claimer: Option<UncheckedAccount<'info>>,
+
+    /// 予約された claim を keeper が実行する場合のみ必要（受給者 = PendingClaim.claimer）
+    /// 実行後に close し、rent は実行した keeper（claimer アカウント）が受け取る
+    #[account(
+        mut,
+        has_one = grant,
+        close = claimer,
+        seeds = [b"pending-claim", grant.key().as_ref(), pending_claim.claimer.as_ref(), &period_index.to_le_bytes()],
+        bump = pending_claim.bump
+    )]
+    pub pending_claim: Option<Box<Account<'info, PendingClaim>>>,
+
+    /// CHECK: 受給アテステーションが有効な Grant の場合のみ必要（grant.attestation_program と一致すること）
+    pub attestation_program: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: アテステーションの発行先（検証は attestation_program 側で行う）
+    #[account(mut)]
+    pub attestation_record: Option<UncheckedAccount<'info>>,
+
+    /// 受給資格の述語に token-holding がある場合のみ（受給者が保有する grant.holding_mint のトークンアカウント）
+    #[account(
+        constraint = holding_token_account.mint == grant.holding_mint @ ErrorCode::InvalidEligibilityAccount,
+        constraint = holding_token_account.owner == claim_beneficiary(&claimer, &session_authorization, &intent_claimer, &pending_claim) @ ErrorCode::InvalidEligibilityAccount
+    )]
+    pub holding_token_account: Option<Box<Account<'info, TokenAccount>>>,
+
+    /// 受給資格の述語に enrollment がある場合のみ（grant.enrollment_grant での受給者の ClaimerProfile）
+    #[account(
+        constraint = enrollment_profile.grant == grant.enrollment_grant @ ErrorCode::InvalidEligibilityAccount,
+        constraint = enrollment_profile.claimer == claim_beneficiary(&claimer, &session_authorization, &intent_claimer, &pending_claim) @ ErrorCode::InvalidEligibilityAccount
+    )]
+    pub enrollment_profile: Option<Box<Account<'info, ClaimerProfile>>>,
+
+    /// 凍結された claimer_ata を解凍する場合のみ（mint の freeze authority が Grant PDA の場合）
+    #[account(address = grant.mint @ ErrorCode::MintMismatch)]
+    pub mint: Option<Box<Account<'info, Mint>>>,
+
+    /// 受給者が opt_out_canonical_destination で ATA 以外の受給先を許可している場合のみ
+    #[account(
+        seeds = [b"destination-opt-out", grant.key().as_ref(), claim_beneficiary(&claimer, &session_authorization, &intent_claimer, &pending_claim).as_ref()],
+        bump = destination_opt_out.bump
+    )]
+    pub destination_opt_out: Option<Box<Account<'info, DestinationOptOut>>>,
+
+    /// BudgetForecast がある Grant の場合のみ必要（この命令の結果で再計算する）
+    #[account(
+        mut,
+        has_one = grant,
+        seeds = [b"budget-forecast", grant.key().as_ref()],
+        bump = budget_forecast.bump
+    )]
+    pub budget_forecast: Option<Box<Account<'info, BudgetForecast>>>,
+
+    /// 受給者が open_claim_index 済みの場合のみ必要（今回の期間を受給済みとして記録する）
+    #[account(
+        mut,
+        seeds = [b"claim-index", grant.key().as_ref(), claim_beneficiary(&claimer, &session_authorization, &intent_claimer, &pending_claim).as_ref()],
+        bump = claim_index.load()?.bump
+    )]
+    pub claim_index: Option<AccountLoader<'info, ClaimIndex>>,
+
+    /// CHECK: ステーク期間の倍率を受ける場合のみ（grant.stake_program が所有するステーク記録。execute_claim で検証）
+    pub stake_record: Option<UncheckedAccount<'info>>,
+
+    /// 受給者宛ての ClaimerMemo がある場合のみ（未配信なら ClaimerMemoDelivered で配信する）
+    #[account(
+        mut,
+        seeds = [b"claimer-memo", grant.key().as_ref(), claim_beneficiary(&claimer, &session_authorization, &intent_claimer, &pending_claim).as_ref()],
+        bump = claimer_memo.bump
+    )]
+    pub claimer_memo: Option<Box<Account<'info, ClaimerMemo>>>,
+}
+
+impl ClaimGrant<'_> {
+    /// 受給者（session key で claim する場合は SessionAuthorization.claimer、claim intent の場合は intent_claimer、
+    /// 予約実行の場合は PendingClaim.claimer）
+    pub fn beneficiary(&self) -> Pubkey {
+        claim_beneficiary(
+            &self.claimer,
+            &self.session_authorization,
+            &self.intent_claimer,
+            &self.pending_claim,
+        )
+    }
+}
+
+#[derive(Accounts)]
+pub struct AuthorizeSession<'info> {
+    #[account(
+        seeds = [b"grant", grant.seed_authority.as_ref(), grant.seed_mint.as_ref(), &grant.grant_id.to_le_bytes()],
+        bump = grant.bump
+    )]
+    pub grant: Account<'info, Grant>,
+
+    #[account(
+        init_if_needed,
+        payer = claimer,
+        space = 8 + SessionAuthorization::INIT_SPACE,
+        seeds = [b"session", grant.key().as_ref(), claimer.key().as_ref()],
+        bump
+    )]
+    pub session_authorization: Account<'info, SessionAuthorization>,
+
+    #[account(mut)]
+    pub claimer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeSession<'info> {
+    #[account(
+        mut,
+        has_one = claimer,
+        close = claimer,
+        seeds = [b"session", session_authorization.grant.as_ref(), claimer.key().as_ref()],
+        bump = session_authorization.bump
+    )]
+    pub session_authorization: Account<'info, SessionAuthorization>,
+
+    #[account(mut)]
+    pub claimer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct OptOutCanonicalDestination<'info> {
+    #[account(
+        seeds = [b"grant", grant.seed_authority.as_ref(), grant.seed_mint.as_ref(), &grant.grant_id.to_le_bytes()],
+        bump = grant.bump
+    )]
+    pub grant: Account<'info, Grant>,
+
+    #[account(
+        init,
+        payer = claimer,
+        space = 8 + DestinationOptOut::INIT_SPACE,
+        seeds = [b"destination-opt-out", grant.key().as_ref(), claimer.key().as_ref()],
+        bump
+    )]
+    pub destination_opt_out: Account<'info, DestinationOptOut>,
+
+    #[account(mut)]
+    pub claimer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeDestinationOptOut<'info> {
+    #[account(
+        mut,
+        has_one = claimer,
+        close = claimer,
+        seeds = [b"destination-opt-out", destination_opt_out.grant.as_ref(), claimer.key().as_ref()],
+        bump = destination_opt_out.bump
+    )]
+    pub destination_opt_out: Account<'info, DestinationOptOut>,
+
+    #[account(mut)]
+    pub claimer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetNotificationPreference<'info> {
+    #[account(
+        init_if_needed,
+        payer = claimer,
+        space = 8 + NotificationPreference::INIT_SPACE,
+        seeds = [b"notification-preference", claimer.key().as_ref()],
+        bump
+    )]
+    pub notification_preference: Account<'info, NotificationPreference>,
+
+    #[account(mut)]
+    pub claimer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClearNotificationPreference<'info> {
+    #[account(
+        mut,
+        has_one = claimer,
+        close = claimer,
+        seeds = [b"notification-preference", claimer.key().as_ref()],
+        bump = notification_preference.bump
+    )]
+    pub notification_preference: Account<'info, NotificationPreference>,
+
+    #[account(mut)]
+    pub claimer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(claimer: Pubkey)]
+pub struct AttachClaimerMemo<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [b"grant", grant.seed_authority.as_ref(), grant.seed_mint.as_ref(), &grant.grant_id.to_le_bytes()],
+        bump = grant.bump
+    )]
+    pub grant: Account<'info, Grant>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + ClaimerMemo::INIT_SPACE,
+        seeds = [b"claimer-memo", grant.key().as_ref(), claimer.as_ref()],
+        bump
+    )]
+    pub claimer_memo: Account<'info, ClaimerMemo>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CloseClaimerMemo<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [b"grant", grant.seed_authority.as_ref(), grant.seed_mint.as_ref(), &grant.grant_id.to_le_bytes()],
+        bump = grant.bump
+    )]
+    pub grant: Account<'info, Grant>,
+
+    #[account(
+        mut,
+        has_one = grant,
+        close = authority,
+        seeds = [b"claimer-memo", grant.key().as_ref(), claimer_memo.claimer.as_ref()],
+        bump = claimer_memo.bump
+    )]
+    pub claimer_memo: Account<'info, ClaimerMemo>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(page: u32)]
+pub struct OpenDelegateIndexPage<'info> {
+    #[account(
+        seeds = [b"grant", grant.seed_authority.as_ref(), grant.seed_mint.as_ref(), &grant.grant_id.to_le_bytes()],
+        bump = grant.bump
+    )]
+    pub grant: Account<'info, Grant>,
+
+    #[account(
+        init,
+        payer = delegate,
+        space = DelegateIndexPage::space(0),
+        seeds = [b"delegate-index", grant.key().as_ref(), delegate.key().as_ref(), &page.to_le_bytes()],
+        bump
+    )]
+    pub delegate_index: Account<'info, DelegateIndexPage>,
+
+    #[account(mut)]
+    pub delegate: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(page: u32)]
+pub struct AddDelegatedClaimer<'info> {
+    #[account(
+        mut,
+        has_one = delegate,
+        realloc = DelegateIndexPage::space(delegate_index.claimers.len() + 1),
+        realloc::payer = delegate,
+        realloc::zero = false,
+        seeds = [b"delegate-index", delegate_index.grant.as_ref(), delegate.key().as_ref(), &page.to_le_bytes()],
+        bump = delegate_index.bump
+    )]
+    pub delegate_index: Account<'info, DelegateIndexPage>,
+
+    #[account(
+        constraint = session_authorization.grant == delegate_index.grant @ ErrorCode::InvalidSession,
+        seeds = [b"session", delegate_index.grant.as_ref(), session_authorization.claimer.as_ref()],
+        bump = session_authorization.bump
+    )]
+    pub session_authorization: Account<'info, SessionAuthorization>,
+
+    #[account(mut)]
+    pub delegate: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(page: u32)]
+pub struct RemoveDelegatedClaimer<'info> {
+    #[account(
+        mut,
+        has_one = delegate,
+        realloc = DelegateIndexPage::space(delegate_index.claimers.len().saturating_sub(1)),
+        realloc::payer = delegate,
+        realloc::zero = false,
+        seeds = [b"delegate-index", delegate_index.grant.as_ref(), delegate.key().as_ref(), &page.to_le_bytes()],
+        bump = delegate_index.bump
+    )]
+    pub delegate_index: Account<'info, DelegateIndexPage>,
+
+    #[account(mut)]
+    pub delegate: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(page: u32)]
+pub struct CloseDelegateIndexPage<'info> {
+    #[account(
+        mut,
+        has_one = delegate,
+        close = delegate,
+        seeds = [b"delegate-index", delegate_index.grant.as_ref(), delegate.key().as_ref(), &page.to_le_bytes()],
+        bump = delegate_index.bump
+    )]
+    pub delegate_index: Account<'info, DelegateIndexPage>,
+
+    #[account(mut)]
+    pub delegate: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(period_index: u64)]
+pub struct ScheduleClaim<'info> {
+    #[account(
+        seeds = [b"grant", grant.seed_authority.as_ref(), grant.seed_mint.as_ref(), &grant.grant_id.to_le_bytes()],
+        bump = grant.bump
+    )]
+    pub grant: Account<'info, Grant>,
+
+    /// 同じ期間の予約は1件まで（取り消してから再予約する）
+    #[account(
+        init,
+        payer = claimer,
+        space = 8 + PendingClaim::INIT_SPACE,
+        seeds = [b"pending-claim", grant.key().as_ref(), claimer.key().as_ref(), &period_index.to_le_bytes()],
+        bump
+    )]
+    pub pending_claim: Account<'info, PendingClaim>,
+
+    #[account(mut)]
+    pub claimer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelScheduledClaim<'info> {
+    #[account(
+        mut,
+        has_one = claimer,
+        close = claimer,
+        seeds = [
+            b"pending-claim",
+            pending_claim.grant.as_ref(),
+            claimer.key().as_ref(),
+            &pending_claim.period_index.to_le_bytes(),
+        ],
+        bump = pending_claim.bump
+    )]
+    pub pending_claim: Account<'info, PendingClaim>,
+
+    #[account(mut)]
+    pub claimer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct OpenStatementLog<'info> {
+    #[account(
+        seeds = [b"grant", grant.seed_authority.as_ref(), grant.seed_mint.as_ref(), &grant.grant_id.to_le_bytes()],
+        bump = grant.bump
+    )]
+    pub grant: Account<'info, Grant>,
+
+    #[account(
+        init,
+        payer = claimer,
+        space = 8 + StatementLog::INIT_SPACE,
+        seeds = [b"statement", grant.key().as_ref(), claimer.key().as_ref()],
+        bump
+    )]
+    pub statement_log: AccountLoader<'info, StatementLog>,
+
+    #[account(mut)]
+    pub claimer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CloseStatementLog<'info> {
+    #[account(
+        mut,
+        has_one = claimer,
+        close = claimer,
+        seeds = [b"statement", statement_log.load()?.grant.as_ref(), claimer.key().as_ref()],
+        bump = statement_log.load()?.bump
+    )]
+    pub statement_log: AccountLoader<'info, StatementLog>,
+
+    #[account(mut)]
+    pub claimer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct OpenClaimIndex<'info> {
+    #[account(
+        seeds = [b"grant", grant.seed_authority.as_ref(), grant.seed_mint.as_ref(), &grant.grant_id.to_le_bytes()],
+        bump = grant.bump
+    )]
+    pub grant: Account<'info, Grant>,
+
+    #[account(
+        init,
+        payer = claimer,
+        space = 8 + ClaimIndex::INIT_SPACE,
+        seeds = [b"claim-index", grant.key().as_ref(), claimer.key().as_ref()],
+        bump
+    )]
+    pub claim_index: AccountLoader<'info, ClaimIndex>,
+
+    #[account(
+        init_if_needed,
+        payer = claimer,
+        space = 8 + ClaimerProfile::INIT_SPACE,
+        seeds = [b"claimer-profile", grant.key().as_ref(), claimer.key().as_ref()],
+        bump
+    )]
+    pub claimer_profile: Box<Account<'info, ClaimerProfile>>,
+
+    #[account(mut)]
+    pub claimer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CloseClaimIndex<'info> {
+    #[account(
+        mut,
+        has_one = claimer,
+        close = claimer,
+        seeds = [b"claim-index", claim_index.load()?.grant.as_ref(), claimer.key().as_ref()],
+        bump = claim_index.load()?.bump
+    )]
+    pub claim_index: AccountLoader<'info, ClaimIndex>,
+
+    #[account(
+        mut,
+        seeds = [b"claimer-profile", claim_index.load()?.grant.as_ref(), claimer.key().as_ref()],
+        bump = claimer_profile.bump
+    )]
+    pub claimer_profile: Box<Account<'info, ClaimerProfile>>,
+
+    #[account(mut)]
+    pub claimer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpsertPopConfig<'info> {
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + PopConfig::INIT_SPACE,
+        seeds = [b"pop-config", authority.key().as_ref()],
+        bump
+    )]
+    pub pop_config: Account<'info, PopConfig>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct PopHeartbeat<'info> {
+    #[account(
+        mut,
+        seeds = [b"pop-config", pop_config.authority.as_ref()],
+        bump = pop_config.bump,
+        constraint = pop_config.signer_pubkey == signer.key() @ ErrorCode::InvalidPopSigner
+    )]
+    pub pop_config: Account<'info, PopConfig>,
+
+    /// PoP 署名者（PopConfig.signer_pubkey）
+    pub signer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetPopHeartbeatPolicy<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [b"pop-config", authority.key().as_ref()],
+        bump = pop_config.bump
+    )]
+    pub pop_config: Account<'info, PopConfig>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(batch_root: [u8; 32])]
+pub struct AnchorAuditBatch<'info> {
+    #[account(
+        seeds = [b"pop-config", pop_config.authority.as_ref()],
+        bump = pop_config.bump,
+        constraint = pop_config.signer_pubkey == signer.key() @ ErrorCode::InvalidPopSigner
+    )]
+    pub pop_config: Account<'info, PopConfig>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + AuditAnchor::INIT_SPACE,
+        seeds = [b"audit-anchor", pop_config.key().as_ref(), batch_root.as_ref()],
+        bump
+    )]
+    pub audit_anchor: Account<'info, AuditAnchor>,
+
+    /// PoP 署名者（PopConfig.signer_pubkey）
+    pub signer: Signer<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct LookupAuditHash<'info> {
+    #[account(
+        seeds = [b"audit-anchor", audit_anchor.pop_config.as_ref(), audit_anchor.batch_root.as_ref()],
+        bump = audit_anchor.bump
+    )]
+    pub audit_anchor: Account<'info, AuditAnchor>,
+}
+
+#[derive(Accounts)]
+pub struct BeginWindDown<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [b"grant", grant.seed_authority.as_ref(), grant.seed_mint.as_ref(), &grant.grant_id.to_le_bytes()],
+        bump = grant.bump
+    )]
+    pub grant: Account<'info, Grant>,
+
+    #[account(address = grant.vault @ ErrorCode::VaultMismatch)]
+    pub vault: Account<'info, TokenAccount>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloseGrant<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        close = authority,
+        has_one = mint @ ErrorCode::MintMismatch,
+        seeds = [b"grant", grant.seed_authority.as_ref(), grant.seed_mint.as_ref(), &grant.grant_id.to_le_bytes()],
+        bump = grant.bump
+    )]
+    pub grant: Account<'info, Grant>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        address = grant.vault @ ErrorCode::VaultMismatch
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    /// 返金先（authorityのATA）
+    #[account(
+        mut,
+        constraint = authority_ata.mint == mint.key() @ ErrorCode::MintMismatch,
+        constraint = authority_ata.owner == authority.key() @ ErrorCode::Unauthorized
+    )]
+    pub authority_ata: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+
+    /// create_grant で数えられた Grant の場合は必須（active_grants を減算する）
+    #[account(
+        mut,
+        seeds = [b"pop-config", authority.key().as_ref()],
+        bump = pop_config.bump
+    )]
+    pub pop_config: Option<Account<'info, PopConfig>>,
+
+    /// 任意: 渡された場合は同一トランザクションで close する
+    #[account(
+        mut,
+        seeds = [b"pop-state", grant.key().as_ref(), seed_salt_seed(&grant.seed_salt)],
+        bump = pop_state.bump
+    )]
+    pub pop_state: Option<Account<'info, PopState>>,
+
+    /// CHECK: pop_state を close する場合の返金先（pop_state.payer と一致を検証）
+    #[account(mut)]
+    pub pop_state_payer: Option<UncheckedAccount<'info>>,
+}
+
+#[derive(Accounts)]
+pub struct SetMaxUniqueClaimers<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [b"grant", grant.seed_authority.as_ref(), grant.seed_mint.as_ref(), &grant.grant_id.to_le_bytes()],
+        bump = grant.bump
+    )]
+    pub grant: Account<'info, Grant>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetKpiTargets<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [b"grant", grant.seed_authority.as_ref(), grant.seed_mint.as_ref(), &grant.grant_id.to_le_bytes()],
+        bump = grant.bump
+    )]
+    pub grant: Account<'info, Grant>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetSeedSalt<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [b"grant", grant.seed_authority.as_ref(), grant.seed_mint.as_ref(), &grant.grant_id.to_le_bytes()],
+        bump = grant.bump
+    )]
+    pub grant: Account<'info, Grant>,
+
+    /// CHECK: salt なしの PopState PDA（未作成であることを検証する）
+    #[account(seeds = [b"pop-state", grant.key().as_ref()], bump)]
+    pub pop_state: UncheckedAccount<'info>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetPopBypass<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [b"grant", grant.seed_authority.as_ref(), grant.seed_mint.as_ref(), &grant.grant_id.to_le_bytes()],
+        bump = grant.bump
+    )]
+    pub grant: Account<'info, Grant>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetSponsor<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [b"grant", grant.seed_authority.as_ref(), grant.seed_mint.as_ref(), &grant.grant_id.to_le_bytes()],
+        bump = grant.bump
+    )]
+    pub grant: Account<'info, Grant>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetPopMonotonicIssuedAt<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [b"grant", grant.seed_authority.as_ref(), grant.seed_mint.as_ref(), &grant.grant_id.to_le_bytes()],
+        bump = grant.bump
+    )]
+    pub grant: Account<'info, Grant>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetPopRequireAuditHash<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [b"grant", grant.seed_authority.as_ref(), grant.seed_mint.as_ref(), &grant.grant_id.to_le_bytes()],
+        bump = grant.bump
+    )]
+    pub grant: Account<'info, Grant>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetPopAmountCeiling<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [b"grant", grant.seed_authority.as_ref(), grant.seed_mint.as_ref(), &grant.grant_id.to_le_bytes()],
+        bump = grant.bump
+    )]
+    pub grant: Account<'info, Grant>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ResetPopIssuedAt<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [b"grant", grant.seed_authority.as_ref(), grant.seed_mint.as_ref(), &grant.grant_id.to_le_bytes()],
+        bump = grant.bump
+    )]
+    pub grant: Account<'info, Grant>,
+
+    #[account(
+        mut,
+        seeds = [b"pop-state", grant.key().as_ref(), seed_salt_seed(&grant.seed_salt)],
+        bump = pop_state.bump,
+        constraint = pop_state.grant == grant.key() @ ErrorCode::PopStateGrantMismatch
+    )]
+    pub pop_state: Account<'info, PopState>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ClosePopState<'info> {
+    /// CHECK: close 済みの Grant PDA。アドレスは pop_state.grant と一致し、空であることを検証する
+    #[account(address = pop_state.grant)]
+    pub grant: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        has_one = payer,
+        close = payer,
+        seeds = [b"pop-state", grant.key().as_ref(), seed_salt_seed(&pop_state.seed_salt)],
+        bump = pop_state.bump
+    )]
+    pub pop_state: Account<'info, PopState>,
+
+    /// CHECK: PopState の rent を立て替えた payer（has_one で検証）
+    #[account(mut)]
+    pub payer: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct VerifyInvariants<'info> {
+    #[account(
+        seeds = [b"grant", grant.seed_authority.as_ref(), grant.seed_mint.as_ref(), &grant.grant_id.to_le_bytes()],
+        bump = grant.bump
+    )]
+    pub grant: Account<'info, Grant>,
+
+    #[account(address = grant.vault @ ErrorCode::VaultMismatch)]
+    pub vault: Account<'info, TokenAccount>,
+}
+
+#[derive(Accounts)]
+#[instruction(period_index: u64)]
+pub struct ClosePeriod<'info> {
+    #[account(
+        mut,
+        seeds = [b"grant", grant.seed_authority.as_ref(), grant.seed_mint.as_ref(), &grant.grant_id.to_le_bytes()],
+        bump = grant.bump
+    )]
+    pub grant: Account<'info, Grant>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + PeriodStats::INIT_SPACE,
+        seeds = [b"period-stats", grant.key().as_ref(), &period_index.to_le_bytes()],
+        bump
+    )]
+    pub period_stats: AccountLoader<'info, PeriodStats>,
+
+    /// keeper（bounty の受取人）
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    // 以下 optional（keeper bounty を受け取る場合のみ）
+    #[account(
+        mut,
+        has_one = grant,
+        seeds = [b"keeper-config", grant.key().as_ref()],
+        bump = keeper_config.bump
+    )]
+    pub keeper_config: Option<Account<'info, KeeperConfig>>,
+
+    /// grant.vault または KeeperConfig.bounty_pool（bounty_from_pool に応じて検証）
+    #[account(mut)]
+    pub bounty_source: Option<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = keeper_ata.mint == grant.mint @ ErrorCode::MintMismatch,
+        constraint = keeper_ata.owner == payer.key() @ ErrorCode::Unauthorized
+    )]
+    pub keeper_ata: Option<Account<'info, TokenAccount>>,
+
+    pub token_program: Option<Program<'info, Token>>,
+}
+
+#[derive(Accounts)]
+pub struct UpsertKeeperConfig<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        has_one = mint @ ErrorCode::MintMismatch,
+        seeds = [b"grant", grant.seed_authority.as_ref(), grant.seed_mint.as_ref(), &grant.grant_id.to_le_bytes()],
+        bump = grant.bump
+    )]
+    pub grant: Account<'info, Grant>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + KeeperConfig::INIT_SPACE,
+        seeds = [b"keeper-config", grant.key().as_ref()],
+        bump
+    )]
+    pub keeper_config: Account<'info, KeeperConfig>,
+
+    /// bounty の原資。authority は Grant PDA（誰でも直接送金して補充できる）
+    #[account(
+        init_if_needed,
+        payer = authority,
+        token::mint = mint,
+        token::authority = grant,
+        seeds = [b"keeper-pool", grant.key().as_ref()],
+        bump,
+    )]
+    pub bounty_pool: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct CloseKeeperConfig<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [b"grant", grant.seed_authority.as_ref(), grant.seed_mint.as_ref(), &grant.grant_id.to_le_bytes()],
+        bump = grant.bump
+    )]
+    pub grant: Account<'info, Grant>,
+
+    #[account(
+        mut,
+        has_one = grant,
+        has_one = bounty_pool @ ErrorCode::KeeperBountySourceMismatch,
+        close = authority,
+        seeds = [b"keeper-config", grant.key().as_ref()],
+        bump = keeper_config.bump
+    )]
+    pub keeper_config: Account<'info, KeeperConfig>,
+
+    #[account(mut)]
+    pub bounty_pool: Account<'info, TokenAccount>,
+
+    /// bounty_pool の残高の返金先
+    #[account(
+        mut,
+        constraint = authority_ata.mint == bounty_pool.mint @ ErrorCode::MintMismatch,
+        constraint = authority_ata.owner == authority.key() @ ErrorCode::Unauthorized
+    )]
+    pub authority_ata: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct SetReceiptStatus<'info> {
+    #[account(
+        mut,
+        seeds = [b"grant", grant.seed_authority.as_ref(), grant.seed_mint.as_ref(), &grant.grant_id.to_le_bytes()],
+        bump = grant.bump
+    )]
+    pub grant: Account<'info, Grant>,
+
+    #[account(
+        mut,
+        has_one = grant @ ErrorCode::InvalidReceipt,
+        seeds = [
+            b"receipt",
+            grant.key().as_ref(),
+            receipt.claimer.as_ref(),
+            &receipt.period_index.to_le_bytes(),
+            seed_salt_seed(&grant.seed_salt),
+        ],
+        bump = receipt.bump
+    )]
+    pub receipt: Account<'info, ClaimReceipt>,
+
+    /// authority / co_admin / reviewer のいずれか（遷移ごとに検証）
+    pub actor: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(claimer: Pubkey, period_index: u64)]
+pub struct RecordHistoricalClaim<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [b"grant", grant.seed_authority.as_ref(), grant.seed_mint.as_ref(), &grant.grant_id.to_le_bytes()],
+        bump = grant.bump
+    )]
+    pub grant: Account<'info, Grant>,
+
+    /// 同じ期間の receipt（予約を含む）が既にあれば作成に失敗する
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + ClaimReceipt::INIT_SPACE,
+        seeds = [
+            b"receipt",
+            grant.key().as_ref(),
+            claimer.as_ref(),
+            &period_index.to_le_bytes(),
+            seed_salt_seed(&grant.seed_salt),
+        ],
+        bump
+    )]
+    pub receipt: Account<'info, ClaimReceipt>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + ClaimerProfile::INIT_SPACE,
+        seeds = [b"claimer-profile", grant.key().as_ref(), claimer.as_ref()],
+        bump
+    )]
+    pub claimer_profile: Box<Account<'info, ClaimerProfile>>,
+
+    /// claimer が open_claim_index 済みの場合のみ必要（記録した期間を受給済みにする）
+    #[account(
+        mut,
+        seeds = [b"claim-index", grant.key().as_ref(), claimer.as_ref()],
+        bump = claim_index.load()?.bump
+    )]
+    pub claim_index: Option<AccountLoader<'info, ClaimIndex>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct OpenReceiptNftCollection<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [b"grant", grant.seed_authority.as_ref(), grant.seed_mint.as_ref(), &grant.grant_id.to_le_bytes()],
+        bump = grant.bump
+    )]
+    pub grant: Account<'info, Grant>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + ReceiptNftCollection::INIT_SPACE,
+        seeds = [b"receipt-nft-collection", grant.key().as_ref()],
+        bump
+    )]
+    pub collection: Account<'info, ReceiptNftCollection>,
+
+    /// CHECK: 命令内で Token-2022 の group mint として作成する（PDA のため既存なら作成に失敗する）
+    #[account(mut, seeds = [b"receipt-nft-group", grant.key().as_ref()], bump)]
+    pub group_mint: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub token_2022_program: Program<'info, Token2022>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MintReceiptNft<'info> {
+    #[account(
+        seeds = [b"grant", grant.seed_authority.as_ref(), grant.seed_mint.as_ref(), &grant.grant_id.to_le_bytes()],
+        bump = grant.bump
+    )]
+    pub grant: Account<'info, Grant>,
+
+    #[account(
+        mut,
+        has_one = grant,
+        has_one = group_mint,
+        seeds = [b"receipt-nft-collection", grant.key().as_ref()],
+        bump = collection.bump
+    )]
+    pub collection: Account<'info, ReceiptNftCollection>,
+
+    #[account(
+        has_one = grant @ ErrorCode::InvalidReceipt,
+        has_one = claimer @ ErrorCode::InvalidReceipt,
+        seeds = [
+            b"receipt",
+            grant.key().as_ref(),
+            receipt.claimer.as_ref(),
+            &receipt.period_index.to_le_bytes(),
+            seed_salt_seed(&grant.seed_salt),
+        ],
+        bump = receipt.bump
+    )]
+    pub receipt: Account<'info, ClaimReceipt>,
+
+    /// CHECK: 命令内で Token-2022 の mint として作成する（receipt ごとの PDA のため2回目は作成に失敗する）
+    #[account(mut, seeds = [b"receipt-nft", receipt.key().as_ref()], bump)]
+    pub receipt_nft_mint: UncheckedAccount<'info>,
+
+    /// CHECK: collection.group_mint（has_one で検証。TokenGroup の member 数が更新される）
+    #[account(mut)]
+    pub group_mint: UncheckedAccount<'info>,
+
+    /// CHECK: receipt.claimer（has_one で検証。NFT の受取人で署名は不要）
+    pub claimer: UncheckedAccount<'info>,
+
+    /// CHECK: claimer の Token-2022 ATA（命令内で作成する）
+    #[account(
+        mut,
+        address = get_associated_token_address_with_program_id(&claimer.key(), &receipt_nft_mint.key(), &token_2022::ID)
+    )]
+    pub claimer_nft_account: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub token_2022_program: Program<'info, Token2022>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ReturnClaim<'info> {
+    #[account(
+        mut,
+        seeds = [b"grant", grant.seed_authority.as_ref(), grant.seed_mint.as_ref(), &grant.grant_id.to_le_bytes()],
+        bump = grant.bump
+    )]
+    pub grant: Account<'info, Grant>,
+
+    #[account(
+        mut,
+        address = grant.vault @ ErrorCode::VaultMismatch
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        has_one = grant @ ErrorCode::InvalidReceipt,
+        has_one = claimer @ ErrorCode::InvalidReceipt,
+        seeds = [
+            b"receipt",
+            grant.key().as_ref(),
+            claimer.key().as_ref(),
+            &receipt.period_index.to_le_bytes(),
+            seed_salt_seed(&grant.seed_salt),
+        ],
+        bump = receipt.bump
+    )]
+    pub receipt: Account<'info, ClaimReceipt>,
+
+    #[account(
+        mut,
+        seeds = [b"claimer-profile", grant.key().as_ref(), claimer.key().as_ref()],
+        bump = claimer_profile.bump
+    )]
+    pub claimer_profile: Box<Account<'info, ClaimerProfile>>,
+
+    #[account(
+        mut,
+        seeds = [b"period-stats", grant.key().as_ref(), &receipt.period_index.to_le_bytes()],
+        bump = period_stats.load()?.bump
+    )]
+    pub period_stats: AccountLoader<'info, PeriodStats>,
+
+    pub claimer: Signer<'info>,
+
+    /// 返金元（claimer の ATA）
+    #[account(
+        mut,
+        constraint = claimer_ata.mint == grant.mint @ ErrorCode::MintMismatch,
+        constraint = claimer_ata.owner == claimer.key() @ ErrorCode::Unauthorized
+    )]
+    pub claimer_ata: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+
+    /// 期間別 earmark が有効な Grant の場合のみ必要（末尾の optional アカウント）
+    #[account(
+        mut,
+        seeds = [b"escrow-schedule", grant.key().as_ref()],
+        bump = escrow_schedule.bump
+    )]
+    pub escrow_schedule: Option<Account<'info, EscrowSchedule>>,
+
+    /// claimer が open_claim_index 済みの場合のみ必要（返還した期間を未受給に戻す）
+    #[account(
+        mut,
+        seeds = [b"claim-index", grant.key().as_ref(), claimer.key().as_ref()],
+        bump = claim_index.load()?.bump
+    )]
+    pub claim_index: Option<AccountLoader<'info, ClaimIndex>>,
+}
+
+#[derive(Accounts)]
+pub struct StartNewSeason<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [b"grant", grant.seed_authority.as_ref(), grant.seed_mint.as_ref(), &grant.grant_id.to_le_bytes()],
+        bump = grant.bump
+    )]
+    pub grant: Account<'info, Grant>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + SeasonSummary::INIT_SPACE,
+        seeds = [b"season-summary", grant.key().as_ref(), &grant.season.to_le_bytes()],
+        bump
+    )]
+    pub season_summary: Account<'info, SeasonSummary>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClosePopConfig<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        close = authority,
+        seeds = [b"pop-config", authority.key().as_ref()],
+        bump = pop_config.bump
+    )]
+    pub pop_config: Account<'info, PopConfig>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetPaused<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        has_one = mint @ ErrorCode::MintMismatch,
+        seeds = [b"grant", grant.seed_authority.as_ref(), grant.seed_mint.as_ref(), &grant.grant_id.to_le_bytes()],
+        bump = grant.bump
+    )]
+    pub grant: Account<'info, Grant>,
+
+    pub mint: Account<'info, Mint>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetAllowlistRoot<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        has_one = mint @ ErrorCode::MintMismatch,
+        seeds = [b"grant", grant.seed_authority.as_ref(), grant.seed_mint.as_ref(), &grant.grant_id.to_le_bytes()],
+        bump = grant.bump
+    )]
+    pub grant: Account<'info, Grant>,
+
+    pub mint: Account<'info, Mint>,
+
+    pub authority: Signer<'info>,
+
+    /// BudgetForecast がある Grant の場合のみ必要（この命令の結果で再計算する）
+    #[account(
+        mut,
+        has_one = grant,
+        seeds = [b"budget-forecast", grant.key().as_ref()],
+        bump = budget_forecast.bump
+    )]
+    pub budget_forecast: Option<Account<'info, BudgetForecast>>,
+}
+
+#[derive(Accounts)]
+pub struct AppendAllowlistDelta<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [b"grant", grant.seed_authority.as_ref(), grant.seed_mint.as_ref(), &grant.grant_id.to_le_bytes()],
+        bump = grant.bump
+    )]
+    pub grant: Account<'info, Grant>,
+
+    pub authority: Signer<'info>,
+
+    /// BudgetForecast がある Grant の場合のみ必要（この命令の結果で再計算する）
+    #[account(
+        mut,
+        has_one = grant,
+        seeds = [b"budget-forecast", grant.key().as_ref()],
+        bump = budget_forecast.bump
+    )]
+    pub budget_forecast: Option<Account<'info, BudgetForecast>>,
+}
+
+#[derive(Accounts)]
+pub struct UpsertRateConfig<'info> {
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + RateConfig::INIT_SPACE,
+        seeds = [b"rate-config", authority.key().as_ref()],
+        bump
+    )]
+    pub rate_config: Account<'info, RateConfig>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetYenPerPeriod<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        has_one = mint @ ErrorCode::MintMismatch,
+        seeds = [b"grant", grant.seed_authority.as_ref(), grant.seed_mint.as_ref(), &grant.grant_id.to_le_bytes()],
+        bump = grant.bump
+    )]
+    pub grant: Account<'info, Grant>,
+
+    pub mint: Account<'info, Mint>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetProration<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [b"grant", grant.seed_authority.as_ref(), grant.seed_mint.as_ref(), &grant.grant_id.to_le_bytes()],
+        bump = grant.bump
+    )]
+    pub grant: Account<'info, Grant>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetEscrowSchedule<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [b"grant", grant.seed_authority.as_ref(), grant.seed_mint.as_ref(), &grant.grant_id.to_le_bytes()],
+        bump = grant.bump
+    )]
+    pub grant: Account<'info, Grant>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + EscrowSchedule::INIT_SPACE,
+        seeds = [b"escrow-schedule", grant.key().as_ref()],
+        bump
+    )]
+    pub escrow_schedule: Account<'info, EscrowSchedule>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetMinClaimInterval<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [b"grant", grant.seed_authority.as_ref(), grant.seed_mint.as_ref(), &grant.grant_id.to_le_bytes()],
+        bump = grant.bump
+    )]
+    pub grant: Account<'info, Grant>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetBlackoutWindows<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [b"grant", grant.seed_authority.as_ref(), grant.seed_mint.as_ref(), &grant.grant_id.to_le_bytes()],
+        bump = grant.bump
+    )]
+    pub grant: Account<'info, Grant>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetStreakBonus<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [b"grant", grant.seed_authority.as_ref(), grant.seed_mint.as_ref(), &grant.grant_id.to_le_bytes()],
+        bump = grant.bump
+    )]
+    pub grant: Account<'info, Grant>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetStakeMultiplier<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [b"grant", grant.seed_authority.as_ref(), grant.seed_mint.as_ref(), &grant.grant_id.to_le_bytes()],
+        bump = grant.bump
+    )]
+    pub grant: Account<'info, Grant>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct GetVersion {}
+
+#[derive(Accounts)]
+pub struct GetClaimStatus<'info> {
+    #[account(
+        seeds = [b"grant", grant.seed_authority.as_ref(), grant.seed_mint.as_ref(), &grant.grant_id.to_le_bytes()],
+        bump = grant.bump
+    )]
+    pub grant: Account<'info, Grant>,
+
+    /// CHECK: 現在の期間の receipt PDA（get_claim_status でアドレスを検証する。未作成でよい）
+    pub receipt: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct GetRunway<'info> {
+    #[account(
+        seeds = [b"grant", grant.seed_authority.as_ref(), grant.seed_mint.as_ref(), &grant.grant_id.to_le_bytes()],
+        bump = grant.bump
+    )]
+    pub grant: Account<'info, Grant>,
+
+    #[account(address = grant.vault @ ErrorCode::VaultMismatch)]
+    pub vault: Account<'info, TokenAccount>,
+
+    /// 円建て Grant の場合のみ必要
+    #[account(
+        seeds = [b"rate-config", grant.authority.as_ref()],
+        bump = rate_config.bump,
+        constraint = rate_config.authority == grant.authority @ ErrorCode::Unauthorized
+    )]
+    pub rate_config: Option<Account<'info, RateConfig>>,
+}
+
+#[derive(Accounts)]
+pub struct OpenBudgetForecast<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [b"grant", grant.seed_authority.as_ref(), grant.seed_mint.as_ref(), &grant.grant_id.to_le_bytes()],
+        bump = grant.bump
+    )]
+    pub grant: Account<'info, Grant>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + BudgetForecast::INIT_SPACE,
+        seeds = [b"budget-forecast", grant.key().as_ref()],
+        bump
+    )]
+    pub budget_forecast: Account<'info, BudgetForecast>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    /// 円建て Grant の場合のみ必要
+    #[account(
+        seeds = [b"rate-config", grant.authority.as_ref()],
+        bump = rate_config.bump,
+        constraint = rate_config.authority == grant.authority @ ErrorCode::Unauthorized
+    )]
+    pub rate_config: Option<Account<'info, RateConfig>>,
+}
+
+#[derive(Accounts)]
+pub struct RefreshBudgetForecast<'info> {
+    #[account(
+        seeds = [b"grant", grant.seed_authority.as_ref(), grant.seed_mint.as_ref(), &grant.grant_id.to_le_bytes()],
+        bump = grant.bump
+    )]
+    pub grant: Account<'info, Grant>,
+
+    #[account(
+        mut,
+        has_one = grant,
+        seeds = [b"budget-forecast", grant.key().as_ref()],
+        bump = budget_forecast.bump
+    )]
+    pub budget_forecast: Account<'info, BudgetForecast>,
+
+    /// 任意: 円建て Grant で1回あたりの支出を現在のレートに合わせる場合
+    #[account(
+        seeds = [b"rate-config", grant.authority.as_ref()],
+        bump = rate_config.bump,
+        constraint = rate_config.authority == grant.authority @ ErrorCode::Unauthorized
+    )]
+    pub rate_config: Option<Account<'info, RateConfig>>,
+}
+
+#[derive(Accounts)]
+pub struct GetBudgetForecast<'info> {
+    #[account(
+        seeds = [b"grant", grant.seed_authority.as_ref(), grant.seed_mint.as_ref(), &grant.grant_id.to_le_bytes()],
+        bump = grant.bump
+    )]
+    pub grant: Account<'info, Grant>,
+
+    #[account(
+        has_one = grant,
+        seeds = [b"budget-forecast", grant.key().as_ref()],
+        bump = budget_forecast.bump
+    )]
+    pub budget_forecast: Account<'info, BudgetForecast>,
+}
+
+#[derive(Accounts)]
+pub struct CloseBudgetForecast<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [b"grant", grant.seed_authority.as_ref(), grant.seed_mint.as_ref(), &grant.grant_id.to_le_bytes()],
+        bump = grant.bump
+    )]
+    pub grant: Account<'info, Grant>,
+
+    #[account(
+        mut,
+        has_one = grant,
+        close = authority,
+        seeds = [b"budget-forecast", grant.key().as_ref()],
+        bump = budget_forecast.bump
+    )]
+    pub budget_forecast: Account<'info, BudgetForecast>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct OpenArchive<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [b"grant", grant.seed_authority.as_ref(), grant.seed_mint.as_ref(), &grant.grant_id.to_le_bytes()],
+        bump = grant.bump
+    )]
+    pub grant: Account<'info, Grant>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + GrantArchive::INIT_SPACE,
+        seeds = [b"archive", grant.key().as_ref()],
+        bump
+    )]
+    pub archive: Account<'info, GrantArchive>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ArchiveReceipts<'info> {
+    #[account(
+        seeds = [b"grant", grant.seed_authority.as_ref(), grant.seed_mint.as_ref(), &grant.grant_id.to_le_bytes()],
+        bump = grant.bump
+    )]
+    pub grant: Account<'info, Grant>,
+
+    #[account(
+        mut,
+        has_one = grant,
+        seeds = [b"archive", grant.key().as_ref()],
+        bump = archive.bump
+    )]
+    pub archive: Account<'info, GrantArchive>,
+}
+
+#[derive(Accounts)]
+pub struct FinalizeArchive<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [b"grant", grant.seed_authority.as_ref(), grant.seed_mint.as_ref(), &grant.grant_id.to_le_bytes()],
+        bump = grant.bump
+    )]
+    pub grant: Account<'info, Grant>,
+
+    #[account(
+        mut,
+        has_one = grant,
+        seeds = [b"archive", grant.key().as_ref()],
+        bump = archive.bump
+    )]
+    pub archive: Account<'info, GrantArchive>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloseArchivedReceipts<'info> {
+    /// Grant を close した後も使えるように、archive.grant から seeds を導出する
+    #[account(
+        mut,
+        seeds = [b"archive", archive.grant.as_ref()],
+        bump = archive.bump
+    )]
+    pub archive: Account<'info, GrantArchive>,
+}
+
+#[derive(Accounts)]
+pub struct VerifyPopProof<'info> {
+    #[account(
+        seeds = [b"grant", grant.seed_authority.as_ref(), grant.seed_mint.as_ref(), &grant.grant_id.to_le_bytes()],
+        bump = grant.bump
+    )]
+    pub grant: Account<'info, Grant>,
+
+    #[account(
+        seeds = [b"pop-config", grant.authority.as_ref()],
+        bump = pop_config.bump,
+        constraint = pop_config.authority == grant.authority @ ErrorCode::InvalidPopConfigAuthority
+    )]
+    pub pop_config: Account<'info, PopConfig>,
+
+    /// CHECK: Instructions Sysvar account (required for Ed25519 proof verification)
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+
+    /// 渡された場合は proof が現在のチェーンヘッドかを返す（末尾の optional アカウント）
+    #[account(
+        seeds = [b"pop-state", grant.key().as_ref(), seed_salt_seed(&grant.seed_salt)],
+        bump = pop_state.bump
+    )]
+    pub pop_state: Option<Account<'info, PopState>>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeFeatureFlags<'info> {
+    #[account(
+        init,
+        payer = upgrade_authority,
+        space = 8 + FeatureFlags::INIT_SPACE,
+        seeds = [b"feature-flags"],
+        bump
+    )]
+    pub feature_flags: Account<'info, FeatureFlags>,
+
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()) @ ErrorCode::Unauthorized)]
+    pub program: Program<'info, crate::program::GrantProgram>,
+
+    #[account(constraint = program_data.upgrade_authority_address == Some(upgrade_authority.key()) @ ErrorCode::Unauthorized)]
+    pub program_data: Account<'info, ProgramData>,
+
+    #[account(mut)]
+    pub upgrade_authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeDeployment<'info> {
+    #[account(
+        init,
+        payer = upgrade_authority,
+        space = 8 + FeatureFlags::INIT_SPACE,
+        seeds = [b"feature-flags"],
+        bump
+    )]
+    pub feature_flags: Account<'info, FeatureFlags>,
+
+    #[account(
+        init,
+        payer = upgrade_authority,
+        space = 8 + TreasuryConfig::INIT_SPACE,
+        seeds = [b"treasury-config"],
+        bump
+    )]
+    pub treasury_config: Account<'info, TreasuryConfig>,
+
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()) @ ErrorCode::Unauthorized)]
+    pub program: Program<'info, crate::program::GrantProgram>,
+
+    #[account(constraint = program_data.upgrade_authority_address == Some(upgrade_authority.key()) @ ErrorCode::Unauthorized)]
+    pub program_data: Account<'info, ProgramData>,
+
+    #[account(mut)]
+    pub upgrade_authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeHashDomain<'info> {
+    #[account(
+        init,
+        payer = upgrade_authority,
+        space = 8 + HashDomainConfig::INIT_SPACE,
+        seeds = [b"hash-domain"],
+        bump
+    )]
+    pub hash_domain_config: Account<'info, HashDomainConfig>,
+
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()) @ ErrorCode::Unauthorized)]
+    pub program: Program<'info, crate::program::GrantProgram>,
+
+    #[account(constraint = program_data.upgrade_authority_address == Some(upgrade_authority.key()) @ ErrorCode::Unauthorized)]
+    pub program_data: Account<'info, ProgramData>,
+
+    #[account(mut)]
+    pub upgrade_authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetFeatureFlags<'info> {
+    #[account(
+        mut,
+        has_one = admin,
+        seeds = [b"feature-flags"],
+        bump = feature_flags.bump
+    )]
+    pub feature_flags: Account<'info, FeatureFlags>,
+
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetTreasuryFeeSplits<'info> {
+    #[account(
+        has_one = admin,
+        seeds = [b"feature-flags"],
+        bump = feature_flags.bump
+    )]
+    pub feature_flags: Account<'info, FeatureFlags>,
+
+    #[account(
+        mut,
+        seeds = [b"treasury-config"],
+        bump = treasury_config.bump
+    )]
+    pub treasury_config: Account<'info, TreasuryConfig>,
+
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpsertTreasuryConfig<'info> {
+    #[account(
+        has_one = admin,
+        seeds = [b"feature-flags"],
+        bump = feature_flags.bump
+    )]
+    pub feature_flags: Account<'info, FeatureFlags>,
+
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = 8 + TreasuryConfig::INIT_SPACE,
+        seeds = [b"treasury-config"],
+        bump
+    )]
+    pub treasury_config: Account<'info, TreasuryConfig>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetGate<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        has_one = mint @ ErrorCode::MintMismatch,
+        seeds = [b"grant", grant.seed_authority.as_ref(), grant.seed_mint.as_ref(), &grant.grant_id.to_le_bytes()],
+        bump = grant.bump
+    )]
+    pub grant: Account<'info, Grant>,
+
+    pub mint: Account<'info, Mint>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetClaimCpiGuard<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [b"grant", grant.seed_authority.as_ref(), grant.seed_mint.as_ref(), &grant.grant_id.to_le_bytes()],
+        bump = grant.bump
+    )]
+    pub grant: Account<'info, Grant>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetEligibilityPredicate<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [b"grant", grant.seed_authority.as_ref(), grant.seed_mint.as_ref(), &grant.grant_id.to_le_bytes()],
+        bump = grant.bump
+    )]
+    pub grant: Account<'info, Grant>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetAttestationProgram<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [b"grant", grant.seed_authority.as_ref(), grant.seed_mint.as_ref(), &grant.grant_id.to_le_bytes()],
+        bump = grant.bump
+    )]
+    pub grant: Account<'info, Grant>,
+
+    pub authority: Signer<'info>,
+}
+
+#[cfg(feature = "test-clock")]
+#[derive(Accounts)]
+pub struct SetClockOverride<'info> {
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + ClockOverride::INIT_SPACE,
+        seeds = [b"clock-override"],
+        bump
+    )]
+    pub clock_override: Account<'info, ClockOverride>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+// ===== State =====
+
+#[account]
+#[derive(Default, InitSpace)]
+pub struct Grant {
+    pub authority: Pubkey,
+    pub mint: Pubkey,
+    pub vault: Pubkey,
+    pub grant_id: u64,
+
+    pub amount_per_period: u64,
+    pub period_seconds: i64,
+    pub start_ts: i64,
+    pub expires_at: i64, // 0 = no expiry
+
+    /// allowlist Merkle root. [0;32] means disabled.
+    pub merkle_root: [u8; 32],
+
+    pub paused: bool,
+    pub bump: u8,
+    /// 分類（GRANT_CATEGORY_*）。先頭の固定オフセットに置き、getProgramAccounts の memcmp で絞り込めるようにする
+    pub category: u16,
+
+    /// 円建ての1期間あたり支給額。0 = 無効（amount_per_period を使用）
+    pub yen_per_period: u64,
+
+    /// attestation gate を発行するプログラム。Pubkey::default() = 無効
+    pub gate_program: Pubkey,
+    /// gate プロバイダ側の設定アカウント（アテステーションの config と一致が必要）
+    pub gate_config: Pubkey,
+
+    /// create_grant 時に PopConfig.active_grants へ加算されたか
+    pub pop_config_tracked: bool,
+
+    /// 累計入金額 / 累計支給額 / 累計 claim 回数
+    pub total_funded: u128,
+    pub total_claimed: u128,
+    pub claims_count: u64,
+
+    /// platform 経由で作成された場合の co-admin（platform_signer）。default = なし
+    pub co_admin: Pubkey,
+    /// claim ごとに vault から platform へ支払う手数料（支給額に対する basis points）
+    pub platform_fee_bps: u16,
+    pub platform_fee_account: Pubkey,
+    pub total_platform_fees: u128,
+
+    /// 承認者。default = 承認不要（作成直後から claim 可能）
+    pub reviewer: Pubkey,
+    /// reviewer による承認済みか（reviewer 設定時のみ意味を持つ）
+    pub activated: bool,
+    pub activated_at: i64,
+
+    /// PDA seed に使う作成時の authority（recovery で authority が変わっても不変）
+    pub seed_authority: Pubkey,
+    /// recovery guardian。default = 未登録
+    pub recovery: Pubkey,
+    pub recovery_pending_authority: Pubkey,
+    pub recovery_unlocks_at: i64,
+
+    /// 連続受給ボーナス（min_streak == 0 のステップは未使用）
+    pub streak_bonus_steps: [StreakBonusStep; MAX_STREAK_BONUS_STEPS],
+
+    /// 期間計算の起点（既定は start_ts）
+    pub period_anchor_ts: i64,
+    /// 期間途中で始まる/終わる期間の支給額を按分するか
+    pub prorate_partial_periods: bool,
+
+    /// claim 停止期間（end_ts == 0 のエントリは未使用）
+    pub blackout_windows: [BlackoutWindow; MAX_BLACKOUT_WINDOWS],
+
+    /// 作成時に freeze authority 付きの mint を明示的に許容したか
+    pub freeze_authority_acknowledged: bool,
+
+    /// mint の decimals（作成時にキャッシュ。claim では Mint アカウントを読まない）
+    pub decimals: u8,
+
+    /// 同一 claimer の claim 間隔の下限（秒）。0 = 制限なし
+    pub min_claim_interval_seconds: i64,
+
+    /// 期間別 earmark（EscrowSchedule）が有効か
+    pub escrow_earmarked: bool,
+    /// マッチングプールが存在するか（存在する間は close_grant できない）
+    pub has_match_pool: bool,
+    /// allowlist の人数、または想定受給者数（take-up の分母。0 = 未設定）
+    pub eligible_count: u64,
+    /// PoP message の issued_at が PopState.last_issued_at を超えることを要求するか
+    pub pop_monotonic_issued_at: bool,
+    /// 1回以上受給した claimer 数（ClaimerProfile の新規作成で加算）
+    pub unique_claimers: u64,
+    /// unique_claimers の上限（0 = 無制限）
+    pub max_unique_claimers: u64,
+
+    /// 現在のシーズン番号（0 始まり。start_new_season で加算）
+    pub season: u32,
+    /// 現シーズン最初の period_index（period_anchor_ts の期間がこの番号になる）
+    pub season_period_offset: u64,
+    /// シーズン開始時点の claims_count / total_claimed（シーズン内の集計は累計との差分）
+    pub season_base_claims_count: u64,
+    pub season_base_total_claimed: u128,
+
+    /// PDA seed に使う作成時の mint（complete_mint_migration で mint が変わっても不変）
+    pub seed_mint: Pubkey,
+    /// 切り替え予約中の mint（default = 予約なし）と、切り替え後の amount_per_period
+    pub mint_migration_pending_mint: Pubkey,
+    pub mint_migration_amount_per_period: u64,
+    pub mint_migration_unlocks_at: i64,
+
+    /// rotate_vault の実行回数（新しい vault の seed に使う）
+    pub vault_nonce: u32,
+
+    /// append_allowlist_delta で追加した差分 root（先頭 allowlist_delta_count 個が有効）
+    pub allowlist_delta_roots: [[u8; 32]; MAX_ALLOWLIST_DELTAS],
+    pub allowlist_delta_count: u8,
+
+    /// v2 形式（audit_hash 付き）の PoP message のみ受け付けるか（false なら v1 も受け付ける）
+    pub pop_require_audit_hash: bool,
+
+    /// commit_allowlist_root で記録した元リストのスナップショットのハッシュ（[0;32] = 要約なし）
+    pub allowlist_snapshot_hash: [u8; 32],
+    /// commit_allowlist_root の実行時刻（0 = 要約なし）
+    pub allowlist_committed_at: i64,
+    /// allowlist の更新に commit_allowlist_root を必須にするか
+    pub allowlist_summary_required: bool,
+
+    /// KeeperConfig が存在するか（存在する間は close_grant できない）
+    pub has_keeper_config: bool,
+
+    /// 親 Grant（create_child_grant で作成した子の場合のみ。Pubkey::default() = 親なし）
+    pub parent: Pubkey,
+    /// 運用側で定める地域などの区分（子 Grant のラベル。0 = 未設定）
+    pub region_code: u16,
+    /// 作成した子 Grant の数
+    pub child_count: u32,
+    /// roll_up_child_stats で集計した子 Grant の受給回数・受給額の合計
+    pub children_claims_count: u64,
+    pub children_total_claimed: u128,
+    /// 子として最後に親へ集計した時点の claims_count / total_claimed
+    pub rolled_up_claims_count: u64,
+    pub rolled_up_total_claimed: u128,
+
+    /// begin_wind_down で設定した wind-down の終了時刻（0 = wind-down なし）
+    pub wind_down_ends_at: i64,
+
+    /// set_pop_bypass で設定した PoP なし claim の許可期限（0 = 無効）
+    pub pop_bypass_until: i64,
+
+    /// set_seed_salt で設定した receipt / pop_state の seed salt（[0; SEED_SALT_LEN] = salt なし）
+    pub seed_salt: [u8; SEED_SALT_LEN],
+
+    /// 次の管理命令が指定すべき admin_nonce（実行ごとに 1 増える）
+    pub admin_nonce: u64,
+
+    /// 受給アテステーションを発行するプログラム。Pubkey::default() = 無効
+    pub attestation_program: Pubkey,
+    /// attestation_program 側の設定（スキーマ / credential など。CPI の config にそのまま渡す）
+    pub attestation_config: Pubkey,
+
+    /// 期間ごとの受給率の目標（bps、0 = なし）
+    pub target_take_up_bps: u16,
+    /// ユニーク受給者数の目標（0 = なし）
+    pub target_unique_claimers: u64,
+    /// 達成済みの KPI_MILESTONE_* のビット集合
+    pub kpi_milestones: u8,
+
+    /// dead-man switch の successor。default = 未設定
+    pub successor: Pubkey,
+    /// successor が引き継ぎを表明できるまでの authority の無操作期間
+    pub succession_inactivity_seconds: i64,
+    /// authority が最後に管理命令を実行した時刻
+    pub last_admin_action_at: i64,
+    /// 引き継ぎ表明後、complete_succession を実行できる時刻（0 = 表明なし）
+    pub succession_unlocks_at: i64,
+
+    /// 受給資格の述語（ELIGIBILITY_* の AND を要素とする OR。すべて 0 = 述語なし）
+    pub eligibility_clauses: [u8; MAX_ELIGIBILITY_CLAUSES],
+    /// token-holding 条件の mint と最小保有量（最小単位）
+    pub holding_mint: Pubkey,
+    pub holding_min_amount: u64,
+    /// enrollment 条件で受給実績を確認する Grant
+    pub enrollment_grant: Pubkey,
+
+    /// BudgetForecast が存在するか（存在する間は funding / allowlist / claim の各命令で更新を必須にし、close_grant できない）
+    pub has_budget_forecast: bool,
+
+    /// create_grant で will_use_allowlist を指定し、まだ allowlist を設定していない（この間の claim は NotYetOpen）
+    pub allowlist_pending: bool,
+
+    /// open_archive / finalize_archive の実行時刻（0 = 未実行。open 済みで未 finalize の間は close_grant できない）
+    pub archive_opened_at: i64,
+    pub archived_at: i64,
+
+    /// CPI 経由の claim を制限するか（set_claim_cpi_guard）と、例外として認める呼び出し元のプログラム
+    pub claim_cpi_guard: bool,
+    pub claim_cpi_allowed_caller: Pubkey,
+
+    /// v3 形式の PoP message で指定できる支給額の上限（0 = v3 を受け付けない）
+    pub pop_amount_ceiling: u64,
+
+    /// ステーク期間による支給額の倍率（set_stake_multiplier。stake_program = default なら無効）
+    pub stake_program: Pubkey,
+    pub stake_mint: Pubkey,
+    pub stake_min_amount: u64,
+    pub stake_multiplier_steps: [StakeMultiplierStep; MAX_STAKE_MULTIPLIER_STEPS],
+
+    /// PoP / allowlist のハッシュの domain（作成時に HashDomainConfig から写す。len == 0 は DEFAULT_HASH_DOMAIN）
+    pub hash_domain: HashDomain,
+
+    /// claims_count に数えない receipt の数（record_historical_claim で作成した OffChainSettled の receipt、
+    /// reserve_receipts で作成してまだ受給されていない Reserved の receipt、return_claim で Refunded になった receipt）
+    /// receipt PDA の namespace や period_index の意味を変える設定は、claims_count と合わせてこれも 0 の間だけ変更できる
+    pub uncounted_receipts: u64,
+
+    /// sponsor 名のハッシュ（[0;32] = sponsor なし）と、ロゴの URI
+    /// 可変長のため末尾に置く（これより後ろにフィールドを追加しないこと）
+    pub sponsor_name_hash: [u8; 32],
+    #[max_len(MAX_SPONSOR_URI_LEN)]
+    pub sponsor_logo_uri: String,
+}
+
+impl Grant {
+    /// receipt が1つでも作成されたか（claim した receipt に加え、claims_count に数えない receipt を含む）
+    pub fn has_receipts(&self) -> bool {
+        self.claims_count > 0 || self.uncounted_receipts > 0
+    }
+
+    pub fn has_eligibility_predicate(&self) -> bool {
+        self.eligibility_clauses.iter().any(|clause| *clause != 0)
+    }
+
+    /// satisfied（成立した ELIGIBILITY_* のビット集合）で述語が成立するか
+    pub fn eligibility_satisfied(&self, satisfied: u8) -> bool {
+        self.eligibility_clauses
+            .iter()
+            .any(|clause| *clause != 0 && clause & satisfied == *clause)
+    }
+
+    /// now の時点の period_index（開始前は最初の期間）
+    pub fn period_index_at(&self, now: i64) -> Result<u64> {
+        current_period_index(self, now)
+    }
+
+    /// claimer の proof が allowlist（merkle_root または差分 root）に届くか
+    pub fn is_allowlisted(&self, claimer: &Pubkey, proof: &[[u8; 32]]) -> bool {
+        allowlist_contains(self, self.allowlist_leaf(claimer), proof)
+    }
+
+    /// この Grant の hash_domain での allowlist の葉
+    pub fn allowlist_leaf(&self, claimer: &Pubkey) -> [u8; 32] {
+        merkle::allowlist_leaf_in(self.hash_domain.as_bytes(), claimer)
+    }
+
+    /// claimer が now の時点で現在の期間に claim できるか
+    /// PoP・attestation gate・受給資格の述語のうち allowlist 以外の条件・vault 残高は見ない
+    /// （述語のある Grant では allowlist は条件の1つにすぎないため、proof がなくても NotInAllowlist にしない）
+    /// - claimed: 現在の期間の receipt が受給済み（Reserved 以外）か
+    /// - allowlist_proof: allowlist の proof（None = proof を入手できない）
+    pub fn claim_availability(
+        &self,
+        claimer: &Pubkey,
+        now: i64,
+        claimed: bool,
+        allowlist_proof: Option<&[[u8; 32]]>,
+    ) -> ClaimAvailability {
+        if self.paused {
+            ClaimAvailability::Paused
+        } else if self.reviewer != Pubkey::default() && !self.activated {
+            ClaimAvailability::NotActivated
+        } else if self.allowlist_pending {
+            ClaimAvailability::AllowlistPending
+        } else if now < self.start_ts {
+            ClaimAvailability::NotStarted
+        } else if self.expires_at != 0 && now > self.expires_at {
+            ClaimAvailability::Expired
+        } else if self.blackout_windows.iter().any(|w| w.contains(now)) {
+            ClaimAvailability::Blackout
+        } else if claimed {
+            ClaimAvailability::AlreadyClaimed
+        } else if self.merkle_root != [0u8; 32]
+            && !self.has_eligibility_predicate()
+            && !allowlist_proof.is_some_and(|proof| self.is_allowlisted(claimer, proof))
+        {
+            ClaimAvailability::NotInAllowlist
+        } else {
+            ClaimAvailability::Claimable
+        }
+    }
+}
+
+/// 受給記録（レイアウトと他プログラムからの検証方法は receipt モジュールを参照）
+#[account]
+#[derive(InitSpace)]
+pub struct ClaimReceipt {
+    pub grant: Pubkey,
+    pub claimer: Pubkey,
+    pub period_index: u64,
+    pub claimed_at: i64,
+    /// receipt PDA の bump（以降の命令・他プログラムは再導出せずに bump = receipt.bump で検証できる）
+    pub bump: u8,
+    /// 異議申し立て・取り消しの状態（受給時は Valid。reserve_receipts で予約しただけなら Reserved）
+    pub status: ReceiptStatus,
+    /// claimer に支給した額（streak ボーナスを含み、platform fee は含まない）
+    pub amount: u64,
+    /// set_pop_bypass の緊急期間中に PoP なしで受給した
+    pub pop_bypassed: bool,
+    /// 作成時の Grant.seed_salt（receipt PDA の seed。Grant を読まずに PDA を再計算するため）
+    pub seed_salt: [u8; SEED_SALT_LEN],
+}
+
+/// reserve_receipts で作成した receipt の rent の支払者（PDA [b"receipt-reservation", receipt]）
+/// ClaimReceipt のレイアウトを変えずに、release_receipt_reservations で rent を支払者に戻すため
+#[account]
+#[derive(InitSpace)]
+pub struct ReceiptReservation {
+    pub grant: Pubkey,
+    pub receipt: Pubkey,
+    /// reserve_receipts の実行者（authority または keeper）
+    pub payer: Pubkey,
+    pub bump: u8,
+}
+
+/// 受給記録を Token-2022 NFT として発行するための Grant ごとの設定（open_receipt_nft_collection）
+#[account]
+#[derive(InitSpace)]
+pub struct ReceiptNftCollection {
+    pub grant: Pubkey,
+    /// TokenGroup を持つ Token-2022 の mint（PDA [b"receipt-nft-group", grant]）
+    pub group_mint: Pubkey,
+    #[max_len(MAX_RECEIPT_NFT_NAME_LEN)]
+    pub name: String,
+    #[max_len(MAX_RECEIPT_NFT_SYMBOL_LEN)]
+    pub symbol: String,
+    #[max_len(MAX_SPONSOR_URI_LEN)]
+    pub uri: String,
+    pub max_size: u64,
+    /// mint_receipt_nft で発行した数（TokenGroup の member 数と一致する）
+    pub minted_count: u64,
+    pub bump: u8,
+    pub group_mint_bump: u8,
+}
+
+/// 受給記録の状態
+/// Valid → Disputed → (Valid | Revoked)、Revoked → Refunded の順にのみ遷移する
+/// Reserved は claim で Valid として上書きされるだけで、set_receipt_status の遷移の対象にならない
+/// Archived は archive_receipts でだけ設定され、以降は遷移しない
+/// OffChainSettled は record_historical_claim でだけ設定され、archive_receipts 以外では遷移しない
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default, Debug, InitSpace)]
+pub enum ReceiptStatus {
+    #[default]
+    Valid,
+    /// 異議申し立て中（審査の結果 Valid に戻るか Revoked になる）
+    Disputed,
+    /// 支給が取り消された（受給者への返還請求の対象）
+    Revoked,
+    /// 取り消された支給の返還が確認された
+    Refunded,
+    /// reserve_receipts で事前に作成された未受給の receipt（受給記録ではない）
+    Reserved,
+    /// archive_receipts で GrantArchive の receipts_root に記録された（以降は close_archived_receipts で close するだけ）
+    Archived,
+    /// 移行前にこのプログラムの外で支給済み（record_historical_claim。vault からの送金はない）
+    OffChainSettled,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct PopConfig {
+    pub authority: Pubkey,
+    pub signer_pubkey: Pubkey,
+    pub bump: u8,
+    /// この PopConfig に依存する（close されていない）Grant 数
+    pub active_grants: u64,
+    /// 最後に pop_heartbeat を受信した時刻（0 = 未受信）
+    pub last_heartbeat_at: i64,
+    /// claim 時の heartbeat 途絶判定（0 = 確認しない）と、途絶時に claim を拒否するか
+    pub heartbeat_max_silence_seconds: i64,
+    pub heartbeat_enforced: bool,
+}
+
+/// PoP signer が記録した audit_hash のバッチ（Grant とは独立、PopConfig 単位）
+#[account]
+#[derive(InitSpace)]
+pub struct AuditAnchor {
+    pub pop_config: Pubkey,
+    /// 記録時の PopConfig.signer_pubkey（後で signer を差し替えても残る）
+    pub signer: Pubkey,
+    pub batch_root: [u8; 32],
+    pub entry_count: u32,
+    pub first_issued_at: i64,
+    pub last_issued_at: i64,
+    pub anchored_at: i64,
+    pub anchored_slot: u64,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct PopState {
+    pub grant: Pubkey,
+    pub last_global_hash: [u8; 32],
+    pub last_stream_hash: [u8; 32],
+    pub last_period_index: u64,
+    pub last_issued_at: i64,
+    pub initialized: bool,
+    pub bump: u8,
+    /// 初回 claim で rent を立て替えた payer（close_pop_state の返金先）
+    pub payer: Pubkey,
+    /// 作成時の Grant.seed_salt（close_pop_state は Grant を読めないため）
+    pub seed_salt: [u8; SEED_SALT_LEN],
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct RateConfig {
+    pub authority: Pubkey,
+    /// 1円あたりのトークン最小単位数
+    pub tokens_per_yen: u64,
+    pub last_updated: i64,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct GrantTemplate {
+    pub authority: Pubkey,
+    pub template_id: u64,
+    pub amount_per_period: u64,
+    pub period_seconds: i64,
+    /// start_ts からの有効期間（0 = 無期限）
+    pub duration_seconds: i64,
+    pub yen_per_period: u64,
+    pub gate_program: Pubkey,
+    pub gate_config: Pubkey,
+    /// true の場合、作成時に PopConfig（PoP 署名者設定）が存在することを要求する
+    pub require_pop_config: bool,
+    pub bump: u8,
+    /// 作成される Grant の分類（GRANT_CATEGORY_*）
+    pub category: u16,
+}
+
+/// create_grant の表示上の額（amount / 10^decimal_places トークン。1.5 トークンなら amount = 15, decimal_places = 1）
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct UiAmount {
+    pub amount: u64,
+    pub decimal_places: u8,
+    /// 想定している mint の decimals
+    pub expected_decimals: u8,
+}
+
+impl UiAmount {
+    /// mint の最小単位の額に変換する（decimal_places は mint の decimals 以下であること）
+    pub fn to_raw(&self, mint_decimals: u8) -> Result<u64> {
+        require!(mint_decimals == self.expected_decimals, ErrorCode::DecimalsMismatch);
+        let shift = mint_decimals
+            .checked_sub(self.decimal_places)
+            .ok_or(ErrorCode::InvalidAmount)?;
+        let scale = 10u64.checked_pow(u32::from(shift)).ok_or(ErrorCode::MathOverflow)?;
+        Ok(self.amount.checked_mul(scale).ok_or(ErrorCode::MathOverflow)?)
+    }
+}
+
+/// initialize_deployment の引数
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct DeploymentParams {
+    /// 以後 set_feature_flags / upsert_treasury_config を実行できる protocol admin
+    pub admin: Pubkey,
+    /// 最初から有効にする FEATURE_* のビット集合
+    pub enabled_features: u64,
+    pub treasury: Pubkey,
+    pub claim_fee_lamports: u64,
+    /// set_treasury_fee_splits と同じ規則で検証する（空で分配なし）
+    pub fee_splits: Vec<FeeSplit>,
+}
+
+/// upsert_grant_template の引数
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct GrantTemplateParams {
+    pub amount_per_period: u64,
+    pub period_seconds: i64,
+    pub duration_seconds: i64,
+    pub yen_per_period: u64,
+    pub gate_program: Pubkey,
+    pub gate_config: Pubkey,
+    pub require_pop_config: bool,
+    pub category: u16,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, InitSpace)]
+pub struct StreakBonusStep {
+    /// 今回の claim を含む連続期間数の下限（0 = 未使用）
+    pub min_streak: u16,
+    pub bonus_bps: u16,
+}
+
+/// ハッシュの domain（"<domain>:pop:v1" などの接頭辞。len == 0 は DEFAULT_HASH_DOMAIN）
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace)]
+pub struct HashDomain {
+    pub len: u8,
+    pub bytes: [u8; MAX_HASH_DOMAIN_LEN],
+}
+
+impl HashDomain {
+    /// 1〜MAX_HASH_DOMAIN_LEN バイトの [a-z0-9-]（":" を含めると他の domain と区切りが曖昧になる）
+    pub fn new(domain: &[u8]) -> Result<Self> {
+        require!(
+            !domain.is_empty()
+                && domain.len() <= MAX_HASH_DOMAIN_LEN
+                && domain.iter().all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || *b == b'-'),
+            ErrorCode::InvalidHashDomain
+        );
+        let mut bytes = [0u8; MAX_HASH_DOMAIN_LEN];
+        bytes[..domain.len()].copy_from_slice(domain);
+        Ok(Self {
+            len: domain.len() as u8,
+            bytes,
+        })
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        match self.len as usize {
+            0 => DEFAULT_HASH_DOMAIN,
+            len => &self.bytes[..len.min(MAX_HASH_DOMAIN_LEN)],
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, InitSpace)]
+pub struct StakeMultiplierStep {
+    /// ステークを保持し続けた秒数の下限（0 = 未使用）
+    pub min_stake_seconds: i64,
+    /// 支給額に掛ける倍率（10_000 = 等倍）
+    pub multiplier_bps: u16,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, InitSpace)]
+pub struct BlackoutWindow {
+    pub start_ts: i64,
+    /// 終了時刻（排他的）。0 = 未使用
+    pub end_ts: i64,
+    /// 繰り返し間隔（秒）。0 = 一回限り
+    pub recurrence_seconds: i64,
+}
+
+impl BlackoutWindow {
+    fn contains(&self, now: i64) -> bool {
+        if self.end_ts == 0 || now < self.start_ts {
+            return false;
+        }
+        if self.recurrence_seconds == 0 {
+            return now < self.end_ts;
+        }
+        let duration = self.end_ts - self.start_ts;
+        (now - self.start_ts) % self.recurrence_seconds < duration
+    }
+}
+
+/// get_claim_status の戻り値
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub struct ClaimStatus {
+    /// 判定に使った現在の期間
+    pub period_index: u64,
+    pub availability: ClaimAvailability,
+    /// 判定に使った現在時刻
+    pub evaluated_at: i64,
+}
+
+/// Grant::claim_availability の判定結果（Claimable 以外は claim できない理由）
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ClaimAvailability {
+    Claimable,
+    Paused,
+    /// reviewer の承認待ち
+    NotActivated,
+    /// will_use_allowlist を指定し、まだ allowlist が設定されていない
+    AllowlistPending,
+    NotStarted,
+    Expired,
+    /// blackout_windows の期間中
+    Blackout,
+    /// 現在の期間を受給済み
+    AlreadyClaimed,
+    /// allowlist の proof がない、またはどの root にも届かない
+    NotInAllowlist,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct ProgramVersion {
+    pub version: [u16; 3],
+    pub min_client_version: [u16; 3],
+}
+
+/// lookup_audit_hash の戻り値
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct AuditAnchorLookup {
+    pub pop_config: Pubkey,
+    pub signer: Pubkey,
+    pub batch_root: [u8; 32],
+    pub first_issued_at: i64,
+    pub last_issued_at: i64,
+    pub anchored_at: i64,
+    pub anchored_slot: u64,
+}
+
+/// get_runway の戻り値
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct RunwayEstimate {
+    pub vault_balance: u64,
+    /// eligible_count（未設定なら unique_claimers）
+    pub active_claimers: u64,
+    /// 1期間あたりの想定支出（platform fee を含む）
+    pub per_period_outflow: u128,
+    /// 残高で賄える期間数（支出が 0 の場合は u64::MAX）
+    pub periods_remaining: u64,
+    /// 残高が尽きる見込み時刻（支出が 0 の場合は 0）
+    pub depletion_ts: i64,
+    /// Grant の期限（0 = 無期限）。depletion_ts と比べて追加入金の要否を判断する
+    pub expires_at: i64,
+}
+
+/// verify_pop_proof の戻り値
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct PopProofVerification {
+    pub version: u8,
+    pub signer: Pubkey,
+    pub entry_hash: [u8; 32],
+    pub issued_at: i64,
+    /// PopState.last_global_hash と一致する（pop_state を渡さない場合は false）
+    pub is_current_head: bool,
+    /// v3 形式で署名者が指定した支給額（v1 / v2 は None。Grant.pop_amount_ceiling との比較は claim 時に行う）
+    pub authorized_amount: Option<u64>,
+}
+
+/// 第三者の入金に sponsor の原資を上乗せするマッチングプール（Grant ごとに1つ）
+#[account]
+#[derive(InitSpace)]
+pub struct MatchPool {
+    pub grant: Pubkey,
+    pub sponsor: Pubkey,
+    pub match_vault: Pubkey,
+    /// 入金 1 に対する上乗せ（basis points、10_000 = 1:1）
+    pub ratio_bps: u16,
+    /// 上乗せの累計上限
+    pub cap: u64,
+    pub total_matched: u64,
+    /// cap 到達または原資切れ（MatchPoolExhausted 発行済み）
+    pub exhausted: bool,
+    pub bump: u8,
+}
+
+/// 期間別の earmark。tranches[i] が period_index = i に対応する
+#[account]
+#[derive(InitSpace)]
+pub struct EscrowSchedule {
+    pub grant: Pubkey,
+    #[max_len(MAX_ESCROW_TRANCHES)]
+    pub tranches: Vec<EscrowTranche>,
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, InitSpace)]
+pub struct EscrowTranche {
+    /// この期間に確保する額
+    pub committed: u64,
+    /// 割り当て済みの入金額（committed 以下）
+    pub funded: u64,
+    /// この期間の claim で vault から出た額（支給額 + platform 手数料）
+    pub claimed: u64,
+}
+
+/// デプロイ単位の機能フラグ（同一バイナリを複数クラスタに展開し、段階的に有効化する）
+#[account]
+#[derive(InitSpace)]
+pub struct FeatureFlags {
+    pub admin: Pubkey,
+    /// FEATURE_* のビット集合（ビットが立っている機能のみ有効）
+    pub enabled: u64,
+    pub bump: u8,
+}
+
+/// デプロイ単位のハッシュの domain（initialize_hash_domain で1回だけ作成する。プログラムに1つ）
+#[account]
+#[derive(InitSpace)]
+pub struct HashDomainConfig {
+    pub domain: HashDomain,
+    pub bump: u8,
+}
+
+/// protocol の claim 手数料設定（プログラムに1つ）
+#[account]
+#[derive(InitSpace)]
+pub struct TreasuryConfig {
+    /// 手数料の送金先（system account）
+    pub treasury: Pubkey,
+    /// claim 1回あたりの SOL 手数料（0 = なし）
+    pub claim_fee_lamports: u64,
+    pub bump: u8,
+    /// 手数料の分配先（bps == 0 のエントリは未使用）。残りは treasury が受け取る
+    pub fee_splits: [FeeSplit; MAX_FEE_SPLITS],
+}
+
+/// operator（財務担当）が source から vault へ入金できる月次の枠
+#[account]
+#[derive(InitSpace)]
+pub struct FundingBudget {
+    pub grant: Pubkey,
+    pub operator: Pubkey,
+    /// 入金元（authority 所有の token account。この PDA が delegate）
+    pub source: Pubkey,
+    /// FUNDING_BUDGET_WINDOW_SECONDS あたりの上限
+    pub monthly_cap: u64,
+    /// 現在の期間の開始時刻
+    pub window_start: i64,
+    /// 現在の期間の入金合計
+    pub window_used: u64,
+    /// この枠からの入金の累計
+    pub total_funded: u64,
+    pub bump: u8,
+}
+
+/// fund_with_swap で受け付ける別 mint と、両替に使う AMM（authority が登録する）
+#[account]
+#[derive(InitSpace)]
+pub struct SwapConfig {
+    pub grant: Pubkey,
+    pub input_mint: Pubkey,
+    pub amm_program: Pubkey,
+    pub bump: u8,
+}
+
+/// authority が日常管理を委任した program officer と、その上限
+#[account]
+#[derive(InitSpace)]
+pub struct ProgramOfficer {
+    pub grant: Pubkey,
+    pub officer: Pubkey,
+    /// officer_withdraw の引き出し先（authority が固定する）
+    pub withdrawal_destination: Pubkey,
+    /// FUNDING_BUDGET_WINDOW_SECONDS あたりの引き出し上限（0 = 不可）
+    pub withdrawal_cap: u64,
+    /// 現在の期間の開始時刻
+    pub window_start: i64,
+    /// 現在の期間の引き出し合計
+    pub window_used: u64,
+    /// 引き出しの累計
+    pub total_withdrawn: u64,
+    /// officer が設定できる KeeperConfig.period_close_bounty の上限（0 = 変更不可）
+    pub max_keeper_bounty: u64,
+    pub bump: u8,
+}
+
+/// Grant ごとの keeper（crank 実行者）への報酬設定
+#[account]
+#[derive(InitSpace)]
+pub struct KeeperConfig {
+    pub grant: Pubkey,
+    /// close_period 1回あたりの報酬（0 = 支払わない）
+    pub period_close_bounty: u64,
+    /// true = bounty_pool から、false = vault から支払う
+    pub bounty_from_pool: bool,
+    /// keeper-pool PDA の token account
+    pub bounty_pool: Pubkey,
+    /// 報酬を受け取れる keeper（Pubkey::default() は未使用。すべて未使用なら誰でも）
+    pub keepers: [Pubkey; MAX_KEEPERS],
+    /// 支払った報酬の累計
+    pub total_bounties_paid: u64,
+    pub bump: u8,
+}
+
+impl KeeperConfig {
+    pub fn is_keeper_allowed(&self, keeper: &Pubkey) -> bool {
+        self.keepers.iter().all(|k| *k == Pubkey::default()) || self.keepers.contains(keeper)
+    }
+}
+
+pub const FEE_ROLE_PLATFORM: u8 = 0;
+pub const FEE_ROLE_OPERATOR: u8 = 1;
+pub const FEE_ROLE_REFERRER: u8 = 2;
+
+/// claim 手数料の分配先
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, InitSpace)]
+pub struct FeeSplit {
+    /// 受取先（system account）
+    pub recipient: Pubkey,
+    /// claim_fee_lamports に対する割合
+    pub bps: u16,
+    /// FEE_ROLE_*（集計用のラベル。分配額には影響しない）
+    pub role: u8,
+}
+
+impl FeatureFlags {
+    pub fn is_enabled(&self, feature: u64) -> bool {
+        self.enabled & feature == feature
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct ClaimStreak {
+    pub grant: Pubkey,
+    pub claimer: Pubkey,
+    pub last_period_index: u64,
+    /// last_period_index までの連続受給期間数
+    pub current_streak: u32,
+    pub longest_streak: u32,
+    pub bump: u8,
+    /// 前回 claim の時刻（min_claim_interval_seconds の判定に使う。0 = 未受給）
+    pub last_claimed_at: i64,
+}
+
+/// claimer ごとの受給累計（receipt を走査せずに「これまでの受給額」を1回の取得で返すため）
+#[account]
+#[derive(InitSpace)]
+pub struct ClaimerProfile {
+    pub grant: Pubkey,
+    pub claimer: Pubkey,
+    pub claims_count: u64,
+    /// 受給額の累計（streak ボーナスを含み、platform fee は含まない）
+    pub total_received: u128,
+    /// 直近に受給した period_index
+    pub last_claim_period: u64,
+    /// ClaimIndex が存在するか（存在する間は claim / return_claim に claim_index が必要）
+    pub has_claim_index: bool,
+    pub bump: u8,
+}
+
+/// 残りの期間の支出見込み（open_budget_forecast で作成。入金・allowlist の更新・claim のたびに再計算する）
+/// - committed_obligations = per_claim_outflow × (enrolled_count × remaining_periods - period_claims)
+///   （streak ボーナス・日割りは含まない概算。無期限の Grant は現在の期間だけを数える）
+/// - available_balance は total_funded - total_claimed - total_platform_fees（vault 残高ではなく Grant の集計）
+#[account]
+#[derive(InitSpace)]
+pub struct BudgetForecast {
+    pub grant: Pubkey,
+    /// 想定受給者数（eligible_count、未設定なら unique_claimers）
+    pub enrolled_count: u64,
+    /// 1回あたりの支出（支給額 + platform fee。円建ての場合は直近の claim / refresh 時のレート）
+    pub per_claim_outflow: u64,
+    /// 最後に再計算した時点の period_index
+    pub period_index: u64,
+    /// 現在の期間を含めた残りの期間数（期限切れ後は 0）
+    pub remaining_periods: u64,
+    /// period_index の期間に記録した claim の数（期間が切り替わると 0 に戻る）
+    pub period_claims: u64,
+    pub committed_obligations: u128,
+    pub available_balance: u128,
+    /// committed_obligations が available_balance を上回る分（0 = 不足なし）
+    pub shortfall: u128,
+    pub updated_at: i64,
+    pub bump: u8,
+}
+
+/// 期限後の Grant の archive（open_archive で作成し、receipt を close した後も残す）
+/// - receipts_root は archive_receipts で記録した receipt の receipt_leaf を記録順に並べた、
+///   深さ ARCHIVE_TREE_DEPTH の木の root（未使用の葉は [0; 32]。MerkleProof::Positional で検証する）
+/// - total_* / claims_count / unique_claimers は finalize_archive 時点の Grant の集計
+#[account]
+#[derive(InitSpace)]
+pub struct GrantArchive {
+    pub grant: Pubkey,
+    pub opened_at: i64,
+    /// finalize_archive の実行時刻（0 = receipt の記録中）
+    pub finalized_at: i64,
+    /// 記録した receipt の数（次の葉の位置）
+    pub leaf_count: u64,
+    /// 追記中の木の frontier（merkle::frontier_append）
+    pub frontier: [[u8; 32]; merkle::ARCHIVE_TREE_DEPTH],
+    pub receipts_root: [u8; 32],
+    /// close_archived_receipts で close した receipt の数
+    pub closed_count: u64,
+    pub total_funded: u128,
+    pub total_claimed: u128,
+    pub total_platform_fees: u128,
+    pub claims_count: u64,
+    pub unique_claimers: u64,
+    pub bump: u8,
+}
+
+/// 受給者が ATA 以外の受給先を許可した記録（存在する間だけ有効。revoke_destination_opt_out で close）
+#[account]
+#[derive(InitSpace)]
+pub struct DestinationOptOut {
+    pub grant: Pubkey,
+    pub claimer: Pubkey,
+    pub bump: u8,
+}
+
+/// 受給者の通知先の設定のコミットメント（set_notification_preference で受給者本人が管理する。全 Grant 共通）
+#[account]
+#[derive(InitSpace)]
+pub struct NotificationPreference {
+    pub claimer: Pubkey,
+    /// オフチェーンの通知先の設定のハッシュ（連絡先そのものは置かない）
+    pub preference_hash: [u8; 32],
+    /// 更新回数（1 から）
+    pub revision: u32,
+    pub updated_at: i64,
+    pub bump: u8,
+}
+
+/// authority から受給者への暗号化メモ（attach_claimer_memo で添付し、次の claim で配信する）
+#[account]
+#[derive(InitSpace)]
+pub struct ClaimerMemo {
+    pub grant: Pubkey,
+    pub claimer: Pubkey,
+    /// 送信側の一時公開鍵（X25519）
+    pub ephemeral_pubkey: [u8; 32],
+    pub nonce: [u8; 24],
+    #[max_len(MAX_CLAIMER_MEMO_LEN)]
+    pub ciphertext: Vec<u8>,
+    /// 添付/差し替えの回数（1 から）
+    pub revision: u32,
+    pub attached_at: i64,
+    /// 配信した claim の時刻（0 = 未配信）
+    pub delivered_at: i64,
+    /// 配信した claim の period_index（未配信は 0）
+    pub delivered_period: u64,
+    pub bump: u8,
+}
+
+impl ClaimerMemo {
+    /// ClaimerMemoAttached に載せるコミットメント
+    /// memo_hash = sha256( "we-ne:claimer-memo" || ephemeral_pubkey || nonce || ciphertext )
+    pub fn memo_hash(&self) -> [u8; 32] {
+        hashv(&[
+            b"we-ne:claimer-memo",
+            self.ephemeral_pubkey.as_ref(),
+            self.nonce.as_ref(),
+            &self.ciphertext,
+        ])
+        .to_bytes()
+    }
+
+    pub fn is_delivered(&self) -> bool {
+        self.delivered_at != 0
+    }
+}
+
+/// claimer が登録した session key（scope の操作に限り claimer の代わりに署名できる）
+#[account]
+#[derive(InitSpace)]
+pub struct SessionAuthorization {
+    pub grant: Pubkey,
+    pub claimer: Pubkey,
+    pub session_key: Pubkey,
+    /// SESSION_SCOPE_* のビット集合
+    pub scope: u8,
+    pub expires_at: i64,
+    pub bump: u8,
+}
+
+/// delegate（session key）が代理で claim する受給者の索引（1ページ最大 DELEGATE_INDEX_PAGE_CAPACITY 件）
+/// 件数に応じて realloc するため InitSpace は持たない（サイズは space で計算する）
+/// custodial operator は getProgramAccounts（grant / delegate の memcmp）でページを列挙し、
+/// claimers ごとに session key で claim する
+#[account]
+pub struct DelegateIndexPage {
+    pub grant: Pubkey,
+    pub delegate: Pubkey,
+    pub page: u32,
+    pub bump: u8,
+    pub claimers: Vec<Pubkey>,
+}
+
+impl DelegateIndexPage {
+    /// claimers が len 件のときのアカウントサイズ（discriminator を含む）
+    pub const fn space(len: usize) -> usize {
+        8 + 32 + 32 + 4 + 1 + 4 + 32 * len
+    }
+}
+
+/// schedule_claim で予約された claim（実行または取り消しで close される）
+#[account]
+#[derive(InitSpace)]
+pub struct PendingClaim {
+    pub grant: Pubkey,
+    pub claimer: Pubkey,
+    pub period_index: u64,
+    /// この時刻以降に実行できる
+    pub execute_after: i64,
+    /// 実行できる keeper（Pubkey::default() = 誰でも）
+    pub keeper: Pubkey,
+    pub scheduled_at: i64,
+    pub bump: u8,
+}
+
+/// claimer ごとの受給明細（直近 STATEMENT_LOG_ENTRIES 件のリングバッファ）
+/// claim のたびに1件書き込むため zero-copy。パディングのない 272 bytes
+#[account(zero_copy)]
+#[derive(InitSpace)]
+pub struct StatementLog {
+    pub grant: Pubkey,
+    pub claimer: Pubkey,
+    /// これまでに記録した件数（次の書き込み位置は total_entries % STATEMENT_LOG_ENTRIES）
+    pub total_entries: u64,
+    pub entries: [StatementEntry; STATEMENT_LOG_ENTRIES],
+    pub bump: u8,
+    pub _reserved: [u8; 7],
+}
+
+impl StatementLog {
+    fn record(&mut self, period_index: u64, amount: u64, claimed_at: i64) {
+        let slot = (self.total_entries % STATEMENT_LOG_ENTRIES as u64) as usize;
+        self.entries[slot] = StatementEntry {
+            period_index,
+            amount,
+            claimed_at,
+        };
+        self.total_entries = self.total_entries.saturating_add(1);
+    }
+
+    /// 記録済みの明細（新しい順）
+    pub fn recent(&self) -> impl Iterator<Item = &StatementEntry> {
+        let len = self.total_entries.min(STATEMENT_LOG_ENTRIES as u64) as usize;
+        let next = (self.total_entries % STATEMENT_LOG_ENTRIES as u64) as usize;
+        (1..=len).map(move |i| &self.entries[(next + STATEMENT_LOG_ENTRIES - i) % STATEMENT_LOG_ENTRIES])
+    }
+}
+
+#[zero_copy]
+#[derive(InitSpace)]
+pub struct StatementEntry {
+    pub period_index: u64,
+    /// claimer に支給した額（streak ボーナスを含み、platform fee は含まない）
+    pub amount: u64,
+    pub claimed_at: i64,
+}
+
+/// claimer ごとの受給済み期間のビットセット（直近 CLAIM_INDEX_PERIODS 期間の窓）
+/// - ビット period_index % CLAIM_INDEX_PERIODS が受給の有無。last_period から窓の幅より前の期間は上書きされている
+/// - tracked_from より前の期間は、ここでの受給以外は記録されていない（is_claimed は None を返す）
+/// claim のたびに書き込むため zero-copy。パディングのない 160 bytes
+#[account(zero_copy)]
+#[derive(InitSpace)]
+pub struct ClaimIndex {
+    pub grant: Pubkey,
+    pub claimer: Pubkey,
+    /// 記録を始めた period_index（open_claim_index 時点の期間）
+    pub tracked_from: u64,
+    /// 記録した最新の period_index（受給がなければ tracked_from）
+    pub last_period: u64,
+    /// 窓の中で受給済みの期間数
+    pub claimed_count: u64,
+    pub bits: [u64; CLAIM_INDEX_WORDS],
+    pub bump: u8,
+    pub _reserved: [u8; 7],
+}
+
+impl ClaimIndex {
+    fn set(&mut self, period_index: u64, claimed: bool) {
+        let bit = (period_index % CLAIM_INDEX_PERIODS as u64) as usize;
+        let mask = 1u64 << (bit % 64);
+        let word = &mut self.bits[bit / 64];
+        let was_claimed = *word & mask != 0;
+        if claimed {
+            *word |= mask;
+        } else {
+            *word &= !mask;
+        }
+        match (was_claimed, claimed) {
+            (false, true) => self.claimed_count = self.claimed_count.saturating_add(1),
+            (true, false) => self.claimed_count = self.claimed_count.saturating_sub(1),
+            _ => {}
+        }
+    }
+
+    fn in_window(&self, period_index: u64) -> bool {
+        period_index <= self.last_period && self.last_period - period_index < CLAIM_INDEX_PERIODS as u64
+    }
+
+    fn record(&mut self, period_index: u64) {
+        if period_index > self.last_period {
+            // 窓を進める。新しく窓に入る期間のビットは1周前の期間のものなので消す
+            if period_index - self.last_period >= CLAIM_INDEX_PERIODS as u64 {
+                self.bits = [0u64; CLAIM_INDEX_WORDS];
+                self.claimed_count = 0;
+            } else {
+                for stale in self.last_period + 1..period_index {
+                    self.set(stale, false);
+                }
+            }
+            self.last_period = period_index;
+        } else if !self.in_window(period_index) {
+            return;
+        }
+        self.set(period_index, true);
+    }
+
+    fn clear(&mut self, period_index: u64) {
+        if self.in_window(period_index) {
+            self.set(period_index, false);
+        }
+    }
+
+    /// period_index の期間に受給したか（窓の外、または記録前で受給の記録がない場合は None）
+    pub fn is_claimed(&self, period_index: u64) -> Option<bool> {
+        if period_index > self.last_period {
+            return Some(false);
+        }
+        if !self.in_window(period_index) {
+            return None;
+        }
+        let bit = (period_index % CLAIM_INDEX_PERIODS as u64) as usize;
+        if self.bits[bit / 64] & (1u64 << (bit % 64)) != 0 {
+            Some(true)
+        } else {
+            (period_index >= self.tracked_from).then_some(false)
+        }
+    }
+}
+
+/// 終了したシーズンの集計（seeds にシーズン番号を含む）
+#[account]
+#[derive(InitSpace)]
+pub struct SeasonSummary {
+    pub grant: Pubkey,
+    pub season: u32,
+    pub start_ts: i64,
+    pub ended_at: i64,
+    pub first_period_index: u64,
+    /// 次シーズンの最初の period_index（このシーズンの期間は first_period_index 以上これ未満）
+    pub next_period_index: u64,
+    pub claims_count: u64,
+    pub total_claimed: u128,
+    pub bump: u8,
+}
+
+/// 期間ごとの受給集計（受給率をオフチェーンの申告に頼らず算出するため）
+/// claim のたびに更新するため zero-copy（borsh を経由せず固定レイアウトのまま読み書きする）。
+/// u128 を 16 byte 境界に置き、_reserved で末尾を埋めてパディングのない 96 bytes にしている
+#[account(zero_copy)]
+#[derive(InitSpace)]
+pub struct PeriodStats {
+    pub grant: Pubkey,
+    /// 期間内の受給額合計（platform fee を含まない）
+    pub total_claimed: u128,
+    pub period_index: u64,
+    /// 期間の初回記録時点の grant.eligible_count
+    pub eligible_count: u64,
+    pub claims_count: u64,
+    /// close_period の実行時刻（0 = 未締め）
+    pub closed_at: i64,
+    /// 締め時点の eligible_count - claims_count（eligible_count が未設定なら 0）
+    pub unclaimed_count: u64,
+    /// close_period 済みなら 1（bool は zero-copy で扱えないため u8）
+    pub closed: u8,
+    pub bump: u8,
+    pub _reserved: [u8; 6],
+}
+
+impl PeriodStats {
+    pub fn is_closed(&self) -> bool {
+        self.closed != 0
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct PlatformApproval {
+    pub organization: Pubkey,
+    pub platform_program: Pubkey,
+    /// platform_program の PDA（PLATFORM_SIGNER_SEED）
+    pub platform_signer: Pubkey,
+    pub max_fee_bps: u16,
+    pub bump: u8,
+}
+
+/// テスト用の現在時刻（test-clock feature 付きのビルドのみ。clock モジュール参照）
+#[cfg(feature = "test-clock")]
+#[account]
+#[derive(InitSpace)]
+pub struct ClockOverride {
+    /// 0 = 上書きなし
+    pub unix_timestamp: i64,
+    pub bump: u8,
+}
+
+// 確保するサイズ（8 + INIT_SPACE）の静的検証。レイアウトがずれた場合や上限を超えた場合はビルドを止める
+const _: () = {
+    // receipt モジュール・interface クレートが前提にする固定レイアウト（115 bytes）
+    assert!(8 + ClaimReceipt::INIT_SPACE == receipt::RECEIPT_ACCOUNT_LEN);
+    // zero-copy のアカウントはメモリ上の表現そのものを保存する（パディングがあると一致しない）
+    assert!(PeriodStats::INIT_SPACE == core::mem::size_of::<PeriodStats>());
+    assert!(StatementLog::INIT_SPACE == core::mem::size_of::<StatementLog>());
+    assert!(ClaimIndex::INIT_SPACE == core::mem::size_of::<ClaimIndex>());
+    // init は system program への CPI で作成するため、1命令で確保できる上限に収める
+    let accounts = [
+        Grant::INIT_SPACE,
+        ClaimReceipt::INIT_SPACE,
+        PopConfig::INIT_SPACE,
+        AuditAnchor::INIT_SPACE,
+        PopState::INIT_SPACE,
+        RateConfig::INIT_SPACE,
+        GrantTemplate::INIT_SPACE,
+        MatchPool::INIT_SPACE,
+        EscrowSchedule::INIT_SPACE,
+        FeatureFlags::INIT_SPACE,
+        TreasuryConfig::INIT_SPACE,
+        FundingBudget::INIT_SPACE,
+        SwapConfig::INIT_SPACE,
+        ProgramOfficer::INIT_SPACE,
+        KeeperConfig::INIT_SPACE,
+        ClaimStreak::INIT_SPACE,
+        ClaimerProfile::INIT_SPACE,
+        SessionAuthorization::INIT_SPACE,
+        DestinationOptOut::INIT_SPACE,
+        NotificationPreference::INIT_SPACE,
+        ClaimerMemo::INIT_SPACE,
+        HashDomainConfig::INIT_SPACE,
+        ReceiptNftCollection::INIT_SPACE,
+        ReceiptReservation::INIT_SPACE,
+        PendingClaim::INIT_SPACE,
+        BudgetForecast::INIT_SPACE,
+        GrantArchive::INIT_SPACE,
+        SeasonSummary::INIT_SPACE,
+        PeriodStats::INIT_SPACE,
+        PlatformApproval::INIT_SPACE,
+        StatementLog::INIT_SPACE,
+        ClaimIndex::INIT_SPACE,
+    ];
+    let mut i = 0;
+    while i < accounts.len() {
+        assert!(8 + accounts[i] <= MAX_PERMITTED_DATA_INCREASE);
+        i += 1;
+    }
+};
+
+// 可変長（Vec / String）のアカウントは max_len まで埋めた状態で確保サイズに収まるかを確認する
+#[cfg(test)]
+mod account_size_tests {
+    use super::*;
+
+    fn key(n: u8) -> Pubkey {
+        Pubkey::new_from_array([n; 32])
+    }
+
+    /// 直列化したサイズが space 以下で、デコードして再度直列化しても同じバイト列になること
+    fn assert_fits_and_round_trips<T: AccountSerialize + AccountDeserialize>(account: &T, space: usize) {
+        let mut data = Vec::new();
+        account.try_serialize(&mut data).unwrap();
+        assert!(data.len() <= space, "{} bytes > {} bytes", data.len(), space);
+
+        // 確保したアカウントと同じく末尾を 0 で埋めた状態からデコードする
+        let mut allocated = data.clone();
+        allocated.resize(space, 0);
+        let decoded = T::try_deserialize(&mut allocated.as_slice()).unwrap();
+        let mut reencoded = Vec::new();
+        decoded.try_serialize(&mut reencoded).unwrap();
+        assert_eq!(reencoded, data);
+    }
+
+    #[test]
+    fn grant_with_max_sponsor_logo_uri_fits() {
+        let grant = Grant {
+            authority: key(1),
+            sponsor_name_hash: [7; 32],
+            sponsor_logo_uri: "u".repeat(MAX_SPONSOR_URI_LEN),
+            allowlist_delta_roots: [[9; 32]; MAX_ALLOWLIST_DELTAS],
+            allowlist_delta_count: MAX_ALLOWLIST_DELTAS as u8,
+            ..Grant::default()
+        };
+        assert_fits_and_round_trips(&grant, 8 + Grant::INIT_SPACE);
+    }
+
+    #[test]
+    fn full_delegate_index_page_fits() {
+        let page = DelegateIndexPage {
+            grant: key(1),
+            delegate: key(2),
+            page: u32::MAX,
+            bump: 255,
+            claimers: (0..DELEGATE_INDEX_PAGE_CAPACITY).map(|i| key(i as u8)).collect(),
+        };
+        assert_fits_and_round_trips(&page, DelegateIndexPage::space(DELEGATE_INDEX_PAGE_CAPACITY));
+    }
+
+    #[test]
+    fn claimer_memo_with_max_ciphertext_fits() {
+        let memo = ClaimerMemo {
+            grant: key(1),
+            claimer: key(2),
+            ephemeral_pubkey: [3; 32],
+            nonce: [4; 24],
+            ciphertext: vec![0xff; MAX_CLAIMER_MEMO_LEN],
+            revision: u32::MAX,
+            attached_at: i64::MAX,
+            delivered_at: i64::MAX,
+            delivered_period: u64::MAX,
+            bump: 255,
+        };
+        assert_fits_and_round_trips(&memo, 8 + ClaimerMemo::INIT_SPACE);
+    }
+
+    #[test]
+    fn keeper_config_with_all_keepers_fits() {
+        let config = KeeperConfig {
+            grant: key(1),
+            period_close_bounty: u64::MAX,
+            bounty_from_pool: true,
+            bounty_pool: key(2),
+            keepers: core::array::from_fn(|i| key(10 + i as u8)),
+            total_bounties_paid: u64::MAX,
+            bump: 255,
+        };
+        assert_fits_and_round_trips(&config, 8 + KeeperConfig::INIT_SPACE);
+    }
+
+    #[test]
+    fn receipt_nft_collection_with_max_strings_fits() {
+        let collection = ReceiptNftCollection {
+            grant: key(1),
+            group_mint: key(2),
+            name: "n".repeat(MAX_RECEIPT_NFT_NAME_LEN),
+            symbol: "s".repeat(MAX_RECEIPT_NFT_SYMBOL_LEN),
+            uri: "u".repeat(MAX_SPONSOR_URI_LEN),
+            max_size: u64::MAX,
+            minted_count: u64::MAX,
+            bump: 255,
+            group_mint_bump: 255,
+        };
+        assert_fits_and_round_trips(&collection, 8 + ReceiptNftCollection::INIT_SPACE);
+    }
+
+    #[test]
+    fn escrow_schedule_with_max_tranches_fits() {
+        let tranche = EscrowTranche {
+            committed: u64::MAX,
+            funded: u64::MAX,
+            claimed: u64::MAX,
+        };
+        let schedule = EscrowSchedule {
+            grant: key(1),
+            tranches: vec![tranche; MAX_ESCROW_TRANCHES],
+            bump: 255,
+        };
+        assert_fits_and_round_trips(&schedule, 8 + EscrowSchedule::INIT_SPACE);
+    }
+}
+
+// ===== Helpers =====
+
+/// create_grant / create_grants_batch 共通の Grant パラメータ
+struct GrantParams {
+    amount_per_period: u64,
+    period_seconds: i64,
+    start_ts: i64,
+    expires_at: i64,
+    allow_freeze_authority: bool,
+    category: u16,
+}
+
+/// Grant PDA ごとに異なる値
+struct GrantKeys {
+    authority: Pubkey,
+    mint: Pubkey,
+    decimals: u8,
+    vault: Pubkey,
+    grant_id: u64,
+    bump: u8,
+}
+
+fn validate_grant_params(params: &GrantParams, mint: &Mint, now: i64) -> Result<()> {
+    require!(params.amount_per_period > 0, ErrorCode::InvalidAmount);
+    require_supported_mint(mint, params.allow_freeze_authority)?;
+    require!(params.period_seconds > 0, ErrorCode::InvalidPeriod);
+    require_valid_category(params.category)?;
+
+    // start_tsは未来でも良い（開始前にfundしておく想定）
+    // ただし極端に昔すぎるのはミスの可能性があるので軽く制限
+    require!(
+        params.start_ts <= now + 365_i64 * 24 * 60 * 60,
+        ErrorCode::InvalidStartTs
+    );
+    Ok(())
+}
+
+/// 予約範囲のうち未定義の分類値は拒否する（将来の追加と衝突させない）
+fn require_valid_category(category: u16) -> Result<()> {
+    require!(
+        category <= GRANT_CATEGORY_MAX_RESERVED || category >= GRANT_CATEGORY_CUSTOM_START,
+        ErrorCode::InvalidCategory
+    );
+    Ok(())
+}
+
+/// PDA のアカウントを作成する（Anchor の init と同じく、先に lamports だけ送られたアドレスにも作成できる）
+/// create_account は lamports のあるアドレスで失敗するため、その場合は不足分の送金・allocate・assign に分ける
+fn create_pda_account<'info>(
+    payer: &AccountInfo<'info>,
+    target: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    seeds: &[&[u8]],
+    lamports: u64,
+    space: usize,
+    owner: &Pubkey,
+) -> Result<()> {
+    let signer_seeds: &[&[&[u8]]] = &[seeds];
+    let current = target.lamports();
+    if current == 0 {
+        return create_account(
+            CpiContext::new_with_signer(
+                system_program.clone(),
+                CreateAccount {
+                    from: payer.clone(),
+                    to: target.clone(),
+                },
+                signer_seeds,
+            ),
+            lamports,
+            space as u64,
+            owner,
+        );
+    }
+
+    let top_up = lamports.saturating_sub(current);
+    if top_up > 0 {
+        anchor_lang::system_program::transfer(
+            CpiContext::new(
+                system_program.clone(),
+                anchor_lang::system_program::Transfer {
+                    from: payer.clone(),
+                    to: target.clone(),
+                },
+            ),
+            top_up,
+        )?;
+    }
+    allocate(
+        CpiContext::new_with_signer(
+            system_program.clone(),
+            Allocate {
+                account_to_allocate: target.clone(),
+            },
+            signer_seeds,
+        ),
+        space as u64,
+    )?;
+    assign(
+        CpiContext::new_with_signer(
+            system_program.clone(),
+            Assign {
+                account_to_assign: target.clone(),
+            },
+            signer_seeds,
+        ),
+        owner,
+    )
+}
+
+/// Token-2022 の mint を PDA に作成する（拡張の初期化と InitializeMint2 は呼び出し側）
+/// space は InitializeMint2 の前に必要な拡張の分だけにし、lamports は後から Token-2022 が realloc する
+/// 拡張（later_extensions と TokenMetadata）の分まで先に入れる
+fn create_token_2022_mint_account<'info>(
+    payer: &Signer<'info>,
+    mint: &AccountInfo<'info>,
+    system_program: &Program<'info, System>,
+    mint_seeds: &[&[u8]],
+    extensions: &[ExtensionType],
+    later_extensions: &[ExtensionType],
+    metadata_len: usize,
+) -> Result<()> {
+    let space = ExtensionType::try_calculate_account_len::<token_2022::spl_token_2022::state::Mint>(extensions)?;
+    let all_extensions = [extensions, later_extensions].concat();
+    let final_len =
+        ExtensionType::try_calculate_account_len::<token_2022::spl_token_2022::state::Mint>(&all_extensions)?
+            .checked_add(metadata_len)
+            .ok_or(ErrorCode::MathOverflow)?;
+    // mint のアドレスは予測できるため、先に lamports を送られても作成できるようにする
+    create_pda_account(
+        &payer.to_account_info(),
+        mint,
+        &system_program.to_account_info(),
+        mint_seeds,
+        Rent::get()?.minimum_balance(final_len),
+        space,
+        &token_2022::ID,
+    )
+}
+
+/// 作成する Grant に写す domain（HashDomainConfig が渡されなければ DEFAULT_HASH_DOMAIN）
+fn deployment_hash_domain(config: Option<&HashDomainConfig>) -> HashDomain {
+    config.map_or_else(HashDomain::default, |config| config.domain)
+}
+
+fn init_grant(grant: &mut Grant, keys: GrantKeys, params: &GrantParams) {
+    grant.authority = keys.authority;
+    grant.seed_authority = keys.authority;
+    grant.mint = keys.mint;
+    grant.seed_mint = keys.mint;
+    grant.mint_migration_pending_mint = Pubkey::default();
+    grant.mint_migration_amount_per_period = 0;
+    grant.mint_migration_unlocks_at = 0;
+    grant.vault_nonce = 0;
+    grant.decimals = keys.decimals;
+    grant.min_claim_interval_seconds = 0;
+    grant.escrow_earmarked = false;
+    grant.has_match_pool = false;
+    grant.eligible_count = 0;
+    grant.pop_monotonic_issued_at = false;
+    grant.pop_require_audit_hash = true;
+    grant.unique_claimers = 0;
+    grant.max_unique_claimers = 0;
+    grant.season = 0;
+    grant.season_period_offset = 0;
+    grant.season_base_claims_count = 0;
+    grant.season_base_total_claimed = 0;
+    grant.vault = keys.vault;
+    grant.grant_id = keys.grant_id;
+    grant.amount_per_period = params.amount_per_period;
+    grant.period_seconds = params.period_seconds;
+    grant.start_ts = params.start_ts;
+    grant.expires_at = params.expires_at;
+    // allowlist is optional; default is disabled
+    grant.merkle_root = [0u8; 32];
+    grant.allowlist_delta_roots = [[0u8; 32]; MAX_ALLOWLIST_DELTAS];
+    grant.allowlist_delta_count = 0;
+    grant.allowlist_snapshot_hash = [0u8; 32];
+    grant.allowlist_committed_at = 0;
+    grant.allowlist_summary_required = false;
+    grant.has_keeper_config = false;
+    grant.parent = Pubkey::default();
+    grant.region_code = 0;
+    grant.child_count = 0;
+    grant.children_claims_count = 0;
+    grant.children_total_claimed = 0;
+    grant.rolled_up_claims_count = 0;
+    grant.rolled_up_total_claimed = 0;
+    grant.wind_down_ends_at = 0;
+    grant.pop_bypass_until = 0;
+    grant.seed_salt = [0u8; SEED_SALT_LEN];
+    grant.admin_nonce = 0;
+    grant.attestation_program = Pubkey::default();
+    grant.attestation_config = Pubkey::default();
+    grant.target_take_up_bps = 0;
+    grant.target_unique_claimers = 0;
+    grant.kpi_milestones = 0;
+    grant.successor = Pubkey::default();
+    grant.succession_inactivity_seconds = 0;
+    grant.last_admin_action_at = 0;
+    grant.succession_unlocks_at = 0;
+    grant.eligibility_clauses = [0u8; MAX_ELIGIBILITY_CLAUSES];
+    grant.holding_mint = Pubkey::default();
+    grant.holding_min_amount = 0;
+    grant.enrollment_grant = Pubkey::default();
+    grant.has_budget_forecast = false;
+    grant.allowlist_pending = false;
+    grant.archive_opened_at = 0;
+    grant.archived_at = 0;
+    grant.claim_cpi_guard = false;
+    grant.claim_cpi_allowed_caller = Pubkey::default();
+    grant.pop_amount_ceiling = 0;
+    grant.stake_program = Pubkey::default();
+    grant.stake_mint = Pubkey::default();
+    grant.stake_min_amount = 0;
+    grant.stake_multiplier_steps = [StakeMultiplierStep::default(); MAX_STAKE_MULTIPLIER_STEPS];
+    grant.hash_domain = HashDomain::default();
+    grant.uncounted_receipts = 0;
+    grant.sponsor_name_hash = [0u8; 32];
+    grant.sponsor_logo_uri = String::new();
+    grant.paused = false;
+    grant.bump = keys.bump;
+    grant.category = params.category;
+    // 円建て額は任意（0 の場合は amount_per_period をそのまま使う）
+    grant.yen_per_period = 0;
+    // attestation gate は任意（default の場合は無効）
+    grant.gate_program = Pubkey::default();
+    grant.gate_config = Pubkey::default();
+    grant.pop_config_tracked = false;
+    grant.total_funded = 0;
+    grant.total_claimed = 0;
+    grant.claims_count = 0;
+    grant.co_admin = Pubkey::default();
+    grant.platform_fee_bps = 0;
+    grant.platform_fee_account = Pubkey::default();
+    grant.total_platform_fees = 0;
+    grant.reviewer = Pubkey::default();
+    grant.activated = false;
+    grant.activated_at = 0;
+    grant.recovery = Pubkey::default();
+    grant.recovery_pending_authority = Pubkey::default();
+    grant.recovery_unlocks_at = 0;
+    grant.streak_bonus_steps = [StreakBonusStep::default(); MAX_STREAK_BONUS_STEPS];
+    grant.period_anchor_ts = params.start_ts;
+    grant.prorate_partial_periods = false;
+    grant.blackout_windows = [BlackoutWindow::default(); MAX_BLACKOUT_WINDOWS];
+    grant.freeze_authority_acknowledged = params.allow_freeze_authority;
+}
+
+fn require_feature(flags: &FeatureFlags, feature: u64) -> Result<()> {
+    require!(flags.is_enabled(feature), ErrorCode::FeatureDisabled);
+    Ok(())
+}
+
+/// 発行者が vault を凍結できる mint は、明示的に許容された場合のみ受け付ける
+/// NOTE: 本プログラムは SPL Token（拡張なし）のみ対応のため default-account-state 拡張は存在しない
+fn require_supported_mint(mint: &Mint, allow_freeze_authority: bool) -> Result<()> {
+    require!(
+        mint.freeze_authority.is_none() || allow_freeze_authority,
+        ErrorCode::FreezableMint
+    );
+    Ok(())
+}
+
+/// vault は Grant PDA だけが動かせる状態であること（delegate / close authority / 凍結なし）
+fn require_clean_vault(vault: &TokenAccount) -> Result<()> {
+    require!(!vault.is_frozen(), ErrorCode::VaultFrozen);
+    require!(
+        vault.delegate.is_none() && vault.close_authority.is_none(),
+        ErrorCode::VaultAuthorityNotClean
+    );
+    Ok(())
+}
+
+/// 受給先が受給者の ATA であること（destination_opt_out を渡した場合は検査しない）
+fn require_canonical_destination(accounts: &ClaimGrant) -> Result<()> {
+    if accounts.destination_opt_out.is_some() {
+        return Ok(());
+    }
+    let canonical = get_associated_token_address(&accounts.beneficiary(), &accounts.grant.mint);
+    require_keys_eq!(
+        accounts.claimer_ata.key(),
+        canonical,
+        ErrorCode::NonCanonicalClaimerAta
+    );
+    Ok(())
+}
+
+/// 受給先の token account が受け取れる状態か（token CPI の奥で失敗させず、原因ごとのエラーにする）
+/// - close authority が付いた受給先は拒否する（受給後に owner 以外が close できてしまう）
+/// - 凍結されている場合、mint の freeze authority が Grant PDA で、mint アカウントが渡されていれば
+///   Grant PDA の署名で解凍してから送金する。それ以外は ClaimerAtaFrozen
+fn prepare_claimer_ata(accounts: &ClaimGrant) -> Result<()> {
+    let claimer_ata = &accounts.claimer_ata;
+    require!(
+        claimer_ata.close_authority.is_none(),
+        ErrorCode::ClaimerAtaHasCloseAuthority
+    );
+    if !claimer_ata.is_frozen() {
+        return Ok(());
+    }
 
-    #[account(mut)]
-    pub authority: Signer<'info>,
+    let grant = &accounts.grant;
+    let mint = accounts.mint.as_deref().ok_or(ErrorCode::ClaimerAtaFrozen)?;
+    require!(
+        mint.freeze_authority.contains(&grant.key()),
+        ErrorCode::ClaimerAtaFrozen
+    );
+    let grant_id_bytes = grant.grant_id.to_le_bytes();
+    let grant_seeds: &[&[u8]] = &[
+        b"grant",
+        grant.seed_authority.as_ref(),
+        grant.seed_mint.as_ref(),
+        &grant_id_bytes,
+        &[grant.bump],
+    ];
+    let signer_seeds: &[&[&[u8]]] = &[grant_seeds];
+    let cpi_accounts = ThawAccount {
+        account: claimer_ata.to_account_info(),
+        mint: mint.to_account_info(),
+        authority: grant.to_account_info(),
+    };
+    thaw_account(CpiContext::new_with_signer(
+        accounts.token_program.to_account_info(),
+        cpi_accounts,
+        signer_seeds,
+    ))
+}
 
-    pub token_program: Program<'info, Token>,
-    pub system_program: Program<'info, System>,
-    pub rent: Sysvar<'info, Rent>,
+/// reviewer 付き Grant は claim 前に承認が必要
+fn require_grant_activated(grant: &Grant) -> Result<()> {
+    require!(
+        grant.reviewer == Pubkey::default() || grant.activated,
+        ErrorCode::GrantNotActivated
+    );
+    Ok(())
 }
 
-#[derive(Accounts)]
-pub struct UpdateGrant<'info> {
-    #[account(
-        mut,
-        has_one = authority,
-        seeds = [b"grant", authority.key().as_ref(), grant.mint.as_ref(), &grant.grant_id.to_le_bytes()],
-        bump = grant.bump
-    )]
-    pub grant: Account<'info, Grant>,
+/// claim の受給者（session key の署名なら SessionAuthorization.claimer、claim intent なら intent_claimer、
+/// 予約実行なら PendingClaim.claimer、それ以外は署名者本人）
+fn claim_beneficiary(
+    claimer: &Signer,
+    session: &Option<Box<Account<SessionAuthorization>>>,
+    intent_claimer: &Option<UncheckedAccount>,
+    pending_claim: &Option<Box<Account<PendingClaim>>>,
+) -> Pubkey {
+    match (session, intent_claimer, pending_claim) {
+        (Some(session), _, _) => session.claimer,
+        (None, Some(intent_claimer), _) => intent_claimer.key(),
+        (None, None, Some(pending_claim)) => pending_claim.claimer,
+        (None, None, None) => claimer.key(),
+    }
+}
 
-    pub authority: Signer<'info>,
+/// keeper による予約 claim の実行条件（session / intent とは併用できない）
+fn require_pending_claim_executable(pending: &PendingClaim, keeper: &Pubkey, now: i64) -> Result<()> {
+    require!(
+        pending.keeper == Pubkey::default() || pending.keeper == *keeper,
+        ErrorCode::KeeperNotAllowed
+    );
+    require!(now >= pending.execute_after, ErrorCode::ScheduledClaimNotReady);
+    Ok(())
 }
 
-#[derive(Accounts)]
-pub struct FundGrant<'info> {
-    #[account(
-        mut,
-        has_one = authority,
-        seeds = [b"grant", authority.key().as_ref(), mint.key().as_ref(), &grant.grant_id.to_le_bytes()],
-        bump = grant.bump
-    )]
-    pub grant: Account<'info, Grant>,
+fn require_valid_session(session: &SessionAuthorization, signer: &Pubkey, scope: u8, now: i64) -> Result<()> {
+    require_keys_eq!(session.session_key, *signer, ErrorCode::InvalidSession);
+    require!(session.scope & scope == scope, ErrorCode::SessionScopeNotAllowed);
+    require!(now < session.expires_at, ErrorCode::SessionExpired);
+    Ok(())
+}
 
-    pub mint: Account<'info, Mint>,
+/// 支給条件の変更時、reviewer 付き Grant はドラフトに戻して再承認を要求する
+fn invalidate_review(grant: &mut Grant) {
+    if grant.reviewer != Pubkey::default() {
+        grant.activated = false;
+        grant.activated_at = 0;
+    }
+}
 
-    #[account(
-        mut,
-        seeds = [b"vault", grant.key().as_ref()],
-        bump
-    )]
-    pub vault: Account<'info, TokenAccount>,
+/// now の時点の period_index（開始前は最初の期間）
+fn current_period_index(grant: &Grant, now: i64) -> Result<u64> {
+    let elapsed = now.max(grant.start_ts).saturating_sub(grant.period_anchor_ts).max(0);
+    grant
+        .season_period_offset
+        .checked_add((elapsed / grant.period_seconds) as u64)
+        .ok_or(ErrorCode::MathOverflow.into())
+}
 
-    /// 入金元（ATAなど）
-    #[account(
-        mut,
-        constraint = from_ata.mint == mint.key() @ ErrorCode::MintMismatch,
-        constraint = from_ata.owner == funder.key() @ ErrorCode::Unauthorized
-    )]
-    pub from_ata: Account<'info, TokenAccount>,
+/// BudgetForecast を Grant の現在の状態で再計算する
+/// - per_claim_outflow: 1回あたりの支出（None = 円建て以外は Grant の設定から、円建ては前回の値を使う）
+/// - claimed_period: この命令で記録した claim の period_index（claim 以外は None）
+fn update_budget_forecast(
+    forecast: &mut BudgetForecast,
+    grant: &Grant,
+    per_claim_outflow: Option<u64>,
+    claimed_period: Option<u64>,
+    now: i64,
+) -> Result<()> {
+    if let Some(outflow) = per_claim_outflow {
+        forecast.per_claim_outflow = outflow;
+    } else if grant.yen_per_period == 0 {
+        let payout = grant.amount_per_period;
+        forecast.per_claim_outflow = add_amounts(payout, bps_of(payout, grant.platform_fee_bps)?)?;
+    }
 
-    #[account(mut)]
-    pub funder: Signer<'info>,
+    let period_index = current_period_index(grant, now)?;
+    if period_index != forecast.period_index {
+        forecast.period_index = period_index;
+        forecast.period_claims = 0;
+    }
+    if claimed_period == Some(period_index) {
+        forecast.period_claims = forecast
+            .period_claims
+            .checked_add(1)
+            .ok_or(ErrorCode::MathOverflow)?;
+    }
 
-    /// Grant作成者（has_oneのため）
-    pub authority: SystemAccount<'info>,
+    forecast.remaining_periods = if grant.expires_at == 0 {
+        1
+    } else if now > grant.expires_at {
+        0
+    } else {
+        // 期限の直前の時刻を含む期間までを数える
+        let last_period = current_period_index(grant, grant.expires_at.saturating_sub(1))?;
+        last_period.saturating_sub(period_index).saturating_add(1)
+    };
+    forecast.enrolled_count = if grant.eligible_count > 0 {
+        grant.eligible_count
+    } else {
+        grant.unique_claimers
+    };
 
-    pub token_program: Program<'info, Token>,
+    let pending_claims = (forecast.enrolled_count as u128)
+        .saturating_mul(forecast.remaining_periods as u128)
+        .saturating_sub(forecast.period_claims as u128);
+    forecast.committed_obligations = pending_claims.saturating_mul(forecast.per_claim_outflow as u128);
+    forecast.available_balance = grant
+        .total_funded
+        .saturating_sub(grant.total_claimed)
+        .saturating_sub(grant.total_platform_fees);
+    forecast.shortfall = forecast
+        .committed_obligations
+        .saturating_sub(forecast.available_balance);
+    forecast.updated_at = now;
+    Ok(())
 }
 
-#[derive(Accounts)]
-#[instruction(period_index: u64)]
-pub struct ClaimGrant<'info> {
-    #[account(
-        mut,
-        seeds = [b"grant", grant.authority.as_ref(), grant.mint.as_ref(), &grant.grant_id.to_le_bytes()],
-        bump = grant.bump
-    )]
-    pub grant: Account<'info, Grant>,
+/// budget_forecast が渡されていれば再計算し、Grant に BudgetForecast があるのに渡されていなければ拒否する
+fn update_budget_forecast_if_present(
+    forecast: Option<&mut BudgetForecast>,
+    grant: &Grant,
+    per_claim_outflow: Option<u64>,
+    claimed_period: Option<u64>,
+    now: i64,
+) -> Result<()> {
+    match forecast {
+        Some(forecast) => update_budget_forecast(forecast, grant, per_claim_outflow, claimed_period, now),
+        None => {
+            require!(!grant.has_budget_forecast, ErrorCode::BudgetForecastRequired);
+            Ok(())
+        }
+    }
+}
 
-    pub mint: Account<'info, Mint>,
+fn require_claim_timing(grant: &Grant, now: i64, period_index: u64) -> Result<()> {
+    if grant.expires_at != 0 {
+        require!(now <= grant.expires_at, ErrorCode::GrantExpired);
+    }
 
-    #[account(
-        mut,
-        seeds = [b"vault", grant.key().as_ref()],
-        bump
-    )]
-    pub vault: Account<'info, TokenAccount>,
+    require!(now >= grant.start_ts, ErrorCode::GrantNotStarted);
 
-    /// 受給者
-    #[account(mut)]
-    pub claimer: Signer<'info>,
+    // period_index はクライアントから渡される（receipt PDA の seed 用）
+    // 不正防止のため、オンチェーンで現在の period_index を再計算して一致を要求
+    // 期間の区切りは period_anchor_ts 起点（既定は start_ts と同じ）
+    // 番号はシーズンをまたいで通し（season_period_offset から始まる）
+    let elapsed = now
+        .checked_sub(grant.period_anchor_ts)
+        .ok_or(ErrorCode::MathOverflow)?;
+    let expected_period_index = grant
+        .season_period_offset
+        .checked_add((elapsed / grant.period_seconds) as u64)
+        .ok_or(ErrorCode::MathOverflow)?;
+    // 境界をまたいだ直後（PERIOD_BOUNDARY_GRACE_SECONDS 以内）は直前の期間も可（前シーズンの期間は除く）
+    let within_grace = elapsed % grant.period_seconds <= PERIOD_BOUNDARY_GRACE_SECONDS;
+    let accepted = period_index == expected_period_index
+        || (within_grace
+            && expected_period_index > grant.season_period_offset
+            && period_index == expected_period_index - 1);
+    if !accepted {
+        // クライアントが再計算せずに再試行できるよう、期待値をログに残す
+        msg!("expected period_index: {}", expected_period_index);
+        return err!(ErrorCode::InvalidPeriodIndex);
+    }
 
-    /// 受給先（ATAなど）
-    #[account(
-        mut,
-        constraint = claimer_ata.mint == mint.key() @ ErrorCode::MintMismatch,
-        constraint = claimer_ata.owner == claimer.key() @ ErrorCode::Unauthorized
-    )]
-    pub claimer_ata: Account<'info, TokenAccount>,
+    Ok(())
+}
 
-    /// 期間内1回の受給を保証するレシート（同一期間の二重 claim 時は init が失敗する）
-    #[account(
-        init,
-        payer = claimer,
-        space = 8 + ClaimReceipt::INIT_SPACE,
-        seeds = [
-            b"receipt",
-            grant.key().as_ref(),
-            claimer.key().as_ref(),
-            &period_index.to_le_bytes(),
-        ],
-        bump
-    )]
-    pub receipt: Account<'info, ClaimReceipt>,
+/// period_index の期間の開始時刻
+fn period_start_ts(grant: &Grant, period_index: u64) -> Result<i64> {
+    let season_index = period_index
+        .checked_sub(grant.season_period_offset)
+        .ok_or(ErrorCode::InvalidPeriodIndex)?;
+    let offset = i64::try_from(season_index)
+        .ok()
+        .and_then(|index| index.checked_mul(grant.period_seconds))
+        .ok_or(ErrorCode::MathOverflow)?;
+    grant
+        .period_anchor_ts
+        .checked_add(offset)
+        .ok_or(ErrorCode::MathOverflow.into())
+}
 
-    #[account(
-        init_if_needed,
-        payer = claimer,
-        space = 8 + PopState::INIT_SPACE,
-        seeds = [b"pop-state", grant.key().as_ref()],
-        bump
-    )]
-    pub pop_state: Account<'info, PopState>,
+/// 日割り有効時、期間のうち受給資格がある部分（start_ts 〜 expires_at）の比率で按分する
+fn prorate_amount(grant: &Grant, period_index: u64, amount: u64) -> Result<u64> {
+    if !grant.prorate_partial_periods {
+        return Ok(amount);
+    }
 
-    #[account(
-        seeds = [b"pop-config", grant.authority.as_ref()],
-        bump = pop_config.bump,
-        constraint = pop_config.authority == grant.authority @ ErrorCode::InvalidPopConfigAuthority
-    )]
-    pub pop_config: Account<'info, PopConfig>,
+    let period_start = period_start_ts(grant, period_index)?;
+    let period_end = period_start
+        .checked_add(grant.period_seconds)
+        .ok_or(ErrorCode::MathOverflow)?;
+    let eligible_start = period_start.max(grant.start_ts);
+    let eligible_end = if grant.expires_at != 0 {
+        period_end.min(grant.expires_at)
+    } else {
+        period_end
+    };
+    let eligible_seconds = eligible_end.saturating_sub(eligible_start).max(0);
 
-    /// CHECK: Instructions Sysvar account (required for Ed25519 proof verification)
-    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
-    pub instructions_sysvar: UncheckedAccount<'info>,
+    mul_div(amount, eligible_seconds as u64, grant.period_seconds as u64)
+}
 
-    pub token_program: Program<'info, Token>,
-    pub system_program: Program<'info, System>,
-    pub rent: Sysvar<'info, Rent>,
+/// claim 1回あたりの支給額（最小単位）を決定する
+/// - 円建て無効: amount_per_period
+/// - 円建て有効: yen_per_period * tokens_per_yen（RateConfig 必須）
+fn claim_payout_amount(grant: &Grant, rate_config: Option<&RateConfig>) -> Result<u64> {
+    if grant.yen_per_period == 0 {
+        return Ok(grant.amount_per_period);
+    }
+
+    let rate_config = rate_config.ok_or(ErrorCode::RateConfigRequired)?;
+    let amount = mul_amount(grant.yen_per_period, rate_config.tokens_per_yen)?;
+    require!(amount > 0, ErrorCode::InvalidAmount);
+    Ok(amount)
 }
 
-#[derive(Accounts)]
-pub struct UpsertPopConfig<'info> {
-    #[account(
-        init_if_needed,
-        payer = authority,
-        space = 8 + PopConfig::INIT_SPACE,
-        seeds = [b"pop-config", authority.key().as_ref()],
-        bump
-    )]
-    pub pop_config: Account<'info, PopConfig>,
+/// gate 有効時、claimer 向けの標準アテステーションを検証する（レイアウトはファイル先頭参照）
+fn require_gate_attestation(
+    grant: &Grant,
+    claimer: &Pubkey,
+    attestation: Option<&UncheckedAccount>,
+    now: i64,
+) -> Result<()> {
+    if grant.gate_program == Pubkey::default() {
+        return Ok(());
+    }
 
-    #[account(mut)]
-    pub authority: Signer<'info>,
+    let attestation = attestation.ok_or(ErrorCode::GateAttestationRequired)?;
+    require!(
+        *attestation.owner == grant.gate_program,
+        ErrorCode::InvalidGateAttestation
+    );
 
-    pub system_program: Program<'info, System>,
-    pub rent: Sysvar<'info, Rent>,
+    let data = attestation.try_borrow_data()?;
+    require!(data.len() >= GATE_ATTESTATION_LEN, ErrorCode::InvalidGateAttestation);
+    require!(
+        data[0..8] == GATE_ATTESTATION_DISCRIMINATOR,
+        ErrorCode::InvalidGateAttestation
+    );
+
+    let mut offset = 8usize;
+    let config = read_pubkey(&data, &mut offset)?;
+    let subject = read_pubkey(&data, &mut offset)?;
+    let expires_at = read_i64_le(&data, &mut offset)?;
+    let approved = data[offset];
+
+    require!(config == grant.gate_config, ErrorCode::InvalidGateAttestation);
+    require!(subject == *claimer, ErrorCode::InvalidGateAttestation);
+    require!(approved == 1, ErrorCode::InvalidGateAttestation);
+    if expires_at != 0 {
+        require!(now <= expires_at, ErrorCode::GateAttestationExpired);
+    }
+
+    Ok(())
 }
 
-#[derive(Accounts)]
-pub struct CloseGrant<'info> {
-    #[account(
-        mut,
-        has_one = authority,
-        close = authority,
-        seeds = [b"grant", authority.key().as_ref(), mint.key().as_ref(), &grant.grant_id.to_le_bytes()],
-        bump = grant.bump
-    )]
-    pub grant: Account<'info, Grant>,
+/// 受給資格の述語を評価する（Grant に述語がある場合のみ呼ぶ）
+/// - allowlisted: 呼び出し側で proof を検証済みか
+/// - 各 gate は対応するアカウントが渡されなければ不成立。渡されたアカウントが不正な場合はエラーにする
+fn require_eligibility(accounts: &ClaimGrant, allowlisted: bool, now: i64) -> Result<()> {
+    let grant = &accounts.grant;
+    let beneficiary = accounts.beneficiary();
+    let mut satisfied = 0u8;
+    if allowlisted {
+        satisfied |= ELIGIBILITY_ALLOWLIST;
+    }
+    if let Some(holding) = accounts.holding_token_account.as_deref() {
+        if holding.amount >= grant.holding_min_amount {
+            satisfied |= ELIGIBILITY_TOKEN_HOLDING;
+        }
+    }
+    if grant.gate_program != Pubkey::default() && accounts.gate_attestation.is_some() {
+        require_gate_attestation(grant, &beneficiary, accounts.gate_attestation.as_ref(), now)?;
+        satisfied |= ELIGIBILITY_ATTESTATION;
+    }
+    if let Some(profile) = accounts.enrollment_profile.as_deref() {
+        if profile.claims_count > 0 {
+            satisfied |= ELIGIBILITY_ENROLLMENT;
+        }
+    }
 
-    pub mint: Account<'info, Mint>,
+    require!(grant.eligibility_satisfied(satisfied), ErrorCode::NotEligible);
+    Ok(())
+}
 
-    #[account(
-        mut,
-        seeds = [b"vault", grant.key().as_ref()],
-        bump
-    )]
-    pub vault: Account<'info, TokenAccount>,
+/// claim 系命令の構造化ログの項目（amount は receipt に記録した支給額）
+fn claim_log_line<'a>(instruction: &'a str, accounts: &ClaimGrant, period_index: u64) -> LogLine<'a> {
+    LogLine {
+        instruction,
+        grant: accounts.grant.key(),
+        claimer: Some(accounts.beneficiary()),
+        period: Some(period_index),
+        amount: Some(accounts.receipt.amount),
+    }
+}
 
-    /// 返金先（authorityのATA）
-    #[account(
-        mut,
-        constraint = authority_ata.mint == mint.key() @ ErrorCode::MintMismatch,
-        constraint = authority_ata.owner == authority.key() @ ErrorCode::Unauthorized
-    )]
-    pub authority_ata: Account<'info, TokenAccount>,
+/// PoP 検証 → 期間検証 → 送金 → receipt 記録（claim 系命令の共通処理）
+/// allowlist / pause など命令ごとの前提条件は呼び出し側で検証すること。
+/// fee_recipients: TreasuryConfig.fee_splits の受取先（remaining_accounts、fee_splits と同じ順）
+fn execute_claim<'info>(
+    accounts: &mut ClaimGrant<'info>,
+    bumps: &ClaimGrantBumps,
+    fee_recipients: &[AccountInfo<'info>],
+    period_index: u64,
+    now: i64,
+) -> Result<()> {
+    let pop_state_bump = bumps.pop_state;
+    let streak_bump = bumps.claim_streak.unwrap_or_default();
+    let receipt_bump = bumps.receipt;
+    let profile_bump = bumps.claimer_profile;
+    let period_stats_bump = bumps.period_stats;
+    require_grant_activated(&accounts.grant)?;
+    // allowlist を使う予定の Grant は、root が設定されるまで誰の claim も受け付けない
+    require!(!accounts.grant.allowlist_pending, ErrorCode::NotYetOpen);
+    require_claim_caller(&accounts.grant, &accounts.instructions_sysvar)?;
+    if let Some(session) = accounts.session_authorization.as_deref() {
+        require_valid_session(session, &accounts.claimer.key(), SESSION_SCOPE_CLAIM, now)?;
+    }
+    if let Some(pending) = accounts.pending_claim.as_deref() {
+        require!(
+            accounts.session_authorization.is_none() && accounts.intent_claimer.is_none(),
+            ErrorCode::InvalidScheduledClaim
+        );
+        require_pending_claim_executable(pending, &accounts.claimer.key(), now)?;
+        emit!(ScheduledClaimExecuted {
+            schema_version: EVENT_SCHEMA_VERSION,
+            grant: pending.grant,
+            claimer: pending.claimer,
+            period_index,
+            keeper: accounts.claimer.key(),
+        });
+    }
+    init_pop_state(accounts, pop_state_bump)?;
+    let pop_bypassed = is_pop_bypass_active(&accounts.grant, now);
+    if accounts.intent_claimer.is_some() {
+        require!(accounts.session_authorization.is_none(), ErrorCode::InvalidClaimIntent);
+        verify_claim_intent(
+            &accounts.instructions_sysvar.to_account_info(),
+            &accounts.grant.key(),
+            &accounts.beneficiary(),
+            &accounts.claimer.key(),
+            period_index,
+            now,
+            pop_bypassed,
+        )?;
+    }
+    let beneficiary = accounts.beneficiary();
+    require!(
+        !accounts.grant.blackout_windows.iter().any(|w| w.contains(now)),
+        ErrorCode::ClaimBlackout
+    );
+    // 述語がある場合、attestation gate は require_eligibility で述語の条件として評価済み
+    if !accounts.grant.has_eligibility_predicate() {
+        require_gate_attestation(
+            &accounts.grant,
+            &beneficiary,
+            accounts.gate_attestation.as_ref(),
+            now,
+        )?;
+    }
+    let authorized_amount = if pop_bypassed {
+        None
+    } else {
+        verify_and_record_pop_proof(accounts, period_index, now)?
+    };
+    let grant = &accounts.grant;
+    require_claim_timing(grant, now, period_index)?;
+    // receipt PDA の seed に period_index が含まれているため、同じ期間の receipt は1つしかない
+    // （Accounts 側で init_if_needed される）。新規作成か予約済み（Reserved）の場合のみ受給でき、
+    // 受給済みの receipt（Reserved 以外）があれば二重受給として拒否する
+    require!(
+        accounts.receipt.grant == Pubkey::default() || accounts.receipt.status == ReceiptStatus::Reserved,
+        ErrorCode::ReceiptAlreadyClaimed
+    );
+    let claims_reserved_receipt = accounts.receipt.grant != Pubkey::default();
 
-    #[account(mut)]
-    pub authority: Signer<'info>,
+    let base_amount = prorate_amount(
+        grant,
+        period_index,
+        claim_payout_amount(grant, accounts.rate_config.as_deref())?,
+    )?;
+    require!(
+        grant.min_claim_interval_seconds == 0 || accounts.claim_streak.is_some(),
+        ErrorCode::ClaimStreakRequired
+    );
+    let amount = match accounts.claim_streak.as_deref_mut() {
+        Some(streak) => {
+            if grant.min_claim_interval_seconds > 0 && streak.last_claimed_at != 0 {
+                let since_last = now
+                    .checked_sub(streak.last_claimed_at)
+                    .ok_or(ErrorCode::MathOverflow)?;
+                require!(
+                    since_last >= grant.min_claim_interval_seconds,
+                    ErrorCode::ClaimTooSoon
+                );
+            }
+            streak.last_claimed_at = now;
+            let current = advance_claim_streak(
+                streak,
+                grant.key(),
+                beneficiary,
+                period_index,
+                streak_bump,
+            );
+            let bonus = bps_of(base_amount, streak_bonus_bps(grant, current))?;
+            add_amounts(base_amount, bonus)?
+        }
+        None => base_amount,
+    };
+    let stake_bps = stake_multiplier_bps(grant, &beneficiary, accounts.stake_record.as_ref(), now)?;
+    let amount = mul_div(amount, stake_bps as u64, BPS_DENOMINATOR)?;
+    // v3 の PoP message で署名者が額を指定した場合は、計算した額の代わりにその額を支給する
+    // （streak の記録は上で通常どおり進める）
+    let amount = match authorized_amount {
+        Some(authorized) => {
+            require!(
+                grant.pop_amount_ceiling != 0 && authorized <= grant.pop_amount_ceiling,
+                ErrorCode::PopAmountAboveCeiling
+            );
+            emit!(PopAmountAuthorized {
+                schema_version: EVENT_SCHEMA_VERSION,
+                grant: grant.key(),
+                claimer: beneficiary,
+                period_index,
+                authorized_amount: authorized,
+                computed_amount: amount,
+                ceiling: grant.pop_amount_ceiling,
+            });
+            authorized
+        }
+        None => amount,
+    };
+    // 初回受給の claimer は受給者数の上限を確認する
+    let is_new_claimer = accounts.claimer_profile.claims_count == 0;
+    if is_new_claimer {
+        require!(
+            grant.max_unique_claimers == 0 || grant.unique_claimers < grant.max_unique_claimers,
+            ErrorCode::MaxUniqueClaimersReached
+        );
+    }
+    let platform_fee = bps_of(amount, grant.platform_fee_bps)?;
+    let outflow = add_amounts(amount, platform_fee)?;
+    require!(
+        accounts.vault.amount >= outflow,
+        ErrorCode::InsufficientFunds
+    );
+    if grant.escrow_earmarked {
+        let schedule = accounts
+            .escrow_schedule
+            .as_deref_mut()
+            .ok_or(ErrorCode::EscrowScheduleRequired)?;
+        draw_from_tranche(schedule, period_index, outflow)?;
+    }
+    prepare_claimer_ata(accounts)?;
+    transfer_from_vault(
+        &accounts.grant,
+        &accounts.vault,
+        &accounts.claimer_ata,
+        &accounts.token_program,
+        amount,
+    )?;
+    if platform_fee > 0 {
+        let platform_fee_account = accounts
+            .platform_fee_account
+            .as_ref()
+            .ok_or(ErrorCode::PlatformFeeAccountMismatch)?;
+        transfer_from_vault(
+            &accounts.grant,
+            &accounts.vault,
+            platform_fee_account,
+            &accounts.token_program,
+            platform_fee,
+        )?;
+    }
+    record_receipt(
+        &mut accounts.receipt,
+        grant,
+        beneficiary,
+        period_index,
+        now,
+        amount,
+        receipt_bump,
+        pop_bypassed,
+    );
+    record_claimer_profile(
+        &mut accounts.claimer_profile,
+        grant.key(),
+        beneficiary,
+        period_index,
+        amount,
+        profile_bump,
+    )?;
+    {
+        let mut stats = load_period_stats(&accounts.period_stats)?;
+        touch_period_stats(&mut stats, grant, period_index, period_stats_bump);
+        stats.claims_count = stats
+            .claims_count
+            .checked_add(1)
+            .ok_or(ErrorCode::MathOverflow)?;
+        stats.total_claimed = add_to_total(stats.total_claimed, amount)?;
+    }
+    if let Some(statement_log) = accounts.statement_log.as_ref() {
+        statement_log.load_mut()?.record(period_index, amount, now);
+    }
+    match accounts.claim_index.as_ref() {
+        Some(claim_index) => claim_index.load_mut()?.record(period_index),
+        None => require!(!accounts.claimer_profile.has_claim_index, ErrorCode::ClaimIndexRequired),
+    }
+    if pop_bypassed {
+        emit!(PopBypassClaimed {
+            schema_version: EVENT_SCHEMA_VERSION,
+            grant: grant.key(),
+            claimer: beneficiary,
+            period_index,
+            amount,
+            bypass_until: grant.pop_bypass_until,
+            claimed_at: now,
+        });
+    }
+    if grant.sponsor_name_hash != [0u8; 32] {
+        emit!(SponsoredClaim {
+            schema_version: EVENT_SCHEMA_VERSION,
+            grant: grant.key(),
+            claimer: beneficiary,
+            period_index,
+            amount,
+            sponsor_name_hash: grant.sponsor_name_hash,
+            sponsor_logo_uri: grant.sponsor_logo_uri.clone(),
+        });
+    }
+    if grant.attestation_program != Pubkey::default() {
+        issue_disbursement_attestation(accounts, &beneficiary, period_index, amount, now)?;
+    }
+    if let Some(memo) = accounts.claimer_memo.as_deref_mut() {
+        deliver_claimer_memo(memo, period_index, now);
+    }
 
-    pub token_program: Program<'info, Token>,
+    let grant = &mut accounts.grant;
+    grant.total_claimed = add_to_total(grant.total_claimed, amount)?;
+    grant.claims_count = grant
+        .claims_count
+        .checked_add(1)
+        .ok_or(ErrorCode::MathOverflow)?;
+    grant.total_platform_fees = add_to_total(grant.total_platform_fees, platform_fee)?;
+    if claims_reserved_receipt {
+        // 予約の仕組みの導入前に作成された Reserved の receipt は数えていないため、0 で止める
+        grant.uncounted_receipts = grant.uncounted_receipts.saturating_sub(1);
+    }
+    collect_claim_fee(accounts, fee_recipients)?;
+    let grant = &mut accounts.grant;
+    if is_new_claimer {
+        grant.unique_claimers = grant
+            .unique_claimers
+            .checked_add(1)
+            .ok_or(ErrorCode::MathOverflow)?;
+    }
+    update_budget_forecast_if_present(
+        accounts.budget_forecast.as_deref_mut().map(|forecast| &mut **forecast),
+        &accounts.grant,
+        Some(add_amounts(amount, platform_fee)?),
+        Some(period_index),
+        now,
+    )
 }
 
-#[derive(Accounts)]
-pub struct SetPaused<'info> {
-    #[account(
-        mut,
-        has_one = authority,
-        seeds = [b"grant", authority.key().as_ref(), mint.key().as_ref(), &grant.grant_id.to_le_bytes()],
-        bump = grant.bump
-    )]
-    pub grant: Account<'info, Grant>,
-
-    pub mint: Account<'info, Mint>,
-
-    pub authority: Signer<'info>,
+/// 未配信の ClaimerMemo を ClaimerMemoDelivered で配信済みにする（配信済みなら何もしない）
+fn deliver_claimer_memo(memo: &mut ClaimerMemo, period_index: u64, now: i64) {
+    if memo.is_delivered() {
+        return;
+    }
+    memo.delivered_at = now;
+    memo.delivered_period = period_index;
+    emit!(ClaimerMemoDelivered {
+        schema_version: EVENT_SCHEMA_VERSION,
+        grant: memo.grant,
+        claimer: memo.claimer,
+        period_index,
+        ephemeral_pubkey: memo.ephemeral_pubkey,
+        nonce: memo.nonce,
+        ciphertext: memo.ciphertext.clone(),
+        revision: memo.revision,
+    });
 }
 
-#[derive(Accounts)]
-pub struct SetAllowlistRoot<'info> {
-    #[account(
-        mut,
-        has_one = authority,
-        seeds = [b"grant", authority.key().as_ref(), mint.key().as_ref(), &grant.grant_id.to_le_bytes()],
-        bump = grant.bump
-    )]
-    pub grant: Account<'info, Grant>,
-
-    pub mint: Account<'info, Mint>,
+/// TreasuryConfig.fee_splits を置き換える（bps 合計は 10_000 以下、受取先の重複なし）
+fn write_fee_splits(config: &mut TreasuryConfig, splits: &[FeeSplit]) -> Result<()> {
+    require!(splits.len() <= MAX_FEE_SPLITS, ErrorCode::InvalidFeeSplits);
+    let mut total_bps: u64 = 0;
+    for (i, split) in splits.iter().enumerate() {
+        require!(split.bps > 0, ErrorCode::InvalidFeeSplits);
+        require!(split.recipient != Pubkey::default(), ErrorCode::InvalidFeeSplits);
+        require!(
+            split.role <= FEE_ROLE_REFERRER,
+            ErrorCode::InvalidFeeSplits
+        );
+        require!(
+            splits[..i].iter().all(|other| other.recipient != split.recipient),
+            ErrorCode::InvalidFeeSplits
+        );
+        total_bps += split.bps as u64;
+    }
+    require!(total_bps <= BPS_DENOMINATOR, ErrorCode::InvalidFeeSplits);
 
-    pub authority: Signer<'info>,
+    config.fee_splits = [FeeSplit::default(); MAX_FEE_SPLITS];
+    config.fee_splits[..splits.len()].copy_from_slice(splits);
+    Ok(())
 }
 
-// ===== State =====
-
-#[account]
-pub struct Grant {
-    pub authority: Pubkey,
-    pub mint: Pubkey,
-    pub vault: Pubkey,
-    pub grant_id: u64,
-
-    pub amount_per_period: u64,
-    pub period_seconds: i64,
-    pub start_ts: i64,
-    pub expires_at: i64, // 0 = no expiry
-
-    /// allowlist Merkle root. [0;32] means disabled.
-    pub merkle_root: [u8; 32],
+/// grant.attestation_program に標準の disbursement attestation を CPI で発行する（grant が issuer として署名）
+fn issue_disbursement_attestation(
+    accounts: &ClaimGrant,
+    subject: &Pubkey,
+    period_index: u64,
+    amount: u64,
+    claimed_at: i64,
+) -> Result<()> {
+    let grant = &accounts.grant;
+    let (Some(program), Some(record)) = (
+        accounts.attestation_program.as_ref(),
+        accounts.attestation_record.as_ref(),
+    ) else {
+        return err!(ErrorCode::AttestationAccountsRequired);
+    };
+    require!(
+        program.key() == grant.attestation_program && program.executable,
+        ErrorCode::InvalidAttestationProgram
+    );
 
-    pub paused: bool,
-    pub bump: u8,
-}
+    let mut data = Vec::with_capacity(DISBURSEMENT_ATTESTATION_DATA_LEN);
+    data.extend_from_slice(&DISBURSEMENT_ATTESTATION_DISCRIMINATOR);
+    data.extend_from_slice(grant.attestation_config.as_ref());
+    data.extend_from_slice(subject.as_ref());
+    data.extend_from_slice(accounts.receipt.key().as_ref());
+    data.extend_from_slice(&period_index.to_le_bytes());
+    data.extend_from_slice(&amount.to_le_bytes());
+    data.extend_from_slice(&claimed_at.to_le_bytes());
+    let ix = Instruction {
+        program_id: program.key(),
+        accounts: vec![
+            AccountMeta::new_readonly(grant.key(), true),
+            AccountMeta::new(record.key(), false),
+            AccountMeta::new(accounts.claimer.key(), true),
+            AccountMeta::new_readonly(accounts.system_program.key(), false),
+        ],
+        data,
+    };
 
-impl Grant {
-    pub const INIT_SPACE: usize =
-        32 + 32 + 32 + 8 + // keys + grant_id
-        8 + 8 + 8 + 8 +    // amounts/timestamps
-        32 +               // merkle_root
-        1 + 1;             // paused + bump
-}
+    let grant_id_bytes = grant.grant_id.to_le_bytes();
+    let grant_seeds: &[&[u8]] = &[
+        b"grant",
+        grant.seed_authority.as_ref(),
+        grant.seed_mint.as_ref(),
+        &grant_id_bytes,
+        &[grant.bump],
+    ];
+    invoke_signed(
+        &ix,
+        &[
+            grant.to_account_info(),
+            record.to_account_info(),
+            accounts.claimer.to_account_info(),
+            accounts.system_program.to_account_info(),
+            program.to_account_info(),
+        ],
+        &[grant_seeds],
+    )?;
 
-#[account]
-pub struct ClaimReceipt {
-    pub grant: Pubkey,
-    pub claimer: Pubkey,
-    pub period_index: u64,
-    pub claimed_at: i64,
+    emit!(DisbursementAttested {
+        schema_version: EVENT_SCHEMA_VERSION,
+        grant: grant.key(),
+        claimer: *subject,
+        period_index,
+        amount,
+        attestation_program: program.key(),
+        attestation_record: record.key(),
+    });
+    Ok(())
 }
 
-impl ClaimReceipt {
-    pub const INIT_SPACE: usize = 32 + 32 + 8 + 8;
+/// 入金 contribution に対する上乗せ額（ratio、cap の残り、原資残高のうち最小）
+fn match_amount(pool: &MatchPool, match_vault_balance: u64, contribution: u64) -> Result<u64> {
+    let by_ratio = mul_div(contribution, pool.ratio_bps as u64, BPS_DENOMINATOR)?;
+    let cap_left = pool.cap.saturating_sub(pool.total_matched);
+    Ok(by_ratio.min(cap_left).min(match_vault_balance))
 }
 
-#[account]
-pub struct PopConfig {
-    pub authority: Pubkey,
-    pub signer_pubkey: Pubkey,
-    pub bump: u8,
+/// 入金額を未充足の tranche に先頭から割り当てる（全 tranche の合計を超える入金は拒否）
+fn allocate_to_tranches(schedule: &mut EscrowSchedule, amount: u64) -> Result<()> {
+    let mut remaining = amount;
+    for tranche in schedule.tranches.iter_mut() {
+        if remaining == 0 {
+            break;
+        }
+        let open = tranche
+            .committed
+            .checked_sub(tranche.funded)
+            .ok_or(ErrorCode::MathOverflow)?;
+        let allocated = open.min(remaining);
+        tranche.funded = add_amounts(tranche.funded, allocated)?;
+        remaining = remaining.checked_sub(allocated).ok_or(ErrorCode::MathOverflow)?;
+    }
+    require!(remaining == 0, ErrorCode::EscrowOverfunded);
+    Ok(())
 }
 
-impl PopConfig {
-    pub const INIT_SPACE: usize = 32 + 32 + 1;
+/// period_index の tranche が全額充足済みであることを確認し、outflow を引き当てる
+fn draw_from_tranche(schedule: &mut EscrowSchedule, period_index: u64, outflow: u64) -> Result<()> {
+    let tranche = usize::try_from(period_index)
+        .ok()
+        .and_then(|index| schedule.tranches.get_mut(index))
+        .ok_or(ErrorCode::PeriodNotFunded)?;
+    require!(tranche.funded == tranche.committed, ErrorCode::PeriodNotFunded);
+    let claimed = add_amounts(tranche.claimed, outflow)?;
+    require!(claimed <= tranche.funded, ErrorCode::TrancheExhausted);
+    tranche.claimed = claimed;
+    Ok(())
 }
 
-#[account]
-pub struct PopState {
-    pub grant: Pubkey,
-    pub last_global_hash: [u8; 32],
-    pub last_stream_hash: [u8; 32],
-    pub last_period_index: u64,
-    pub last_issued_at: i64,
-    pub initialized: bool,
-    pub bump: u8,
+/// 連続受給記録を今回の period_index で更新し、今回を含む連続期間数を返す
+fn advance_claim_streak(
+    streak: &mut ClaimStreak,
+    grant: Pubkey,
+    claimer: Pubkey,
+    period_index: u64,
+    bump: u8,
+) -> u32 {
+    let continues = streak.current_streak > 0
+        && streak.last_period_index.checked_add(1) == Some(period_index);
+    streak.current_streak = if continues {
+        streak.current_streak.saturating_add(1)
+    } else {
+        1
+    };
+    streak.grant = grant;
+    streak.claimer = claimer;
+    streak.last_period_index = period_index;
+    streak.longest_streak = streak.longest_streak.max(streak.current_streak);
+    streak.bump = bump;
+    streak.current_streak
 }
 
-impl PopState {
-    pub const INIT_SPACE: usize = 32 + 32 + 32 + 8 + 8 + 1 + 1;
+/// 連続期間数に対して適用されるボーナス（basis points）
+fn streak_bonus_bps(grant: &Grant, streak: u32) -> u16 {
+    grant
+        .streak_bonus_steps
+        .iter()
+        .filter(|step| step.min_streak > 0 && streak >= step.min_streak as u32)
+        .max_by_key(|step| step.min_streak)
+        .map(|step| step.bonus_bps)
+        .unwrap_or(0)
 }
 
-// ===== Helpers =====
-
-fn require_claim_timing(grant: &Grant, now: i64, period_index: u64) -> Result<()> {
-    if grant.expires_at != 0 {
-        require!(now <= grant.expires_at, ErrorCode::GrantExpired);
+/// ステーク記録から支給額の倍率（bps）を求める（倍率が無効・記録なし・条件未達は等倍）
+/// 渡されたステーク記録が不正な場合はエラーにする
+fn stake_multiplier_bps(
+    grant: &Grant,
+    claimer: &Pubkey,
+    stake_record: Option<&UncheckedAccount>,
+    now: i64,
+) -> Result<u16> {
+    const UNIT: u16 = BPS_DENOMINATOR as u16;
+    let Some(stake_record) = stake_record.filter(|_| grant.stake_program != Pubkey::default()) else {
+        return Ok(UNIT);
+    };
+    require!(*stake_record.owner == grant.stake_program, ErrorCode::InvalidStakeRecord);
+
+    let data = stake_record.try_borrow_data()?;
+    require!(data.len() >= STAKE_RECORD_LEN, ErrorCode::InvalidStakeRecord);
+    require!(data[0..8] == STAKE_RECORD_DISCRIMINATOR, ErrorCode::InvalidStakeRecord);
+
+    let mut offset = 8usize;
+    let owner = read_pubkey(&data, &mut offset)?;
+    let mint = read_pubkey(&data, &mut offset)?;
+    let amount = read_u64_le(&data, &mut offset)?;
+    let staked_since = read_i64_le(&data, &mut offset)?;
+    require!(owner == *claimer, ErrorCode::InvalidStakeRecord);
+    require!(mint == grant.stake_mint, ErrorCode::InvalidStakeRecord);
+    require!(staked_since <= now, ErrorCode::InvalidStakeRecord);
+    if amount < grant.stake_min_amount {
+        return Ok(UNIT);
     }
 
-    require!(now >= grant.start_ts, ErrorCode::GrantNotStarted);
-
-    // period_index はクライアントから渡される（receipt PDA の seed 用）
-    // 不正防止のため、オンチェーンで現在の period_index を再計算して一致を要求
-    let elapsed = now
-        .checked_sub(grant.start_ts)
-        .ok_or(ErrorCode::MathOverflow)?;
-    let expected_period_index = (elapsed / grant.period_seconds) as u64;
-    require!(period_index == expected_period_index, ErrorCode::InvalidPeriodIndex);
+    let held_seconds = now.checked_sub(staked_since).ok_or(ErrorCode::MathOverflow)?;
+    Ok(grant
+        .stake_multiplier_steps
+        .iter()
+        .filter(|step| step.min_stake_seconds > 0 && held_seconds >= step.min_stake_seconds)
+        .max_by_key(|step| step.min_stake_seconds)
+        .map_or(UNIT, |step| step.multiplier_bps))
+}
 
-    Ok(())
+/// amount * bps / 10_000（切り捨て）
+fn bps_of(amount: u64, bps: u16) -> Result<u64> {
+    mul_div(amount, bps as u64, BPS_DENOMINATOR)
 }
 
 fn transfer_from_vault<'info>(
     grant_account: &Account<'info, Grant>,
     vault: &Account<'info, TokenAccount>,
-    mint: &Account<'info, Mint>,
     destination: &Account<'info, TokenAccount>,
     token_program: &Program<'info, Token>,
     amount: u64,
@@ -636,39 +10346,349 @@ fn transfer_from_vault<'info>(
     let grant_id_bytes = grant_account.grant_id.to_le_bytes();
     let grant_seeds: &[&[u8]] = &[
         b"grant",
-        grant_account.authority.as_ref(),
-        grant_account.mint.as_ref(),
+        grant_account.seed_authority.as_ref(),
+        grant_account.seed_mint.as_ref(),
         &grant_id_bytes,
         &[grant_account.bump],
     ];
 
-    let decimals = mint.decimals;
-
-    let cpi_accounts = TransferChecked {
+    // SPL Token（拡張なし）のみ対応のため、Mint を渡さない transfer で十分
+    // （vault / destination の mint 一致は呼び出し側の constraint で保証済み）
+    let cpi_accounts = Transfer {
         from: vault.to_account_info(),
-        mint: mint.to_account_info(),
         to: destination.to_account_info(),
         authority: grant_account.to_account_info(),
     };
     let signer_seeds: &[&[&[u8]]] = &[grant_seeds];
     let cpi_ctx = CpiContext::new_with_signer(token_program.to_account_info(), cpi_accounts, signer_seeds);
-    transfer_checked(cpi_ctx, amount, decimals)
+    transfer(cpi_ctx, amount)
+}
+
+/// FUNDING_BUDGET_WINDOW_SECONDS 単位の枠の (window_start, window_used) を now の期間まで進める
+/// 期間の区切りは作成時刻から固定間隔
+fn roll_budget_window(window_start: i64, window_used: u64, now: i64) -> (i64, u64) {
+    let elapsed = now.saturating_sub(window_start);
+    if elapsed >= FUNDING_BUDGET_WINDOW_SECONDS {
+        (
+            window_start + elapsed / FUNDING_BUDGET_WINDOW_SECONDS * FUNDING_BUDGET_WINDOW_SECONDS,
+            0,
+        )
+    } else {
+        (window_start, window_used)
+    }
+}
+
+/// close_period の実行者に KeeperConfig.period_close_bounty を支払う（原資の残高を上限とする）
+fn pay_period_close_bounty(accounts: &mut ClosePeriod, period_index: u64) -> Result<()> {
+    let keeper = accounts.payer.key();
+    let Some(config) = accounts.keeper_config.as_mut() else {
+        return Ok(());
+    };
+    require!(config.is_keeper_allowed(&keeper), ErrorCode::KeeperNotAllowed);
+    if config.period_close_bounty == 0 {
+        return Ok(());
+    }
+
+    let (Some(source), Some(keeper_ata), Some(token_program)) = (
+        accounts.bounty_source.as_ref(),
+        accounts.keeper_ata.as_ref(),
+        accounts.token_program.as_ref(),
+    ) else {
+        return err!(ErrorCode::MissingKeeperBountyAccounts);
+    };
+    let expected_source = if config.bounty_from_pool {
+        config.bounty_pool
+    } else {
+        accounts.grant.vault
+    };
+    require!(source.key() == expected_source, ErrorCode::KeeperBountySourceMismatch);
+
+    let amount = config.period_close_bounty.min(source.amount);
+    if amount == 0 {
+        return Ok(());
+    }
+    transfer_from_vault(&accounts.grant, source, keeper_ata, token_program, amount)?;
+    config.total_bounties_paid = config.total_bounties_paid.saturating_add(amount);
+    if !config.bounty_from_pool {
+        // vault の会計（verify_invariants）と整合させる
+        let grant = &mut accounts.grant;
+        grant.total_funded = grant
+            .total_funded
+            .checked_sub(amount as u128)
+            .ok_or(ErrorCode::MathOverflow)?;
+    }
+
+    emit!(KeeperBountyPaid {
+        schema_version: EVENT_SCHEMA_VERSION,
+        grant: accounts.grant.key(),
+        keeper,
+        period_index,
+        amount,
+        from_pool: config.bounty_from_pool,
+    });
+    Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 fn record_receipt(
     receipt: &mut Account<ClaimReceipt>,
-    grant: Pubkey,
+    grant: &Account<Grant>,
     claimer: Pubkey,
     period_index: u64,
     claimed_at: i64,
+    amount: u64,
+    bump: u8,
+    pop_bypassed: bool,
 ) {
-    receipt.grant = grant;
+    receipt.grant = grant.key();
     receipt.claimer = claimer;
     receipt.period_index = period_index;
     receipt.claimed_at = claimed_at;
+    receipt.bump = bump;
+    receipt.status = ReceiptStatus::Valid;
+    receipt.amount = amount;
+    receipt.pop_bypassed = pop_bypassed;
+    receipt.seed_salt = grant.seed_salt;
+}
+
+/// 管理命令の admin_nonce を検証して進める（同じ nonce の命令は2回実行されない）
+/// 承認システムは grant.admin_nonce を読んで命令に含め、AdminActionExecuted で実行を確認する
+/// authority 本人が署名した実行は dead-man switch の活動として記録し、進行中の引き継ぎ表明を取り消す
+/// （actor は命令の署名者。署名していないアカウントを渡せないよう Signer で受け取る）
+fn consume_admin_nonce(
+    grant: &mut Account<Grant>,
+    actor: &Signer,
+    admin_nonce: u64,
+    action: &str,
+    remaining_accounts: &[AccountInfo],
+) -> Result<()> {
+    require!(admin_nonce == grant.admin_nonce, ErrorCode::AdminNonceMismatch);
+    grant.admin_nonce = admin_nonce.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+    let actor = actor.key();
+    if actor == grant.authority {
+        grant.last_admin_action_at = clock::now(remaining_accounts)?;
+        if grant.succession_unlocks_at != 0 {
+            grant.succession_unlocks_at = 0;
+            emit!(SuccessionCancelled {
+                schema_version: EVENT_SCHEMA_VERSION,
+                grant: grant.key(),
+                authority: actor,
+                successor: grant.successor,
+            });
+        }
+    }
+    emit!(AdminActionExecuted {
+        schema_version: EVENT_SCHEMA_VERSION,
+        grant: grant.key(),
+        actor,
+        action: action.to_string(),
+        admin_nonce,
+    });
+    Ok(())
+}
+
+/// PDA seed としての salt（salt なしは空の seed = salt 導入前と同じアドレス）
+pub fn seed_salt_seed(salt: &[u8; SEED_SALT_LEN]) -> &[u8] {
+    if *salt == [0u8; SEED_SALT_LEN] {
+        &[]
+    } else {
+        salt
+    }
+}
+
+/// set_pop_bypass の緊急期間中か（期限ちょうどは期間外）
+fn is_pop_bypass_active(grant: &Grant, now: i64) -> bool {
+    grant.pop_bypass_until != 0 && now < grant.pop_bypass_until
+}
+
+/// receipt の状態遷移と、遷移ごとに許可されるロールを検証する
+/// - Valid → Disputed: authority / co_admin / reviewer
+/// - Disputed → Valid | Revoked: authority / reviewer（co_admin は申し立てのみ）
+/// - Revoked → Refunded: authority / co_admin
+fn require_receipt_transition(
+    grant: &Grant,
+    actor: Pubkey,
+    from: ReceiptStatus,
+    to: ReceiptStatus,
+) -> Result<()> {
+    let is_authority = actor == grant.authority;
+    let is_co_admin = grant.co_admin != Pubkey::default() && actor == grant.co_admin;
+    let is_reviewer = grant.reviewer != Pubkey::default() && actor == grant.reviewer;
+    let allowed = match (from, to) {
+        (ReceiptStatus::Valid, ReceiptStatus::Disputed) => is_authority || is_co_admin || is_reviewer,
+        (ReceiptStatus::Disputed, ReceiptStatus::Valid)
+        | (ReceiptStatus::Disputed, ReceiptStatus::Revoked) => is_authority || is_reviewer,
+        (ReceiptStatus::Revoked, ReceiptStatus::Refunded) => is_authority || is_co_admin,
+        _ => return err!(ErrorCode::InvalidReceiptTransition),
+    };
+    require!(allowed, ErrorCode::Unauthorized);
+    Ok(())
+}
+
+/// TreasuryConfig が初期化されていれば、claim 手数料（SOL）を fee_payer（省略時は claimer）から treasury へ送る
+/// fee_splits があれば、各受取先に bps 分を送り、端数を含む残りを treasury へ送る（1回の claim 内で完結）
+fn collect_claim_fee<'info>(accounts: &ClaimGrant<'info>, fee_recipients: &[AccountInfo<'info>]) -> Result<()> {
+    let config_info = accounts.treasury_config.to_account_info();
+    if config_info.data_is_empty() {
+        return Ok(());
+    }
+    let config = TreasuryConfig::try_deserialize(&mut &config_info.data.borrow()[..])?;
+    if config.claim_fee_lamports == 0 {
+        return Ok(());
+    }
+    let treasury = accounts
+        .treasury
+        .as_ref()
+        .ok_or(ErrorCode::InvalidTreasury)?;
+    require_keys_eq!(treasury.key(), config.treasury, ErrorCode::InvalidTreasury);
+    let from = match accounts.fee_payer.as_ref() {
+        Some(fee_payer) => fee_payer.to_account_info(),
+        None => accounts.claimer.to_account_info(),
+    };
+    let pay = |to: AccountInfo<'info>, lamports: u64| -> Result<()> {
+        if lamports == 0 {
+            return Ok(());
+        }
+        anchor_lang::system_program::transfer(
+            CpiContext::new(
+                accounts.system_program.to_account_info(),
+                anchor_lang::system_program::Transfer {
+                    from: from.clone(),
+                    to,
+                },
+            ),
+            lamports,
+        )
+    };
+
+    let splits = config.fee_splits.iter().filter(|split| split.bps > 0);
+    require!(
+        fee_recipients.len() >= splits.clone().count(),
+        ErrorCode::FeeRecipientMismatch
+    );
+    let mut remaining = config.claim_fee_lamports;
+    for (split, recipient) in splits.zip(fee_recipients.iter()) {
+        require_keys_eq!(recipient.key(), split.recipient, ErrorCode::FeeRecipientMismatch);
+        let share = bps_of(config.claim_fee_lamports, split.bps)?;
+        pay(recipient.clone(), share)?;
+        remaining -= share;
+    }
+    pay(treasury.to_account_info(), remaining)
+}
+
+/// 受給が始まっているか（開始済みで、reviewer がいる場合は承認済み）
+fn is_claimable(grant: &Grant, now: i64) -> bool {
+    now >= grant.start_ts && (grant.reviewer == Pubkey::default() || grant.activated)
+}
+
+/// 期限前の close_grant に wind-down を要求するか
+/// vault に残高があり、受給が始まっていて、期限を過ぎていない場合
+fn requires_wind_down(grant: &Grant, vault_balance: u64, now: i64) -> bool {
+    let expired = grant.expires_at != 0 && now > grant.expires_at;
+    vault_balance > 0 && is_claimable(grant, now) && !expired
+}
+
+/// 期限の前倒しで wind-down を迂回させない
+/// 受給が始まっている Grant では、期限を早める場合の新しい期限は WIND_DOWN_MIN_SECONDS 以上先であること
+fn require_no_abrupt_expiry(grant: &Grant, expires_at: i64, now: i64) -> Result<()> {
+    let shortened = expires_at != 0 && (grant.expires_at == 0 || expires_at < grant.expires_at);
+    if shortened && is_claimable(grant, now) {
+        require!(
+            expires_at >= now.saturating_add(WIND_DOWN_MIN_SECONDS),
+            ErrorCode::WindDownRequired
+        );
+    }
+    Ok(())
+}
+
+/// init_if_needed で作成した直後（discriminator 未設定）は load_init、既存のものは load_mut で開く
+fn load_period_stats<'a>(loader: &'a AccountLoader<PeriodStats>) -> Result<RefMut<'a, PeriodStats>> {
+    let initialized = loader.as_ref().try_borrow_data()?.starts_with(PeriodStats::DISCRIMINATOR);
+    if initialized {
+        loader.load_mut()
+    } else {
+        loader.load_init()
+    }
+}
+
+/// 受給率（bps）。eligible_count が未設定なら 0
+fn take_up_bps(claims_count: u64, eligible_count: u64) -> Result<u16> {
+    if eligible_count == 0 {
+        return Ok(0);
+    }
+    let bps = mul_div(claims_count, BPS_DENOMINATOR, eligible_count)?.min(BPS_DENOMINATOR);
+    Ok(bps as u16)
+}
+
+/// close_period で KPI 目標に対する進捗を発行し、初めて達成した目標を kpi_milestones に記録する
+fn record_kpi_progress(grant: &mut Account<Grant>, period_index: u64, take_up_bps: u16, now: i64) {
+    if grant.target_take_up_bps == 0 && grant.target_unique_claimers == 0 {
+        return;
+    }
+    let mut reached = 0u8;
+    if grant.target_take_up_bps > 0 && take_up_bps >= grant.target_take_up_bps {
+        reached |= KPI_MILESTONE_TAKE_UP;
+    }
+    if grant.target_unique_claimers > 0 && grant.unique_claimers >= grant.target_unique_claimers {
+        reached |= KPI_MILESTONE_UNIQUE_CLAIMERS;
+    }
+    let new_milestones = reached & !grant.kpi_milestones;
+    grant.kpi_milestones |= reached;
+
+    emit!(KpiProgress {
+        schema_version: EVENT_SCHEMA_VERSION,
+        grant: grant.key(),
+        period_index,
+        take_up_bps,
+        target_take_up_bps: grant.target_take_up_bps,
+        unique_claimers: grant.unique_claimers,
+        target_unique_claimers: grant.target_unique_claimers,
+        kpi_milestones: grant.kpi_milestones,
+    });
+    if new_milestones != 0 {
+        emit!(KpiMilestoneReached {
+            schema_version: EVENT_SCHEMA_VERSION,
+            grant: grant.key(),
+            period_index,
+            milestones: new_milestones,
+            reached_at: now,
+        });
+    }
+}
+
+/// 期間の初回記録時に grant / period_index / eligible_count を書き込む
+fn touch_period_stats(stats: &mut PeriodStats, grant: &Account<Grant>, period_index: u64, bump: u8) {
+    if stats.grant == Pubkey::default() {
+        stats.grant = grant.key();
+        stats.period_index = period_index;
+        stats.eligible_count = grant.eligible_count;
+        stats.bump = bump;
+    }
+}
+
+fn record_claimer_profile(
+    profile: &mut ClaimerProfile,
+    grant: Pubkey,
+    claimer: Pubkey,
+    period_index: u64,
+    amount: u64,
+    bump: u8,
+) -> Result<()> {
+    profile.grant = grant;
+    profile.claimer = claimer;
+    profile.claims_count = profile
+        .claims_count
+        .checked_add(1)
+        .ok_or(ErrorCode::MathOverflow)?;
+    profile.total_received = add_to_total(profile.total_received, amount)?;
+    profile.last_claim_period = period_index;
+    profile.bump = bump;
+    Ok(())
 }
 
 struct PopEntryHashInput<'a> {
+    /// Grant.hash_domain（"<domain>:pop:v1" / "<domain>:pop:v2" の接頭辞）
+    domain: &'a [u8],
     version: u8,
     prev_hash: &'a [u8; 32],
     stream_prev_hash: &'a [u8; 32],
@@ -690,20 +10710,41 @@ struct PopProofMessage {
     audit_hash: [u8; POP_HASH_LEN],
     entry_hash: [u8; POP_HASH_LEN],
     issued_at: i64,
+    /// v3 のみ（v1 / v2 は None）
+    authorized_amount: Option<u64>,
 }
 
-fn verify_and_record_pop_proof<'info>(
-    accounts: &mut ClaimGrant<'info>,
+/// claim_cpi_guard が有効な Grant では、最上位の命令が grant_program 自身か claim_cpi_allowed_caller であること
+/// （grant_program は自身を CPI で呼ばないため、最上位が grant_program なら直接の呼び出し）
+fn require_claim_caller(grant: &Grant, instructions_info: &AccountInfo) -> Result<()> {
+    if !grant.claim_cpi_guard {
+        return Ok(());
+    }
+    let current_index = load_current_index_checked(instructions_info)? as usize;
+    let caller = load_instruction_at_checked(current_index, instructions_info)?.program_id;
+    require!(
+        caller == crate::ID
+            || (grant.claim_cpi_allowed_caller != Pubkey::default() && caller == grant.claim_cpi_allowed_caller),
+        ErrorCode::ClaimCpiNotAllowed
+    );
+    Ok(())
+}
+
+/// 実行中のトップレベル命令の直前にある ed25519 命令から PoP message を取り出し、
+/// 署名者・対象（grant / claimer / period_index）・version・entry_hash を検証する
+/// （Grant.pop_require_audit_hash が有効な場合は v2 / v3 形式のみ受け付ける）
+fn load_verified_pop_message(
+    instructions_info: &AccountInfo,
+    pop_config: &PopConfig,
+    grant: &Account<Grant>,
+    claimer: Pubkey,
     period_index: u64,
-    now: i64,
-    pop_state_bump: u8,
-) -> Result<()> {
-    let instructions_info = accounts.instructions_sysvar.to_account_info();
-    let current_index = load_current_index_checked(&instructions_info)
+) -> Result<(Pubkey, PopProofMessage)> {
+    let current_index = load_current_index_checked(instructions_info)
         .map_err(|_| error!(ErrorCode::MissingPopSignatureInstruction))? as usize;
     require!(current_index > 0, ErrorCode::MissingPopSignatureInstruction);
 
-    let ed25519_ix = load_instruction_at_checked(current_index - 1, &instructions_info)
+    let ed25519_ix = load_instruction_at_checked(current_index - 1, instructions_info)
         .map_err(|_| error!(ErrorCode::MissingPopSignatureInstruction))?;
     require!(
         ed25519_ix.program_id == ed25519_program::id(),
@@ -712,32 +10753,31 @@ fn verify_and_record_pop_proof<'info>(
 
     let (signer_pubkey, message_bytes) = extract_ed25519_signer_and_message(&ed25519_ix)?;
     require!(
-        signer_pubkey == accounts.pop_config.signer_pubkey,
+        signer_pubkey == pop_config.signer_pubkey,
         ErrorCode::InvalidPopSigner
     );
 
     let message = parse_pop_message(&message_bytes)?;
-    // オフチェーン参加レシートのコミット（audit_hash）を含む v2 形式のみ受け付ける。
-    require!(
-        message.version == POP_MESSAGE_VERSION_V2,
-        ErrorCode::PopReceiptCommitmentRequired
-    );
-    require!(message.grant == accounts.grant.key(), ErrorCode::PopProofGrantMismatch);
-    require!(
-        message.claimer == accounts.claimer.key(),
-        ErrorCode::PopProofClaimerMismatch
-    );
+    require!(message.grant == grant.key(), ErrorCode::PopProofGrantMismatch);
+    require!(message.claimer == claimer, ErrorCode::PopProofClaimerMismatch);
     require!(
         message.period_index == period_index,
         ErrorCode::PopProofPeriodMismatch
     );
-
+    // オフチェーン参加レシートのコミット（audit_hash）を含む v2 形式を要求する Grant
     require!(
-        message.audit_hash != [0u8; 32],
-        ErrorCode::PopAuditHashMissing
+        !grant.pop_require_audit_hash || message.version != POP_MESSAGE_VERSION_V1,
+        ErrorCode::PopReceiptCommitmentRequired
     );
+    if message.version != POP_MESSAGE_VERSION_V1 {
+        require!(
+            message.audit_hash != [0u8; 32],
+            ErrorCode::PopAuditHashMissing
+        );
+    }
 
     let expected_entry_hash = pop_entry_hash(PopEntryHashInput {
+        domain: grant.hash_domain.as_bytes(),
         version: message.version,
         prev_hash: &message.prev_hash,
         stream_prev_hash: &message.stream_prev_hash,
@@ -751,17 +10791,53 @@ fn verify_and_record_pop_proof<'info>(
         expected_entry_hash == message.entry_hash,
         ErrorCode::PopEntryHashMismatch
     );
+    Ok((signer_pubkey, message))
+}
+
+/// 初回の claim で作成された PopState に所有者情報を記録する（PoP bypass 中の claim でも行う）
+fn init_pop_state(accounts: &mut ClaimGrant, pop_state_bump: u8) -> Result<()> {
+    let grant = accounts.grant.key();
+    let payer = accounts.claimer.key();
+    let seed_salt = accounts.grant.seed_salt;
+    let pop_state = &mut accounts.pop_state;
+    if pop_state.grant == Pubkey::default() {
+        pop_state.grant = grant;
+        pop_state.bump = pop_state_bump;
+        pop_state.payer = payer;
+        pop_state.seed_salt = seed_salt;
+    }
+    require!(pop_state.grant == grant, ErrorCode::PopStateGrantMismatch);
+    Ok(())
+}
+
+/// 戻り値は v3 形式で署名者が指定した支給額（v1 / v2 は None）
+fn verify_and_record_pop_proof<'info>(
+    accounts: &mut ClaimGrant<'info>,
+    period_index: u64,
+    now: i64,
+) -> Result<Option<u64>> {
+    let (signer_pubkey, message) = load_verified_pop_message(
+        &accounts.instructions_sysvar.to_account_info(),
+        &accounts.pop_config,
+        &accounts.grant,
+        accounts.beneficiary(),
+        period_index,
+    )?;
 
     let skew = absolute_i64_diff(now, message.issued_at)?;
     require!(skew <= POP_MAX_SKEW_SECONDS, ErrorCode::PopProofExpired);
+    check_pop_signer_heartbeat(&accounts.pop_config, &accounts.grant.key(), now)?;
 
     let pop_state = &mut accounts.pop_state;
     let was_initialized = pop_state.initialized;
     if was_initialized {
-        require!(pop_state.grant == accounts.grant.key(), ErrorCode::PopStateGrantMismatch);
+        if accounts.grant.pop_monotonic_issued_at {
+            require!(
+                message.issued_at > pop_state.last_issued_at,
+                ErrorCode::PopIssuedAtNotIncreasing
+            );
+        }
     } else {
-        pop_state.grant = accounts.grant.key();
-        pop_state.bump = pop_state_bump;
         pop_state.initialized = true;
     }
 
@@ -778,6 +10854,45 @@ fn verify_and_record_pop_proof<'info>(
         pop_state.last_issued_at = message.issued_at;
     }
 
+    // entry_hash の入力をすべて残し、署名者の DB なしでログからチェーンを再構成・検証できるようにする
+    emit!(PopEntryRecorded {
+        schema_version: EVENT_SCHEMA_VERSION,
+        grant: message.grant,
+        claimer: message.claimer,
+        signer: signer_pubkey,
+        version: message.version,
+        prev_hash: message.prev_hash,
+        stream_prev_hash: message.stream_prev_hash,
+        audit_hash: message.audit_hash,
+        entry_hash: message.entry_hash,
+        period_index: message.period_index,
+        issued_at: message.issued_at,
+        head_advanced: should_advance_head,
+    });
+
+    Ok(message.authorized_amount)
+}
+
+/// heartbeat が heartbeat_max_silence_seconds を超えて途絶えていれば、
+/// enforced なら拒否し、そうでなければ PopSignerSilent を emit して続行する
+fn check_pop_signer_heartbeat(pop_config: &Account<PopConfig>, grant: &Pubkey, now: i64) -> Result<()> {
+    if pop_config.heartbeat_max_silence_seconds == 0 {
+        return Ok(());
+    }
+    let silent_for = now.saturating_sub(pop_config.last_heartbeat_at);
+    if silent_for <= pop_config.heartbeat_max_silence_seconds {
+        return Ok(());
+    }
+    require!(!pop_config.heartbeat_enforced, ErrorCode::PopSignerHeartbeatOverdue);
+    msg!("PoP signer silent for {}s", silent_for);
+    emit!(PopSignerSilent {
+        schema_version: EVENT_SCHEMA_VERSION,
+        pop_config: pop_config.key(),
+        signer: pop_config.signer_pubkey,
+        grant: *grant,
+        last_heartbeat_at: pop_config.last_heartbeat_at,
+        silent_for,
+    });
     Ok(())
 }
 
@@ -828,17 +10943,81 @@ fn extract_ed25519_signer_and_message(ix: &Instruction) -> Result<(Pubkey, Vec<u
     Ok((signer_pubkey, message_bytes))
 }
 
+struct ClaimIntent {
+    grant: Pubkey,
+    claimer: Pubkey,
+    period_index: u64,
+    relayer: Pubkey,
+    expires_at: i64,
+}
+
+/// 受給者が署名した claim intent を検証する
+/// intent の ed25519 命令は PoP の ed25519 命令の直前（PoP bypass 中は claim 命令の直前）に置く
+/// - 署名者 = intent の claimer = 受給者、grant / period_index が一致し、期限内であること
+/// - relayer が指定されていれば、claim を送る署名者（claimer アカウント）と一致すること
+#[allow(clippy::too_many_arguments)]
+fn verify_claim_intent(
+    instructions_info: &AccountInfo,
+    grant: &Pubkey,
+    beneficiary: &Pubkey,
+    relayer: &Pubkey,
+    period_index: u64,
+    now: i64,
+    pop_bypassed: bool,
+) -> Result<()> {
+    let current_index = load_current_index_checked(instructions_info)
+        .map_err(|_| error!(ErrorCode::MissingClaimIntent))? as usize;
+    let distance = if pop_bypassed { 1 } else { 2 };
+    require!(current_index >= distance, ErrorCode::MissingClaimIntent);
+
+    let ed25519_ix = load_instruction_at_checked(current_index - distance, instructions_info)
+        .map_err(|_| error!(ErrorCode::MissingClaimIntent))?;
+    require!(
+        ed25519_ix.program_id == ed25519_program::id(),
+        ErrorCode::MissingClaimIntent
+    );
+
+    let (signer_pubkey, message_bytes) = extract_ed25519_signer_and_message(&ed25519_ix)?;
+    require_keys_eq!(signer_pubkey, *beneficiary, ErrorCode::InvalidClaimIntent);
+    let intent = parse_claim_intent(&message_bytes)?;
+    require!(
+        intent.grant == *grant && intent.claimer == *beneficiary && intent.period_index == period_index,
+        ErrorCode::InvalidClaimIntent
+    );
+    require!(
+        intent.relayer == Pubkey::default() || intent.relayer == *relayer,
+        ErrorCode::InvalidClaimIntent
+    );
+    require!(now < intent.expires_at, ErrorCode::ClaimIntentExpired);
+    Ok(())
+}
+
+fn parse_claim_intent(message: &[u8]) -> Result<ClaimIntent> {
+    require!(
+        message.len() == CLAIM_INTENT_LEN && message.starts_with(CLAIM_INTENT_DOMAIN),
+        ErrorCode::InvalidClaimIntent
+    );
+    let mut offset = CLAIM_INTENT_DOMAIN.len();
+    Ok(ClaimIntent {
+        grant: read_pubkey(message, &mut offset)?,
+        claimer: read_pubkey(message, &mut offset)?,
+        period_index: read_u64_le(message, &mut offset)?,
+        relayer: read_pubkey(message, &mut offset)?,
+        expires_at: read_i64_le(message, &mut offset)?,
+    })
+}
+
 fn parse_pop_message(message: &[u8]) -> Result<PopProofMessage> {
     require!(!message.is_empty(), ErrorCode::InvalidPopMessageLength);
     let version = message[0];
     require!(
-        version == POP_MESSAGE_VERSION_V1 || version == POP_MESSAGE_VERSION_V2,
+        version == POP_MESSAGE_VERSION_V1 || version == POP_MESSAGE_VERSION_V2 || version == POP_MESSAGE_VERSION_V3,
         ErrorCode::InvalidPopMessageVersion
     );
-    let expected_len = if version == POP_MESSAGE_VERSION_V2 {
-        POP_MESSAGE_LEN_V2
-    } else {
-        POP_MESSAGE_LEN_V1
+    let expected_len = match version {
+        POP_MESSAGE_VERSION_V1 => POP_MESSAGE_LEN_V1,
+        POP_MESSAGE_VERSION_V2 => POP_MESSAGE_LEN_V2,
+        _ => POP_MESSAGE_LEN_V3,
     };
     require!(
         message.len() == expected_len,
@@ -851,13 +11030,20 @@ fn parse_pop_message(message: &[u8]) -> Result<PopProofMessage> {
     let period_index = read_u64_le(message, &mut offset)?;
     let prev_hash = read_hash(message, &mut offset)?;
     let stream_prev_hash = read_hash(message, &mut offset)?;
-    let audit_hash = if version == POP_MESSAGE_VERSION_V2 {
+    let audit_hash = if version != POP_MESSAGE_VERSION_V1 {
         read_hash(message, &mut offset)?
     } else {
         [0u8; 32]
     };
     let entry_hash = read_hash(message, &mut offset)?;
     let issued_at = read_i64_le(message, &mut offset)?;
+    let authorized_amount = if version == POP_MESSAGE_VERSION_V3 {
+        let amount = read_u64_le(message, &mut offset)?;
+        require!(amount > 0, ErrorCode::InvalidAmount);
+        Some(amount)
+    } else {
+        None
+    };
 
     Ok(PopProofMessage {
         version,
@@ -869,6 +11055,7 @@ fn parse_pop_message(message: &[u8]) -> Result<PopProofMessage> {
         audit_hash,
         entry_hash,
         issued_at,
+        authorized_amount,
     })
 }
 
@@ -877,7 +11064,8 @@ fn pop_entry_hash(input: PopEntryHashInput<'_>) -> Result<[u8; 32]> {
     let issued_at_bytes = input.issued_at.to_le_bytes();
     match input.version {
         POP_MESSAGE_VERSION_V1 => Ok(hashv(&[
-            b"we-ne:pop:v1",
+            input.domain,
+            b":pop:v1",
             input.prev_hash.as_ref(),
             input.stream_prev_hash.as_ref(),
             input.grant.as_ref(),
@@ -886,8 +11074,10 @@ fn pop_entry_hash(input: PopEntryHashInput<'_>) -> Result<[u8; 32]> {
             issued_at_bytes.as_ref(),
         ])
         .to_bytes()),
-        POP_MESSAGE_VERSION_V2 => Ok(hashv(&[
-            b"we-ne:pop:v2",
+        // v3 の authorized_amount は署名の対象だが entry_hash には含めない（PopEntryRecorded から再計算できるようにする）
+        POP_MESSAGE_VERSION_V2 | POP_MESSAGE_VERSION_V3 => Ok(hashv(&[
+            input.domain,
+            b":pop:v2",
             input.prev_hash.as_ref(),
             input.stream_prev_hash.as_ref(),
             input.audit_hash.as_ref(),
@@ -962,29 +11152,720 @@ fn read_i64_le(data: &[u8], offset: &mut usize) -> Result<i64> {
 }
 
 // ===== Allowlist (Merkle) helpers =====
+// 葉の計算と proof の検証は merkle モジュール（grant_program_interface::merkle）を参照
+
+/// allowlist の root を置き換え、差分 root を破棄する（snapshot_hash = [0;32] は要約なし）
+fn replace_allowlist_root(
+    grant: &mut Grant,
+    merkle_root: [u8; 32],
+    eligible_count: u64,
+    snapshot_hash: [u8; 32],
+    committed_at: i64,
+) {
+    grant.merkle_root = merkle_root;
+    grant.eligible_count = eligible_count;
+    // root を明示的に設定した時点で準備期間は終わる（[0;32] を設定した場合は allowlist なしで開く）
+    grant.allowlist_pending = false;
+    grant.allowlist_delta_roots = [[0u8; 32]; MAX_ALLOWLIST_DELTAS];
+    grant.allowlist_delta_count = 0;
+    grant.allowlist_snapshot_hash = snapshot_hash;
+    grant.allowlist_committed_at = committed_at;
+}
+
+/// merkle_root または append_allowlist_delta の差分 root のいずれかに含まれるか
+/// （proof から root を1回だけ計算し、登録済みの root と突き合わせる）
+fn allowlist_contains(grant: &Grant, leaf: [u8; 32], proof: &[[u8; 32]]) -> bool {
+    let computed = merkle::sorted_pair_root(leaf, proof);
+    computed == grant.merkle_root
+        || grant.allowlist_delta_roots[..grant.allowlist_delta_count as usize].contains(&computed)
+}
+
+// ===== Events =====
+// 全イベントの先頭フィールドは schema_version（= EVENT_SCHEMA_VERSION）。
+// 公開済みイベントのフィールドは並べ替え・型変更・削除をしない。追加は末尾のみとし、
+// その場合も EVENT_SCHEMA_VERSION を上げて tests/event_layouts.json と changelog を更新する。
+
+/// verify_invariants で vault 残高が会計上の残高と一致しなかった
+#[event]
+pub struct Discrepancy {
+    pub schema_version: u8,
+    pub grant: Pubkey,
+    pub vault_balance: u64,
+    /// total_funded - total_claimed - total_platform_fees
+    pub expected_balance: i128,
+    /// vault_balance - expected_balance（正 = 余剰、負 = 不足）
+    pub difference: i128,
+    pub total_funded: u128,
+    pub total_claimed: u128,
+    pub total_platform_fees: u128,
+    pub checked_at: i64,
+}
+
+/// 第三者の入金にマッチングが上乗せされた
+#[event]
+pub struct MatchApplied {
+    pub schema_version: u8,
+    pub grant: Pubkey,
+    pub funder: Pubkey,
+    pub contribution: u64,
+    pub matched: u64,
+    pub total_matched: u64,
+}
+
+/// マッチングプールが cap に到達した、または原資が尽きた
+#[event]
+pub struct MatchPoolExhausted {
+    pub schema_version: u8,
+    pub grant: Pubkey,
+    pub sponsor: Pubkey,
+    pub total_matched: u64,
+    /// true = cap 到達、false = 原資切れ
+    pub cap_reached: bool,
+}
+
+/// Grant の終端イベント（close_grant 時に1回だけ発行される）
+#[event]
+pub struct GrantClosed {
+    pub schema_version: u8,
+    pub grant: Pubkey,
+    pub authority: Pubkey,
+    pub mint: Pubkey,
+    pub grant_id: u64,
+    pub total_funded: u128,
+    pub total_claimed: u128,
+    pub claims_count: u64,
+    /// close 時に authority へ返金した vault 残高
+    pub refunded: u64,
+    pub pop_state_closed: bool,
+    pub closed_at: i64,
+}
+
+/// sponsor 付きの Grant で claim された（claim の記録に sponsor の表示情報を残す）
+#[event]
+pub struct SponsoredClaim {
+    pub schema_version: u8,
+    pub grant: Pubkey,
+    pub claimer: Pubkey,
+    pub period_index: u64,
+    /// claimer に支給した額（streak ボーナスを含み、platform fee は含まない）
+    pub amount: u64,
+    pub sponsor_name_hash: [u8; 32],
+    pub sponsor_logo_uri: String,
+}
+
+/// begin_wind_down で期限前の終了に向けた wind-down が始まった
+#[event]
+pub struct WindDownStarted {
+    pub schema_version: u8,
+    pub grant: Pubkey,
+    pub authority: Pubkey,
+    /// 開始時点の vault 残高（ends_at まで受給者のために残される）
+    pub vault_balance: u64,
+    pub started_at: i64,
+    /// 新しい expires_at（これ以降 close_grant できる）
+    pub ends_at: i64,
+}
+
+/// reviewer がドラフト Grant を承認したとき
+#[event]
+pub struct GrantActivated {
+    pub schema_version: u8,
+    pub grant: Pubkey,
+    pub reviewer: Pubkey,
+    pub activated_at: i64,
+}
+
+#[event]
+pub struct RecoveryInitiated {
+    pub schema_version: u8,
+    pub grant: Pubkey,
+    pub recovery: Pubkey,
+    pub new_authority: Pubkey,
+    pub unlocks_at: i64,
+}
+
+#[event]
+pub struct RecoveryCancelled {
+    pub schema_version: u8,
+    pub grant: Pubkey,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct AuthorityRecovered {
+    pub schema_version: u8,
+    pub grant: Pubkey,
+    pub old_authority: Pubkey,
+    pub new_authority: Pubkey,
+    pub recovered_at: i64,
+}
+
+/// upsert_program_officer で program officer が登録・更新された
+#[event]
+pub struct ProgramOfficerUpdated {
+    pub schema_version: u8,
+    pub grant: Pubkey,
+    pub officer: Pubkey,
+    pub withdrawal_destination: Pubkey,
+    pub withdrawal_cap: u64,
+    pub max_keeper_bounty: u64,
+}
+
+/// remove_program_officer で program officer の登録が削除された
+#[event]
+pub struct ProgramOfficerRemoved {
+    pub schema_version: u8,
+    pub grant: Pubkey,
+    pub officer: Pubkey,
+}
+
+/// officer_withdraw で vault から引き出された
+#[event]
+pub struct OfficerWithdrawal {
+    pub schema_version: u8,
+    pub grant: Pubkey,
+    pub officer: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+    /// 今回を含む現在の期間の引き出し合計
+    pub window_used: u64,
+    pub withdrawal_cap: u64,
+}
+
+/// authority の無操作期間が経過し、successor が引き継ぎを表明した
+#[event]
+pub struct SuccessionAnnounced {
+    pub schema_version: u8,
+    pub grant: Pubkey,
+    pub authority: Pubkey,
+    pub successor: Pubkey,
+    pub last_admin_action_at: i64,
+    pub unlocks_at: i64,
+}
+
+/// authority が管理命令を実行したため、引き継ぎ表明が取り消された
+#[event]
+pub struct SuccessionCancelled {
+    pub schema_version: u8,
+    pub grant: Pubkey,
+    pub authority: Pubkey,
+    pub successor: Pubkey,
+}
+
+/// successor が authority を引き継いだ
+#[event]
+pub struct AuthoritySucceeded {
+    pub schema_version: u8,
+    pub grant: Pubkey,
+    pub old_authority: Pubkey,
+    pub new_authority: Pubkey,
+    pub succeeded_at: i64,
+}
+
+#[event]
+pub struct MintMigrationInitiated {
+    pub schema_version: u8,
+    pub grant: Pubkey,
+    pub old_mint: Pubkey,
+    pub new_mint: Pubkey,
+    pub amount_per_period: u64,
+    pub unlocks_at: i64,
+}
+
+#[event]
+pub struct MintMigrated {
+    pub schema_version: u8,
+    pub grant: Pubkey,
+    pub old_mint: Pubkey,
+    pub new_mint: Pubkey,
+    pub new_vault: Pubkey,
+    pub amount_per_period: u64,
+    /// authority に返金した旧 mint の残高
+    pub refunded: u64,
+    pub migrated_at: i64,
+}
+
+#[event]
+pub struct VaultRotated {
+    pub schema_version: u8,
+    pub grant: Pubkey,
+    pub old_vault: Pubkey,
+    pub new_vault: Pubkey,
+    pub vault_nonce: u32,
+    /// 新しい vault に移した残高
+    pub moved: u64,
+    /// 旧 vault を close できたか（close authority が付いている場合は false）
+    pub old_vault_closed: bool,
+    pub rotated_at: i64,
+}
 
-/// Domain-separated leaf hash for allowlist membership.
-/// leaf = sha256( "we-ne:allowlist" || claimer_pubkey )
-fn allowlist_leaf(claimer: Pubkey) -> [u8; 32] {
-    use anchor_lang::solana_program::hash::hashv;
-    let h = hashv(&[b"we-ne:allowlist", claimer.as_ref()]);
-    h.to_bytes()
+/// claim で受理された PoP エントリ（entry_hash の入力一式）
+/// entry_hash = pop_entry_hash(version, prev_hash, stream_prev_hash, audit_hash, grant, claimer, period_index, issued_at)
+#[event]
+pub struct PopEntryRecorded {
+    pub schema_version: u8,
+    pub grant: Pubkey,
+    pub claimer: Pubkey,
+    /// PoP 署名者（PopConfig.signer_pubkey）
+    pub signer: Pubkey,
+    pub version: u8,
+    pub prev_hash: [u8; 32],
+    pub stream_prev_hash: [u8; 32],
+    pub audit_hash: [u8; 32],
+    pub entry_hash: [u8; 32],
+    pub period_index: u64,
+    pub issued_at: i64,
+    /// PopState の最新ヘッドを更新したか（issued_at が古い並行 claim では false）
+    pub head_advanced: bool,
 }
 
-/// Verifies a Merkle proof using *sorted pair hashing* (no left/right flag).
-/// Each step: parent = sha256( min(a,b) || max(a,b) )
-///
-/// IMPORTANT: Off-chain Merkle tree builder must use the same sorted-pair rule.
-fn verify_merkle_sorted(root: [u8; 32], leaf: [u8; 32], proof: &[[u8; 32]]) -> bool {
-    use anchor_lang::solana_program::hash::hashv;
+impl PopEntryRecorded {
+    /// イベントの内容から entry_hash を再計算する（DEFAULT_HASH_DOMAIN の Grant。未知の version は None）
+    pub fn computed_entry_hash(&self) -> Option<[u8; 32]> {
+        self.computed_entry_hash_in(DEFAULT_HASH_DOMAIN)
+    }
 
-    let mut computed = leaf;
-    for p in proof.iter() {
-        let (left, right) = if computed <= *p { (computed, *p) } else { (*p, computed) };
-        let h = hashv(&[left.as_ref(), right.as_ref()]);
-        computed = h.to_bytes();
+    /// computed_entry_hash を Grant.hash_domain を指定して計算する（initialize_hash_domain 済みのデプロイ用）
+    pub fn computed_entry_hash_in(&self, domain: &[u8]) -> Option<[u8; 32]> {
+        pop_entry_hash(PopEntryHashInput {
+            domain,
+            version: self.version,
+            prev_hash: &self.prev_hash,
+            stream_prev_hash: &self.stream_prev_hash,
+            audit_hash: &self.audit_hash,
+            grant: &self.grant,
+            claimer: &self.claimer,
+            period_index: self.period_index,
+            issued_at: self.issued_at,
+        })
+        .ok()
     }
-    computed == root
+}
+
+/// v3 形式の PoP message で署名者が指定した額を支給した（computed_amount は指定がなかった場合の額）
+#[event]
+pub struct PopAmountAuthorized {
+    pub schema_version: u8,
+    pub grant: Pubkey,
+    pub claimer: Pubkey,
+    pub period_index: u64,
+    pub authorized_amount: u64,
+    pub computed_amount: u64,
+    pub ceiling: u64,
+}
+
+/// pop_heartbeat で PoP signer の死活通知を受信した（previous_heartbeat_at = 0 は初回）
+#[event]
+pub struct PopSignerHeartbeat {
+    pub schema_version: u8,
+    pub pop_config: Pubkey,
+    pub signer: Pubkey,
+    pub heartbeat_at: i64,
+    pub previous_heartbeat_at: i64,
+}
+
+/// admin_nonce 付きの管理命令が実行された（action は命令名、admin_nonce は消費した値）
+#[event]
+pub struct AdminActionExecuted {
+    pub schema_version: u8,
+    pub grant: Pubkey,
+    pub actor: Pubkey,
+    pub action: String,
+    pub admin_nonce: u64,
+}
+
+/// set_pop_bypass で PoP なし claim の緊急期間を設定・解除した（until = 0 は解除）
+#[event]
+pub struct PopBypassSet {
+    pub schema_version: u8,
+    pub grant: Pubkey,
+    pub authority: Pubkey,
+    pub until: i64,
+    pub set_at: i64,
+}
+
+/// 緊急期間中に PoP なしで claim された（receipt の pop_bypassed も立つ）
+#[event]
+pub struct PopBypassClaimed {
+    pub schema_version: u8,
+    pub grant: Pubkey,
+    pub claimer: Pubkey,
+    pub period_index: u64,
+    /// claimer に支給した額（streak ボーナスを含み、platform fee は含まない）
+    pub amount: u64,
+    pub bypass_until: i64,
+    pub claimed_at: i64,
+}
+
+/// claim 時に PoP signer の heartbeat 途絶を検出した（enforced でない場合のみ、claim は続行）
+#[event]
+pub struct PopSignerSilent {
+    pub schema_version: u8,
+    pub pop_config: Pubkey,
+    pub signer: Pubkey,
+    pub grant: Pubkey,
+    pub last_heartbeat_at: i64,
+    pub silent_for: i64,
+}
+
+/// anchor_audit_batch で audit_hash のバッチが記録された
+#[event]
+pub struct AuditBatchAnchored {
+    pub schema_version: u8,
+    pub pop_config: Pubkey,
+    pub signer: Pubkey,
+    pub audit_anchor: Pubkey,
+    pub batch_root: [u8; 32],
+    pub entry_count: u32,
+    pub first_issued_at: i64,
+    pub last_issued_at: i64,
+    pub anchored_at: i64,
+}
+
+#[event]
+pub struct ReceiptStatusChanged {
+    pub schema_version: u8,
+    pub grant: Pubkey,
+    pub claimer: Pubkey,
+    pub period_index: u64,
+    pub receipt: Pubkey,
+    pub from: ReceiptStatus,
+    pub to: ReceiptStatus,
+    pub actor: Pubkey,
+    pub reason_code: u16,
+    pub changed_at: i64,
+}
+
+/// return_claim で claimer が受給額を vault に返した
+#[event]
+pub struct ClaimReturned {
+    pub schema_version: u8,
+    pub grant: Pubkey,
+    pub claimer: Pubkey,
+    pub period_index: u64,
+    pub amount: u64,
+    /// 初回受給だったため unique_claimers の枠が戻った
+    pub freed_claimer_slot: bool,
+    pub returned_at: i64,
+}
+
+/// start_new_season で新しいシーズンが始まった
+#[event]
+pub struct SeasonStarted {
+    pub schema_version: u8,
+    pub grant: Pubkey,
+    pub season: u32,
+    pub start_ts: i64,
+    pub expires_at: i64,
+    pub first_period_index: u64,
+}
+
+/// fund_grant_from_budget で operator が入金した
+#[event]
+pub struct FundedFromBudget {
+    pub schema_version: u8,
+    pub grant: Pubkey,
+    pub operator: Pubkey,
+    pub amount: u64,
+    pub window_start: i64,
+    pub window_used: u64,
+    pub monthly_cap: u64,
+}
+
+/// roll_up_child_stats で子 Grant の実績が親に集計された
+#[event]
+pub struct ChildStatsRolledUp {
+    pub schema_version: u8,
+    pub parent: Pubkey,
+    pub child: Pubkey,
+    pub region_code: u16,
+    pub child_claims_count: u64,
+    pub child_total_claimed: u128,
+    pub children_claims_count: u64,
+    pub children_total_claimed: u128,
+}
+
+/// authorize_session で session key が登録された
+#[event]
+pub struct SessionAuthorized {
+    pub schema_version: u8,
+    pub grant: Pubkey,
+    pub claimer: Pubkey,
+    pub session_key: Pubkey,
+    pub scope: u8,
+    pub expires_at: i64,
+}
+
+/// claim で受給アテステーションを発行した
+#[event]
+pub struct DisbursementAttested {
+    pub schema_version: u8,
+    pub grant: Pubkey,
+    pub claimer: Pubkey,
+    pub period_index: u64,
+    pub amount: u64,
+    pub attestation_program: Pubkey,
+    pub attestation_record: Pubkey,
+}
+
+/// schedule_claim で claim が予約された
+#[event]
+pub struct ClaimScheduled {
+    pub schema_version: u8,
+    pub grant: Pubkey,
+    pub claimer: Pubkey,
+    pub period_index: u64,
+    pub execute_after: i64,
+    pub keeper: Pubkey,
+}
+
+/// 予約された claim を keeper が実行した
+#[event]
+pub struct ScheduledClaimExecuted {
+    pub schema_version: u8,
+    pub grant: Pubkey,
+    pub claimer: Pubkey,
+    pub period_index: u64,
+    pub keeper: Pubkey,
+}
+
+/// cancel_scheduled_claim で予約が取り消された
+#[event]
+pub struct ScheduledClaimCancelled {
+    pub schema_version: u8,
+    pub grant: Pubkey,
+    pub claimer: Pubkey,
+    pub period_index: u64,
+}
+
+/// 受給者が ATA 以外の受給先を許可した（opted_out = true）/ 取り消した（opted_out = false）
+#[event]
+pub struct DestinationOptOutChanged {
+    pub schema_version: u8,
+    pub grant: Pubkey,
+    pub claimer: Pubkey,
+    pub opted_out: bool,
+}
+
+/// record_historical_claim で移行前の受給を receipt として記録した（送金なし）
+#[event]
+pub struct HistoricalClaimRecorded {
+    pub schema_version: u8,
+    pub grant: Pubkey,
+    pub claimer: Pubkey,
+    pub period_index: u64,
+    pub receipt: Pubkey,
+    pub amount: u64,
+    /// 実際に支給された時刻（移行元の記録）
+    pub claimed_at: i64,
+    pub recorded_at: i64,
+}
+
+/// mint_receipt_nft で受給記録の NFT を発行した（member_number は group 内の通し番号、1 から）
+#[event]
+pub struct ReceiptNftMinted {
+    pub schema_version: u8,
+    pub grant: Pubkey,
+    pub claimer: Pubkey,
+    pub period_index: u64,
+    pub receipt: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub member_number: u64,
+}
+
+/// initialize_deployment でクラスタの FeatureFlags / TreasuryConfig が作成された
+#[event]
+pub struct DeploymentInitialized {
+    pub schema_version: u8,
+    pub program_version: [u16; 3],
+    pub upgrade_authority: Pubkey,
+    pub admin: Pubkey,
+    pub enabled_features: u64,
+    pub treasury: Pubkey,
+    pub claim_fee_lamports: u64,
+    pub fee_split_count: u8,
+    pub initialized_at: i64,
+}
+
+/// authority が受給者宛ての暗号化メモを添付/差し替えた（中身は memo_hash のみ。配信は ClaimerMemoDelivered）
+#[event]
+pub struct ClaimerMemoAttached {
+    pub schema_version: u8,
+    pub grant: Pubkey,
+    pub claimer: Pubkey,
+    pub memo_hash: [u8; 32],
+    pub revision: u32,
+    pub attached_at: i64,
+}
+
+/// claim で受給者宛ての暗号化メモを配信した（受給者は自分の鍵で ciphertext を復号する）
+#[event]
+pub struct ClaimerMemoDelivered {
+    pub schema_version: u8,
+    pub grant: Pubkey,
+    pub claimer: Pubkey,
+    pub period_index: u64,
+    pub ephemeral_pubkey: [u8; 32],
+    pub nonce: [u8; 24],
+    pub ciphertext: Vec<u8>,
+    pub revision: u32,
+}
+
+/// 受給者が通知先の設定を更新した（preference_hash = [0;32] は削除 = 同意の撤回）
+#[event]
+pub struct NotificationPreferenceChanged {
+    pub schema_version: u8,
+    pub claimer: Pubkey,
+    pub preference_hash: [u8; 32],
+    pub revision: u32,
+    pub updated_at: i64,
+}
+
+/// revoke_session で session key の登録が取り消された
+#[event]
+pub struct SessionRevoked {
+    pub schema_version: u8,
+    pub grant: Pubkey,
+    pub claimer: Pubkey,
+    pub session_key: Pubkey,
+}
+
+/// delegate の索引ページに受給者が追加された（added = true）/ 外された（added = false）
+#[event]
+pub struct DelegateIndexUpdated {
+    pub schema_version: u8,
+    pub grant: Pubkey,
+    pub delegate: Pubkey,
+    pub page: u32,
+    pub claimer: Pubkey,
+    pub added: bool,
+    /// 更新後のページ内の件数
+    pub len: u32,
+}
+
+/// reserve_receipts で receipt が予約された（reserved = 新規に作成した件数）
+#[event]
+pub struct ReceiptsReserved {
+    pub schema_version: u8,
+    pub grant: Pubkey,
+    pub period_index: u64,
+    pub reserver: Pubkey,
+    pub reserved: u32,
+}
+
+/// fund_with_swap で別 mint の入金が両替されて vault に入った（amount_in は from_ata からの実際の支出）
+#[event]
+pub struct FundedWithSwap {
+    pub schema_version: u8,
+    pub grant: Pubkey,
+    pub funder: Pubkey,
+    pub input_mint: Pubkey,
+    pub amm_program: Pubkey,
+    pub amount_in: u64,
+    pub amount_out: u64,
+}
+
+/// archive_receipts で receipt が archive に記録された（葉の位置は first_leaf_index から count 個）
+#[event]
+pub struct ReceiptsArchived {
+    pub schema_version: u8,
+    pub grant: Pubkey,
+    pub first_leaf_index: u64,
+    pub count: u32,
+    pub leaf_count: u64,
+}
+
+/// archive が確定した（Grant の最終的な記録。receipt はこの後 close される）
+#[event]
+pub struct GrantArchived {
+    pub schema_version: u8,
+    pub grant: Pubkey,
+    pub receipts_root: [u8; 32],
+    pub leaf_count: u64,
+    pub tree_depth: u8,
+    pub total_funded: u128,
+    pub total_claimed: u128,
+    pub total_platform_fees: u128,
+    pub claims_count: u64,
+    pub unique_claimers: u64,
+    pub archived_at: i64,
+}
+
+/// close_period の実行者に keeper bounty が支払われた
+#[event]
+pub struct KeeperBountyPaid {
+    pub schema_version: u8,
+    pub grant: Pubkey,
+    pub keeper: Pubkey,
+    pub period_index: u64,
+    pub amount: u64,
+    pub from_pool: bool,
+}
+
+/// commit_allowlist_root で allowlist が要約付きで設定された
+#[event]
+pub struct AllowlistRootCommitted {
+    pub schema_version: u8,
+    pub grant: Pubkey,
+    pub merkle_root: [u8; 32],
+    pub leaf_count: u64,
+    pub snapshot_hash: [u8; 32],
+    pub committed_by: Pubkey,
+    pub committed_at: i64,
+}
+
+/// append_allowlist_delta で allowlist に差分 root が追加された
+#[event]
+pub struct AllowlistDeltaAppended {
+    pub schema_version: u8,
+    pub grant: Pubkey,
+    pub base_root: [u8; 32],
+    pub delta_root: [u8; 32],
+    pub delta_index: u8,
+    pub added_count: u64,
+    pub eligible_count: u64,
+}
+
+/// close_period で期間が締められた（受給率 = claims_count / eligible_count）
+#[event]
+pub struct PeriodClosed {
+    pub schema_version: u8,
+    pub grant: Pubkey,
+    pub period_index: u64,
+    /// 0 = 未設定（受給率は算出できない）
+    pub eligible_count: u64,
+    pub claims_count: u64,
+    pub total_claimed: u128,
+    /// eligible_count - claims_count（未受給の人数。eligible_count が未設定なら 0）
+    pub unclaimed_count: u64,
+    pub closed_at: i64,
+}
+
+/// KPI 目標が設定された Grant の close_period で、目標に対する進捗を通知する
+#[event]
+pub struct KpiProgress {
+    pub schema_version: u8,
+    pub grant: Pubkey,
+    pub period_index: u64,
+    /// 締めた期間の受給率（eligible_count が未設定なら 0）
+    pub take_up_bps: u16,
+    pub target_take_up_bps: u16,
+    /// 締め時点の grant.unique_claimers
+    pub unique_claimers: u64,
+    pub target_unique_claimers: u64,
+    /// 達成済みの KPI_MILESTONE_*（今回の達成分を含む）
+    pub kpi_milestones: u8,
+}
+
+/// KPI 目標を初めて達成した（milestones = 今回新たに達成した KPI_MILESTONE_*）
+#[event]
+pub struct KpiMilestoneReached {
+    pub schema_version: u8,
+    pub grant: Pubkey,
+    pub period_index: u64,
+    pub milestones: u8,
+    pub reached_at: i64,
 }
 
 // ===== Errors =====
@@ -1051,8 +11932,274 @@ pub enum ErrorCode {
     PopGenesisMismatch,
     #[msg("PoP state grant mismatch")]
     PopStateGrantMismatch,
-    #[msg("PoP proof must include off-chain receipt commitment (v2)")]
+    #[msg("PoP proof must include off-chain receipt commitment (v2) for this grant")]
     PopReceiptCommitmentRequired,
     #[msg("PoP audit hash is missing")]
     PopAuditHashMissing,
+    #[msg("Invalid rate")]
+    InvalidRate,
+    #[msg("Rate config is required for yen-denominated grant")]
+    RateConfigRequired,
+    #[msg("Grant mint is not wrapped SOL")]
+    NotNativeMint,
+    #[msg("Gate attestation is required for this grant")]
+    GateAttestationRequired,
+    #[msg("Invalid gate attestation")]
+    InvalidGateAttestation,
+    #[msg("Gate attestation expired")]
+    GateAttestationExpired,
+    #[msg("PoP config account is required")]
+    PopConfigRequired,
+    #[msg("PoP config is still used by active grants")]
+    PopConfigInUse,
+    #[msg("Grant is still active")]
+    GrantStillActive,
+    #[msg("PoP state payer mismatch")]
+    PopStatePayerMismatch,
+    #[msg("Invalid batch size")]
+    InvalidBatchSize,
+    #[msg("Invalid batch account")]
+    InvalidBatchAccount,
+    #[msg("Invalid fee basis points")]
+    InvalidFeeBps,
+    #[msg("Platform fee account mismatch")]
+    PlatformFeeAccountMismatch,
+    #[msg("Grant is a draft awaiting reviewer activation")]
+    GrantNotActivated,
+    #[msg("Grant is already activated")]
+    GrantAlreadyActivated,
+    #[msg("Grant has already been claimed")]
+    GrantAlreadyClaimed,
+    #[msg("Reviewer must differ from authority")]
+    ReviewerMustDiffer,
+    #[msg("Invalid recovery parameters")]
+    InvalidRecovery,
+    #[msg("No pending recovery")]
+    NoPendingRecovery,
+    #[msg("Recovery delay has not elapsed")]
+    RecoveryLocked,
+    #[msg("Invalid streak bonus schedule")]
+    InvalidStreakBonus,
+    #[msg("Invalid blackout window")]
+    InvalidBlackoutWindow,
+    #[msg("Claims are disabled during a blackout window")]
+    ClaimBlackout,
+    #[msg("Mint has a freeze authority; pass allow_freeze_authority to accept it")]
+    FreezableMint,
+    #[msg("Vault is frozen")]
+    VaultFrozen,
+    #[msg("Vault has a delegate or close authority set")]
+    VaultAuthorityNotClean,
+    #[msg("Feature is not enabled on this deployment")]
+    FeatureDisabled,
+    #[msg("Claim streak account is required for this grant")]
+    ClaimStreakRequired,
+    #[msg("Minimum interval since the previous claim has not elapsed")]
+    ClaimTooSoon,
+    #[msg("Invalid escrow schedule")]
+    InvalidEscrowSchedule,
+    #[msg("Escrow schedule account is required for this grant")]
+    EscrowScheduleRequired,
+    #[msg("Funding exceeds the remaining escrow commitments")]
+    EscrowOverfunded,
+    #[msg("Tranche for this period is not fully funded")]
+    PeriodNotFunded,
+    #[msg("Tranche for this period is exhausted")]
+    TrancheExhausted,
+    #[msg("Grant has already been funded")]
+    GrantAlreadyFunded,
+    #[msg("Invalid match pool parameters")]
+    InvalidMatchPool,
+    #[msg("Match vault does not match the match pool")]
+    MatchVaultMismatch,
+    #[msg("Close the match pool before closing the grant")]
+    MatchPoolActive,
+    #[msg("Receipt account is not a valid ClaimReceipt for this grant, claimer and period")]
+    InvalidReceipt,
+    #[msg("Period has not ended yet")]
+    PeriodNotEnded,
+    #[msg("Period already closed")]
+    PeriodAlreadyClosed,
+    #[msg("PoP issued_at must be greater than the last accepted issued_at")]
+    PopIssuedAtNotIncreasing,
+    #[msg("Category is in the reserved range but not defined")]
+    InvalidCategory,
+    #[msg("Grant has reached its maximum number of unique claimers")]
+    MaxUniqueClaimersReached,
+    #[msg("Treasury account is missing or does not match the treasury config")]
+    InvalidTreasury,
+    #[msg("Max unique claimers cannot be below the current number of claimers")]
+    InvalidMaxUniqueClaimers,
+    #[msg("Vault does not match the grant")]
+    VaultMismatch,
+    #[msg("No mint migration is pending")]
+    NoPendingMintMigration,
+    #[msg("Mint migration is still time-locked")]
+    MintMigrationLocked,
+    #[msg("Mint migration is not allowed for this grant or mint")]
+    InvalidMintMigration,
+    #[msg("Audit batch root, entry count or issued_at range is invalid")]
+    InvalidAuditBatch,
+    #[msg("Audit hash is not included in the anchored batch")]
+    AuditHashNotAnchored,
+    #[msg("Receipt status transition is not allowed")]
+    InvalidReceiptTransition,
+    #[msg("Fee splits must be unique non-default recipients with a total of at most 10000 bps")]
+    InvalidFeeSplits,
+    #[msg("Fee recipient accounts are missing or do not match the treasury fee splits")]
+    FeeRecipientMismatch,
+    #[msg("Allowlist delta root is empty, duplicated or applied out of order")]
+    InvalidAllowlistDelta,
+    #[msg("Allowlist delta slots are full; consolidate with set_allowlist_root")]
+    AllowlistDeltasFull,
+    #[msg("Allowlist summary must have a snapshot hash and a leaf count matching the root")]
+    InvalidAllowlistSummary,
+    #[msg("This grant requires allowlist roots to be committed with a summary")]
+    AllowlistSummaryRequired,
+    #[msg("Keeper config is invalid (too many, empty or duplicated keepers)")]
+    InvalidKeeperConfig,
+    #[msg("Signer is not a registered keeper for this grant")]
+    KeeperNotAllowed,
+    #[msg("Keeper bounty requires bounty_source, keeper_ata and token_program")]
+    MissingKeeperBountyAccounts,
+    #[msg("Bounty source does not match the keeper config")]
+    KeeperBountySourceMismatch,
+    #[msg("Close the keeper config before closing the grant or changing its mint")]
+    KeeperConfigActive,
+    #[msg("Session key is invalid or does not match the session authorization")]
+    InvalidSession,
+    #[msg("Session authorization has expired")]
+    SessionExpired,
+    #[msg("Session key is not allowed to perform this action")]
+    SessionScopeNotAllowed,
+    #[msg("Parent grant is invalid (child grants cannot have children, or child does not belong to parent)")]
+    InvalidParentGrant,
+    #[msg("Funding budget requires an operator and a non-zero monthly cap")]
+    InvalidFundingBudget,
+    #[msg("Funding exceeds the operator's monthly budget")]
+    FundingBudgetExceeded,
+    #[msg("Funding source does not match the funding budget")]
+    FundingSourceMismatch,
+    #[msg("Invalid wind-down window")]
+    InvalidWindDown,
+    #[msg("Closing a claimable grant before expiry requires a completed wind-down")]
+    WindDownRequired,
+    #[msg("Not allowed while the grant is winding down")]
+    WindDownActive,
+    #[msg("Invalid sponsor")]
+    InvalidSponsor,
+    #[msg("PoP signer heartbeat is overdue")]
+    PopSignerHeartbeatOverdue,
+    #[msg("Invalid heartbeat policy")]
+    InvalidHeartbeatPolicy,
+    #[msg("Invalid PoP bypass window")]
+    InvalidPopBypass,
+    #[msg("Missing claim intent signature instruction")]
+    MissingClaimIntent,
+    #[msg("Claim intent is invalid or does not match this claim")]
+    InvalidClaimIntent,
+    #[msg("Claim intent has expired")]
+    ClaimIntentExpired,
+    #[msg("Invalid seed salt")]
+    InvalidSeedSalt,
+    #[msg("Seed salt can only be set once, before the first claim")]
+    SeedSaltLocked,
+    #[msg("Admin nonce does not match the grant's next admin nonce")]
+    AdminNonceMismatch,
+    #[msg("Scheduled claim is invalid")]
+    InvalidScheduledClaim,
+    #[msg("Scheduled claim cannot be executed yet")]
+    ScheduledClaimNotReady,
+    #[msg("Attestation program is invalid or does not match the grant")]
+    InvalidAttestationProgram,
+    #[msg("Attestation program and record accounts are required for this grant")]
+    AttestationAccountsRequired,
+    #[msg("Invalid KPI targets")]
+    InvalidKpiTargets,
+    #[msg("Invalid succession parameters")]
+    InvalidSuccession,
+    #[msg("Authority has been active within the inactivity timeout")]
+    AuthorityStillActive,
+    #[msg("No pending succession")]
+    NoPendingSuccession,
+    #[msg("Succession delay has not elapsed")]
+    SuccessionLocked,
+    #[msg("Invalid program officer")]
+    InvalidProgramOfficer,
+    #[msg("Program officer limit exceeded")]
+    OfficerLimitExceeded,
+    #[msg("Withdrawal destination does not match the program officer")]
+    OfficerDestinationMismatch,
+    #[msg("Officer withdrawals are not allowed for earmarked grants")]
+    OfficerWithdrawalNotAllowed,
+    #[msg("Receipt for this period already records a claim")]
+    ReceiptAlreadyClaimed,
+    #[msg("Period has already ended")]
+    PeriodAlreadyEnded,
+    #[msg("Budget forecast account is required for this grant")]
+    BudgetForecastRequired,
+    #[msg("Close the budget forecast before closing the grant")]
+    BudgetForecastActive,
+    #[msg("Grant is not yet open: the allowlist has not been set")]
+    NotYetOpen,
+    #[msg("Claim index account is required for this claimer")]
+    ClaimIndexRequired,
+    #[msg("Claimer token account is not the beneficiary's associated token account")]
+    NonCanonicalClaimerAta,
+    #[msg("Claimer token account is frozen")]
+    ClaimerAtaFrozen,
+    #[msg("Claimer token account has a close authority set")]
+    ClaimerAtaHasCloseAuthority,
+    #[msg("Delegate index page is full")]
+    DelegateIndexPageFull,
+    #[msg("Claimer is already in this delegate index page")]
+    DelegatedClaimerAlreadyIndexed,
+    #[msg("Claimer is not in this delegate index page")]
+    DelegatedClaimerNotIndexed,
+    #[msg("Delegate index page is not empty")]
+    DelegateIndexPageNotEmpty,
+    #[msg("Invalid eligibility predicate")]
+    InvalidEligibilityPredicate,
+    #[msg("Eligibility account does not match the grant or beneficiary")]
+    InvalidEligibilityAccount,
+    #[msg("Claimer does not satisfy the grant's eligibility predicate")]
+    NotEligible,
+    #[msg("Grant has not expired yet")]
+    GrantNotExpired,
+    #[msg("Archive is already finalized")]
+    ArchiveFinalized,
+    #[msg("Archive is not finalized yet")]
+    ArchiveNotFinalized,
+    #[msg("Finalize the archive before closing the grant")]
+    ArchiveInProgress,
+    #[msg("Mint decimals do not match expected_decimals")]
+    DecimalsMismatch,
+    #[msg("Invalid swap config")]
+    InvalidSwapConfig,
+    #[msg("Swap output is below min_amount_out")]
+    SwapSlippageExceeded,
+    #[msg("Swap spent more than amount_in")]
+    SwapInputExceeded,
+    #[msg("Claims for this grant cannot be invoked through this program")]
+    ClaimCpiNotAllowed,
+    #[msg("Invalid allowed claim caller")]
+    InvalidClaimCaller,
+    #[msg("Notification preference hash must not be empty")]
+    InvalidNotificationPreference,
+    #[msg("PoP authorized amount exceeds the grant ceiling or is not enabled")]
+    PopAmountAboveCeiling,
+    #[msg("Invalid stake multiplier configuration")]
+    InvalidStakeMultiplier,
+    #[msg("Invalid stake record")]
+    InvalidStakeRecord,
+    #[msg("Invalid claimer memo")]
+    InvalidClaimerMemo,
+    #[msg("Invalid hash domain")]
+    InvalidHashDomain,
+    #[msg("Invalid receipt NFT collection")]
+    InvalidReceiptNftCollection,
+    #[msg("Receipt NFT collection is full")]
+    ReceiptNftCollectionFull,
+    #[msg("Invalid historical claim")]
+    InvalidHistoricalClaim,
 }
@@ -0,0 +1,106 @@
+// ===== 金額計算 =====
+// 累計（total_funded / total_claimed / total_platform_fees）は u128 で保持する。
+// 高 decimals の mint で多数の期間を合計すると u64 を超えうるため、
+// 集計・按分の中間値はすべて u128 で計算し、送金額として使う値だけを u64 に戻す。
+
+use anchor_lang::prelude::*;
+
+use crate::ErrorCode;
+
+/// 累計に金額を加算する
+pub fn add_to_total(total: u128, amount: u64) -> Result<u128> {
+    total
+        .checked_add(amount as u128)
+        .ok_or(ErrorCode::MathOverflow.into())
+}
+
+/// 金額同士の和（送金額の検証用。u64 に収まらなければエラー）
+pub fn add_amounts(a: u64, b: u64) -> Result<u64> {
+    to_u64(a as u128 + b as u128)
+}
+
+/// 金額 × 係数（u64 に収まらなければエラー）
+pub fn mul_amount(amount: u64, factor: u64) -> Result<u64> {
+    to_u64(amount as u128 * factor as u128)
+}
+
+/// amount * numerator / denominator（切り捨て）
+pub fn mul_div(amount: u64, numerator: u64, denominator: u64) -> Result<u64> {
+    require!(denominator > 0, ErrorCode::MathOverflow);
+    to_u64(amount as u128 * numerator as u128 / denominator as u128)
+}
+
+/// 集計済みの値 previous を current に置き換えた累計（total は previous を含んでいること）
+/// 減少（current < previous）も扱い、previous が total に含まれていない不整合はエラーにする
+pub fn replace_in_total(total: u128, previous: u128, current: u128) -> Result<u128> {
+    total
+        .checked_sub(previous)
+        .and_then(|rest| rest.checked_add(current))
+        .ok_or(ErrorCode::MathOverflow.into())
+}
+
+pub fn to_u64(value: u128) -> Result<u64> {
+    u64::try_from(value).map_err(|_| error!(ErrorCode::MathOverflow))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn overflow() -> Error {
+        ErrorCode::MathOverflow.into()
+    }
+
+    #[test]
+    fn add_to_total_accumulates_past_u64() {
+        let total = add_to_total(u64::MAX as u128, u64::MAX).unwrap();
+        assert_eq!(total, 2 * u64::MAX as u128);
+        assert_eq!(add_to_total(u128::MAX - 1, 1).unwrap(), u128::MAX);
+        assert_eq!(add_to_total(u128::MAX, 1).unwrap_err(), overflow());
+    }
+
+    #[test]
+    fn add_amounts_rejects_u64_overflow() {
+        assert_eq!(add_amounts(u64::MAX - 1, 1).unwrap(), u64::MAX);
+        assert_eq!(add_amounts(u64::MAX, 1).unwrap_err(), overflow());
+    }
+
+    #[test]
+    fn mul_amount_rejects_u64_overflow() {
+        assert_eq!(mul_amount(u64::MAX, 1).unwrap(), u64::MAX);
+        assert_eq!(mul_amount(u32::MAX as u64 + 1, u32::MAX as u64 + 1).unwrap_err(), overflow());
+    }
+
+    #[test]
+    fn mul_div_uses_u128_intermediate_and_truncates() {
+        // amount * numerator は u64 を超えるが、結果は u64 に収まる
+        assert_eq!(mul_div(u64::MAX, 10_000, 10_000).unwrap(), u64::MAX);
+        assert_eq!(mul_div(u64::MAX, 2, 4).unwrap(), u64::MAX / 2);
+        assert_eq!(mul_div(999, 1, 10).unwrap(), 99);
+        assert_eq!(mul_div(0, u64::MAX, 1).unwrap(), 0);
+        assert_eq!(mul_div(u64::MAX, 2, 1).unwrap_err(), overflow());
+    }
+
+    #[test]
+    fn mul_div_rejects_zero_denominator() {
+        assert_eq!(mul_div(1, 1, 0).unwrap_err(), overflow());
+        assert_eq!(mul_div(0, 0, 0).unwrap_err(), overflow());
+    }
+
+    #[test]
+    fn replace_in_total_applies_increase_and_decrease() {
+        assert_eq!(replace_in_total(10, 3, 5).unwrap(), 12);
+        assert_eq!(replace_in_total(10, 5, 3).unwrap(), 8);
+        assert_eq!(replace_in_total(10, 10, 0).unwrap(), 0);
+        assert_eq!(replace_in_total(u128::MAX, 1, 1).unwrap(), u128::MAX);
+        assert_eq!(replace_in_total(2, 3, 5).unwrap_err(), overflow());
+        assert_eq!(replace_in_total(u128::MAX, 0, 1).unwrap_err(), overflow());
+    }
+
+    #[test]
+    fn to_u64_at_the_boundary() {
+        assert_eq!(to_u64(u64::MAX as u128).unwrap(), u64::MAX);
+        assert_eq!(to_u64(u64::MAX as u128 + 1).unwrap_err(), overflow());
+        assert_eq!(to_u64(0).unwrap(), 0);
+    }
+}
@@ -0,0 +1,105 @@
+// ===== receipt 検証（他プログラム向け） =====
+// ClaimReceipt は (grant, claimer, period_index) ごとに1つだけ作られ、存在すること自体が受給済みの証明になる
+// （ただし reserve_receipts で事前に作られた status = Reserved のものは未受給）。
+// 他プログラムは grant_program クレート（features = ["cpi"] など）に依存し、
+// #[account(constraint = grant_program::receipt::is_valid_receipt(...))] の形で使える。
+// 支給は後から取り消されることがある（status）。is_valid_receipt は status == Valid のものだけを通す。
+// record_historical_claim で記録した移行前の受給（OffChainSettled）も is_valid_receipt では通らない。
+//
+// アカウントレイアウト（owner = grant_program::ID、合計 115 bytes）:
+//   [0..8)   discriminator = sha256("account:ClaimReceipt")[..8]
+//   [8..40)  grant        Pubkey
+//   [40..72) claimer      Pubkey
+//   [72..80) period_index u64 (LE)
+//   [80..88) claimed_at   i64 (LE, unix 秒)
+//   [88]     bump         u8
+//   [89]     status       u8 (0 = Valid, 1 = Disputed, 2 = Revoked, 3 = Refunded,
+//                             4 = Reserved, 5 = Archived, 6 = OffChainSettled)
+//   [90..98) amount       u64 (LE, claimer に支給した額)
+//   [98]     pop_bypassed u8 (1 = set_pop_bypass の緊急期間中に PoP なしで受給)
+//   [99..115) seed_salt   [u8; 16] (Grant.seed_salt。全 0 = salt なし)
+// seeds = [b"receipt", grant, claimer, period_index.to_le_bytes(), salt, &[bump]]
+//   salt は seed_salt が全 0 なら空（salt 導入前と同じアドレス）、それ以外は seed_salt の 16 bytes
+
+use anchor_lang::prelude::*;
+
+use crate::{seed_salt_seed, ClaimReceipt, ErrorCode, ReceiptStatus, SEED_SALT_LEN};
+
+/// 上記レイアウトの合計サイズ（discriminator を含む）
+pub const RECEIPT_ACCOUNT_LEN: usize = 115;
+
+/// receipt PDA のアドレスと bump（seed_salt は Grant.seed_salt）
+pub fn receipt_address(
+    grant: &Pubkey,
+    claimer: &Pubkey,
+    period_index: u64,
+    seed_salt: &[u8; SEED_SALT_LEN],
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            b"receipt",
+            grant.as_ref(),
+            claimer.as_ref(),
+            &period_index.to_le_bytes(),
+            seed_salt_seed(seed_salt),
+        ],
+        &crate::ID,
+    )
+}
+
+/// 保存済みの bump から receipt PDA のアドレスを再計算する（find_program_address より安価）
+pub fn receipt_address_with_bump(
+    grant: &Pubkey,
+    claimer: &Pubkey,
+    period_index: u64,
+    seed_salt: &[u8; SEED_SALT_LEN],
+    bump: u8,
+) -> Result<Pubkey> {
+    Pubkey::create_program_address(
+        &[
+            b"receipt",
+            grant.as_ref(),
+            claimer.as_ref(),
+            &period_index.to_le_bytes(),
+            seed_salt_seed(seed_salt),
+            &[bump],
+        ],
+        &crate::ID,
+    )
+    .map_err(|_| error!(ErrorCode::InvalidReceipt))
+}
+
+/// receipt が grant_program の所有で、(grant, claimer, period_index) の PDA と一致し、内容も一致することを検証する
+/// status は見ない（取り消し済みかどうかは呼び出し側で判断する）
+pub fn verify_receipt(
+    receipt: &AccountInfo,
+    grant: &Pubkey,
+    claimer: &Pubkey,
+    period_index: u64,
+) -> Result<ClaimReceipt> {
+    require_keys_eq!(*receipt.owner, crate::ID, ErrorCode::InvalidReceipt);
+
+    let data = receipt.try_borrow_data()?;
+    let decoded = ClaimReceipt::try_deserialize(&mut &data[..])?;
+    require!(
+        decoded.grant == *grant
+            && decoded.claimer == *claimer
+            && decoded.period_index == period_index,
+        ErrorCode::InvalidReceipt
+    );
+    // salt は receipt に保存された値を使う（grant が seed に含まれるため、別 Grant の receipt は通らない）
+    let expected = receipt_address_with_bump(grant, claimer, period_index, &decoded.seed_salt, decoded.bump)?;
+    require_keys_eq!(receipt.key(), expected, ErrorCode::InvalidReceipt);
+    Ok(decoded)
+}
+
+/// account constraint 用（検証に失敗した場合、または予約のみ・異議申し立て中・取り消し済みの場合は false）
+pub fn is_valid_receipt(
+    receipt: &AccountInfo,
+    grant: &Pubkey,
+    claimer: &Pubkey,
+    period_index: u64,
+) -> bool {
+    verify_receipt(receipt, grant, claimer, period_index)
+        .is_ok_and(|decoded| decoded.status == ReceiptStatus::Valid)
+}
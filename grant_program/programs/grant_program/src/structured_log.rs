@@ -0,0 +1,50 @@
+// ===== 構造化ログ =====
+// structured-logs feature 付きのビルドでは、資金が動く命令
+// （claim 系 / fund_grant / fund_grant_from_budget / fund_with_swap / return_claim）が
+// 命令ごとに1行の JSON を msg! で出力する。event を取得できず、ログしか取れない環境のオフチェーン解析用。
+//   {"v":1,"ix":"claim_grant","grant":"<base58>","claimer":"<base58>","period":12,"amount":1000000,"result":"ok","error":null}
+// - キーの順序と意味は v が同じ間は変えない（項目を足す場合は末尾に追加し、意味を変える場合は v を上げる）
+// - 値がない項目は null（入金の claimer / period など。失敗時の amount も null）
+// - 失敗時は result = "error"、error = エラーコード番号（ErrorCode は 6000 + 定義順）
+// feature なしのビルドでは何も出力せず、ログの項目も計算しない。
+
+use anchor_lang::prelude::*;
+
+/// JSON の v（キーの意味を変える場合に上げる）
+pub const STRUCTURED_LOG_VERSION: u8 = 1;
+
+/// 1命令分のログの項目
+pub struct LogLine<'a> {
+    pub instruction: &'a str,
+    pub grant: Pubkey,
+    pub claimer: Option<Pubkey>,
+    pub period: Option<u64>,
+    pub amount: Option<u64>,
+}
+
+/// 命令の結果を1行の JSON で出力する（line は feature 有効時にだけ呼ばれる）
+#[cfg(feature = "structured-logs")]
+pub fn emit<'a, T>(result: &Result<T>, line: impl FnOnce() -> LogLine<'a>) {
+    let line = line();
+    let key = |key: Option<Pubkey>| key.map_or("null".to_string(), |key| format!("\"{key}\""));
+    let number = |value: Option<u64>| value.map_or("null".to_string(), |value| value.to_string());
+    let (status, error) = match result {
+        Ok(_) => ("ok", None),
+        Err(Error::AnchorError(error)) => ("error", Some(error.error_code_number as u64)),
+        Err(Error::ProgramError(error)) => ("error", Some(u64::from(error.program_error.clone()))),
+    };
+    msg!(
+        "{{\"v\":{},\"ix\":\"{}\",\"grant\":\"{}\",\"claimer\":{},\"period\":{},\"amount\":{},\"result\":\"{}\",\"error\":{}}}",
+        STRUCTURED_LOG_VERSION,
+        line.instruction,
+        line.grant,
+        key(line.claimer),
+        number(line.period),
+        number(line.amount.filter(|_| result.is_ok())),
+        status,
+        number(error),
+    );
+}
+
+#[cfg(not(feature = "structured-logs"))]
+pub fn emit<'a, T>(_result: &Result<T>, _line: impl FnOnce() -> LogLine<'a>) {}
@@ -111,7 +111,11 @@ async function main() {
       new anchor.BN(amountPerPeriod.toString()),
       new anchor.BN(periodSeconds.toString()),
       new anchor.BN(startTs.toString()),
-      new anchor.BN(expiresAt.toString())
+      new anchor.BN(expiresAt.toString()),
+      false,
+      0,
+      false,
+      null
     )
     .accounts({
       grant: grantPda,
@@ -0,0 +1,176 @@
+/**
+ * 新しいクラスタの初期化スクリプト（genesis）
+ *
+ * grant_program を対象クラスタに deploy 済みであること。
+ * このスクリプトは: 設定ファイルの読み込み → initialize_deployment（FeatureFlags / TreasuryConfig を1命令で作成）
+ * → 作成されたアカウントの読み戻し を行い、結果を deployment manifest（JSON）として書き出す。
+ *
+ * 実行:
+ *   cd grant_program && DEPLOYMENT_CLUSTER=devnet ANCHOR_WALLET=~/.config/solana/id.json npm run deployment:init
+ *
+ * - 設定: deployments/<cluster>.config.json（DEPLOYMENT_CONFIG で別のパスを指定できる）
+ * - 出力: deployments/<cluster>.manifest.json（DEPLOYMENT_MANIFEST で別のパスを指定できる）
+ * - ANCHOR_WALLET はプログラムの upgrade authority であること（初期化の手数料と rent も支払う）
+ * - DRY_RUN=1 で送信せず、作成される内容とアドレスだけを表示する
+ *
+ * 前提: 対象クラスタで一度だけ実行する。FeatureFlags または TreasuryConfig が既にあれば
+ *       命令は失敗する（途中まで設定された状態のクラスタは作らない）。
+ */
+import fs from 'fs';
+import path from 'path';
+
+import * as anchor from '@coral-xyz/anchor';
+import { Program } from '@coral-xyz/anchor';
+import { PublicKey, Keypair, Connection } from '@solana/web3.js';
+
+import { GrantProgram } from '../target/types/grant_program';
+
+const BPF_LOADER_UPGRADEABLE_ID = new PublicKey('BPFLoaderUpgradeab1e11111111111111111111111');
+
+const CLUSTER = process.env.DEPLOYMENT_CLUSTER || 'devnet';
+const CONFIG_PATH =
+  process.env.DEPLOYMENT_CONFIG || path.resolve(__dirname, `../deployments/${CLUSTER}.config.json`);
+const MANIFEST_PATH =
+  process.env.DEPLOYMENT_MANIFEST || path.resolve(__dirname, `../deployments/${CLUSTER}.manifest.json`);
+const WALLET_PATH = process.env.ANCHOR_WALLET || path.join(process.env.HOME!, '.config/solana/id.json');
+const DRY_RUN = process.env.DRY_RUN === '1';
+
+/** deployments/<cluster>.config.json の形式（null の鍵は wallet を使う。treasury は claimFeeLamports が 0 なら未設定でよい） */
+interface DeploymentConfig {
+  cluster: string;
+  rpcUrl: string;
+  admin: string | null;
+  /** FEATURE_* の名前（例: "BATCH_CREATE"）。IDL の constants から bit を引く */
+  enabledFeatures: string[];
+  treasury: string | null;
+  claimFeeLamports: number;
+  /** role は FEE_ROLE_*（0 = platform, 1 = operator, 2 = referrer） */
+  feeSplits: { recipient: string; bps: number; role: number }[];
+}
+
+function idlConstant(idl: any, name: string): string {
+  const constant = (idl.constants || []).find((c: any) => c.name === name);
+  if (!constant) {
+    throw new Error(`IDL に定数 ${name} がありません（anchor build をやり直してください）`);
+  }
+  return constant.value;
+}
+
+/** IDL の定数は Rust の式のまま入る（FEATURE_* は "1 << n"） */
+function featureBit(idl: any, name: string): bigint {
+  const value = idlConstant(idl, `FEATURE_${name}`).replace(/_/g, '');
+  const shift = value.match(/^\s*1\s*<<\s*(\d+)\s*$/);
+  return shift ? BigInt(1) << BigInt(shift[1]) : BigInt(value);
+}
+
+function featureBits(idl: any, names: string[]): bigint {
+  return names.reduce((bits, name) => bits | featureBit(idl, name), BigInt(0));
+}
+
+async function main() {
+  const config: DeploymentConfig = JSON.parse(fs.readFileSync(CONFIG_PATH, 'utf8'));
+  if (config.cluster !== CLUSTER) {
+    throw new Error(`設定ファイルの cluster (${config.cluster}) が DEPLOYMENT_CLUSTER (${CLUSTER}) と一致しません`);
+  }
+  const rpcUrl = process.env.ANCHOR_PROVIDER_URL || config.rpcUrl;
+  console.log('[initialize_deployment] cluster:', CLUSTER);
+  console.log('[initialize_deployment] RPC:', rpcUrl);
+  console.log('[initialize_deployment] config:', CONFIG_PATH);
+
+  const walletKeypair = JSON.parse(fs.readFileSync(WALLET_PATH, 'utf8'));
+  const wallet = Keypair.fromSecretKey(Uint8Array.from(walletKeypair));
+  const connection = new Connection(rpcUrl);
+  const provider = new anchor.AnchorProvider(connection, new anchor.Wallet(wallet), { commitment: 'confirmed' });
+  anchor.setProvider(provider);
+
+  const idlPath = path.resolve(__dirname, '../target/idl/grant_program.json');
+  const idl = JSON.parse(fs.readFileSync(idlPath, 'utf8'));
+  const program = new Program(idl as any, provider) as Program<GrantProgram>;
+
+  const [featureFlagsPda] = PublicKey.findProgramAddressSync([Buffer.from('feature-flags')], program.programId);
+  const [treasuryConfigPda] = PublicKey.findProgramAddressSync([Buffer.from('treasury-config')], program.programId);
+  const [programDataAddress] = PublicKey.findProgramAddressSync(
+    [program.programId.toBuffer()],
+    BPF_LOADER_UPGRADEABLE_ID
+  );
+
+  const params = {
+    admin: config.admin ? new PublicKey(config.admin) : wallet.publicKey,
+    enabledFeatures: new anchor.BN(featureBits(idl, config.enabledFeatures).toString()),
+    treasury: config.treasury ? new PublicKey(config.treasury) : PublicKey.default,
+    claimFeeLamports: new anchor.BN(config.claimFeeLamports),
+    feeSplits: config.feeSplits.map((split) => ({
+      recipient: new PublicKey(split.recipient),
+      bps: split.bps,
+      role: split.role,
+    })),
+  };
+  console.log('[initialize_deployment] programId:', program.programId.toBase58());
+  console.log('[initialize_deployment] upgrade authority:', wallet.publicKey.toBase58());
+  console.log('[initialize_deployment] admin:', params.admin.toBase58());
+  console.log('[initialize_deployment] enabledFeatures:', config.enabledFeatures.join(', ') || '(none)');
+  console.log('[initialize_deployment] treasury:', params.treasury.toBase58(), 'fee:', config.claimFeeLamports);
+
+  if (DRY_RUN) {
+    console.log('[initialize_deployment] DRY_RUN=1 のため送信しません');
+    console.log('featureFlags:', featureFlagsPda.toBase58());
+    console.log('treasuryConfig:', treasuryConfigPda.toBase58());
+    return;
+  }
+
+  const signature = await program.methods
+    .initializeDeployment(params)
+    .accounts({
+      featureFlags: featureFlagsPda,
+      treasuryConfig: treasuryConfigPda,
+      program: program.programId,
+      programData: programDataAddress,
+      upgradeAuthority: wallet.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId,
+    } as any)
+    .rpc();
+  console.log('[initialize_deployment] initialize_deployment 完了:', signature);
+
+  const transaction = await connection.getTransaction(signature, {
+    commitment: 'confirmed',
+    maxSupportedTransactionVersion: 0,
+  });
+  const featureFlags = await (program.account as any).featureFlags.fetch(featureFlagsPda);
+  const treasuryConfig = await (program.account as any).treasuryConfig.fetch(treasuryConfigPda);
+
+  // ---------- deployment manifest（オンチェーンから読み戻した値だけを書く） ----------
+  const manifest = {
+    cluster: CLUSTER,
+    rpcUrl,
+    programId: program.programId.toBase58(),
+    programData: programDataAddress.toBase58(),
+    programVersion: idlConstant(idl, 'PROGRAM_VERSION'),
+    eventSchemaVersion: idlConstant(idl, 'EVENT_SCHEMA_VERSION'),
+    upgradeAuthority: wallet.publicKey.toBase58(),
+    initializeSignature: signature,
+    initializeSlot: transaction ? transaction.slot : null,
+    initializedAt: transaction && transaction.blockTime ? transaction.blockTime : null,
+    featureFlags: {
+      address: featureFlagsPda.toBase58(),
+      admin: featureFlags.admin.toBase58(),
+      enabled: featureFlags.enabled.toString(),
+      enabledNames: config.enabledFeatures,
+    },
+    treasuryConfig: {
+      address: treasuryConfigPda.toBase58(),
+      treasury: treasuryConfig.treasury.toBase58(),
+      claimFeeLamports: treasuryConfig.claimFeeLamports.toString(),
+      feeSplits: treasuryConfig.feeSplits
+        .filter((split: any) => split.bps > 0)
+        .map((split: any) => ({ recipient: split.recipient.toBase58(), bps: split.bps, role: split.role })),
+    },
+  };
+  fs.writeFileSync(MANIFEST_PATH, JSON.stringify(manifest, null, 2) + '\n');
+  console.log('[initialize_deployment] manifest:', MANIFEST_PATH);
+  console.log('========== 完了 ==========');
+}
+
+main().catch((e) => {
+  console.error(e);
+  process.exit(1);
+});
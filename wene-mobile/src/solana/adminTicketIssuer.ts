@@ -483,7 +483,15 @@ export async function issueEventTicketToken(
           new BN(amountPerPeriod.toString()),
           new BN(periodSeconds.toString()),
           new BN(startTs.toString()),
-          new BN('0')
+          new BN('0'),
+          // freeze authority は Tx4 で放棄するため、作成時点では明示的に許容する
+          true,
+          // category: 未分類
+          0,
+          // 参加券は allowlist を使わない
+          false,
+          // amountPerPeriod は最小単位で指定済み
+          null
         )
         .accounts({
           grant: grantPda,
@@ -25,6 +25,24 @@ import { getConnection } from './singleton';
 // PDA 計算（純粋関数）
 // ============================================================
 
+/** Grant.seed_salt の長さ */
+export const SEED_SALT_LEN = 16;
+
+/**
+ * receipt / pop_state の seed に付ける Grant.seed_salt
+ * - set_seed_salt 未実行（すべて 0 または未指定）なら seed を付けない（プログラムの seed_salt_seed と同じ）
+ */
+export const seedSaltSeeds = (seedSalt?: Uint8Array | number[]): Buffer[] => {
+  if (!seedSalt) {
+    return [];
+  }
+  const salt = Buffer.from(seedSalt);
+  if (salt.length !== SEED_SALT_LEN) {
+    throw new Error(`seedSalt must be ${SEED_SALT_LEN} bytes`);
+  }
+  return salt.every((b) => b === 0) ? [] : [salt];
+};
+
 /**
  * Grant PDAを計算
  */
@@ -63,11 +81,13 @@ export const getVaultPda = (
 
 /**
  * Receipt PDAを計算
+ * - seedSalt: grant.seedSalt（Grant アカウントから読んだ値をそのまま渡す）
  */
 export const getReceiptPda = (
   grant: PublicKey,
   claimer: PublicKey,
   periodIndex: bigint,
+  seedSalt?: Uint8Array | number[],
   programId: PublicKey = GRANT_PROGRAM_ID
 ): [PublicKey, number] => {
   const periodIndexBytes = Buffer.alloc(8);
@@ -79,6 +99,7 @@ export const getReceiptPda = (
       grant.toBuffer(),
       claimer.toBuffer(),
       periodIndexBytes,
+      ...seedSaltSeeds(seedSalt),
     ],
     programId
   );
@@ -97,15 +118,57 @@ export const getPopConfigPda = (
   );
 };
 
+/**
+ * 受給累計 PDA（grant × claimer 単位）
+ */
+export const getClaimerProfilePda = (
+  grant: PublicKey,
+  claimer: PublicKey,
+  programId: PublicKey = GRANT_PROGRAM_ID
+): [PublicKey, number] => {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from('claimer-profile'), grant.toBuffer(), claimer.toBuffer()],
+    programId
+  );
+};
+
+/**
+ * 期間別の受給集計 PDA（grant × period_index 単位）
+ */
+export const getPeriodStatsPda = (
+  grant: PublicKey,
+  periodIndex: bigint,
+  programId: PublicKey = GRANT_PROGRAM_ID
+): [PublicKey, number] => {
+  const periodIndexBytes = Buffer.alloc(8);
+  periodIndexBytes.writeBigUInt64LE(periodIndex, 0);
+
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from('period-stats'), grant.toBuffer(), periodIndexBytes],
+    programId
+  );
+};
+
+/**
+ * protocol の claim 手数料設定 PDA（プログラムに1つ）
+ */
+export const getTreasuryConfigPda = (
+  programId: PublicKey = GRANT_PROGRAM_ID
+): [PublicKey, number] => {
+  return PublicKey.findProgramAddressSync([Buffer.from('treasury-config')], programId);
+};
+
 /**
  * PoP chain state PDA（grant 単位）
+ * - seedSalt: grant.seedSalt（Grant アカウントから読んだ値をそのまま渡す）
  */
 export const getPopStatePda = (
   grant: PublicKey,
+  seedSalt?: Uint8Array | number[],
   programId: PublicKey = GRANT_PROGRAM_ID
 ): [PublicKey, number] => {
   return PublicKey.findProgramAddressSync(
-    [Buffer.from('pop-state'), grant.toBuffer()],
+    [Buffer.from('pop-state'), grant.toBuffer(), ...seedSaltSeeds(seedSalt)],
     programId
   );
 };
@@ -148,15 +211,17 @@ export const buildClaimGrantTransaction = async (
     mint: PublicKey;
     grantId: bigint;
     periodIndex: bigint;
+    /** grant.seedSalt（set_seed_salt 済みの Grant では必須） */
+    seedSalt?: Uint8Array | number[];
   }
 ): Promise<Transaction> => {
   const connection = getConnection();
-  const { authority, mint, grantId, periodIndex } = params;
+  const { authority, mint, grantId, periodIndex, seedSalt } = params;
   
   // PDAを計算
   const [grantPda] = getGrantPda(authority, mint, grantId);
   const [_vaultPda] = getVaultPda(grantPda);
-  const [_receiptPda] = getReceiptPda(grantPda, claimer, periodIndex);
+  const [_receiptPda] = getReceiptPda(grantPda, claimer, periodIndex, seedSalt);
   
   // 受給者のATAを取得/作成
   const claimerAta = await getAssociatedTokenAddress(mint, claimer);
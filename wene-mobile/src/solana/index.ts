@@ -66,13 +66,40 @@ export {
 // ============================================================
 export {
   buildClaimTx,
+  buildClaimV0Tx,
   buildUseTx,
   type BuildClaimTxParams,
   type BuildClaimTxResult,
+  type BuildClaimV0TxParams,
+  type BuildClaimV0TxResult,
   type BuildUseTxParams,
   type BuildUseTxResult,
 } from './txBuilders';
 
+// ============================================================
+// プログラムのバージョン互換性（起動時チェック）
+// ============================================================
+export {
+  CLIENT_VERSION,
+  MIN_PROGRAM_VERSION,
+  checkProgramCompatibility,
+  assertProgramCompatibility,
+  type ProgramCompatibility,
+  type ProgramCompatibilityStatus,
+} from './programVersion';
+
+// ============================================================
+// Address Lookup Table（v0 トランザクション）
+// ============================================================
+export {
+  getCampaignLookupTableAddresses,
+  buildCreateLookupTableInstructions,
+  buildExtendLookupTableInstructions,
+  buildEnsureCampaignLookupTableInstructions,
+  fetchLookupTable,
+  buildV0Transaction,
+} from './lookupTable';
+
 // ============================================================
 // ウォレット残高（SOL / SPL）
 // ============================================================
@@ -0,0 +1,211 @@
+/**
+ * Address Lookup Table (ALT) ヘルパー
+ *
+ * 【目的】
+ * - キャンペーン（Grant）ごとに固定のアドレスを ALT にまとめ、
+ *   claim トランザクションを v0 message で構築してサイズ上限（1232 bytes）を回避する
+ *
+ * 【安定性のポイント】
+ * - アドレス一覧の計算は純粋関数（副作用なし）
+ * - ALT の作成・拡張は命令の構築のみ。署名・送信は呼び出し側で行う
+ * - 拡張時は既存のアドレスを除外するため、何度呼んでも重複しない
+ */
+
+import {
+  AddressLookupTableAccount,
+  AddressLookupTableProgram,
+  Connection,
+  Ed25519Program,
+  PublicKey,
+  SYSVAR_INSTRUCTIONS_PUBKEY,
+  SYSVAR_RENT_PUBKEY,
+  SystemProgram,
+  TransactionInstruction,
+  TransactionMessage,
+  VersionedTransaction,
+} from '@solana/web3.js';
+import { TOKEN_PROGRAM_ID } from '@solana/spl-token';
+
+import { GRANT_PROGRAM_ID } from './config';
+import { getGrantPda, getPopConfigPda, getPopStatePda, getVaultPda } from './grantProgram';
+
+/** extendLookupTable 1命令あたりのアドレス数（トランザクションサイズに収まる上限） */
+const MAX_ADDRESSES_PER_EXTEND = 20;
+
+/**
+ * キャンペーンの claim で毎回使われるアドレス一覧
+ * - 受給者ごとに異なる claimer / claimer_ata / receipt は含めない
+ */
+export const getCampaignLookupTableAddresses = (params: {
+  authority: PublicKey;
+  mint: PublicKey;
+  grantId: bigint;
+  /** grant.seedSalt（pop_state の seed。set_seed_salt 後は pop_state のアドレスが変わる） */
+  seedSalt?: Uint8Array | number[];
+  programId?: PublicKey;
+}): PublicKey[] => {
+  const programId = params.programId ?? GRANT_PROGRAM_ID;
+  const [grantPda] = getGrantPda(params.authority, params.mint, params.grantId, programId);
+  const [vaultPda] = getVaultPda(grantPda, programId);
+  const [popConfigPda] = getPopConfigPda(params.authority, programId);
+  const [popStatePda] = getPopStatePda(grantPda, params.seedSalt, programId);
+
+  return [
+    programId,
+    grantPda,
+    vaultPda,
+    params.mint,
+    popConfigPda,
+    popStatePda,
+    TOKEN_PROGRAM_ID,
+    SystemProgram.programId,
+    Ed25519Program.programId,
+    SYSVAR_INSTRUCTIONS_PUBKEY,
+    SYSVAR_RENT_PUBKEY,
+  ];
+};
+
+const chunk = <T>(items: T[], size: number): T[][] => {
+  const out: T[][] = [];
+  for (let i = 0; i < items.length; i += size) {
+    out.push(items.slice(i, i + size));
+  }
+  return out;
+};
+
+/**
+ * ALT を作成し、アドレスを登録する命令を構築
+ * - recentSlot は connection.getSlot('finalized') などで取得した値を渡す
+ * - 返り値の instructions は 1 トランザクションに収まる単位で送信すること
+ */
+export const buildCreateLookupTableInstructions = (params: {
+  authority: PublicKey;
+  payer?: PublicKey;
+  recentSlot: number;
+  addresses: PublicKey[];
+}): { lookupTable: PublicKey; instructions: TransactionInstruction[] } => {
+  const payer = params.payer ?? params.authority;
+  const [createIx, lookupTable] = AddressLookupTableProgram.createLookupTable({
+    authority: params.authority,
+    payer,
+    recentSlot: params.recentSlot,
+  });
+
+  const extendIxs = buildExtendLookupTableInstructions({
+    lookupTable,
+    authority: params.authority,
+    payer,
+    addresses: params.addresses,
+    existing: [],
+  });
+
+  return { lookupTable, instructions: [createIx, ...extendIxs] };
+};
+
+/**
+ * 既存 ALT に未登録のアドレスだけを追加する命令を構築（維持用）
+ * - 追加が不要な場合は空配列を返す
+ */
+export const buildExtendLookupTableInstructions = (params: {
+  lookupTable: PublicKey;
+  authority: PublicKey;
+  payer?: PublicKey;
+  addresses: PublicKey[];
+  existing: PublicKey[];
+}): TransactionInstruction[] => {
+  const payer = params.payer ?? params.authority;
+  const known = new Set(params.existing.map((address) => address.toBase58()));
+  const missing: PublicKey[] = [];
+  for (const address of params.addresses) {
+    const key = address.toBase58();
+    if (known.has(key)) continue;
+    known.add(key);
+    missing.push(address);
+  }
+
+  return chunk(missing, MAX_ADDRESSES_PER_EXTEND).map((addresses) =>
+    AddressLookupTableProgram.extendLookupTable({
+      lookupTable: params.lookupTable,
+      authority: params.authority,
+      payer,
+      addresses,
+    })
+  );
+};
+
+/**
+ * ALT を取得（存在しない場合は null）
+ */
+export const fetchLookupTable = async (
+  connection: Connection,
+  lookupTable: PublicKey
+): Promise<AddressLookupTableAccount | null> => {
+  const result = await connection.getAddressLookupTable(lookupTable);
+  return result.value;
+};
+
+/**
+ * キャンペーン用 ALT を最新の状態にする命令を構築
+ * - lookupTable が未指定または存在しない場合は新規作成
+ * - 存在する場合は不足アドレスのみ追加
+ */
+export const buildEnsureCampaignLookupTableInstructions = async (params: {
+  connection: Connection;
+  authority: PublicKey;
+  payer?: PublicKey;
+  mint: PublicKey;
+  grantId: bigint;
+  /** grant.seedSalt */
+  seedSalt?: Uint8Array | number[];
+  lookupTable?: PublicKey | null;
+}): Promise<{ lookupTable: PublicKey; instructions: TransactionInstruction[]; created: boolean }> => {
+  const addresses = getCampaignLookupTableAddresses({
+    authority: params.authority,
+    mint: params.mint,
+    grantId: params.grantId,
+    seedSalt: params.seedSalt,
+  });
+
+  const existing = params.lookupTable
+    ? await fetchLookupTable(params.connection, params.lookupTable)
+    : null;
+  if (params.lookupTable && existing) {
+    return {
+      lookupTable: params.lookupTable,
+      instructions: buildExtendLookupTableInstructions({
+        lookupTable: params.lookupTable,
+        authority: params.authority,
+        payer: params.payer,
+        addresses,
+        existing: existing.state.addresses,
+      }),
+      created: false,
+    };
+  }
+
+  const recentSlot = await params.connection.getSlot('finalized');
+  const { lookupTable, instructions } = buildCreateLookupTableInstructions({
+    authority: params.authority,
+    payer: params.payer,
+    recentSlot,
+    addresses,
+  });
+  return { lookupTable, instructions, created: true };
+};
+
+/**
+ * v0 message のトランザクションを構築（署名は呼び出し側で行う）
+ */
+export const buildV0Transaction = (params: {
+  payer: PublicKey;
+  recentBlockhash: string;
+  instructions: TransactionInstruction[];
+  lookupTables: AddressLookupTableAccount[];
+}): VersionedTransaction => {
+  const message = new TransactionMessage({
+    payerKey: params.payer,
+    recentBlockhash: params.recentBlockhash,
+    instructions: params.instructions,
+  }).compileToV0Message(params.lookupTables);
+  return new VersionedTransaction(message);
+};
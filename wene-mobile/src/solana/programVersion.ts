@@ -0,0 +1,137 @@
+/**
+ * Grant Program バージョン互換性チェック
+ *
+ * 【目的】
+ * - 再デプロイ後に古いクライアントがデシリアライズ失敗で原因不明のエラーになるのを防ぐ
+ * - 起動時に get_version を simulate し、互換性がなければ明示的なエラーを返す
+ *
+ * 【安定性のポイント】
+ * - simulate のみ（署名・送信しない）
+ * - get_version を持たない旧プログラムは 'unknown' として扱い、呼び出し側で判断する
+ */
+
+import {
+  PublicKey,
+  TransactionInstruction,
+  TransactionMessage,
+  VersionedTransaction,
+} from '@solana/web3.js';
+
+import { GRANT_PROGRAM_ID } from './config';
+import { getConnection } from './singleton';
+
+/** このクライアントのバージョン（major, minor, patch） */
+export const CLIENT_VERSION: readonly [number, number, number] = [0, 2, 0];
+/** このクライアントが対応するプログラムの最小バージョン */
+export const MIN_PROGRAM_VERSION: readonly [number, number, number] = [0, 2, 0];
+
+const GET_VERSION_DISCRIMINATOR = Buffer.from([168, 85, 244, 45, 81, 56, 130, 50]);
+const PROGRAM_VERSION_DATA_LEN = 2 * 3 * 2;
+
+export type SemVer = readonly [number, number, number];
+
+export type ProgramCompatibilityStatus = 'compatible' | 'client_outdated' | 'program_outdated' | 'unknown';
+
+export interface ProgramCompatibility {
+  status: ProgramCompatibilityStatus;
+  programVersion: SemVer | null;
+  minClientVersion: SemVer | null;
+  clientVersion: SemVer;
+}
+
+export const compareSemVer = (a: SemVer, b: SemVer): number => {
+  for (let i = 0; i < 3; i += 1) {
+    if (a[i] !== b[i]) return a[i] < b[i] ? -1 : 1;
+  }
+  return 0;
+};
+
+export const formatSemVer = (v: SemVer): string => `${v[0]}.${v[1]}.${v[2]}`;
+
+/**
+ * get_version の戻り値（ProgramVersion）をデコード
+ */
+export const decodeProgramVersion = (
+  data: Buffer
+): { programVersion: SemVer; minClientVersion: SemVer } | null => {
+  if (data.length < PROGRAM_VERSION_DATA_LEN) return null;
+  const read = (offset: number): SemVer => [
+    data.readUInt16LE(offset),
+    data.readUInt16LE(offset + 2),
+    data.readUInt16LE(offset + 4),
+  ];
+  return { programVersion: read(0), minClientVersion: read(6) };
+};
+
+/**
+ * デプロイ済みプログラムのバージョンを取得し、互換性を判定する
+ * - payer: simulate の fee payer（既存アカウントであること。署名は不要）
+ */
+export async function checkProgramCompatibility(
+  payer: PublicKey,
+  programId: PublicKey = GRANT_PROGRAM_ID
+): Promise<ProgramCompatibility> {
+  const connection = getConnection();
+  const unknown: ProgramCompatibility = {
+    status: 'unknown',
+    programVersion: null,
+    minClientVersion: null,
+    clientVersion: CLIENT_VERSION,
+  };
+
+  const ix = new TransactionInstruction({
+    programId,
+    keys: [],
+    data: GET_VERSION_DISCRIMINATOR,
+  });
+  const { blockhash } = await connection.getLatestBlockhash('confirmed');
+  const message = new TransactionMessage({
+    payerKey: payer,
+    recentBlockhash: blockhash,
+    instructions: [ix],
+  }).compileToV0Message();
+
+  const sim = await connection.simulateTransaction(new VersionedTransaction(message), {
+    sigVerify: false,
+    replaceRecentBlockhash: true,
+  });
+  const returnData = sim.value.returnData;
+  if (sim.value.err || !returnData || !returnData.programId || returnData.programId !== programId.toBase58()) {
+    return unknown;
+  }
+
+  const decoded = decodeProgramVersion(Buffer.from(returnData.data[0], 'base64'));
+  if (!decoded) return unknown;
+
+  let status: ProgramCompatibilityStatus = 'compatible';
+  if (compareSemVer(CLIENT_VERSION, decoded.minClientVersion) < 0) {
+    status = 'client_outdated';
+  } else if (compareSemVer(decoded.programVersion, MIN_PROGRAM_VERSION) < 0) {
+    status = 'program_outdated';
+  }
+
+  return {
+    status,
+    programVersion: decoded.programVersion,
+    minClientVersion: decoded.minClientVersion,
+    clientVersion: CLIENT_VERSION,
+  };
+}
+
+/**
+ * 互換性がない場合にエラーを投げる（起動時チェック用）
+ */
+export async function assertProgramCompatibility(payer: PublicKey): Promise<ProgramCompatibility> {
+  const result = await checkProgramCompatibility(payer);
+  if (result.status === 'client_outdated' && result.minClientVersion) {
+    throw new Error(
+      `アプリの更新が必要です: client=${formatSemVer(CLIENT_VERSION)} required>=${formatSemVer(result.minClientVersion)}`
+    );
+  }
+  if (result.status === 'program_outdated' && result.programVersion) {
+    throw new Error(
+      `Grant Program が古いバージョンです: program=${formatSemVer(result.programVersion)} required>=${formatSemVer(MIN_PROGRAM_VERSION)}`
+    );
+  }
+  return result;
+}
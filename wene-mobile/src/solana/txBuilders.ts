@@ -6,6 +6,7 @@ import {
   SystemProgram,
   Transaction,
   TransactionInstruction,
+  VersionedTransaction,
 } from '@solana/web3.js';
 import {
   TOKEN_PROGRAM_ID,
@@ -18,13 +19,17 @@ import { GRANT_PROGRAM_ID } from './config';
 import { getConnection, getProgram } from './anchorClient';
 import {
   calculatePeriodIndex,
+  getClaimerProfilePda,
   getGrantPda,
+  getPeriodStatsPda,
   getPopConfigPda,
   getPopStatePda,
   getReceiptPda,
+  getTreasuryConfigPda,
   getVaultPda,
 } from './grantProgram';
 import { DEFAULT_CLUSTER } from './cluster';
+import { buildV0Transaction, fetchLookupTable } from './lookupTable';
 import { DEVNET_GRANT_CONFIG } from './devnetConfig';
 import { RPC_URL } from './singleton';
 import { resolveApiBaseUrl } from '../api/resolveApiBaseUrl';
@@ -183,13 +188,14 @@ async function getOnchainPopPrevHashes(popStatePda: PublicKey): Promise<{
 
 function buildClaimGrantInstruction(params: {
   grant: PublicKey;
-  mint: PublicKey;
   vault: PublicKey;
   claimer: PublicKey;
   claimerAta: PublicKey;
   receipt: PublicKey;
   popState: PublicKey;
   popConfig: PublicKey;
+  claimerProfile: PublicKey;
+  periodStats: PublicKey;
   periodIndex: bigint;
 }): TransactionInstruction {
   const data = Buffer.alloc(16);
@@ -198,9 +204,9 @@ function buildClaimGrantInstruction(params: {
 
   return new TransactionInstruction({
     programId: GRANT_PROGRAM_ID,
+    // mint は Grant にキャッシュされているため claim_grant のアカウントには含めない
     keys: [
       { pubkey: params.grant, isSigner: false, isWritable: true },
-      { pubkey: params.mint, isSigner: false, isWritable: false },
       { pubkey: params.vault, isSigner: false, isWritable: true },
       { pubkey: params.claimer, isSigner: true, isWritable: true },
       { pubkey: params.claimerAta, isSigner: false, isWritable: true },
@@ -211,6 +217,10 @@ function buildClaimGrantInstruction(params: {
       { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
       { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
       { pubkey: SYSVAR_RENT_PUBKEY, isSigner: false, isWritable: false },
+      { pubkey: params.claimerProfile, isSigner: false, isWritable: true },
+      { pubkey: params.periodStats, isSigner: false, isWritable: true },
+      // TreasuryConfig が未初期化（claim 手数料なし）の場合のみ optional の treasury / fee_payer を省略できる
+      { pubkey: getTreasuryConfigPda()[0], isSigner: false, isWritable: false },
     ],
     data,
   });
@@ -370,10 +380,13 @@ export async function buildClaimTx(
   let periodSource: 'grantAccount' | 'devnetConfig' | 'fallback' = 'fallback';
   let startTs: bigint;
   let periodSeconds: bigint;
+  // receipt / pop_state の seed（Grant を読めない fallback では set_seed_salt 未実行とみなす）
+  let seedSalt: number[] | undefined;
   try {
     const grantAccount = await (program as any).account.grant.fetch(grantPda);
     startTs = toBigIntValue(grantAccount.startTs, 'startTs');
     periodSeconds = toBigIntValue(grantAccount.periodSeconds, 'periodSeconds');
+    seedSalt = grantAccount.seedSalt;
     periodSource = 'grantAccount';
   } catch (grantFetchError) {
     if (hasExplicitGrantParams) {
@@ -399,9 +412,11 @@ export async function buildClaimTx(
   const periodClockSource = clusterNowTs == null ? 'clientClockFallback' : 'clusterClock';
 
   const [vaultPda] = getVaultPda(grantPda);
-  const [receiptPda] = getReceiptPda(grantPda, recipientPubkey, periodIndex);
-  const [popStatePda] = getPopStatePda(grantPda);
+  const [receiptPda] = getReceiptPda(grantPda, recipientPubkey, periodIndex, seedSalt);
+  const [popStatePda] = getPopStatePda(grantPda, seedSalt);
   const [popConfigPda] = getPopConfigPda(authority);
+  const [claimerProfilePda] = getClaimerProfilePda(grantPda, recipientPubkey);
+  const [periodStatsPda] = getPeriodStatsPda(grantPda, periodIndex);
 
   const onchainPopClaimEnabled = hasExplicitGrantParams
     ? !params.forceLegacyClaim
@@ -541,13 +556,14 @@ export async function buildClaimTx(
     tx.add(
       buildClaimGrantInstruction({
         grant: grantPda,
-        mint,
         vault: vaultPda,
         claimer: recipientPubkey,
         claimerAta,
         receipt: receiptPda,
         popState: popStatePda,
         popConfig: popConfigPda,
+        claimerProfile: claimerProfilePda,
+        periodStats: periodStatsPda,
         periodIndex,
       })
     );
@@ -594,6 +610,43 @@ export async function buildClaimTx(
   };
 }
 
+/**
+ * Claim Transaction（v0 message）構築パラメータ
+ * - lookupTable: getCampaignLookupTableAddresses のアドレスを登録済みの ALT
+ */
+export interface BuildClaimV0TxParams extends BuildClaimTxParams {
+  lookupTable: PublicKey;
+}
+
+export interface BuildClaimV0TxResult {
+  tx: VersionedTransaction;
+  meta: BuildClaimTxResult['meta'];
+}
+
+/**
+ * Claim Grant トランザクションを v0 message で構築
+ *
+ * 注意: 署名・送信は行わない。構築のみ。
+ * proof 付きの claim でレガシートランザクションのサイズ上限を超える場合に使う。
+ */
+export async function buildClaimV0Tx(
+  params: BuildClaimV0TxParams
+): Promise<BuildClaimV0TxResult> {
+  const { tx: legacyTx, meta } = await buildClaimTx(params);
+  const lookupTable = await fetchLookupTable(getConnection(), params.lookupTable);
+  if (!lookupTable) {
+    throw new Error(`Address Lookup Table が見つかりません: ${params.lookupTable.toBase58()}`);
+  }
+
+  const tx = buildV0Transaction({
+    payer: params.recipientPubkey,
+    recentBlockhash: meta.recentBlockhash,
+    instructions: legacyTx.instructions,
+    lookupTables: [lookupTable],
+  });
+  return { tx, meta };
+}
+
 // ===== Use Transaction =====
 
 /**